@@ -9,11 +9,27 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DdnsConfig {
     /// IP source configuration
+    ///
+    /// Used by every record that is not bound to a named IP source.
     pub ip_source: IpSourceConfig,
 
+    /// Additional named IP sources
+    ///
+    /// Records select one of these with [`RecordConfig::ip_source`].
+    #[serde(default)]
+    pub ip_sources: HashMap<String, IpSourceConfig>,
+
     /// DNS provider configuration
+    ///
+    /// Used by every record that is not bound to a named provider.
     pub provider: ProviderConfig,
 
+    /// Additional named DNS providers
+    ///
+    /// Records select one of these with [`RecordConfig::provider`].
+    #[serde(default)]
+    pub providers: HashMap<String, ProviderConfig>,
+
     /// State store configuration
     pub state_store: StateStoreConfig,
 
@@ -30,7 +46,9 @@ impl DdnsConfig {
     pub fn new() -> Self {
         Self {
             ip_source: IpSourceConfig::default(),
+            ip_sources: HashMap::new(),
             provider: ProviderConfig::default(),
+            providers: HashMap::new(),
             state_store: StateStoreConfig::default(),
             records: Vec::new(),
             engine: EngineConfig::default(),
@@ -44,7 +62,43 @@ impl DdnsConfig {
         }
 
         self.provider.validate()?;
+        for (name, provider) in &self.providers {
+            if name.is_empty() {
+                return Err(crate::Error::config("Provider name cannot be empty"));
+            }
+            provider
+                .validate()
+                .map_err(|e| crate::Error::config(format!("Invalid provider '{}': {}", name, e)))?;
+        }
+
         self.ip_source.validate()?;
+        for (name, ip_source) in &self.ip_sources {
+            if name.is_empty() {
+                return Err(crate::Error::config("IP source name cannot be empty"));
+            }
+            ip_source.validate().map_err(|e| {
+                crate::Error::config(format!("Invalid IP source '{}': {}", name, e))
+            })?;
+        }
+
+        for record in &self.records {
+            if let Some(ref provider) = record.provider
+                && !self.providers.contains_key(provider)
+            {
+                return Err(crate::Error::config(format!(
+                    "Record {} references unknown provider '{}'",
+                    record.name, provider
+                )));
+            }
+            if let Some(ref ip_source) = record.ip_source
+                && !self.ip_sources.contains_key(ip_source)
+            {
+                return Err(crate::Error::config(format!(
+                    "Record {} references unknown IP source '{}'",
+                    record.name, ip_source
+                )));
+            }
+        }
 
         Ok(())
     }
@@ -66,16 +120,124 @@ pub enum IpSourceConfig {
         interface: Option<String>,
         /// IP version to monitor (v4, v6, or both)
         version: Option<IpVersion>,
+        /// Which of the interface's addresses may be published
+        #[serde(default)]
+        selection: AddressSelection,
     },
 
     /// HTTP-based IP source (uses external service)
     Http {
-        /// URL to fetch IP from
+        /// URL to fetch IP from (empty if only per-family URLs are used)
+        #[serde(default)]
         url: String,
+        /// Further URLs to fetch IP from, queried after `url`
+        #[serde(default)]
+        urls: Vec<String>,
+        /// URLs to fetch the IPv4 address from, always queried over IPv4
+        ///
+        /// Per-family URLs cannot be combined with `url` and `urls`.
+        #[serde(default)]
+        v4_urls: Vec<String>,
+        /// URLs to fetch the IPv6 address from, always queried over IPv6
+        #[serde(default)]
+        v6_urls: Vec<String>,
+        /// How the URLs are queried
+        #[serde(default)]
+        strategy: HttpStrategy,
+        /// Where the address is found in the response
+        #[serde(default)]
+        extractor: HttpExtractor,
+        /// Extra request headers (e.g. `Authorization` for internal endpoints)
+        #[serde(default)]
+        headers: HashMap<String, String>,
         /// Request interval in seconds
         interval_secs: u64,
     },
 
+    /// UPnP Internet Gateway Device (the home router's WAN address)
+    ///
+    /// The gateway is discovered via SSDP unless `location` is set.
+    Upnp {
+        /// URL of the gateway's device description, skipping discovery
+        #[serde(default)]
+        location: Option<String>,
+        /// Gateway to ask via NAT-PMP if UPnP fails
+        #[serde(default)]
+        nat_pmp_gateway: Option<std::net::IpAddr>,
+        /// Request interval in seconds
+        interval_secs: u64,
+    },
+
+    /// STUN-based IP source (RFC 5389 Binding Requests over UDP)
+    Stun {
+        /// STUN servers as `host[:port]` (default port 3478), tried in order
+        servers: Vec<String>,
+        /// IP version to monitor (v4, v6, or both; default both)
+        #[serde(default)]
+        version: Option<IpVersion>,
+        /// Request interval in seconds
+        interval_secs: u64,
+    },
+
+    /// External program printing the address (e.g. a modem CLI wrapper)
+    ///
+    /// The program is run directly, without a shell. Its standard output
+    /// is either the address itself or JSON containing it.
+    Exec {
+        /// Program and arguments
+        command: Vec<String>,
+        /// Where the address is found if the output is JSON (an RFC 6901
+        /// JSON pointer, e.g. `/0/addr_info/0/local`)
+        #[serde(default)]
+        json_pointer: Option<String>,
+        /// Time the program may run before it is killed, in seconds
+        #[serde(default = "default_exec_timeout_secs")]
+        timeout_secs: u64,
+        /// Signal that makes the source run the program immediately
+        #[serde(default)]
+        signal: Option<ExecSignal>,
+        /// Run interval in seconds
+        interval_secs: u64,
+    },
+
+    /// File holding the address, written e.g. by a DHCP hook or PPP
+    /// `ip-up` script and watched with inotify (Linux)
+    File {
+        /// Path to the file
+        path: String,
+        /// Quiet time after the last change before the file is read, in
+        /// milliseconds
+        #[serde(default = "default_file_debounce_ms")]
+        debounce_ms: u64,
+    },
+
+    /// Fixed addresses, for maintenance windows, failover to a standby host
+    /// or testing
+    ///
+    /// The addresses can be changed at runtime through the control handle
+    /// published under `control` (see `source::StaticIpFactory`).
+    Static {
+        /// Pinned IPv4 address
+        #[serde(default)]
+        v4: Option<std::net::Ipv4Addr>,
+        /// Pinned IPv6 address
+        #[serde(default)]
+        v6: Option<std::net::Ipv6Addr>,
+        /// Name under which the control handle is published
+        #[serde(default)]
+        control: Option<String>,
+    },
+
+    /// Composite IP source that tries other sources in order
+    ///
+    /// `current()` returns the first address any source can provide; change
+    /// events from a source are ignored while a higher-priority source
+    /// provides the same IP family.
+    Fallback {
+        /// Sources in priority order
+        sources: Vec<IpSourceConfig>,
+    },
+
     /// Custom IP source
     Custom {
         /// Factory name to use
@@ -89,13 +251,60 @@ impl IpSourceConfig {
     /// Validate the IP source configuration
     pub fn validate(&self) -> Result<(), crate::Error> {
         match self {
-            IpSourceConfig::Http { url, interval_secs } => {
-                if url.is_empty() {
+            IpSourceConfig::Http {
+                url,
+                urls,
+                v4_urls,
+                v6_urls,
+                strategy,
+                extractor,
+                headers,
+                interval_secs,
+            } => {
+                let generic = !url.is_empty() as usize + urls.len();
+                let per_family = [v4_urls.len(), v6_urls.len()];
+
+                if generic == 0 && per_family == [0, 0] {
+                    return Err(crate::Error::config("HTTP IP source URL cannot be empty"));
+                }
+                if generic > 0 && per_family != [0, 0] {
+                    return Err(crate::Error::config(
+                        "HTTP IP source URLs must either all be family-agnostic (url, urls) \
+                        or all per family (v4_urls, v6_urls)",
+                    ));
+                }
+                if urls
+                    .iter()
+                    .chain(v4_urls)
+                    .chain(v6_urls)
+                    .any(String::is_empty)
+                {
                     return Err(crate::Error::config("HTTP IP source URL cannot be empty"));
                 }
                 if *interval_secs == 0 {
                     return Err(crate::Error::config("HTTP IP source interval must be > 0"));
                 }
+                if headers.keys().any(String::is_empty) {
+                    return Err(crate::Error::config(
+                        "HTTP IP source header names cannot be empty",
+                    ));
+                }
+                extractor.validate()?;
+
+                // Every family must be able to reach the quorum on its own
+                let url_count = if generic > 0 {
+                    generic
+                } else {
+                    per_family.into_iter().filter(|n| *n > 0).min().unwrap_or(0)
+                };
+                if let HttpStrategy::Consensus { quorum } = strategy
+                    && (*quorum == 0 || *quorum > url_count)
+                {
+                    return Err(crate::Error::config(format!(
+                        "HTTP IP source quorum must be between 1 and the number of URLs ({}), got {}",
+                        url_count, quorum
+                    )));
+                }
                 Ok(())
             }
             IpSourceConfig::Custom { factory, config } => {
@@ -111,7 +320,120 @@ impl IpSourceConfig {
                 }
                 Ok(())
             }
-            IpSourceConfig::Netlink { .. } => Ok(()),
+            IpSourceConfig::Upnp {
+                location,
+                interval_secs,
+                ..
+            } => {
+                if let Some(location) = location
+                    && !location.starts_with("http://")
+                    && !location.starts_with("https://")
+                {
+                    return Err(crate::Error::config(format!(
+                        "UPnP device description location must be an HTTP URL, got: {}",
+                        location
+                    )));
+                }
+                if *interval_secs == 0 {
+                    return Err(crate::Error::config(
+                        "UPnP IP source interval must be positive",
+                    ));
+                }
+                Ok(())
+            }
+            IpSourceConfig::Stun {
+                servers,
+                interval_secs,
+                ..
+            } => {
+                if servers.is_empty() {
+                    return Err(crate::Error::config(
+                        "STUN IP source needs at least one server",
+                    ));
+                }
+                if servers.iter().any(|server| server.trim().is_empty()) {
+                    return Err(crate::Error::config("STUN server cannot be empty"));
+                }
+                if *interval_secs == 0 {
+                    return Err(crate::Error::config(
+                        "STUN IP source interval must be positive",
+                    ));
+                }
+                Ok(())
+            }
+            IpSourceConfig::Exec {
+                command,
+                json_pointer,
+                timeout_secs,
+                interval_secs,
+                ..
+            } => {
+                if command
+                    .first()
+                    .is_none_or(|program| program.trim().is_empty())
+                {
+                    return Err(crate::Error::config(
+                        "Exec IP source command cannot be empty",
+                    ));
+                }
+                if let Some(pointer) = json_pointer
+                    && !pointer.is_empty()
+                    && !pointer.starts_with('/')
+                {
+                    return Err(crate::Error::config(format!(
+                        "JSON pointer must be empty or start with '/', got '{}'",
+                        pointer
+                    )));
+                }
+                if *timeout_secs == 0 {
+                    return Err(crate::Error::config(
+                        "Exec IP source timeout must be positive",
+                    ));
+                }
+                if *interval_secs == 0 {
+                    return Err(crate::Error::config(
+                        "Exec IP source interval must be positive",
+                    ));
+                }
+                Ok(())
+            }
+            IpSourceConfig::File { path, .. } => {
+                if path.is_empty() {
+                    return Err(crate::Error::config("File IP source path cannot be empty"));
+                }
+                if std::path::Path::new(path).file_name().is_none() {
+                    return Err(crate::Error::config(format!(
+                        "File IP source path must name a file, got: {}",
+                        path
+                    )));
+                }
+                Ok(())
+            }
+            IpSourceConfig::Static { v4, v6, control } => {
+                if let Some(control) = control
+                    && control.is_empty()
+                {
+                    return Err(crate::Error::config(
+                        "Static IP source control name cannot be empty",
+                    ));
+                }
+                // Without a control handle, nothing could ever be pinned
+                if v4.is_none() && v6.is_none() && control.is_none() {
+                    return Err(crate::Error::config(
+                        "Static IP source needs an address or a control handle",
+                    ));
+                }
+                Ok(())
+            }
+            IpSourceConfig::Netlink { selection, .. } => selection.validate(),
+            IpSourceConfig::Fallback { sources } => {
+                if sources.is_empty() {
+                    return Err(crate::Error::config(
+                        "Fallback IP source needs at least one source",
+                    ));
+                }
+                sources.iter().try_for_each(IpSourceConfig::validate)
+            }
         }
     }
 }
@@ -121,10 +443,199 @@ impl Default for IpSourceConfig {
         IpSourceConfig::Netlink {
             interface: None,
             version: None,
+            selection: AddressSelection::default(),
+        }
+    }
+}
+
+fn default_exec_timeout_secs() -> u64 {
+    10
+}
+
+fn default_file_debounce_ms() -> u64 {
+    500
+}
+
+/// Signal that triggers an immediate run of an exec IP source
+///
+/// Lets scripts (e.g. a VPN client hook) announce a change instead of
+/// waiting for the next interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecSignal {
+    /// SIGHUP
+    Hup,
+    /// SIGUSR1
+    Usr1,
+    /// SIGUSR2
+    Usr2,
+}
+
+/// How an HTTP IP source queries its URLs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum HttpStrategy {
+    /// Query the URLs in order and use the first answer
+    #[default]
+    Failover,
+    /// Like `Failover`, but start at the next URL on every query to spread
+    /// the load across services
+    RoundRobin,
+    /// Query every URL and only accept an address at least `quorum` of them
+    /// agree on, guarding against a single service returning a wrong address
+    Consensus {
+        /// Number of URLs that must return the same address
+        quorum: usize,
+    },
+}
+
+/// Where an HTTP IP source finds the address in a response
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HttpExtractor {
+    /// The whole body, trimmed, is the address
+    #[default]
+    Body,
+    /// A string in a JSON body, addressed by an RFC 6901 JSON pointer
+    /// (e.g. `/ip` or `/data/address`)
+    JsonPointer {
+        /// JSON pointer to the address
+        pointer: String,
+    },
+    /// The first capture group of a regular expression matched against the
+    /// body (or the whole match, if the expression has no group)
+    Regex {
+        /// Regular expression
+        pattern: String,
+    },
+    /// The value of a `key=value` line in the body (e.g. `ip` for
+    /// Cloudflare's `/cdn-cgi/trace`)
+    KeyValue {
+        /// Key of the line holding the address
+        key: String,
+    },
+    /// A response header
+    Header {
+        /// Header name
+        name: String,
+    },
+}
+
+impl HttpExtractor {
+    /// Validate the extractor settings
+    ///
+    /// Regular expressions are compiled (and checked) by the IP source.
+    pub fn validate(&self) -> Result<(), crate::Error> {
+        match self {
+            HttpExtractor::Body => Ok(()),
+            HttpExtractor::JsonPointer { pointer } => {
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    return Err(crate::Error::config(format!(
+                        "JSON pointer must be empty or start with '/', got '{}'",
+                        pointer
+                    )));
+                }
+                Ok(())
+            }
+            HttpExtractor::Regex { pattern } if pattern.is_empty() => {
+                Err(crate::Error::config("HTTP IP source regex cannot be empty"))
+            }
+            HttpExtractor::KeyValue { key } if key.is_empty() => {
+                Err(crate::Error::config("HTTP IP source key cannot be empty"))
+            }
+            HttpExtractor::Header { name } if name.is_empty() => Err(crate::Error::config(
+                "HTTP IP source header name cannot be empty",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Address selection policy for interface-based IP sources
+///
+/// A host typically carries several addresses per interface: RFC1918 and
+/// CGNAT IPv4, IPv6 link-local (fe80::/10), ULA (fc00::/7), privacy-extension
+/// temporary addresses and deprecated addresses left over from a previous
+/// prefix. Only one of them should end up in DNS.
+///
+/// The defaults select globally routable, stable addresses only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressSelection {
+    /// Only select globally routable addresses (no private, CGNAT,
+    /// link-local, ULA or site-local addresses)
+    #[serde(default = "default_global_only")]
+    pub global_only: bool,
+
+    /// Allow IPv6 privacy-extension temporary addresses (IFA_F_TEMPORARY)
+    ///
+    /// Even when allowed, stable addresses are preferred.
+    #[serde(default)]
+    pub allow_temporary: bool,
+
+    /// Allow deprecated IPv6 addresses (IFA_F_DEPRECATED)
+    ///
+    /// Even when allowed, non-deprecated addresses are preferred.
+    #[serde(default)]
+    pub allow_deprecated: bool,
+
+    /// If non-empty, only addresses inside one of these CIDRs are selected
+    /// (e.g. "2001:db8:1::/48")
+    #[serde(default)]
+    pub allow_cidrs: Vec<String>,
+
+    /// Addresses inside any of these CIDRs are never selected
+    #[serde(default)]
+    pub deny_cidrs: Vec<String>,
+
+    /// Prefer IPv6 addresses with a modified EUI-64 interface identifier
+    /// (derived from the MAC address, stable across prefix changes)
+    #[serde(default)]
+    pub prefer_eui64: bool,
+
+    /// Prefer the IPv6 address with this interface identifier, written as
+    /// an IPv6 address whose upper 64 bits are ignored (e.g. "::1234")
+    #[serde(default)]
+    pub interface_id: Option<String>,
+}
+
+impl AddressSelection {
+    /// Validate the selection policy
+    pub fn validate(&self) -> Result<(), crate::Error> {
+        for cidr in self.allow_cidrs.iter().chain(&self.deny_cidrs) {
+            crate::net::Cidr::parse(cidr)?;
+        }
+
+        if let Some(ref interface_id) = self.interface_id
+            && interface_id.parse::<std::net::Ipv6Addr>().is_err()
+        {
+            return Err(crate::Error::config(format!(
+                "Invalid interface identifier '{}': expected IPv6 notation such as ::1234",
+                interface_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AddressSelection {
+    fn default() -> Self {
+        Self {
+            global_only: default_global_only(),
+            allow_temporary: false,
+            allow_deprecated: false,
+            allow_cidrs: Vec::new(),
+            deny_cidrs: Vec::new(),
+            prefer_eui64: false,
+            interface_id: None,
         }
     }
 }
 
+fn default_global_only() -> bool {
+    true
+}
+
 /// IP version to monitor
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -149,6 +660,155 @@ pub enum ProviderConfig {
         zone_id: Option<String>,
         /// Account ID (optional)
         account_id: Option<String>,
+        /// Create the A/AAAA record if it does not exist yet
+        /// (otherwise a missing record is an error)
+        #[serde(default)]
+        create_if_missing: bool,
+        /// TTL in seconds for created records (None = automatic)
+        #[serde(default)]
+        ttl: Option<u32>,
+        /// Whether created records are proxied through Cloudflare
+        #[serde(default)]
+        proxied: bool,
+        /// Comment attached to created records
+        #[serde(default)]
+        comment: Option<String>,
+        /// API base URL (None = the public Cloudflare API)
+        #[serde(default)]
+        api_base_url: Option<String>,
+        /// Zone IDs for specific record names, bypassing zone lookup
+        #[serde(default)]
+        zone_overrides: HashMap<String, String>,
+    },
+
+    /// RFC 2136 dynamic updates sent to an authoritative server
+    /// (BIND, Knot, ...)
+    Rfc2136 {
+        /// Primary server as `host[:port]` (default port 53)
+        server: String,
+        /// Zone the records belong to (None = discovered with a SOA query)
+        #[serde(default)]
+        zone: Option<String>,
+        /// TSIG key name (None = unsigned messages)
+        #[serde(default)]
+        tsig_key_name: Option<String>,
+        /// TSIG key secret, base64-encoded
+        #[serde(default)]
+        tsig_secret: Option<String>,
+        /// TSIG algorithm
+        #[serde(default)]
+        tsig_algorithm: TsigAlgorithm,
+        /// TTL in seconds of the records written
+        #[serde(default = "default_rfc2136_ttl")]
+        ttl: u32,
+    },
+
+    /// AWS Route 53
+    Route53 {
+        /// Access key ID (None = the AWS environment variables or shared
+        /// credentials file)
+        #[serde(default)]
+        access_key_id: Option<String>,
+        /// Secret access key
+        #[serde(default)]
+        secret_access_key: Option<String>,
+        /// Session token for temporary credentials
+        #[serde(default)]
+        session_token: Option<String>,
+        /// Profile in the shared credentials file (None = `AWS_PROFILE`
+        /// or "default")
+        #[serde(default)]
+        profile: Option<String>,
+        /// Hosted zone ID (None = looked up by the record name)
+        #[serde(default)]
+        hosted_zone_id: Option<String>,
+        /// TTL in seconds for created records
+        #[serde(default = "default_route53_ttl")]
+        ttl: u32,
+        /// Wait until a change has propagated to all Route 53 name servers
+        #[serde(default)]
+        wait_for_sync: bool,
+        /// API endpoint (None = https://route53.amazonaws.com)
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+
+    /// dyndns2 protocol (`/nic/update`), as spoken by DynDNS, No-IP and
+    /// many registrars and routers
+    Dyndns2 {
+        /// Update URL, e.g. `https://members.dyndns.org/nic/update`
+        update_url: String,
+        /// Username for basic authentication
+        username: String,
+        /// Password or update key for basic authentication
+        password: String,
+        /// Hostnames updated in the same request as a record, keyed by
+        /// record name
+        #[serde(default)]
+        extra_hostnames: HashMap<String, Vec<String>>,
+    },
+
+    /// PowerDNS Authoritative HTTP API
+    #[serde(rename = "powerdns")]
+    PowerDns {
+        /// API URL, e.g. `http://127.0.0.1:8081`
+        api_url: String,
+        /// API key (`api-key` in the server configuration)
+        api_key: String,
+        /// Server ID in API paths
+        #[serde(default = "default_powerdns_server_id")]
+        server_id: String,
+        /// Zone the records belong to (None = looked up by the record name)
+        #[serde(default)]
+        zone: Option<String>,
+        /// TTL in seconds for created records
+        #[serde(default = "default_powerdns_ttl")]
+        ttl: u32,
+        /// Rectify the zone after a change
+        #[serde(default)]
+        rectify: bool,
+        /// Send a NOTIFY to the zone's secondaries after a change
+        #[serde(default)]
+        notify: bool,
+    },
+
+    /// Hetzner DNS
+    Hetzner {
+        /// Hetzner DNS API token
+        api_token: String,
+        /// Zone ID (None = looked up by the record name)
+        #[serde(default)]
+        zone_id: Option<String>,
+        /// Create the A/AAAA record if it does not exist yet
+        /// (otherwise a missing record is an error)
+        #[serde(default)]
+        create_if_missing: bool,
+        /// TTL in seconds for created records (None = the zone's default)
+        #[serde(default)]
+        ttl: Option<u32>,
+        /// API base URL (None = https://dns.hetzner.com/api/v1)
+        #[serde(default)]
+        api_base_url: Option<String>,
+    },
+
+    /// DigitalOcean DNS
+    #[serde(rename = "digitalocean")]
+    DigitalOcean {
+        /// DigitalOcean API token
+        api_token: String,
+        /// Domain the records belong to (None = looked up by the record name)
+        #[serde(default)]
+        domain: Option<String>,
+        /// Create the A/AAAA record if it does not exist yet
+        /// (otherwise a missing record is an error)
+        #[serde(default)]
+        create_if_missing: bool,
+        /// TTL in seconds for created records (None = 1800)
+        #[serde(default)]
+        ttl: Option<u32>,
+        /// API base URL (None = https://api.digitalocean.com/v2)
+        #[serde(default)]
+        api_base_url: Option<String>,
     },
 
     /// Custom provider
@@ -164,10 +824,255 @@ impl ProviderConfig {
     /// Validate the provider configuration
     pub fn validate(&self) -> Result<(), crate::Error> {
         match self {
-            ProviderConfig::Cloudflare { api_token, .. } => {
+            ProviderConfig::Cloudflare {
+                api_token,
+                ttl,
+                api_base_url,
+                ..
+            } => {
                 if api_token.is_empty() {
                     return Err(crate::Error::config("Cloudflare API token cannot be empty"));
                 }
+                // Cloudflare accepts 1 (automatic) or 60..=86400 seconds
+                if let Some(ttl) = ttl
+                    && *ttl != 1
+                    && !(60..=86400).contains(ttl)
+                {
+                    return Err(crate::Error::config(format!(
+                        "Cloudflare TTL must be 1 (automatic) or between 60 and 86400 seconds, got {}",
+                        ttl
+                    )));
+                }
+                if let Some(url) = api_base_url
+                    && !url.starts_with("http://")
+                    && !url.starts_with("https://")
+                {
+                    return Err(crate::Error::config(format!(
+                        "Cloudflare API base URL must start with http:// or https://, got {}",
+                        url
+                    )));
+                }
+                Ok(())
+            }
+            ProviderConfig::Rfc2136 {
+                server,
+                zone,
+                tsig_key_name,
+                tsig_secret,
+                ttl,
+                ..
+            } => {
+                if server.trim().is_empty() {
+                    return Err(crate::Error::config("RFC 2136 server cannot be empty"));
+                }
+                if zone.as_ref().is_some_and(|zone| zone.trim().is_empty()) {
+                    return Err(crate::Error::config("RFC 2136 zone cannot be empty"));
+                }
+                if tsig_key_name.is_some() != tsig_secret.is_some() {
+                    return Err(crate::Error::config(
+                        "RFC 2136 TSIG key name and secret must be set together",
+                    ));
+                }
+                if tsig_secret.as_ref().is_some_and(|secret| secret.is_empty()) {
+                    return Err(crate::Error::config("RFC 2136 TSIG secret cannot be empty"));
+                }
+                // TTLs are 31-bit values (RFC 2181, section 8)
+                if *ttl > i32::MAX as u32 {
+                    return Err(crate::Error::config(format!(
+                        "RFC 2136 TTL must be at most {} seconds, got {}",
+                        i32::MAX,
+                        ttl
+                    )));
+                }
+                Ok(())
+            }
+            ProviderConfig::Route53 {
+                access_key_id,
+                secret_access_key,
+                session_token,
+                hosted_zone_id,
+                ttl,
+                endpoint,
+                ..
+            } => {
+                if access_key_id.is_some() != secret_access_key.is_some() {
+                    return Err(crate::Error::config(
+                        "Route 53 access key ID and secret access key must be set together",
+                    ));
+                }
+                if access_key_id.as_ref().is_some_and(|id| id.is_empty())
+                    || secret_access_key
+                        .as_ref()
+                        .is_some_and(|secret| secret.is_empty())
+                {
+                    return Err(crate::Error::config(
+                        "Route 53 access key ID and secret access key cannot be empty",
+                    ));
+                }
+                if session_token.is_some() && access_key_id.is_none() {
+                    return Err(crate::Error::config(
+                        "Route 53 session token requires an access key ID and secret access key",
+                    ));
+                }
+                if hosted_zone_id
+                    .as_ref()
+                    .is_some_and(|id| id.trim().is_empty())
+                {
+                    return Err(crate::Error::config(
+                        "Route 53 hosted zone ID cannot be empty",
+                    ));
+                }
+                if *ttl > i32::MAX as u32 {
+                    return Err(crate::Error::config(format!(
+                        "Route 53 TTL must be at most {} seconds, got {}",
+                        i32::MAX,
+                        ttl
+                    )));
+                }
+                if let Some(url) = endpoint
+                    && !url.starts_with("http://")
+                    && !url.starts_with("https://")
+                {
+                    return Err(crate::Error::config(format!(
+                        "Route 53 endpoint must start with http:// or https://, got {}",
+                        url
+                    )));
+                }
+                Ok(())
+            }
+            ProviderConfig::Dyndns2 {
+                update_url,
+                username,
+                password,
+                extra_hostnames,
+            } => {
+                if !update_url.starts_with("http://") && !update_url.starts_with("https://") {
+                    return Err(crate::Error::config(format!(
+                        "dyndns2 update URL must start with http:// or https://, got {}",
+                        update_url
+                    )));
+                }
+                if username.is_empty() || password.is_empty() {
+                    return Err(crate::Error::config(
+                        "dyndns2 username and password cannot be empty",
+                    ));
+                }
+                if extra_hostnames
+                    .values()
+                    .flatten()
+                    .any(|hostname| hostname.trim().is_empty() || hostname.contains(','))
+                {
+                    return Err(crate::Error::config(
+                        "dyndns2 extra hostnames cannot be empty or contain commas",
+                    ));
+                }
+                Ok(())
+            }
+            ProviderConfig::PowerDns {
+                api_url,
+                api_key,
+                server_id,
+                zone,
+                ttl,
+                ..
+            } => {
+                if !api_url.starts_with("http://") && !api_url.starts_with("https://") {
+                    return Err(crate::Error::config(format!(
+                        "PowerDNS API URL must start with http:// or https://, got {}",
+                        api_url
+                    )));
+                }
+                if api_key.is_empty() {
+                    return Err(crate::Error::config("PowerDNS API key cannot be empty"));
+                }
+                if server_id.trim().is_empty() {
+                    return Err(crate::Error::config("PowerDNS server ID cannot be empty"));
+                }
+                if zone
+                    .as_ref()
+                    .is_some_and(|zone| zone.trim().trim_end_matches('.').is_empty())
+                {
+                    return Err(crate::Error::config("PowerDNS zone cannot be empty"));
+                }
+                if *ttl > i32::MAX as u32 {
+                    return Err(crate::Error::config(format!(
+                        "PowerDNS TTL must be at most {} seconds, got {}",
+                        i32::MAX,
+                        ttl
+                    )));
+                }
+                Ok(())
+            }
+            ProviderConfig::Hetzner {
+                api_token,
+                zone_id,
+                ttl,
+                api_base_url,
+                ..
+            } => {
+                if api_token.is_empty() {
+                    return Err(crate::Error::config("Hetzner API token cannot be empty"));
+                }
+                if zone_id.as_ref().is_some_and(|id| id.trim().is_empty()) {
+                    return Err(crate::Error::config("Hetzner zone ID cannot be empty"));
+                }
+                if let Some(ttl) = ttl
+                    && *ttl > i32::MAX as u32
+                {
+                    return Err(crate::Error::config(format!(
+                        "Hetzner TTL must be at most {} seconds, got {}",
+                        i32::MAX,
+                        ttl
+                    )));
+                }
+                if let Some(url) = api_base_url
+                    && !url.starts_with("http://")
+                    && !url.starts_with("https://")
+                {
+                    return Err(crate::Error::config(format!(
+                        "Hetzner API base URL must start with http:// or https://, got {}",
+                        url
+                    )));
+                }
+                Ok(())
+            }
+            ProviderConfig::DigitalOcean {
+                api_token,
+                domain,
+                ttl,
+                api_base_url,
+                ..
+            } => {
+                if api_token.is_empty() {
+                    return Err(crate::Error::config(
+                        "DigitalOcean API token cannot be empty",
+                    ));
+                }
+                if domain
+                    .as_ref()
+                    .is_some_and(|domain| domain.trim().trim_end_matches('.').is_empty())
+                {
+                    return Err(crate::Error::config("DigitalOcean domain cannot be empty"));
+                }
+                // DigitalOcean rejects TTLs below 30 seconds
+                if let Some(ttl) = ttl
+                    && !(30..=i32::MAX as u32).contains(ttl)
+                {
+                    return Err(crate::Error::config(format!(
+                        "DigitalOcean TTL must be between 30 and {} seconds, got {}",
+                        i32::MAX,
+                        ttl
+                    )));
+                }
+                if let Some(url) = api_base_url
+                    && !url.starts_with("http://")
+                    && !url.starts_with("https://")
+                {
+                    return Err(crate::Error::config(format!(
+                        "DigitalOcean API base URL must start with http:// or https://, got {}",
+                        url
+                    )));
+                }
                 Ok(())
             }
             ProviderConfig::Custom { factory, config } => {
@@ -190,6 +1095,12 @@ impl ProviderConfig {
     pub fn type_name(&self) -> &str {
         match self {
             ProviderConfig::Cloudflare { .. } => "cloudflare",
+            ProviderConfig::Rfc2136 { .. } => "rfc2136",
+            ProviderConfig::Route53 { .. } => "route53",
+            ProviderConfig::Dyndns2 { .. } => "dyndns2",
+            ProviderConfig::PowerDns { .. } => "powerdns",
+            ProviderConfig::Hetzner { .. } => "hetzner",
+            ProviderConfig::DigitalOcean { .. } => "digitalocean",
             ProviderConfig::Custom { factory, .. } => factory,
         }
     }
@@ -201,10 +1112,44 @@ impl Default for ProviderConfig {
             api_token: String::new(),
             zone_id: None,
             account_id: None,
+            create_if_missing: false,
+            ttl: None,
+            proxied: false,
+            comment: None,
+            api_base_url: None,
+            zone_overrides: Default::default(),
         }
     }
 }
 
+/// TSIG algorithm for signing RFC 2136 messages (RFC 8945)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TsigAlgorithm {
+    /// HMAC-SHA256
+    #[default]
+    #[serde(rename = "hmac-sha256")]
+    HmacSha256,
+    /// HMAC-SHA512
+    #[serde(rename = "hmac-sha512")]
+    HmacSha512,
+}
+
+fn default_rfc2136_ttl() -> u32 {
+    300
+}
+
+fn default_route53_ttl() -> u32 {
+    300
+}
+
+fn default_powerdns_server_id() -> String {
+    "localhost".to_string()
+}
+
+fn default_powerdns_ttl() -> u32 {
+    300
+}
+
 /// State store configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -241,6 +1186,16 @@ pub struct RecordConfig {
     /// Whether this record is enabled
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+
+    /// Name of the provider in [`DdnsConfig::providers`] that manages this
+    /// record (None = the default provider)
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// Name of the IP source in [`DdnsConfig::ip_sources`] whose address
+    /// this record publishes (None = the default IP source)
+    #[serde(default)]
+    pub ip_source: Option<String>,
 }
 
 impl RecordConfig {
@@ -250,6 +1205,8 @@ impl RecordConfig {
             name: name.into(),
             record_type: RecordType::Auto,
             enabled: true,
+            provider: None,
+            ip_source: None,
         }
     }
 
@@ -264,6 +1221,18 @@ impl RecordConfig {
         self.enabled = enabled;
         self
     }
+
+    /// Bind the record to a named provider
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Bind the record to a named IP source
+    pub fn with_ip_source(mut self, ip_source: impl Into<String>) -> Self {
+        self.ip_source = Some(ip_source.into());
+        self
+    }
 }
 
 /// DNS record type
@@ -320,6 +1289,16 @@ pub struct EngineConfig {
     #[serde(default = "default_event_channel_capacity")]
     pub event_channel_capacity: usize,
 
+    /// Interval between drift checks against the provider (in seconds)
+    ///
+    /// When set, the engine periodically reads each managed record from the
+    /// provider and re-publishes the current IP if the record was changed
+    /// outside of this engine.
+    ///
+    /// Set to 0 to disable drift checks (default).
+    #[serde(default)]
+    pub reconcile_interval_secs: u64,
+
     /// Additional metadata to attach to operations
     #[serde(default)]
     pub metadata: HashMap<String, String>,
@@ -333,6 +1312,7 @@ impl Default for EngineConfig {
             startup_delay_secs: default_startup_delay_secs(),
             min_update_interval_secs: default_min_update_interval_secs(),
             event_channel_capacity: default_event_channel_capacity(),
+            reconcile_interval_secs: 0,
             metadata: HashMap::new(),
         }
     }
@@ -0,0 +1,94 @@
+// # DNS Name Helpers
+//
+// Record name handling shared by the DNS provider crates, so every provider
+// matches names, discovers zones and picks record types the same way.
+
+use crate::traits::IpVersion;
+
+/// Lowercase a name and remove its trailing dot
+pub fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Candidate zone names for a normalized record name, longest first
+///
+/// `a.lab.example.com` yields `a.lab.example.com`, `lab.example.com` and
+/// `example.com`. The top-level domain is never a candidate, and a name
+/// with an empty label has none.
+pub fn zone_candidates(name: &str) -> Vec<String> {
+    let labels: Vec<&str> = name.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return Vec::new();
+    }
+
+    (0..labels.len() - 1)
+        .map(|start| labels[start..].join("."))
+        .collect()
+}
+
+/// Name of `name` relative to `zone` (`@` for the apex), if it is in the zone
+///
+/// Both names are expected to be normalized.
+pub fn relative_name(name: &str, zone: &str) -> Option<String> {
+    if name == zone {
+        return Some("@".to_string());
+    }
+    name.strip_suffix(zone)?
+        .strip_suffix('.')
+        .filter(|relative| !relative.is_empty())
+        .map(str::to_string)
+}
+
+/// Address record type for a family: `A` or `AAAA`
+pub fn record_type(version: IpVersion) -> &'static str {
+    match version {
+        IpVersion::V4 => "A",
+        IpVersion::V6 => "AAAA",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Home.Example.com."), "home.example.com");
+        assert_eq!(normalize_name("example.com"), "example.com");
+    }
+
+    #[test]
+    fn test_zone_candidates() {
+        assert_eq!(
+            zone_candidates("a.lab.example.com"),
+            vec!["a.lab.example.com", "lab.example.com", "example.com"]
+        );
+        assert_eq!(zone_candidates("a.abc.net"), vec!["a.abc.net", "abc.net"]);
+        assert_eq!(zone_candidates("example.com"), vec!["example.com"]);
+        assert!(zone_candidates("localhost").is_empty());
+        assert!(zone_candidates("bad..example.com").is_empty());
+    }
+
+    #[test]
+    fn test_relative_name() {
+        assert_eq!(
+            relative_name("home.example.com", "example.com").as_deref(),
+            Some("home")
+        );
+        assert_eq!(
+            relative_name("a.b.example.com", "example.com").as_deref(),
+            Some("a.b")
+        );
+        assert_eq!(
+            relative_name("example.com", "example.com").as_deref(),
+            Some("@")
+        );
+        assert_eq!(relative_name("notexample.com", "example.com"), None);
+    }
+
+    #[test]
+    fn test_record_type() {
+        assert_eq!(record_type(IpVersion::V4), "A");
+        assert_eq!(record_type(IpVersion::V6), "AAAA");
+    }
+}
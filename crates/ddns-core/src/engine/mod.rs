@@ -26,6 +26,16 @@
 //! └─────────────┘           └──────────────┘           └─────────────┘
 //! ```
 //!
+//! ## Startup
+//!
+//! After the optional `startup_delay_secs`, the engine queries the current IP
+//! of every family its records follow (see [`IpSource::current_for`]) and
+//! pushes it to every enabled record through the same idempotent update
+//! path used for changes, so records are correct even if the IP source never
+//! reports a change. A dual-stack record gets both its A and its AAAA record
+//! reconciled. A source that fails its initial query is logged and still
+//! watched; its records are reconciled with its first change instead.
+//!
 //! ## Event Flow
 //!
 //! 1. IP change detected
@@ -33,12 +43,61 @@
 //! 3. If changed, call DnsProvider::update_record()
 //! 4. On success, update StateStore
 //! 5. Emit event for monitoring/logging
+//!
+//! ## Drift Reconciliation
+//!
+//! The state store only knows what the engine itself published. If
+//! `reconcile_interval_secs` is set, the engine periodically reads every
+//! managed record back from the provider and, if it no longer matches the
+//! current IP (edited by hand, clobbered by another host, deleted), emits
+//! [`EngineEvent::DriftDetected`] and pushes the current IP again.
+//!
+//! Before each check it also queries its IP sources again, which catches
+//! changes no event reports: a lost address, a fallback source taking over,
+//! or a source that failed its initial query.
+//!
+//! ## Dual-Stack
+//!
+//! IPv4 and IPv6 are tracked independently. An event is only dispatched to
+//! records whose [`RecordType`] accepts its [`IpVersion`]: `A` records see
+//! IPv4 changes, `Aaaa` records see IPv6 changes and `Auto` records see both,
+//! maintaining an A and an AAAA record under the same name.
+//!
+//! State is kept per record *and* family, see [`state_key`].
+//!
+//! ## Multiple Providers
+//!
+//! Records are dispatched to the default provider unless they name one of
+//! the providers in [`DdnsConfig::providers`] (see
+//! [`RecordConfig::provider`]). Provider instances for named providers are
+//! passed to [`DdnsEngine::with_providers`], typically created with
+//! [`ProviderRegistry::create_providers`](crate::ProviderRegistry::create_providers).
+//!
+//! ## Multiple IP Sources
+//!
+//! Likewise, records publish the address of the default IP source unless
+//! they name one of the sources in [`DdnsConfig::ip_sources`] (see
+//! [`RecordConfig::ip_source`]). Each source's events only reach the records
+//! bound to it, and the current IP is tracked per source and family. Named
+//! sources that no record uses are never queried or watched.
+//!
+//! ## IP Source Health
+//!
+//! Sources that report their health (see [`IpSource::health`]) are
+//! subscribed to while the engine runs. Every change is logged and emitted
+//! as [`EngineEvent::IpSourceHealthChanged`], so that a source that keeps
+//! failing is visible even though it reports no IP changes.
 
-use crate::config::{DdnsConfig, RecordConfig};
+use crate::config::{DdnsConfig, RecordConfig, RecordType};
 use crate::error::{Error, Result};
-use crate::traits::{DnsProvider, IpChangeEvent, IpSource, StateStore};
+use crate::traits::{DnsProvider, IpChangeEvent, IpSource, IpSourceHealth, IpVersion, StateStore};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio_stream::StreamExt;
+use tokio_stream::wrappers::WatchStream;
+use tokio_stream::{StreamExt, StreamMap};
 use tracing::{debug, error, info, warn};
 
 /// Events emitted by the DdnsEngine
@@ -79,10 +138,55 @@ pub enum EngineEvent {
     /// Engine started
     Started { records_count: usize },
 
+    /// The published record differs from the current IP
+    ///
+    /// `actual_ip` is `None` if the record does not exist at the provider.
+    DriftDetected {
+        record_name: String,
+        expected_ip: std::net::IpAddr,
+        actual_ip: Option<std::net::IpAddr>,
+    },
+
+    /// The health reported by an IP source changed
+    ///
+    /// `source` is `None` for the default IP source.
+    IpSourceHealthChanged {
+        source: Option<String>,
+        health: IpSourceHealth,
+    },
+
     /// Engine stopped
     Stopped { reason: String },
 }
 
+/// Build the [`StateStore`] key for a record and IP family
+///
+/// IPv4 state is stored under the bare record name (so state written before
+/// dual-stack support stays valid); IPv6 state is stored under
+/// `"<record_name>/AAAA"`.
+pub fn state_key(record_name: &str, version: IpVersion) -> String {
+    match version {
+        IpVersion::V4 => record_name.to_string(),
+        IpVersion::V6 => format!("{}/AAAA", record_name),
+    }
+}
+
+/// IP family of an address
+fn ip_version(ip: &IpAddr) -> IpVersion {
+    match ip {
+        IpAddr::V4(_) => IpVersion::V4,
+        IpAddr::V6(_) => IpVersion::V6,
+    }
+}
+
+/// Check whether a record of the given type should follow changes of a family
+fn record_accepts(record_type: RecordType, version: IpVersion) -> bool {
+    matches!(
+        (record_type, version),
+        (RecordType::Auto, _) | (RecordType::A, IpVersion::V4) | (RecordType::Aaaa, IpVersion::V6)
+    )
+}
+
 /// Core DDNS engine
 ///
 /// The engine orchestrates the entire IP change → DNS update flow.
@@ -107,12 +211,18 @@ pub enum EngineEvent {
 /// - **Rate limiting**: Minimum interval between updates prevents API storms
 /// - **Event dropping**: When channel is full, oldest events are dropped (logged)
 pub struct DdnsEngine {
-    /// IP source for monitoring changes
+    /// Default IP source for records not bound to a named IP source
     ip_source: Box<dyn IpSource>,
 
-    /// DNS provider for updating records
+    /// Named IP sources, selected by `RecordConfig::ip_source`
+    ip_sources: HashMap<String, Box<dyn IpSource>>,
+
+    /// Default DNS provider for records not bound to a named provider
     provider: Box<dyn DnsProvider>,
 
+    /// Named DNS providers, selected by `RecordConfig::provider`
+    providers: HashMap<String, Box<dyn DnsProvider>>,
+
     /// State store for idempotency
     state_store: Box<dyn StateStore>,
 
@@ -128,6 +238,15 @@ pub struct DdnsEngine {
     /// Minimum interval between updates for the same record (rate limiting)
     min_update_interval_secs: u64,
 
+    /// Delay before the initial IP query and startup reconciliation (in seconds)
+    startup_delay_secs: u64,
+
+    /// Interval between drift checks against the provider (0 = disabled)
+    reconcile_interval_secs: u64,
+
+    /// Current IP address per IP source (None = default) and family
+    current_ips: Mutex<HashMap<(Option<String>, IpVersion), IpAddr>>,
+
     /// Event sender for external monitoring
     event_tx: mpsc::Sender<EngineEvent>,
 }
@@ -150,19 +269,100 @@ impl DdnsEngine {
         provider: Box<dyn DnsProvider>,
         state_store: Box<dyn StateStore>,
         config: DdnsConfig,
+    ) -> Result<(Self, mpsc::Receiver<EngineEvent>)> {
+        Self::with_providers(ip_source, provider, HashMap::new(), state_store, config)
+    }
+
+    /// Create a new DDNS engine with named providers
+    ///
+    /// # Parameters
+    ///
+    /// - `ip_source`: IP source implementation
+    /// - `provider`: Default DNS provider implementation
+    /// - `providers`: Named DNS provider implementations
+    /// - `state_store`: State store implementation
+    /// - `config`: DDNS configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if a record is bound to a provider that has
+    /// no implementation in `providers`.
+    pub fn with_providers(
+        ip_source: Box<dyn IpSource>,
+        provider: Box<dyn DnsProvider>,
+        providers: HashMap<String, Box<dyn DnsProvider>>,
+        state_store: Box<dyn StateStore>,
+        config: DdnsConfig,
+    ) -> Result<(Self, mpsc::Receiver<EngineEvent>)> {
+        Self::with_components(
+            ip_source,
+            HashMap::new(),
+            provider,
+            providers,
+            state_store,
+            config,
+        )
+    }
+
+    /// Create a new DDNS engine with named IP sources and named providers
+    ///
+    /// # Parameters
+    ///
+    /// - `ip_source`: Default IP source implementation
+    /// - `ip_sources`: Named IP source implementations
+    /// - `provider`: Default DNS provider implementation
+    /// - `providers`: Named DNS provider implementations
+    /// - `state_store`: State store implementation
+    /// - `config`: DDNS configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if a record is bound to an IP source or
+    /// provider that has no implementation in `ip_sources` or `providers`.
+    pub fn with_components(
+        ip_source: Box<dyn IpSource>,
+        ip_sources: HashMap<String, Box<dyn IpSource>>,
+        provider: Box<dyn DnsProvider>,
+        providers: HashMap<String, Box<dyn DnsProvider>>,
+        state_store: Box<dyn StateStore>,
+        config: DdnsConfig,
     ) -> Result<(Self, mpsc::Receiver<EngineEvent>)> {
         config.validate()?;
 
+        for record in &config.records {
+            if let Some(ref name) = record.provider
+                && !providers.contains_key(name)
+            {
+                return Err(Error::config(format!(
+                    "No provider instance for '{}' (used by record {})",
+                    name, record.name
+                )));
+            }
+            if let Some(ref name) = record.ip_source
+                && !ip_sources.contains_key(name)
+            {
+                return Err(Error::config(format!(
+                    "No IP source instance for '{}' (used by record {})",
+                    name, record.name
+                )));
+            }
+        }
+
         let (tx, rx) = mpsc::channel(config.engine.event_channel_capacity);
 
         let engine = Self {
             ip_source,
+            ip_sources,
             provider,
+            providers,
             state_store,
             records: config.records,
             max_retries: config.engine.max_retries,
             retry_delay_secs: config.engine.retry_delay_secs,
             min_update_interval_secs: config.engine.min_update_interval_secs,
+            startup_delay_secs: config.engine.startup_delay_secs,
+            reconcile_interval_secs: config.engine.reconcile_interval_secs,
+            current_ips: Mutex::new(HashMap::new()),
             event_tx: tx,
         };
 
@@ -195,59 +395,146 @@ impl DdnsEngine {
             records_count: self.records.len(),
         });
 
-        // Get initial IP
-        let current_ip = self.ip_source.current().await?;
-        info!("Initial IP: {}", current_ip);
+        // Test mode resolves on the provided signal, production mode on SIGINT/SIGTERM
+        let shutdown = async move {
+            match shutdown_rx {
+                Some(rx) => {
+                    let _ = rx.await;
+                }
+                None => {
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+        };
+        tokio::pin!(shutdown);
+
+        // Give the network time to come up before the first query
+        if self.startup_delay_secs > 0 {
+            info!("Waiting {}s before startup", self.startup_delay_secs);
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(self.startup_delay_secs)) => {}
+                _ = &mut shutdown => return self.stop().await,
+            }
+        }
 
-        // Watch for IP changes
-        let mut ip_stream = self.ip_source.watch();
+        let sources = self.active_sources();
 
-        // Main event loop
-        if let Some(mut rx) = shutdown_rx {
-            // Test mode: wait for provided shutdown signal
-            loop {
-                tokio::select! {
-                    // Handle IP changes
-                    Some(event) = ip_stream.next() => {
-                        if let Err(e) = self.handle_ip_change(event).await {
-                            error!("Failed to handle IP change: {}", e);
-                        }
-                    }
+        // Subscribe to source health before the first query, so that its
+        // outcome is reported as well
+        let mut health_stream = StreamMap::new();
+        for (name, source) in &sources {
+            if let Some(health) = source.health() {
+                health_stream.insert(name.clone(), WatchStream::from_changes(health));
+            }
+        }
+
+        // Get the initial IP of every family the records of a source follow.
+        // A source that cannot answer yet is still watched; its records are
+        // reconciled with its first change instead.
+        let mut initial_ips = Vec::new();
+        for (name, source) in &sources {
+            let label = name.as_deref().unwrap_or("default");
 
-                    // Handle test shutdown signal
-                    _ = &mut rx => {
-                        info!("Shutdown signal received");
-                        self.emit_event(EngineEvent::Stopped {
-                            reason: "Shutdown signal".to_string(),
-                        });
+            for version in self.followed_families(name.as_deref(), *source) {
+                let current_ip = match source.current_for(version).await {
+                    Ok(ip) => ip,
+                    Err(Error::NotFound(e)) => {
+                        debug!("No initial {:?} IP from source '{}': {}", version, label, e);
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Initial IP query of source '{}' failed, skipping startup reconciliation of its records: {}",
+                            label, e
+                        );
                         break;
                     }
+                };
+
+                match name {
+                    Some(name) => info!("Initial IP of source '{}': {}", name, current_ip),
+                    None => info!("Initial IP: {}", current_ip),
                 }
+                self.set_current_ip(name.as_deref(), current_ip);
+                initial_ips.push((name.clone(), current_ip));
             }
-        } else {
-            // Production mode: wait for SIGINT/SIGTERM
-            loop {
-                tokio::select! {
-                    // Handle IP changes
-                    Some(event) = ip_stream.next() => {
-                        if let Err(e) = self.handle_ip_change(event).await {
-                            error!("Failed to handle IP change: {}", e);
-                            // Continue running despite errors
-                        }
-                    }
+        }
 
-                    // Handle shutdown signal (production)
-                    _ = tokio::signal::ctrl_c() => {
-                        info!("Shutdown signal received");
-                        self.emit_event(EngineEvent::Stopped {
-                            reason: "Shutdown signal".to_string(),
-                        });
-                        break;
+        // Watch for IP changes, tagging each event with its source
+        let mut ip_stream = StreamMap::new();
+        for (name, source) in &sources {
+            ip_stream.insert(name.clone(), source.watch());
+        }
+
+        // Startup reconciliation: records may be stale from before the engine
+        // started, and the IP source may not report a change for a long time
+        for (name, current_ip) in initial_ips {
+            let version = ip_version(&current_ip);
+            let count = self
+                .records
+                .iter()
+                .filter(|record| {
+                    record.ip_source == name
+                        && record.enabled
+                        && record_accepts(record.record_type, version)
+                })
+                .count();
+            info!(
+                "Reconciling {} record(s) with initial IP {}",
+                count, current_ip
+            );
+            tokio::select! {
+                _ = self.update_records(name.as_deref(), current_ip, version) => {}
+                _ = &mut shutdown => return self.stop().await,
+            }
+        }
+
+        // Drift checks start one interval after startup reconciliation
+        let reconcile_enabled = self.reconcile_interval_secs > 0;
+        let reconcile_period = Duration::from_secs(self.reconcile_interval_secs.max(1));
+        let mut reconcile_timer = tokio::time::interval_at(
+            tokio::time::Instant::now() + reconcile_period,
+            reconcile_period,
+        );
+        reconcile_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // Main event loop
+        loop {
+            tokio::select! {
+                // Handle IP changes
+                Some((source, event)) = ip_stream.next() => {
+                    if let Err(e) = self.handle_ip_change(source.as_deref(), event).await {
+                        error!("Failed to handle IP change: {}", e);
+                        // Continue running despite errors
                     }
                 }
+
+                // Handle IP source health changes
+                Some((source, health)) = health_stream.next() => {
+                    self.handle_health_change(source, health);
+                }
+
+                // Periodic source re-query and drift check
+                _ = reconcile_timer.tick(), if reconcile_enabled => {
+                    self.refresh_source_ips().await;
+                    self.reconcile_drift().await;
+                }
+
+                // Handle shutdown signal
+                _ = &mut shutdown => break,
             }
         }
 
+        self.stop().await
+    }
+
+    /// Emit the stop event and flush state
+    async fn stop(&self) -> Result<()> {
+        info!("Shutdown signal received");
+        self.emit_event(EngineEvent::Stopped {
+            reason: "Shutdown signal".to_string(),
+        });
+
         // Flush state before exiting
         self.state_store.flush().await?;
         info!("State flushed, engine stopped");
@@ -255,12 +542,73 @@ impl DdnsEngine {
         Ok(())
     }
 
+    /// The default IP source and every named IP source bound to a record
+    fn active_sources(&self) -> Vec<(Option<String>, &dyn IpSource)> {
+        let mut sources = vec![(None, self.ip_source.as_ref())];
+
+        let mut names: Vec<&String> = self
+            .ip_sources
+            .keys()
+            .filter(|name| {
+                self.records
+                    .iter()
+                    .any(|record| record.ip_source.as_ref() == Some(*name))
+            })
+            .collect();
+        names.sort();
+
+        for name in names {
+            sources.push((Some(name.clone()), self.ip_sources[name].as_ref()));
+        }
+
+        sources
+    }
+
+    /// The families followed by the enabled records bound to an IP source
+    /// that the source can provide
+    fn followed_families(
+        &self,
+        source_name: Option<&str>,
+        source: &dyn IpSource,
+    ) -> Vec<IpVersion> {
+        [IpVersion::V4, IpVersion::V6]
+            .into_iter()
+            .filter(|version| source.version().is_none_or(|v| v == *version))
+            .filter(|version| {
+                self.records.iter().any(|record| {
+                    record.enabled
+                        && record.ip_source.as_deref() == source_name
+                        && record_accepts(record.record_type, *version)
+                })
+            })
+            .collect()
+    }
+
+    /// Log and emit a change of an IP source's health
+    fn handle_health_change(&self, source: Option<String>, health: IpSourceHealth) {
+        let name = source.as_deref().unwrap_or("default");
+        match &health {
+            IpSourceHealth::Unknown => debug!("IP source '{}' health unknown", name),
+            IpSourceHealth::Healthy => info!("IP source '{}' is healthy", name),
+            IpSourceHealth::Failing {
+                consecutive_failures,
+                last_error,
+            } => warn!(
+                "IP source '{}' failing ({} consecutive failures): {}",
+                name, consecutive_failures, last_error
+            ),
+        }
+
+        self.emit_event(EngineEvent::IpSourceHealthChanged { source, health });
+    }
+
     /// Handle an IP change event
     ///
     /// # Parameters
     ///
+    /// - `source`: Name of the IP source that reported it (None = default)
     /// - `event`: The IP change event
-    async fn handle_ip_change(&self, event: IpChangeEvent) -> Result<()> {
+    async fn handle_ip_change(&self, source: Option<&str>, event: IpChangeEvent) -> Result<()> {
         debug!(
             "IP change detected: {} -> {:?}",
             event
@@ -270,18 +618,44 @@ impl DdnsEngine {
             event.new_ip
         );
 
+        self.set_current_ip(source, event.new_ip);
+        self.update_records(source, event.new_ip, event.version)
+            .await;
+
+        Ok(())
+    }
+
+    /// Push an IP address to every enabled record that is bound to its IP
+    /// source and follows its family
+    ///
+    /// Failures are logged and reported via events; remaining records are
+    /// still processed.
+    async fn update_records(&self, source: Option<&str>, new_ip: IpAddr, version: IpVersion) {
         // Process each configured record
         for record in &self.records {
+            if record.ip_source.as_deref() != source {
+                continue;
+            }
+
             if !record.enabled {
                 debug!("Record {} is disabled, skipping", record.name);
                 continue;
             }
 
+            if !record_accepts(record.record_type, version) {
+                debug!(
+                    "Record {} ({:?}) does not follow {:?} changes, skipping",
+                    record.name, record.record_type, version
+                );
+                continue;
+            }
+
             // Check if provider supports this record
-            if !self.provider.supports_record(&record.name) {
+            let provider = self.provider_for(record);
+            if !provider.supports_record(&record.name) {
                 warn!(
                     "Provider {} does not support record {}",
-                    self.provider.provider_name(),
+                    provider.provider_name(),
                     record.name
                 );
                 continue;
@@ -290,14 +664,11 @@ impl DdnsEngine {
             // Emit event
             self.emit_event(EngineEvent::IpChangeDetected {
                 record_name: record.name.clone(),
-                new_ip: event.new_ip,
+                new_ip,
             });
 
             // Update the record
-            match self
-                .update_record_with_retry(&record.name, event.new_ip)
-                .await
-            {
+            match self.update_record_with_retry(record, new_ip).await {
                 Ok(_) => {
                     debug!("Successfully updated record {}", record.name);
                 }
@@ -307,25 +678,173 @@ impl DdnsEngine {
                 }
             }
         }
+    }
 
-        Ok(())
+    /// Query every active IP source again and handle addresses that changed
+    /// without an event
+    ///
+    /// Catches changes a source cannot report as events, such as the loss of
+    /// an address or a fallback source taking over, and reconciles sources
+    /// whose initial query failed. Failures are logged; the source is asked
+    /// again on the next interval.
+    async fn refresh_source_ips(&self) {
+        for (name, source) in self.active_sources() {
+            for version in self.followed_families(name.as_deref(), source) {
+                let label = name.as_deref().unwrap_or("default");
+                let current_ip = match source.current_for(version).await {
+                    Ok(ip) => ip,
+                    Err(Error::NotFound(e)) => {
+                        debug!("No {:?} IP from source '{}': {}", version, label, e);
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!("IP query of source '{}' failed: {}", label, e);
+                        continue;
+                    }
+                };
+
+                let previous_ip = self.source_ip(name.as_deref(), version);
+                if previous_ip == Some(current_ip) {
+                    continue;
+                }
+
+                info!(
+                    "IP of source '{}' is now {} (found by periodic check)",
+                    label, current_ip
+                );
+                let event = IpChangeEvent::new(current_ip, previous_ip);
+                if let Err(e) = self.handle_ip_change(name.as_deref(), event).await {
+                    error!("Failed to handle IP change: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Compare every managed record with the provider and correct drift
+    ///
+    /// Lookup failures are logged and the record is checked again on the
+    /// next interval; they never stop the engine.
+    async fn reconcile_drift(&self) {
+        debug!("Checking records for drift");
+
+        for record in &self.records {
+            let provider = self.provider_for(record);
+            if !record.enabled || !provider.supports_record(&record.name) {
+                continue;
+            }
+
+            for version in [IpVersion::V4, IpVersion::V6] {
+                if !record_accepts(record.record_type, version) {
+                    continue;
+                }
+
+                // Nothing to compare against until the family has an address
+                let Some(expected_ip) = self.source_ip(record.ip_source.as_deref(), version) else {
+                    continue;
+                };
+
+                let actual_ip = match provider.get_record_for(&record.name, version).await {
+                    Ok(metadata) => Some(metadata.ip),
+                    Err(Error::NotFound(_)) => None,
+                    Err(Error::Unsupported(e)) => {
+                        debug!(
+                            "Skipping drift check for {} ({:?}): {}",
+                            record.name, version, e
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Drift check failed for {} ({:?}): {}",
+                            record.name, version, e
+                        );
+                        continue;
+                    }
+                };
+
+                if actual_ip == Some(expected_ip) {
+                    continue;
+                }
+
+                warn!(
+                    "Drift detected for {}: expected {}, found {:?}",
+                    record.name, expected_ip, actual_ip
+                );
+                self.emit_event(EngineEvent::DriftDetected {
+                    record_name: record.name.clone(),
+                    expected_ip,
+                    actual_ip,
+                });
+
+                // The stored state no longer describes the live record; drop
+                // it so the idempotency check does not skip the correction
+                let key = state_key(&record.name, version);
+                if let Err(e) = self.state_store.delete_record(&key).await {
+                    error!("Failed to invalidate state for {}: {}", record.name, e);
+                    continue;
+                }
+
+                if let Err(e) = self.update_record_with_retry(record, expected_ip).await {
+                    error!("Failed to correct drift for {}: {}", record.name, e);
+                }
+            }
+        }
+    }
+
+    /// Get the provider that manages a record
+    fn provider_for(&self, record: &RecordConfig) -> &dyn DnsProvider {
+        // Bindings are checked against `providers` at construction
+        record
+            .provider
+            .as_ref()
+            .and_then(|name| self.providers.get(name))
+            .unwrap_or(&self.provider)
+            .as_ref()
+    }
+
+    /// Get the current IP address of a family, as last reported by the
+    /// default IP source
+    pub fn current_ip(&self, version: IpVersion) -> Option<IpAddr> {
+        self.source_ip(None, version)
+    }
+
+    /// Get the current IP address of a family, as last reported by an IP
+    /// source (None = the default IP source)
+    pub fn source_ip(&self, source: Option<&str>, version: IpVersion) -> Option<IpAddr> {
+        self.current_ips
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(source.map(str::to_string), version))
+            .copied()
+    }
+
+    /// Remember the current IP address of an IP source and family
+    fn set_current_ip(&self, source: Option<&str>, ip: IpAddr) {
+        self.current_ips
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((source.map(str::to_string), ip_version(&ip)), ip);
     }
 
     /// Update a DNS record with retry logic
     ///
     /// # Parameters
     ///
-    /// - `record_name`: The DNS record name
+    /// - `record`: The DNS record
     /// - `new_ip`: The new IP address
     async fn update_record_with_retry(
         &self,
-        record_name: &str,
+        record: &RecordConfig,
         new_ip: std::net::IpAddr,
     ) -> Result<()> {
+        let record_name = record.name.as_str();
+        let provider = self.provider_for(record);
+        let key = state_key(record_name, ip_version(&new_ip));
+
         // Check if update is needed (idempotency)
         // Note: Using nested if statements instead of let-chains for better Docker compatibility
         #[allow(clippy::collapsible_if)]
-        if let Some(last_ip) = self.state_store.get_last_ip(record_name).await? {
+        if let Some(last_ip) = self.state_store.get_last_ip(&key).await? {
             if last_ip == new_ip {
                 debug!(
                     "Record {} already has IP {}, skipping update",
@@ -343,9 +862,9 @@ impl DdnsEngine {
         // Note: Using nested if statements instead of let-chains for better Docker compatibility
         #[allow(clippy::collapsible_if)]
         if self.min_update_interval_secs > 0 {
-            if let Some(record) = self.state_store.get_record(record_name).await? {
+            if let Some(state) = self.state_store.get_record(&key).await? {
                 let now = chrono::Utc::now();
-                let elapsed = now.signed_duration_since(record.last_updated);
+                let elapsed = now.signed_duration_since(state.last_updated);
                 let min_interval = chrono::Duration::seconds(self.min_update_interval_secs as i64);
 
                 if elapsed < min_interval {
@@ -373,7 +892,7 @@ impl DdnsEngine {
         // Attempt update with retries
         let mut last_error = None;
         for attempt in 0..=self.max_retries {
-            match self.do_update(record_name, new_ip).await {
+            match self.do_update(provider, record_name, new_ip).await {
                 Ok(result) => {
                     match result {
                         crate::traits::UpdateResult::Updated { previous_ip, .. } => {
@@ -401,7 +920,7 @@ impl DdnsEngine {
                     }
 
                     // Update state store
-                    self.state_store.set_last_ip(record_name, new_ip).await?;
+                    self.state_store.set_last_ip(&key, new_ip).await?;
                     return Ok(());
                 }
                 Err(e) => {
@@ -434,17 +953,19 @@ impl DdnsEngine {
     ///
     /// # Parameters
     ///
+    /// - `provider`: The provider that manages the record
     /// - `record_name`: The DNS record name
     /// - `new_ip`: The new IP address
     async fn do_update(
         &self,
+        provider: &dyn DnsProvider,
         record_name: &str,
         new_ip: std::net::IpAddr,
     ) -> Result<crate::traits::UpdateResult> {
-        self.provider
+        provider
             .update_record(record_name, new_ip)
             .await
-            .map_err(|e| Error::provider(self.provider.provider_name(), e.to_string()))
+            .map_err(|e| Error::provider(provider.provider_name(), e.to_string()))
     }
 
     /// Emit an engine event
@@ -498,4 +1019,20 @@ mod tests {
         let _ = event.clone();
         assert_eq!(event.clone(), event);
     }
+
+    #[test]
+    fn test_state_key_distinguishes_families() {
+        assert_eq!(state_key("example.com", IpVersion::V4), "example.com");
+        assert_eq!(state_key("example.com", IpVersion::V6), "example.com/AAAA");
+    }
+
+    #[test]
+    fn test_record_accepts() {
+        assert!(record_accepts(RecordType::A, IpVersion::V4));
+        assert!(!record_accepts(RecordType::A, IpVersion::V6));
+        assert!(record_accepts(RecordType::Aaaa, IpVersion::V6));
+        assert!(!record_accepts(RecordType::Aaaa, IpVersion::V4));
+        assert!(record_accepts(RecordType::Auto, IpVersion::V4));
+        assert!(record_accepts(RecordType::Auto, IpVersion::V6));
+    }
 }
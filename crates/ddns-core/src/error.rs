@@ -54,6 +54,10 @@ pub enum Error {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// Operation the implementation cannot perform
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
     /// Provider-specific error
     #[error("Provider error ({provider}): {message}")]
     Provider {
@@ -114,6 +118,11 @@ impl Error {
         Self::InvalidInput(msg.into())
     }
 
+    /// Create an "unsupported" error
+    pub fn unsupported(msg: impl Into<String>) -> Self {
+        Self::Unsupported(msg.into())
+    }
+
     /// Create a provider-specific error
     pub fn provider(provider: impl Into<String>, message: impl Into<String>) -> Self {
         Self::Provider {
@@ -28,9 +28,12 @@
 // 5. **Idempotency**: State management ensures safe, repeatable operations
 
 pub mod config;
+pub mod dns;
 pub mod engine;
 pub mod error;
+pub mod net;
 pub mod registry;
+pub mod source;
 pub mod state;
 pub mod traits;
 
@@ -39,5 +42,6 @@ pub use config::{DdnsConfig, IpSourceConfig, ProviderConfig};
 pub use engine::DdnsEngine;
 pub use error::{Error, Result};
 pub use registry::ProviderRegistry;
+pub use source::{FallbackIpSource, StaticIpFactory, StaticIpSource};
 pub use state::{FileStateStore, FileStateStoreFactory, MemoryStateStore, MemoryStateStoreFactory};
 pub use traits::{DnsProvider, IpSource, StateRecord, StateStore, StateStoreFactory};
@@ -0,0 +1,119 @@
+// # Address Helpers
+//
+// Parsing of addresses and networks shared by configuration validation and
+// the IP source crates, so every crate accepts exactly the same syntax.
+
+use crate::{Error, Result};
+use std::net::IpAddr;
+
+/// Parse an IP address, ignoring a trailing "/prefix_len"
+///
+/// Accepts both a bare address and the "address/prefix_len" form printed by
+/// tools such as `ip addr`, so their output can be used as is.
+pub fn parse_address(value: &str) -> Option<IpAddr> {
+    let without_prefix = value
+        .split_once('/')
+        .filter(|(_, len)| !len.is_empty() && len.bytes().all(|b| b.is_ascii_digit()))
+        .map_or(value, |(ip, _)| ip);
+
+    without_prefix.parse().ok()
+}
+
+/// A parsed "address/prefix_len" network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Parse "address/prefix_len"
+    ///
+    /// # Errors
+    ///
+    /// `Error::Config` if the string is not an address followed by a prefix
+    /// length valid for its family.
+    pub fn parse(s: &str) -> Result<Self> {
+        let invalid = || Error::config(format!("Invalid CIDR '{}'", s));
+
+        let (network, prefix_len) = s.split_once('/').ok_or_else(invalid)?;
+        let network: IpAddr = network.parse().map_err(|_| invalid())?;
+        let prefix_len: u8 = prefix_len.parse().map_err(|_| invalid())?;
+
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_len {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Check whether an address lies inside this network
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("203.0.113.5"), Some(ip("203.0.113.5")));
+        assert_eq!(parse_address("203.0.113.5/24"), Some(ip("203.0.113.5")));
+        assert_eq!(parse_address("2001:db8::5/64"), Some(ip("2001:db8::5")));
+        assert_eq!(parse_address("203.0.113.5/"), None);
+        assert_eq!(parse_address("203.0.113.5/x"), None);
+        assert_eq!(parse_address("nonsense"), None);
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let cidr = Cidr::parse("203.0.113.0/24").unwrap();
+
+        assert!(cidr.contains(&ip("203.0.113.200")));
+        assert!(!cidr.contains(&ip("203.0.114.1")));
+        assert!(!cidr.contains(&ip("2001:db8::1")));
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+        assert!(
+            Cidr::parse("2606:4700::/32")
+                .unwrap()
+                .contains(&ip("2606:4700:1::1"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_cidr_rejected() {
+        for cidr in [
+            "10.0.0.0",
+            "10.0.0.0/33",
+            "fe80::/129",
+            "nonsense/8",
+            "10.0.0.0/x",
+        ] {
+            assert!(Cidr::parse(cidr).is_err(), "{}", cidr);
+        }
+    }
+}
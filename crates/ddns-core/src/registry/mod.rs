@@ -39,6 +39,7 @@
 
 use crate::config::{IpSourceConfig, ProviderConfig};
 use crate::error::{Error, Result};
+use crate::source::FallbackIpSource;
 use crate::traits::{DnsProvider, IpSource, StateStore};
 use crate::traits::{DnsProviderFactory, IpSourceFactory, StateStoreFactory};
 use std::collections::HashMap;
@@ -146,6 +147,12 @@ impl ProviderRegistry {
     ///     api_token: "token".to_string(),
     ///     zone_id: None,
     ///     account_id: None,
+    ///     create_if_missing: false,
+    ///     ttl: None,
+    ///     proxied: false,
+    ///     comment: None,
+    ///     api_base_url: None,
+    ///     zone_overrides: Default::default(),
     /// };
     /// let provider = registry.create_provider(&config)?;
     /// # Ok(())
@@ -162,6 +169,30 @@ impl ProviderRegistry {
         factory.create(config)
     }
 
+    /// Create a DNS provider for each named provider configuration
+    ///
+    /// # Parameters
+    ///
+    /// - `configs`: Provider configurations by name (see `DdnsConfig::providers`)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(HashMap)`: Created provider instances by name
+    /// - `Err(Error)`: If any provider cannot be created; the error names it
+    pub fn create_providers(
+        &self,
+        configs: &HashMap<String, ProviderConfig>,
+    ) -> Result<HashMap<String, Box<dyn DnsProvider>>> {
+        configs
+            .iter()
+            .map(|(name, config)| {
+                self.create_provider(config)
+                    .map(|provider| (name.clone(), provider))
+                    .map_err(|e| Error::config(format!("Provider '{}': {}", name, e)))
+            })
+            .collect()
+    }
+
     /// Create an IP source from configuration
     ///
     /// # Parameters
@@ -172,10 +203,25 @@ impl ProviderRegistry {
     ///
     /// - `Ok(Box<dyn IpSource>)`: Created IP source instance
     /// - `Err(Error)`: If source type is not registered or creation fails
+    ///
+    /// Fallback sources are built into a [`FallbackIpSource`] from their
+    /// member sources; they need no registered factory.
     pub fn create_ip_source(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
         let source_type = match config {
             IpSourceConfig::Netlink { .. } => "netlink",
             IpSourceConfig::Http { .. } => "http",
+            IpSourceConfig::Upnp { .. } => "upnp",
+            IpSourceConfig::Stun { .. } => "stun",
+            IpSourceConfig::Exec { .. } => "exec",
+            IpSourceConfig::File { .. } => "file",
+            IpSourceConfig::Static { .. } => "static",
+            IpSourceConfig::Fallback { sources } => {
+                let sources = sources
+                    .iter()
+                    .map(|source| self.create_ip_source(source))
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(Box::new(FallbackIpSource::new(sources)?));
+            }
             IpSourceConfig::Custom { factory, .. } => factory,
         };
 
@@ -188,6 +234,30 @@ impl ProviderRegistry {
         factory.create(config)
     }
 
+    /// Create an IP source for each named IP source configuration
+    ///
+    /// # Parameters
+    ///
+    /// - `configs`: IP source configurations by name (see `DdnsConfig::ip_sources`)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(HashMap)`: Created IP source instances by name
+    /// - `Err(Error)`: If any IP source cannot be created; the error names it
+    pub fn create_ip_sources(
+        &self,
+        configs: &HashMap<String, IpSourceConfig>,
+    ) -> Result<HashMap<String, Box<dyn IpSource>>> {
+        configs
+            .iter()
+            .map(|(name, config)| {
+                self.create_ip_source(config)
+                    .map(|source| (name.clone(), source))
+                    .map_err(|e| Error::config(format!("IP source '{}': {}", name, e)))
+            })
+            .collect()
+    }
+
     /// Create a state store from configuration
     ///
     /// # Parameters
@@ -208,19 +278,19 @@ impl ProviderRegistry {
             crate::config::StateStoreConfig::Custom { factory, .. } => factory,
         };
 
-        let stores = self.state_stores.read().unwrap();
+        // Release the lock before calling async create
+        let factory = {
+            let stores = self.state_stores.read().unwrap();
 
-        let factory = stores
-            .get(store_type)
-            .ok_or_else(|| Error::config(format!("Unknown state store type: {}", store_type)))?
-            .clone();
+            stores
+                .get(store_type)
+                .ok_or_else(|| Error::config(format!("Unknown state store type: {}", store_type)))?
+                .clone()
+        };
 
         // Create the state store config JSON
         let config_json = serde_json::to_value(config)?;
 
-        // Release the lock before calling async create
-        drop(stores);
-
         factory.create(&config_json).await
     }
 
@@ -323,4 +393,28 @@ mod tests {
         assert!(registry.has_provider("mock"));
         assert!(registry.list_providers().contains(&"mock".to_string()));
     }
+
+    #[test]
+    fn test_create_providers_names_failing_provider() {
+        let registry = ProviderRegistry::new();
+        registry.register_provider("mock", Box::new(MockProviderFactory));
+
+        let mut configs = HashMap::new();
+        configs.insert(
+            "work".to_string(),
+            ProviderConfig::Custom {
+                factory: "mock".to_string(),
+                config: serde_json::json!({}),
+            },
+        );
+
+        let err = registry.create_providers(&configs).err().unwrap();
+        assert!(err.to_string().contains("'work'"));
+        assert!(
+            registry
+                .create_providers(&HashMap::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,461 @@
+// # Fallback IP Source
+//
+// Composite IpSource that tries other sources in priority order.
+//
+// ## Purpose
+//
+// A single source is not always able to provide an address: an interface
+// may only carry private addresses, or an HTTP endpoint may be unreachable.
+// The fallback source combines several sources, e.g. netlink first and an
+// HTTP service second.
+//
+// ## Behavior
+//
+// - `current()` asks each source in order and returns the first address.
+//   The source that answered becomes the *active* source for that family.
+// - `watch()` merges the change streams of all sources. An event is
+//   forwarded only if it comes from the active source of its family or from
+//   a higher-priority one (which then becomes active), and only if the
+//   address differs from the last forwarded address of that family.
+// - A source whose `health()` turns `Failing` stops being active, so the
+//   next event of any other source takes over.
+//
+// ## Limitations
+//
+// Change events only carry new addresses, so the loss of an address is not
+// observable in `watch()` alone. The engine's periodic check (see
+// `reconcile_interval_secs`) calls `current_for()` again, which moves the
+// active source down when a higher-priority source no longer answers.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio_stream::wrappers::WatchStream;
+use tokio_stream::{Stream, StreamExt, StreamMap};
+
+use crate::Error;
+use crate::traits::{IpChangeEvent, IpSource, IpSourceHealth, IpVersion};
+
+/// What a source reported: an address change or a health change
+enum Signal {
+    Change(IpChangeEvent),
+    Health(IpSourceHealth),
+}
+
+type SignalStream = Pin<Box<dyn Stream<Item = Signal> + Send>>;
+
+/// Composite IP source that tries other sources in priority order
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use ddns_core::source::FallbackIpSource;
+///
+/// let source = FallbackIpSource::new(vec![netlink_source, http_source])?;
+/// let ip = source.current().await?;
+/// ```
+pub struct FallbackIpSource {
+    /// Sources in priority order
+    sources: Vec<Box<dyn IpSource>>,
+
+    /// Index of the source currently providing each family
+    ///
+    /// Shared with the streams returned by `watch()`.
+    active: Arc<Mutex<HashMap<IpVersion, usize>>>,
+}
+
+impl FallbackIpSource {
+    /// Create a fallback source from sources in priority order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `sources` is empty.
+    pub fn new(sources: Vec<Box<dyn IpSource>>) -> Result<Self, Error> {
+        if sources.is_empty() {
+            return Err(Error::config(
+                "Fallback IP source needs at least one source",
+            ));
+        }
+
+        Ok(Self {
+            sources,
+            active: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+}
+
+#[async_trait]
+impl IpSource for FallbackIpSource {
+    async fn current(&self) -> Result<IpAddr, Error> {
+        let mut failures = Vec::new();
+
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.current().await {
+                Ok(ip) => {
+                    if index > 0 {
+                        tracing::info!("Using fallback IP source #{} ({})", index, ip);
+                    }
+                    self.active
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(IpChangeEvent::new(ip, None).version, index);
+                    return Ok(ip);
+                }
+                Err(e) => {
+                    tracing::debug!("IP source #{} failed: {}", index, e);
+                    failures.push(format!("#{}: {}", index, e));
+                }
+            }
+        }
+
+        Err(Error::ip_source(format!(
+            "All fallback IP sources failed ({})",
+            failures.join("; ")
+        )))
+    }
+
+    async fn current_for(&self, version: IpVersion) -> Result<IpAddr, Error> {
+        let mut failures = Vec::new();
+        let mut missing = true;
+
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.current_for(version).await {
+                Ok(ip) => {
+                    if index > 0 {
+                        tracing::info!("Using fallback IP source #{} ({})", index, ip);
+                    }
+                    self.active
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(version, index);
+                    return Ok(ip);
+                }
+                Err(e) => {
+                    tracing::debug!("IP source #{} failed for {:?}: {}", index, version, e);
+                    missing &= matches!(e, Error::NotFound(_));
+                    failures.push(format!("#{}: {}", index, e));
+                }
+            }
+        }
+
+        let message = format!(
+            "All fallback IP sources failed for {:?} ({})",
+            version,
+            failures.join("; ")
+        );
+        if missing {
+            // No source provides the family at all
+            Err(Error::not_found(message))
+        } else {
+            Err(Error::ip_source(message))
+        }
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let mut streams = StreamMap::new();
+        for (index, source) in self.sources.iter().enumerate() {
+            let events = source.watch().map(Signal::Change);
+            let signals: SignalStream = match source.health() {
+                Some(health) => {
+                    Box::pin(events.merge(WatchStream::from_changes(health).map(Signal::Health)))
+                }
+                None => Box::pin(events),
+            };
+            streams.insert(index, signals);
+        }
+
+        let active = self.active.clone();
+        let mut last_ips: HashMap<IpVersion, IpAddr> = HashMap::new();
+
+        Box::pin(streams.filter_map(move |(index, signal)| {
+            let mut active = active.lock().unwrap_or_else(|e| e.into_inner());
+
+            let event = match signal {
+                Signal::Change(event) => event,
+                Signal::Health(IpSourceHealth::Failing { last_error, .. }) => {
+                    if active.values().any(|&a| a == index) {
+                        tracing::warn!(
+                            "IP source #{} is failing, accepting other sources: {}",
+                            index,
+                            last_error
+                        );
+                        active.retain(|_, a| *a != index);
+                    }
+                    return None;
+                }
+                Signal::Health(_) => return None,
+            };
+
+            // A higher-priority source provides this family
+            if active.get(&event.version).is_some_and(|&a| index > a) {
+                tracing::trace!(
+                    "Ignoring {} from IP source #{} (lower priority)",
+                    event.new_ip,
+                    index
+                );
+                return None;
+            }
+            active.insert(event.version, index);
+
+            // Sources switched over to the same address
+            let previous_ip = last_ips.insert(event.version, event.new_ip);
+            if previous_ip == Some(event.new_ip) {
+                return None;
+            }
+
+            Some(IpChangeEvent {
+                previous_ip: event.previous_ip.or(previous_ip),
+                ..event
+            })
+        }))
+    }
+
+    fn version(&self) -> Option<IpVersion> {
+        let first = self.sources[0].version()?;
+        self.sources
+            .iter()
+            .all(|source| source.version() == Some(first))
+            .then_some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::{mpsc, watch};
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    /// Source with a test-controlled current() result, stream and health
+    struct TestSource {
+        current: Arc<Mutex<Option<IpAddr>>>,
+        rx: Mutex<Option<mpsc::UnboundedReceiver<IpChangeEvent>>>,
+        health: Option<watch::Receiver<IpSourceHealth>>,
+    }
+
+    fn test_source(
+        current: Option<IpAddr>,
+    ) -> (Box<dyn IpSource>, mpsc::UnboundedSender<IpChangeEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let source = TestSource {
+            current: Arc::new(Mutex::new(current)),
+            rx: Mutex::new(Some(rx)),
+            health: None,
+        };
+        (Box::new(source), tx)
+    }
+
+    /// Handles to change a test source after creation
+    struct Controls {
+        events: mpsc::UnboundedSender<IpChangeEvent>,
+        current: Arc<Mutex<Option<IpAddr>>>,
+        health: watch::Sender<IpSourceHealth>,
+    }
+
+    /// A test source whose address and health can change after creation
+    fn changing_source(current: Option<IpAddr>) -> (Box<dyn IpSource>, Controls) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (health_tx, health_rx) = watch::channel(IpSourceHealth::Healthy);
+        let current = Arc::new(Mutex::new(current));
+        let source = TestSource {
+            current: current.clone(),
+            rx: Mutex::new(Some(rx)),
+            health: Some(health_rx),
+        };
+        let controls = Controls {
+            events: tx,
+            current,
+            health: health_tx,
+        };
+        (Box::new(source), controls)
+    }
+
+    #[async_trait]
+    impl IpSource for TestSource {
+        async fn current(&self) -> Result<IpAddr, Error> {
+            self.current
+                .lock()
+                .unwrap()
+                .ok_or_else(|| Error::ip_source("no address"))
+        }
+
+        fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+            let rx = self.rx.lock().unwrap().take().unwrap();
+            Box::pin(UnboundedReceiverStream::new(rx))
+        }
+
+        fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+            self.health.clone()
+        }
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_requires_a_source() {
+        assert!(FallbackIpSource::new(Vec::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_current_uses_first_available_source() {
+        let (primary, _) = test_source(None);
+        let (secondary, _) = test_source(Some(ip("203.0.113.5")));
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+    }
+
+    #[tokio::test]
+    async fn test_current_fails_if_all_sources_fail() {
+        let (primary, _) = test_source(None);
+        let (secondary, _) = test_source(None);
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        let err = source.current().await.unwrap_err();
+        assert!(err.to_string().contains("#0"));
+        assert!(err.to_string().contains("#1"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_prefers_higher_priority_source() {
+        let (primary, primary_tx) = test_source(Some(ip("203.0.113.5")));
+        let (secondary, secondary_tx) = test_source(Some(ip("198.51.100.1")));
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        // The primary source is active for IPv4
+        source.current().await.unwrap();
+        let mut stream = source.watch();
+
+        secondary_tx
+            .send(IpChangeEvent::new(ip("198.51.100.2"), None))
+            .unwrap();
+        primary_tx
+            .send(IpChangeEvent::new(ip("203.0.113.6"), None))
+            .unwrap();
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.new_ip, ip("203.0.113.6"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_uses_fallback_until_primary_reports() {
+        let (primary, primary_tx) = test_source(None);
+        let (secondary, secondary_tx) = test_source(Some(ip("198.51.100.1")));
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        source.current().await.unwrap();
+        let mut stream = source.watch();
+
+        secondary_tx
+            .send(IpChangeEvent::new(ip("198.51.100.2"), None))
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().new_ip, ip("198.51.100.2"));
+
+        // The primary source comes up and takes over
+        primary_tx
+            .send(IpChangeEvent::new(ip("203.0.113.5"), None))
+            .unwrap();
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.new_ip, ip("203.0.113.5"));
+        assert_eq!(event.previous_ip, Some(ip("198.51.100.2")));
+
+        secondary_tx
+            .send(IpChangeEvent::new(ip("198.51.100.3"), None))
+            .unwrap();
+        primary_tx
+            .send(IpChangeEvent::new(ip("203.0.113.6"), None))
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().new_ip, ip("203.0.113.6"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_deduplicates_and_tracks_families_separately() {
+        let (primary, primary_tx) = test_source(None);
+        let (secondary, secondary_tx) = test_source(None);
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+        let mut stream = source.watch();
+
+        primary_tx
+            .send(IpChangeEvent::new(ip("203.0.113.5"), None))
+            .unwrap();
+        primary_tx
+            .send(IpChangeEvent::new(ip("203.0.113.5"), None))
+            .unwrap();
+        // Only the secondary source provides IPv6
+        secondary_tx
+            .send(IpChangeEvent::new(ip("2001:db8::5"), None))
+            .unwrap();
+
+        // Streams are merged without ordering guarantees between sources
+        let mut ips = vec![
+            stream.next().await.unwrap().new_ip,
+            stream.next().await.unwrap().new_ip,
+        ];
+        ips.sort();
+        assert_eq!(ips, vec![ip("203.0.113.5"), ip("2001:db8::5")]);
+
+        // The duplicate was dropped
+        drop(primary_tx);
+        drop(secondary_tx);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current_demotes_primary_that_stops_answering() {
+        let (primary, primary_controls) = changing_source(Some(ip("203.0.113.5")));
+        let (secondary, secondary_tx) = test_source(Some(ip("198.51.100.1")));
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+        let mut stream = source.watch();
+
+        // The primary loses its address without reporting it; the periodic
+        // query moves on to the secondary source
+        *primary_controls.current.lock().unwrap() = None;
+        assert_eq!(
+            source.current_for(IpVersion::V4).await.unwrap(),
+            ip("198.51.100.1")
+        );
+
+        secondary_tx
+            .send(IpChangeEvent::new(ip("198.51.100.2"), None))
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().new_ip, ip("198.51.100.2"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_demotes_failing_primary() {
+        let (primary, primary_controls) = changing_source(Some(ip("203.0.113.5")));
+        let (secondary, secondary_tx) = test_source(Some(ip("198.51.100.1")));
+        let source = FallbackIpSource::new(vec![primary, secondary]).unwrap();
+
+        source.current().await.unwrap();
+        let mut stream = source.watch();
+
+        primary_controls
+            .health
+            .send_replace(IpSourceHealth::Failing {
+                consecutive_failures: 1,
+                last_error: "timed out".to_string(),
+            });
+        // Let the stream see the health change before the secondary's event
+        tokio::time::timeout(std::time::Duration::from_millis(50), stream.next())
+            .await
+            .unwrap_err();
+
+        secondary_tx
+            .send(IpChangeEvent::new(ip("198.51.100.2"), None))
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().new_ip, ip("198.51.100.2"));
+
+        // A recovered primary takes over with its next event
+        primary_controls
+            .events
+            .send(IpChangeEvent::new(ip("203.0.113.6"), None))
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().new_ip, ip("203.0.113.6"));
+    }
+}
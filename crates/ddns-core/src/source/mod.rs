@@ -0,0 +1,12 @@
+// # IP Source Implementations
+//
+// This module provides IP sources that are independent of any platform or
+// external service, such as composites of other sources.
+
+pub mod fallback;
+pub mod static_ip;
+
+pub use fallback::FallbackIpSource;
+pub use static_ip::{
+    StaticAddresses, StaticIpControl, StaticIpControls, StaticIpFactory, StaticIpSource,
+};
@@ -0,0 +1,358 @@
+// # Static IP Source
+//
+// IpSource that returns configured addresses instead of detecting them.
+//
+// ## Purpose
+//
+// Publishing a fixed address is needed for maintenance windows (pointing a
+// record at a holding page), failover to a standby host and integration
+// tests. Combined with a fallback source (`static` first, detection second)
+// it also works as a manual override that is only active while an address
+// is pinned.
+//
+// ## Behavior
+//
+// - `current()` returns the pinned IPv4 address, else the pinned IPv6
+//   address, else fails.
+// - The pinned addresses can be changed at runtime through a
+//   [`StaticIpControl`]. `watch()` emits an event for each family whose
+//   address changed, starting with the addresses pinned when it is called.
+// - Clearing an address emits nothing: change events only carry new
+//   addresses, so the record keeps its last value.
+//
+// ## Control Handles
+//
+// Sources created from configuration publish their control handle under
+// the configured `control` name in the [`StaticIpControls`] of the factory
+// that created them, so that the embedding application can reach them.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::config::IpSourceConfig;
+use crate::traits::{IpChangeEvent, IpSource, IpSourceFactory, IpVersion};
+use crate::{Error, Result};
+
+/// Addresses pinned by a static IP source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StaticAddresses {
+    /// Pinned IPv4 address
+    pub v4: Option<Ipv4Addr>,
+    /// Pinned IPv6 address
+    pub v6: Option<Ipv6Addr>,
+}
+
+impl StaticAddresses {
+    /// Pinned address of `version`
+    pub fn get(&self, version: IpVersion) -> Option<IpAddr> {
+        match version {
+            IpVersion::V4 => self.v4.map(IpAddr::V4),
+            IpVersion::V6 => self.v6.map(IpAddr::V6),
+        }
+    }
+}
+
+/// Handle to change the addresses of a [`StaticIpSource`] at runtime
+#[derive(Debug, Clone)]
+pub struct StaticIpControl {
+    addresses: Arc<watch::Sender<StaticAddresses>>,
+}
+
+impl StaticIpControl {
+    /// Currently pinned addresses
+    pub fn addresses(&self) -> StaticAddresses {
+        *self.addresses.borrow()
+    }
+
+    /// Pin `ip`, replacing the pinned address of its family
+    pub fn set(&self, ip: IpAddr) {
+        self.addresses.send_if_modified(|addresses| {
+            let previous = *addresses;
+            match ip {
+                IpAddr::V4(ip) => addresses.v4 = Some(ip),
+                IpAddr::V6(ip) => addresses.v6 = Some(ip),
+            }
+            *addresses != previous
+        });
+    }
+
+    /// Unpin the address of `version`
+    pub fn clear(&self, version: IpVersion) {
+        self.addresses.send_if_modified(|addresses| {
+            let previous = *addresses;
+            match version {
+                IpVersion::V4 => addresses.v4 = None,
+                IpVersion::V6 => addresses.v6 = None,
+            }
+            *addresses != previous
+        });
+    }
+}
+
+/// IP source returning pinned addresses
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::source::{StaticAddresses, StaticIpSource};
+///
+/// let source = StaticIpSource::new(StaticAddresses {
+///     v4: Some("203.0.113.5".parse().unwrap()),
+///     v6: None,
+/// });
+///
+/// // Fail over to the standby host
+/// source.control().set("203.0.113.6".parse().unwrap());
+/// ```
+pub struct StaticIpSource {
+    control: StaticIpControl,
+}
+
+impl StaticIpSource {
+    /// Create a source pinning `addresses`
+    pub fn new(addresses: StaticAddresses) -> Self {
+        Self {
+            control: StaticIpControl {
+                addresses: Arc::new(watch::Sender::new(addresses)),
+            },
+        }
+    }
+
+    /// Handle to change the pinned addresses
+    pub fn control(&self) -> StaticIpControl {
+        self.control.clone()
+    }
+}
+
+#[async_trait]
+impl IpSource for StaticIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        let addresses = self.control.addresses();
+        addresses
+            .get(IpVersion::V4)
+            .or_else(|| addresses.get(IpVersion::V6))
+            .ok_or_else(|| Error::not_found("No static IP address pinned"))
+    }
+
+    async fn current_for(&self, version: IpVersion) -> Result<IpAddr> {
+        self.control
+            .addresses()
+            .get(version)
+            .ok_or_else(|| Error::not_found(format!("No static {:?} address pinned", version)))
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut addresses = self.control.addresses.subscribe();
+
+        tokio::spawn(async move {
+            let mut last_known = StaticAddresses::default();
+
+            loop {
+                let current = *addresses.borrow_and_update();
+                for version in [IpVersion::V4, IpVersion::V6] {
+                    let Some(ip) = current.get(version) else {
+                        continue;
+                    };
+                    let previous_ip = last_known.get(version);
+                    if previous_ip == Some(ip) {
+                        continue;
+                    }
+
+                    tracing::info!("Static IP changed: {:?} -> {}", previous_ip, ip);
+                    if tx.send(IpChangeEvent::new(ip, previous_ip)).is_err() {
+                        return;
+                    }
+                }
+                // Cleared addresses are forgotten, so re-pinning them is a
+                // change again
+                last_known = current;
+
+                tokio::select! {
+                    changed = addresses.changed() => {
+                        if changed.is_err() {
+                            // Source and all control handles dropped
+                            return;
+                        }
+                    }
+                    _ = tx.closed() => return,
+                }
+            }
+        });
+
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+}
+
+/// Control handles of the static IP sources created by a
+/// [`StaticIpFactory`], by name
+#[derive(Debug, Clone, Default)]
+pub struct StaticIpControls {
+    controls: Arc<Mutex<HashMap<String, StaticIpControl>>>,
+}
+
+impl StaticIpControls {
+    /// Control handle published under `name`
+    pub fn get(&self, name: &str) -> Option<StaticIpControl> {
+        self.controls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// Names of the published control handles, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .controls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn insert(&self, name: String, control: StaticIpControl) {
+        self.controls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name, control);
+    }
+}
+
+/// Factory for creating static IP sources
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+/// use ddns_core::source::StaticIpFactory;
+///
+/// let registry = ProviderRegistry::new();
+/// let factory = StaticIpFactory::new();
+/// let controls = factory.controls();
+/// registry.register_ip_source("static", Box::new(factory));
+///
+/// // Sources created with `control: Some("standby")` are reachable through
+/// // `controls.get("standby")`
+/// assert!(controls.names().is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct StaticIpFactory {
+    controls: StaticIpControls,
+}
+
+impl StaticIpFactory {
+    /// Create a factory with an empty set of control handles
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Control handles of the sources created by this factory
+    pub fn controls(&self) -> StaticIpControls {
+        self.controls.clone()
+    }
+}
+
+impl IpSourceFactory for StaticIpFactory {
+    fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        match config {
+            IpSourceConfig::Static { v4, v6, control } => {
+                let source = StaticIpSource::new(StaticAddresses { v4: *v4, v6: *v6 });
+                if let Some(name) = control {
+                    self.controls.insert(name.clone(), source.control());
+                }
+                Ok(Box::new(source))
+            }
+            _ => Err(Error::config("Invalid config for static IP source")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_current_prefers_ipv4() {
+        let source = StaticIpSource::new(StaticAddresses {
+            v4: Some("203.0.113.5".parse().unwrap()),
+            v6: Some("2001:db8::5".parse().unwrap()),
+        });
+        assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+
+        source.control().clear(IpVersion::V4);
+        assert_eq!(source.current().await.unwrap(), ip("2001:db8::5"));
+
+        source.control().clear(IpVersion::V6);
+        assert!(source.current().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_emits_changes() {
+        let source = StaticIpSource::new(StaticAddresses {
+            v4: Some("203.0.113.5".parse().unwrap()),
+            v6: None,
+        });
+        let control = source.control();
+        let mut events = source.watch();
+
+        let event = events.next().await.unwrap();
+        assert_eq!((event.new_ip, event.previous_ip), (ip("203.0.113.5"), None));
+
+        control.set(ip("203.0.113.6"));
+        let event = events.next().await.unwrap();
+        assert_eq!(
+            (event.new_ip, event.previous_ip),
+            (ip("203.0.113.6"), Some(ip("203.0.113.5")))
+        );
+
+        control.set(ip("2001:db8::5"));
+        let event = events.next().await.unwrap();
+        assert_eq!(
+            (event.version, event.new_ip),
+            (IpVersion::V6, ip("2001:db8::5"))
+        );
+
+        // Pinning the same address again is not a change
+        control.set(ip("2001:db8::5"));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), events.next())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_factory_publishes_controls() {
+        let factory = StaticIpFactory::new();
+        let controls = factory.controls();
+
+        let config = IpSourceConfig::Static {
+            v4: Some("203.0.113.5".parse().unwrap()),
+            v6: None,
+            control: Some("standby".to_string()),
+        };
+        factory.create(&config).unwrap();
+
+        assert_eq!(controls.names(), vec!["standby".to_string()]);
+        let control = controls.get("standby").unwrap();
+        control.set(ip("203.0.113.6"));
+        assert_eq!(control.addresses().v4, Some("203.0.113.6".parse().unwrap()));
+        assert!(controls.get("primary").is_none());
+    }
+}
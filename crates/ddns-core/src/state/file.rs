@@ -411,6 +411,33 @@ impl StateStore for FileStateStore {
     }
 }
 
+/// Factory for creating file state stores
+pub struct FileStateStoreFactory;
+
+#[async_trait]
+impl StateStoreFactory for FileStateStoreFactory {
+    async fn create(
+        &self,
+        config: &serde_json::Value,
+    ) -> Result<Box<dyn StateStore>, crate::Error> {
+        // Try to parse as StateStoreConfig
+        if let Ok(crate::config::StateStoreConfig::File { path }) =
+            serde_json::from_value::<crate::config::StateStoreConfig>(config.clone())
+        {
+            let store = FileStateStore::new(path).await?;
+            return Ok(Box::new(store));
+        }
+
+        // Try to extract path directly
+        if let Some(path_str) = config.get("path").and_then(|v| v.as_str()) {
+            let store = FileStateStore::new(path_str.to_string()).await?;
+            return Ok(Box::new(store));
+        }
+
+        Err(Error::config("Invalid file state store config"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,27 +528,3 @@ mod tests {
         assert_eq!(final_ip, Some("1.2.3.9".parse().unwrap()));
     }
 }
-
-/// Factory for creating file state stores
-pub struct FileStateStoreFactory;
-
-#[async_trait]
-impl StateStoreFactory for FileStateStoreFactory {
-    async fn create(&self, config: &serde_json::Value) -> Result<Box<dyn StateStore>, crate::Error> {
-        // Try to parse as StateStoreConfig
-        if let Ok(state_store_config) = serde_json::from_value::<crate::config::StateStoreConfig>(config.clone()) {
-            if let crate::config::StateStoreConfig::File { path } = state_store_config {
-                let store = FileStateStore::new(path).await?;
-                return Ok(Box::new(store));
-            }
-        }
-
-        // Try to extract path directly
-        if let Some(path_str) = config.get("path").and_then(|v| v.as_str()) {
-            let store = FileStateStore::new(path_str.to_string()).await?;
-            return Ok(Box::new(store));
-        }
-
-        Err(Error::config("Invalid file state store config"))
-    }
-}
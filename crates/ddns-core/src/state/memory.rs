@@ -133,6 +133,19 @@ impl StateStore for MemoryStateStore {
     }
 }
 
+/// Factory for creating memory state stores
+pub struct MemoryStateStoreFactory;
+
+#[async_trait]
+impl StateStoreFactory for MemoryStateStoreFactory {
+    async fn create(
+        &self,
+        _config: &serde_json::Value,
+    ) -> Result<Box<dyn StateStore>, crate::Error> {
+        Ok(Box::new(MemoryStateStore::new()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,13 +203,3 @@ mod tests {
         assert!(records.contains(&"test.com".to_string()));
     }
 }
-
-/// Factory for creating memory state stores
-pub struct MemoryStateStoreFactory;
-
-#[async_trait]
-impl StateStoreFactory for MemoryStateStoreFactory {
-    async fn create(&self, _config: &serde_json::Value) -> Result<Box<dyn StateStore>, crate::Error> {
-        Ok(Box::new(MemoryStateStore::new()))
-    }
-}
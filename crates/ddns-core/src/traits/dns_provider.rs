@@ -26,6 +26,7 @@
 // }
 // ```
 
+use super::IpVersion;
 use async_trait::async_trait;
 use std::net::IpAddr;
 
@@ -195,6 +196,51 @@ pub trait DnsProvider: Send + Sync {
     /// - `Err(Error)`: If the record doesn't exist or the request failed
     async fn get_record(&self, record_name: &str) -> Result<RecordMetadata, crate::Error>;
 
+    /// Get current metadata for the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// Used by the engine to detect drift between the published record and
+    /// the current IP of each family.
+    ///
+    /// The default implementation calls [`get_record`](Self::get_record) and
+    /// returns `Error::Unsupported` if its address is of the other family:
+    /// the name may still hold a record of the requested family that
+    /// `get_record` cannot return, so the engine skips the check instead of
+    /// treating the record as missing. Providers that can query a specific
+    /// record type should override it.
+    ///
+    /// # Parameters
+    ///
+    /// - `record_name`: The DNS record name
+    /// - `version`: Which family's record to read
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(RecordMetadata)`: The record's current metadata
+    /// - `Err(Error::NotFound)`: If the record doesn't exist
+    /// - `Err(Error::Unsupported)`: If the family's record cannot be read
+    /// - `Err(Error)`: If the request failed
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata, crate::Error> {
+        let record = self.get_record(record_name).await?;
+
+        let matches = match version {
+            IpVersion::V4 => record.ip.is_ipv4(),
+            IpVersion::V6 => record.ip.is_ipv6(),
+        };
+
+        if matches {
+            Ok(record)
+        } else {
+            Err(crate::Error::unsupported(format!(
+                "Cannot read the {:?} record of {}",
+                version, record_name
+            )))
+        }
+    }
+
     /// Check if this provider supports the given record type
     ///
     /// Some providers may have limitations on record types or names.
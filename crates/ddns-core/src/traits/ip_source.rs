@@ -46,6 +46,72 @@ pub struct IpChangeEvent {
     pub version: IpVersion,
 }
 
+/// Health of an IP source, as reported by the source itself
+///
+/// Sources that can fail to determine the address (e.g. because a remote
+/// service is unreachable) publish their health through
+/// [`IpSource::health`]. A failing source emits no change events, so
+/// without this signal an outage is indistinguishable from a stable
+/// address.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum IpSourceHealth {
+    /// No attempt to determine the address has completed yet
+    #[default]
+    Unknown,
+    /// The last attempt to determine the address succeeded
+    Healthy,
+    /// The last attempts to determine the address failed
+    Failing {
+        /// Failed attempts since the last success
+        consecutive_failures: u32,
+        /// Error of the most recent attempt
+        last_error: String,
+    },
+}
+
+/// Recording of attempt outcomes on a health signal
+///
+/// Implemented for the sending half of the channel behind
+/// [`IpSource::health`], so that every source counts consecutive failures
+/// the same way.
+pub trait HealthReporter {
+    /// Record a successful attempt
+    ///
+    /// Receivers are only notified if the source was not healthy before.
+    fn record_success(&self);
+
+    /// Record a failed attempt and return the failures since the last success
+    fn record_failure(&self, error: impl std::fmt::Display) -> u32;
+}
+
+impl HealthReporter for tokio::sync::watch::Sender<IpSourceHealth> {
+    fn record_success(&self) {
+        self.send_if_modified(|health| {
+            let changed = *health != IpSourceHealth::Healthy;
+            *health = IpSourceHealth::Healthy;
+            changed
+        });
+    }
+
+    fn record_failure(&self, error: impl std::fmt::Display) -> u32 {
+        let mut failures = 0;
+        self.send_modify(|health| {
+            failures = match health {
+                IpSourceHealth::Failing {
+                    consecutive_failures,
+                    ..
+                } => *consecutive_failures + 1,
+                _ => 1,
+            };
+            *health = IpSourceHealth::Failing {
+                consecutive_failures: failures,
+                last_error: error.to_string(),
+            };
+        });
+        failures
+    }
+}
+
 /// IP version (v4 or v6)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IpVersion {
@@ -150,6 +216,30 @@ pub trait IpSource: Send + Sync {
     /// - `Err(Error)`: If unable to determine the current IP
     async fn current(&self) -> Result<IpAddr, crate::Error>;
 
+    /// Get the current IP address of one family
+    ///
+    /// The engine uses this at startup to reconcile A and AAAA records
+    /// separately. The default asks `current()` and returns
+    /// `Error::NotFound` if it answers with the other family; dual-stack
+    /// sources should override it so that both families are available.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(IpAddr)`: The current IP address of the family
+    /// - `Err(Error::NotFound)`: If the source has no address of the family
+    /// - `Err(Error)`: If unable to determine the current IP
+    async fn current_for(&self, version: IpVersion) -> Result<IpAddr, crate::Error> {
+        let ip = self.current().await?;
+        if IpChangeEvent::new(ip, None).version == version {
+            Ok(ip)
+        } else {
+            Err(crate::Error::not_found(format!(
+                "IP source has no {:?} address",
+                version
+            )))
+        }
+    }
+
     /// Watch for IP changes
     ///
     /// Returns a stream that yields `IpChangeEvent` whenever the IP address changes.
@@ -173,6 +263,14 @@ pub trait IpSource: Send + Sync {
     fn version(&self) -> Option<IpVersion> {
         None
     }
+
+    /// Subscribe to the health of this source
+    ///
+    /// Returns `None` if the source does not report its health (the
+    /// default), e.g. because it cannot fail once constructed.
+    fn health(&self) -> Option<tokio::sync::watch::Receiver<IpSourceHealth>> {
+        None
+    }
 }
 
 /// Helper trait for constructing IP sources from configuration
@@ -191,3 +289,33 @@ pub trait IpSourceFactory: Send + Sync {
         config: &crate::config::IpSourceConfig,
     ) -> Result<Box<dyn IpSource>, crate::Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::watch;
+
+    #[test]
+    fn test_health_reporter_counts_consecutive_failures() {
+        let (tx, mut rx) = watch::channel(IpSourceHealth::Unknown);
+
+        assert_eq!(tx.record_failure("timed out"), 1);
+        assert_eq!(tx.record_failure("refused"), 2);
+        assert_eq!(
+            *rx.borrow_and_update(),
+            IpSourceHealth::Failing {
+                consecutive_failures: 2,
+                last_error: "refused".to_string(),
+            }
+        );
+
+        tx.record_success();
+        assert_eq!(*rx.borrow_and_update(), IpSourceHealth::Healthy);
+
+        // Repeated successes do not wake receivers
+        tx.record_success();
+        assert!(!rx.has_changed().unwrap());
+
+        assert_eq!(tx.record_failure("timed out"), 1);
+    }
+}
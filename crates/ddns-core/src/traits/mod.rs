@@ -11,5 +11,7 @@ pub mod ip_source;
 pub mod state_store;
 
 pub use dns_provider::{DnsProvider, DnsProviderFactory, RecordMetadata, UpdateResult};
-pub use ip_source::{IpChangeEvent, IpSource, IpSourceFactory, IpVersion};
+pub use ip_source::{
+    HealthReporter, IpChangeEvent, IpSource, IpSourceFactory, IpSourceHealth, IpVersion,
+};
 pub use state_store::{StateRecord, StateStore, StateStoreFactory};
@@ -117,6 +117,8 @@ pub struct MockDnsProvider {
     update_call_count: Arc<AtomicUsize>,
     /// Recorded record names from update calls
     updated_records: Arc<std::sync::Mutex<Vec<String>>>,
+    /// Recorded (record name, IP) pairs from update calls
+    updates: Arc<std::sync::Mutex<Vec<(String, IpAddr)>>>,
     /// Provider name
     pub name: &'static str,
 }
@@ -126,6 +128,7 @@ impl MockDnsProvider {
         Self {
             update_call_count: Arc::new(AtomicUsize::new(0)),
             updated_records: Arc::new(std::sync::Mutex::new(Vec::new())),
+            updates: Arc::new(std::sync::Mutex::new(Vec::new())),
             name,
         }
     }
@@ -140,11 +143,17 @@ impl MockDnsProvider {
         self.updated_records.lock().unwrap().clone()
     }
 
+    /// Get the (record name, IP) pairs that were sent to the provider
+    pub fn updates(&self) -> Vec<(String, IpAddr)> {
+        self.updates.lock().unwrap().clone()
+    }
+
     /// Create a new MockDnsProvider that shares counters with an existing one
     pub fn sharing_counters_with(other: &Self) -> Self {
         Self {
             update_call_count: Arc::clone(&other.update_call_count),
             updated_records: Arc::clone(&other.updated_records),
+            updates: Arc::clone(&other.updates),
             name: other.name,
         }
     }
@@ -158,6 +167,10 @@ impl DnsProvider for MockDnsProvider {
             .lock()
             .unwrap()
             .push(record_name.to_string());
+        self.updates
+            .lock()
+            .unwrap()
+            .push((record_name.to_string(), new_ip));
 
         Ok(UpdateResult::Updated {
             previous_ip: None,
@@ -206,6 +219,20 @@ impl MockStateStore {
         }
     }
 
+    /// Create a store that already holds a published IP for a record
+    ///
+    /// Useful to make the engine's startup reconciliation a no-op, so that
+    /// only the updates caused by emitted events are counted.
+    pub fn with_last_ip(record_name: &str, ip: IpAddr) -> Self {
+        let store = Self::new();
+        store
+            .state
+            .lock()
+            .unwrap()
+            .insert(record_name.to_string(), ip);
+        store
+    }
+
     /// Get the number of times get_last_ip() was called
     pub fn get_call_count(&self) -> usize {
         self.get_call_count.load(Ordering::SeqCst)
@@ -263,7 +290,8 @@ impl StateStore for MockStateStore {
         Ok(())
     }
 
-    async fn delete_record(&self, _record_name: &str) -> Result<()> {
+    async fn delete_record(&self, record_name: &str) -> Result<()> {
+        self.state.lock().unwrap().remove(record_name);
         Ok(())
     }
 
@@ -283,12 +311,21 @@ pub fn minimal_config(record_name: &str) -> ddns_core::config::DdnsConfig {
         ip_source: ddns_core::config::IpSourceConfig::Netlink {
             interface: None,
             version: None,
+            selection: Default::default(),
         },
+        ip_sources: Default::default(),
         provider: ddns_core::config::ProviderConfig::Cloudflare {
             api_token: "test-token".to_string(),
             zone_id: None,
             account_id: None,
+            create_if_missing: false,
+            ttl: None,
+            proxied: false,
+            comment: None,
+            api_base_url: None,
+            zone_overrides: Default::default(),
         },
+        providers: Default::default(),
         state_store: ddns_core::config::StateStoreConfig::Memory,
         records: vec![ddns_core::config::RecordConfig::new(record_name)],
         engine: ddns_core::config::EngineConfig {
@@ -297,6 +334,7 @@ pub fn minimal_config(record_name: &str) -> ddns_core::config::DdnsConfig {
             startup_delay_secs: 0,
             min_update_interval_secs: 0, // Disabled for tests
             event_channel_capacity: 100,
+            reconcile_interval_secs: 0,
             metadata: std::collections::HashMap::new(),
         },
     }
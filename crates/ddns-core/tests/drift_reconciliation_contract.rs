@@ -0,0 +1,248 @@
+//! Architectural Contract Test: Drift Reconciliation
+//!
+//! This test verifies that records changed outside of the engine are
+//! detected and corrected when drift checks are enabled.
+//!
+//! Constraints verified:
+//! - A record edited at the provider is re-published with the current IP
+//! - `EngineEvent::DriftDetected` is emitted for the drifted record
+//! - Records that match the current IP are left alone
+//! - A family the provider cannot read back is skipped, not treated as missing
+//!
+//! If this test fails, the engine trusts its state store over the provider
+//! and never notices records clobbered by hand or by another host.
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::engine::EngineEvent;
+use ddns_core::error::{Error, Result};
+use ddns_core::traits::{DnsProvider, IpChangeEvent, IpVersion, RecordMetadata, UpdateResult};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A provider backed by an in-memory zone that tests can edit behind the engine's back
+#[derive(Clone, Default)]
+struct ZoneProvider {
+    zone: Arc<Mutex<HashMap<String, IpAddr>>>,
+    update_call_count: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl DnsProvider for ZoneProvider {
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        self.update_call_count.fetch_add(1, Ordering::SeqCst);
+        let previous_ip = self
+            .zone
+            .lock()
+            .unwrap()
+            .insert(record_name.to_string(), new_ip);
+
+        Ok(UpdateResult::Updated {
+            previous_ip,
+            new_ip,
+        })
+    }
+
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        let ip = self
+            .zone
+            .lock()
+            .unwrap()
+            .get(record_name)
+            .copied()
+            .ok_or_else(|| Error::not_found(record_name))?;
+
+        Ok(RecordMetadata {
+            id: "test-id".to_string(),
+            name: record_name.to_string(),
+            ip,
+            ttl: Some(300),
+            extra: serde_json::json!({}),
+        })
+    }
+
+    fn supports_record(&self, _record_name: &str) -> bool {
+        true
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "zone"
+    }
+}
+
+#[tokio::test]
+async fn drifted_record_is_corrected() {
+    let current_ip = IpAddr::from([203, 0, 113, 5]);
+    let clobbered_ip = IpAddr::from([198, 51, 100, 9]);
+
+    let provider = ZoneProvider::default();
+
+    let mut config = minimal_config("example.com");
+    config.engine.reconcile_interval_secs = 1;
+
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(current_ip)),
+        Box::new(provider.clone()),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    // Startup reconciliation publishes the current IP
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert_eq!(provider.update_call_count.load(Ordering::SeqCst), 1);
+
+    // Someone edits the record in the provider's dashboard
+    provider
+        .zone
+        .lock()
+        .unwrap()
+        .insert("example.com".to_string(), clobbered_ip);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1200)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider.get_record("example.com").await.unwrap().ip,
+        current_ip,
+        "Drifted record should be re-published"
+    );
+    assert_eq!(provider.update_call_count.load(Ordering::SeqCst), 2);
+
+    let mut drift_events = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        if let EngineEvent::DriftDetected { .. } = event {
+            drift_events.push(event);
+        }
+    }
+    assert_eq!(
+        drift_events,
+        vec![EngineEvent::DriftDetected {
+            record_name: "example.com".to_string(),
+            expected_ip: current_ip,
+            actual_ip: Some(clobbered_ip),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn matching_record_is_left_alone() {
+    let current_ip = IpAddr::from([203, 0, 113, 5]);
+    let provider = ZoneProvider::default();
+
+    let mut config = minimal_config("example.com");
+    config.engine.reconcile_interval_secs = 1;
+
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(current_ip)),
+        Box::new(provider.clone()),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1200)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider.update_call_count.load(Ordering::SeqCst),
+        1,
+        "Only the startup reconciliation should update the record"
+    );
+    while let Ok(event) = event_rx.try_recv() {
+        assert!(
+            !matches!(event, EngineEvent::DriftDetected { .. }),
+            "No drift expected, got {:?}",
+            event
+        );
+    }
+}
+
+#[tokio::test]
+async fn default_provider_lookup_filters_by_family() {
+    let provider = ZoneProvider::default();
+    provider
+        .zone
+        .lock()
+        .unwrap()
+        .insert("example.com".to_string(), IpAddr::from([203, 0, 113, 5]));
+
+    assert!(
+        provider
+            .get_record_for("example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+    assert!(matches!(
+        provider.get_record_for("example.com", IpVersion::V6).await,
+        Err(Error::Unsupported(_))
+    ));
+}
+
+#[tokio::test]
+async fn unreadable_family_is_not_drift() {
+    let v4 = IpAddr::from([203, 0, 113, 5]);
+    let v6: IpAddr = "2001:db8::5".parse().unwrap();
+
+    // The zone keeps one address per name, so get_record only ever returns
+    // the family published last
+    let provider = ZoneProvider::default();
+    let (ip_source, ip_event_tx) = ControlledIpSource::new(v4);
+
+    let mut config = minimal_config("example.com");
+    config.engine.reconcile_interval_secs = 1;
+
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(ip_source),
+        Box::new(provider.clone()),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    ip_event_tx
+        .send(IpChangeEvent::new(v6, None))
+        .expect("send succeeds");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(2200)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider.update_call_count.load(Ordering::SeqCst),
+        2,
+        "Only the startup A and the event's AAAA update should be sent"
+    );
+    while let Ok(event) = event_rx.try_recv() {
+        assert!(
+            !matches!(event, EngineEvent::DriftDetected { .. }),
+            "No drift expected, got {:?}",
+            event
+        );
+    }
+}
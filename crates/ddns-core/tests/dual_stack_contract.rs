@@ -0,0 +1,212 @@
+//! Architectural Contract Test: Dual-Stack Routing
+//!
+//! This test verifies that IPv4 and IPv6 changes are routed to the
+//! matching record types and tracked independently.
+//!
+//! Constraints verified:
+//! - IPv4 events only update A and Auto records
+//! - IPv6 events only update AAAA and Auto records
+//! - Idempotency state is kept per record AND family
+//! - Startup reconciles both families of a dual-stack source
+//!
+//! If this test fails, an IPv6 change may overwrite an A record (or
+//! vice versa), or one family's state may suppress the other's updates.
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::config::{RecordConfig, RecordType};
+use ddns_core::engine::state_key;
+use ddns_core::error::Result;
+use ddns_core::traits::{IpChangeEvent, IpSource, IpVersion, StateStore};
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::Stream;
+
+/// An idle IP source holding one address of each family
+struct DualStackIpSource {
+    v4: IpAddr,
+    v6: IpAddr,
+}
+
+#[async_trait::async_trait]
+impl IpSource for DualStackIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        Ok(self.v4)
+    }
+
+    async fn current_for(&self, version: IpVersion) -> Result<IpAddr> {
+        Ok(match version {
+            IpVersion::V4 => self.v4,
+            IpVersion::V6 => self.v6,
+        })
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        Box::pin(tokio_stream::pending())
+    }
+}
+
+#[tokio::test]
+async fn events_are_routed_by_record_type() {
+    let v4: IpAddr = "203.0.113.5".parse().unwrap();
+    let v6: IpAddr = "2001:db8::5".parse().unwrap();
+
+    // Startup reconciliation publishes v4; the v4 event is then a no-op
+    let (ip_source, ip_event_tx) = ControlledIpSource::new(v4);
+
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("v4.example.com");
+    config.records = vec![
+        RecordConfig::new("v4.example.com").with_record_type(RecordType::A),
+        RecordConfig::new("v6.example.com").with_record_type(RecordType::Aaaa),
+        RecordConfig::new("both.example.com"),
+    ];
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(ip_source),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    ip_event_tx
+        .send(IpChangeEvent::new(v4, None))
+        .expect("send succeeds");
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    ip_event_tx
+        .send(IpChangeEvent::new(v6, None))
+        .expect("send succeeds");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    let updates = provider_arc.updates();
+    assert_eq!(
+        updates,
+        vec![
+            ("v4.example.com".to_string(), v4),
+            ("both.example.com".to_string(), v4),
+            ("v6.example.com".to_string(), v6),
+            ("both.example.com".to_string(), v6),
+        ],
+        "Each family must only reach records of a matching type"
+    );
+}
+
+#[tokio::test]
+async fn state_is_tracked_per_family() {
+    let v4: IpAddr = "203.0.113.5".parse().unwrap();
+    let v6: IpAddr = "2001:db8::5".parse().unwrap();
+
+    let (ip_source, ip_event_tx) = ControlledIpSource::new(v4);
+
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+    let state_store_arc = Arc::new(MockStateStore::new());
+
+    // The A record is already up to date; the AAAA record is not
+    state_store_arc
+        .set_last_ip(&state_key("example.com", IpVersion::V4), v4)
+        .await
+        .unwrap();
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(ip_source),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::sharing_counters_with(&state_store_arc)),
+        minimal_config("example.com"),
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    ip_event_tx
+        .send(IpChangeEvent::new(v4, None))
+        .expect("send succeeds");
+    ip_event_tx
+        .send(IpChangeEvent::new(v6, None))
+        .expect("send succeeds");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.updates(),
+        vec![("example.com".to_string(), v6)],
+        "Only the stale AAAA record should be updated"
+    );
+
+    let v4_state = state_store_arc
+        .get_last_ip(&state_key("example.com", IpVersion::V4))
+        .await
+        .unwrap();
+    let v6_state = state_store_arc
+        .get_last_ip(&state_key("example.com", IpVersion::V6))
+        .await
+        .unwrap();
+
+    assert_eq!(v4_state, Some(v4), "IPv6 update must not touch IPv4 state");
+    assert_eq!(v6_state, Some(v6), "IPv6 state should be persisted");
+}
+
+#[tokio::test]
+async fn both_families_are_reconciled_at_startup() {
+    let v4: IpAddr = "203.0.113.5".parse().unwrap();
+    let v6: IpAddr = "2001:db8::5".parse().unwrap();
+
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("both.example.com");
+    config.records = vec![
+        RecordConfig::new("both.example.com"),
+        RecordConfig::new("v6.example.com").with_record_type(RecordType::Aaaa),
+    ];
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(DualStackIpSource { v4, v6 }),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.updates(),
+        vec![
+            ("both.example.com".to_string(), v4),
+            ("both.example.com".to_string(), v6),
+            ("v6.example.com".to_string(), v6),
+        ],
+        "Startup must reconcile the A and the AAAA side of every record"
+    );
+}
@@ -34,7 +34,8 @@ async fn one_ip_change_triggers_exactly_one_dns_update() {
     let provider_arc = std::sync::Arc::new(provider);
 
     // Create state store
-    let state_store = Box::new(MockStateStore::new());
+    // The initial IP is already published, so startup reconciliation is a no-op
+    let state_store = Box::new(MockStateStore::with_last_ip("example.com", initial_ip));
 
     // Create config
     let config = minimal_config("example.com");
@@ -91,7 +92,8 @@ async fn multiple_ip_changes_trigger_multiple_updates() {
     let provider = Box::new(MockDnsProvider::new("test"));
     let provider_arc = std::sync::Arc::new(provider);
 
-    let state_store = Box::new(MockStateStore::new());
+    // The initial IP is already published, so startup reconciliation is a no-op
+    let state_store = Box::new(MockStateStore::with_last_ip("example.com", initial_ip));
     let config = minimal_config("example.com");
 
     let (engine, _event_rx) = DdnsEngine::new(
@@ -124,11 +124,12 @@ async fn idle_no_background_polling() {
 
     engine_handle.await.unwrap().unwrap();
 
-    // Assert: current() called exactly once (at startup)
+    // Assert: current() called once per family the Auto record follows,
+    // both at startup
     assert_eq!(
         current_calls.load(Ordering::SeqCst),
-        1,
-        "current() should be called exactly once at startup"
+        2,
+        "current() should be called once per address family at startup"
     );
 
     // Assert: watch() called exactly once (at startup)
@@ -0,0 +1,117 @@
+//! Architectural Contract Test: IP Source Health
+//!
+//! This test verifies that the health reported by IP sources reaches the
+//! engine's event channel.
+//!
+//! Constraints verified:
+//! - Every health change of a source is emitted as
+//!   `EngineEvent::IpSourceHealthChanged`
+//! - Sources that do not report their health are supported
+//!
+//! If this test fails, an IP source that keeps failing looks exactly like
+//! one whose address never changes.
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::engine::EngineEvent;
+use ddns_core::error::Result;
+use ddns_core::traits::{IpChangeEvent, IpSource, IpSourceHealth};
+use std::net::IpAddr;
+use std::pin::Pin;
+use tokio::sync::watch;
+use tokio_stream::Stream;
+
+/// An idle IP source whose health is controlled by the test
+struct ReportingIpSource {
+    inner: IdleIpSource,
+    health: watch::Sender<IpSourceHealth>,
+}
+
+#[async_trait::async_trait]
+impl IpSource for ReportingIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        self.inner.current().await
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        self.inner.watch()
+    }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.health.subscribe())
+    }
+}
+
+#[tokio::test]
+async fn health_changes_are_emitted() {
+    let health = watch::Sender::new(IpSourceHealth::Unknown);
+    let source = ReportingIpSource {
+        inner: IdleIpSource::new(IpAddr::from([203, 0, 113, 5])),
+        health: health.clone(),
+    };
+
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(source),
+        Box::new(MockDnsProvider::new("mock")),
+        Box::new(MockStateStore::new()),
+        minimal_config("example.com"),
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let failing = IpSourceHealth::Failing {
+        consecutive_failures: 1,
+        last_error: "service unreachable".to_string(),
+    };
+    health.send_replace(failing.clone());
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    health.send_replace(IpSourceHealth::Healthy);
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    let mut health_events = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        if let EngineEvent::IpSourceHealthChanged { source, health } = event {
+            health_events.push((source, health));
+        }
+    }
+    assert_eq!(
+        health_events,
+        vec![(None, failing), (None, IpSourceHealth::Healthy)]
+    );
+}
+
+#[tokio::test]
+async fn sources_without_health_are_supported() {
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(IpAddr::from([203, 0, 113, 5]))),
+        Box::new(MockDnsProvider::new("mock")),
+        Box::new(MockStateStore::new()),
+        minimal_config("example.com"),
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    while let Ok(event) = event_rx.try_recv() {
+        assert!(
+            !matches!(event, EngineEvent::IpSourceHealthChanged { .. }),
+            "{:?}",
+            event
+        );
+    }
+}
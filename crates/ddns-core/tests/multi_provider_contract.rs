@@ -0,0 +1,135 @@
+//! Architectural Contract Test: Multiple Providers
+//!
+//! This test verifies that records are dispatched to the provider they are
+//! bound to, sharing a single IP source.
+//!
+//! Constraints verified:
+//! - Unbound records go to the default provider
+//! - Bound records go to their named provider, and only to it
+//! - Bindings to unknown providers are rejected at construction
+//!
+//! If this test fails, a record may be written through the wrong account
+//! (or the wrong DNS service entirely).
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::config::{ProviderConfig, RecordConfig};
+use ddns_core::traits::{DnsProvider, IpChangeEvent};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// A minimal named provider configuration
+fn named_provider() -> ProviderConfig {
+    ProviderConfig::Custom {
+        factory: "mock".to_string(),
+        config: serde_json::json!({}),
+    }
+}
+
+#[tokio::test]
+async fn records_are_dispatched_to_their_bound_provider() {
+    let initial_ip = IpAddr::from([203, 0, 113, 5]);
+    let new_ip = IpAddr::from([203, 0, 113, 6]);
+
+    let (ip_source, ip_event_tx) = ControlledIpSource::new(initial_ip);
+
+    let default_arc = Arc::new(MockDnsProvider::new("default"));
+    let work_arc = Arc::new(MockDnsProvider::new("work"));
+
+    let mut config = minimal_config("home.example.com");
+    config
+        .providers
+        .insert("work".to_string(), named_provider());
+    config.records = vec![
+        RecordConfig::new("home.example.com"),
+        RecordConfig::new("vpn.example.net").with_provider("work"),
+    ];
+
+    let mut providers: HashMap<String, Box<dyn DnsProvider>> = HashMap::new();
+    providers.insert(
+        "work".to_string(),
+        Box::new(MockDnsProvider::sharing_counters_with(&work_arc)),
+    );
+
+    let (engine, _event_rx) = DdnsEngine::with_providers(
+        Box::new(ip_source),
+        Box::new(MockDnsProvider::sharing_counters_with(&default_arc)),
+        providers,
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    ip_event_tx
+        .send(IpChangeEvent::new(new_ip, Some(initial_ip)))
+        .expect("send succeeds");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        default_arc.updates(),
+        vec![
+            ("home.example.com".to_string(), initial_ip),
+            ("home.example.com".to_string(), new_ip),
+        ],
+        "The default provider must only see unbound records"
+    );
+    assert_eq!(
+        work_arc.updates(),
+        vec![
+            ("vpn.example.net".to_string(), initial_ip),
+            ("vpn.example.net".to_string(), new_ip),
+        ],
+        "The named provider must only see its own records"
+    );
+}
+
+#[test]
+fn binding_to_unknown_provider_is_rejected() {
+    let mut config = minimal_config("home.example.com");
+    config.records = vec![RecordConfig::new("home.example.com").with_provider("missing")];
+
+    let result = DdnsEngine::new(
+        Box::new(IdleIpSource::new(IpAddr::from([203, 0, 113, 5]))),
+        Box::new(MockDnsProvider::new("default")),
+        Box::new(MockStateStore::new()),
+        config,
+    );
+
+    assert!(result.is_err(), "Unknown provider names must be rejected");
+}
+
+#[test]
+fn binding_without_provider_instance_is_rejected() {
+    // Configured, but no instance handed to the engine
+    let mut config = minimal_config("home.example.com");
+    config
+        .providers
+        .insert("work".to_string(), named_provider());
+    config.records = vec![RecordConfig::new("home.example.com").with_provider("work")];
+
+    let result = DdnsEngine::new(
+        Box::new(IdleIpSource::new(IpAddr::from([203, 0, 113, 5]))),
+        Box::new(MockDnsProvider::new("default")),
+        Box::new(MockStateStore::new()),
+        config,
+    );
+
+    assert!(
+        result.is_err(),
+        "Records must not silently fall back to the default provider"
+    );
+}
@@ -0,0 +1,233 @@
+//! Architectural Contract Test: Multiple IP Sources
+//!
+//! This test verifies that records follow the IP source they are bound to,
+//! and that the sources are tracked independently.
+//!
+//! Constraints verified:
+//! - Unbound records follow the default IP source
+//! - Bound records follow their named IP source, and only it
+//! - Named IP sources no record uses are never queried or watched
+//! - A source that fails its initial query is still watched
+//! - Bindings to unknown IP sources are rejected at construction
+//!
+//! If this test fails, a record may publish the address of the wrong
+//! interface (e.g. a LAN address in a public zone).
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::config::{IpSourceConfig, RecordConfig};
+use ddns_core::error::{Error, Result};
+use ddns_core::traits::{IpChangeEvent, IpSource, IpVersion};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::Stream;
+
+/// A minimal named IP source configuration
+fn named_source() -> IpSourceConfig {
+    IpSourceConfig::Custom {
+        factory: "mock".to_string(),
+        config: serde_json::json!({}),
+    }
+}
+
+/// An IP source that fails the test if the engine touches it
+struct UnusedIpSource;
+
+#[async_trait::async_trait]
+impl IpSource for UnusedIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        panic!("current() called on an unused IP source");
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        panic!("watch() called on an unused IP source");
+    }
+}
+
+/// An IP source that cannot answer queries but still reports changes
+struct UnreachableIpSource {
+    rx: std::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<IpChangeEvent>>>,
+}
+
+#[async_trait::async_trait]
+impl IpSource for UnreachableIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        Err(Error::ip_source("unreachable"))
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let rx = self.rx.lock().unwrap().take().expect("watched once");
+        Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx))
+    }
+}
+
+#[tokio::test]
+async fn records_follow_their_bound_ip_source() {
+    let wan_ip = IpAddr::from([203, 0, 113, 5]);
+    let lan_ip = IpAddr::from([192, 168, 1, 5]);
+    let new_lan_ip = IpAddr::from([192, 168, 1, 6]);
+
+    let (lan_source, lan_event_tx) = ControlledIpSource::new(lan_ip);
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("example.com");
+    config.ip_sources.insert("lan".to_string(), named_source());
+    config
+        .ip_sources
+        .insert("unused".to_string(), named_source());
+    config.records = vec![
+        RecordConfig::new("example.com"),
+        RecordConfig::new("nas.home.example.com").with_ip_source("lan"),
+    ];
+
+    let mut ip_sources: HashMap<String, Box<dyn IpSource>> = HashMap::new();
+    ip_sources.insert("lan".to_string(), Box::new(lan_source));
+    ip_sources.insert("unused".to_string(), Box::new(UnusedIpSource));
+
+    let (engine, _event_rx) = DdnsEngine::with_components(
+        Box::new(IdleIpSource::new(wan_ip)),
+        ip_sources,
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        HashMap::new(),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+    let engine = Arc::new(engine);
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.run_with_shutdown(Some(shutdown_rx)).await }
+    });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    lan_event_tx
+        .send(IpChangeEvent::new(new_lan_ip, Some(lan_ip)))
+        .expect("send succeeds");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert_eq!(engine.current_ip(IpVersion::V4), Some(wan_ip));
+    assert_eq!(
+        engine.source_ip(Some("lan"), IpVersion::V4),
+        Some(new_lan_ip)
+    );
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.updates(),
+        vec![
+            ("example.com".to_string(), wan_ip),
+            ("nas.home.example.com".to_string(), lan_ip),
+            ("nas.home.example.com".to_string(), new_lan_ip),
+        ],
+        "Each source's addresses must only reach the records bound to it"
+    );
+}
+
+#[tokio::test]
+async fn unknown_ip_source_binding_is_rejected() {
+    let mut config = minimal_config("example.com");
+    config.records = vec![RecordConfig::new("example.com").with_ip_source("lan")];
+
+    let result = DdnsEngine::new(
+        Box::new(IdleIpSource::new(IpAddr::from([203, 0, 113, 5]))),
+        Box::new(MockDnsProvider::new("test")),
+        Box::new(MockStateStore::new()),
+        config,
+    );
+
+    assert!(
+        result.is_err(),
+        "A record bound to an unknown IP source must be rejected"
+    );
+}
+
+#[tokio::test]
+async fn missing_ip_source_instance_is_rejected() {
+    let mut config = minimal_config("example.com");
+    config.ip_sources.insert("lan".to_string(), named_source());
+    config.records = vec![RecordConfig::new("example.com").with_ip_source("lan")];
+
+    let result = DdnsEngine::with_components(
+        Box::new(IdleIpSource::new(IpAddr::from([203, 0, 113, 5]))),
+        HashMap::new(),
+        Box::new(MockDnsProvider::new("test")),
+        HashMap::new(),
+        Box::new(MockStateStore::new()),
+        config,
+    );
+
+    assert!(
+        result.is_err(),
+        "A configured IP source without an instance must be rejected"
+    );
+}
+
+#[tokio::test]
+async fn unreachable_source_at_startup_is_still_watched() {
+    let wan_ip = IpAddr::from([203, 0, 113, 5]);
+    let lan_ip = IpAddr::from([192, 168, 1, 5]);
+
+    let (lan_event_tx, lan_event_rx) = tokio::sync::mpsc::unbounded_channel();
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("example.com");
+    config.ip_sources.insert("lan".to_string(), named_source());
+    config.records = vec![
+        RecordConfig::new("example.com"),
+        RecordConfig::new("nas.home.example.com").with_ip_source("lan"),
+    ];
+
+    let mut ip_sources: HashMap<String, Box<dyn IpSource>> = HashMap::new();
+    ip_sources.insert(
+        "lan".to_string(),
+        Box::new(UnreachableIpSource {
+            rx: std::sync::Mutex::new(Some(lan_event_rx)),
+        }),
+    );
+
+    let (engine, _event_rx) = DdnsEngine::with_components(
+        Box::new(IdleIpSource::new(wan_ip)),
+        ip_sources,
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        HashMap::new(),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    lan_event_tx
+        .send(IpChangeEvent::new(lan_ip, None))
+        .expect("the engine still watches the source");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.updates(),
+        vec![
+            ("example.com".to_string(), wan_ip),
+            ("nas.home.example.com".to_string(), lan_ip),
+        ],
+        "The failing source must not block the others, and its first change must be published"
+    );
+}
@@ -77,7 +77,8 @@ async fn retries_can_be_disabled_via_config() {
     });
     let provider_arc = std::sync::Arc::new(provider);
 
-    let state_store = Box::new(MockStateStore::new());
+    // The initial IP is already published, so startup reconciliation is a no-op
+    let state_store = Box::new(MockStateStore::with_last_ip("example.com", initial_ip));
     let config = minimal_config("example.com");
 
     // DISABLE retries completely
@@ -177,7 +178,8 @@ async fn retries_honor_explicit_configuration() {
     });
     let provider_arc = std::sync::Arc::new(provider);
 
-    let state_store = Box::new(MockStateStore::new());
+    // The initial IP is already published, so startup reconciliation is a no-op
+    let state_store = Box::new(MockStateStore::with_last_ip("example.com", initial_ip));
     let config = minimal_config("example.com");
 
     // Explicitly configure retries
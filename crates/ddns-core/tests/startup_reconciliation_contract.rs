@@ -0,0 +1,174 @@
+//! Architectural Contract Test: Startup Reconciliation
+//!
+//! This test verifies that the engine publishes the current IP at startup,
+//! without waiting for the IP source to report a change.
+//!
+//! Constraints verified:
+//! - Every enabled record is reconciled with the initial IP
+//! - Reconciliation goes through the idempotent update path
+//! - `startup_delay_secs` is honored and does not block shutdown
+//!
+//! If this test fails, records may stay stale until the first IP change,
+//! which for some IP sources never happens.
+
+mod common;
+
+use common::*;
+use ddns_core::DdnsEngine;
+use ddns_core::config::RecordConfig;
+use ddns_core::engine::EngineEvent;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn initial_ip_is_applied_to_all_enabled_records() {
+    let initial_ip = IpAddr::from([203, 0, 113, 5]);
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("example.com");
+    config.records = vec![
+        RecordConfig::new("example.com"),
+        RecordConfig::new("www.example.com"),
+        RecordConfig::new("disabled.example.com").with_enabled(false),
+    ];
+
+    let (engine, mut event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(initial_ip)),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.updates(),
+        vec![
+            ("example.com".to_string(), initial_ip),
+            ("www.example.com".to_string(), initial_ip),
+        ],
+        "Startup should reconcile every enabled record with the initial IP"
+    );
+
+    let mut succeeded = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        if let EngineEvent::UpdateSucceeded { record_name, .. } = event {
+            succeeded.push(record_name);
+        }
+    }
+    assert_eq!(succeeded, vec!["example.com", "www.example.com"]);
+}
+
+#[tokio::test]
+async fn startup_reconciliation_is_idempotent() {
+    let initial_ip = IpAddr::from([203, 0, 113, 5]);
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(initial_ip)),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::with_last_ip("example.com", initial_ip)),
+        minimal_config("example.com"),
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        provider_arc.update_call_count(),
+        0,
+        "An already published IP must not be pushed again at startup"
+    );
+}
+
+#[tokio::test]
+async fn startup_delay_is_honored() {
+    let initial_ip = IpAddr::from([203, 0, 113, 5]);
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+
+    let mut config = minimal_config("example.com");
+    config.engine.startup_delay_secs = 1;
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(initial_ip)),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::new()),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+    assert_eq!(
+        provider_arc.update_call_count(),
+        0,
+        "No update should happen during the startup delay"
+    );
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    assert_eq!(
+        provider_arc.update_call_count(),
+        1,
+        "Reconciliation should run once the startup delay has elapsed"
+    );
+
+    shutdown_tx.send(()).unwrap();
+    engine_handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn shutdown_during_startup_delay_is_prompt() {
+    let initial_ip = IpAddr::from([203, 0, 113, 5]);
+    let provider_arc = Arc::new(MockDnsProvider::new("test"));
+    let state_store_arc = Arc::new(MockStateStore::new());
+
+    let mut config = minimal_config("example.com");
+    config.engine.startup_delay_secs = 60;
+
+    let (engine, _event_rx) = DdnsEngine::new(
+        Box::new(IdleIpSource::new(initial_ip)),
+        Box::new(MockDnsProvider::sharing_counters_with(&provider_arc)),
+        Box::new(MockStateStore::sharing_counters_with(&state_store_arc)),
+        config,
+    )
+    .expect("engine construction succeeds");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+
+    let engine_handle =
+        tokio::spawn(async move { engine.run_with_shutdown(Some(shutdown_rx)).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    shutdown_tx.send(()).unwrap();
+
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(1), engine_handle).await;
+    assert!(
+        result.is_ok(),
+        "Engine should stop during the startup delay"
+    );
+    result.unwrap().unwrap().unwrap();
+
+    assert_eq!(provider_arc.update_call_count(), 0);
+    assert_eq!(state_store_arc.flush_call_count(), 1, "State is flushed");
+}
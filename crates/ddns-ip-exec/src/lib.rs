@@ -0,0 +1,430 @@
+// # Exec IP Source
+//
+// This crate provides an IP source that runs an external program and
+// reads the address from its output.
+//
+// ## Purpose
+//
+// Some setups only expose the WAN address in odd places: a modem's CLI,
+// `ip -j addr` on another namespace, a VPN client's status file. Rather
+// than growing a dedicated source for each, this source runs a program
+// that prints the address, either plain or as JSON (see `output`).
+//
+// ## Architecture
+//
+// The program is run directly (no shell) with the configured arguments,
+// an empty standard input and a timeout. It is killed if it runs longer.
+// A non-zero exit status is a failure; the first line of its standard
+// error is included in the error.
+//
+// The program runs once per interval. Optionally, a signal (SIGHUP,
+// SIGUSR1 or SIGUSR2) runs it immediately, so that e.g. a VPN client hook
+// can announce a change with `pkill -USR1 ddnsd`. Like the other polling
+// sources, this source reports its health (`IpSource::health`) and stops
+// as soon as the stream returned by `watch()` is dropped.
+//
+// ## Trust
+//
+// The program runs with the daemon's privileges and environment. Only
+// configure programs that are as trusted as the daemon itself.
+
+mod output;
+mod trigger;
+
+use ddns_core::ProviderRegistry;
+use ddns_core::config::{ExecSignal, IpSourceConfig};
+use ddns_core::traits::{HealthReporter, IpChangeEvent, IpSource, IpSourceFactory, IpSourceHealth};
+use ddns_core::{Error, Result};
+
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::process::Command;
+use tokio::sync::watch;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use trigger::Trigger;
+
+/// Default run interval
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default time the program may run
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// IP source running an external program
+pub struct ExecIpSource {
+    /// State shared with the watch loop
+    shared: Arc<Shared>,
+
+    /// Run interval
+    poll_interval: Duration,
+
+    /// Signal that runs the program immediately
+    signal: Option<ExecSignal>,
+}
+
+/// Command, cache and health of an [`ExecIpSource`]
+struct Shared {
+    /// Program and arguments
+    command: Vec<String>,
+
+    /// JSON pointer to the address, if the output is JSON
+    json_pointer: Option<String>,
+
+    /// Time the program may run
+    timeout: Duration,
+
+    /// Current IP address (cached)
+    current_ip: Mutex<Option<IpAddr>>,
+
+    /// Health signal
+    health: watch::Sender<IpSourceHealth>,
+}
+
+impl Shared {
+    /// Run the program and update the cache
+    ///
+    /// This is the only place the program is run.
+    async fn fetch(&self) -> Result<IpAddr> {
+        let result = self.run().await;
+
+        let mut current_ip = self.current_ip.lock().unwrap_or_else(|e| e.into_inner());
+        // On failure, the address can no longer be confirmed
+        *current_ip = result.as_ref().ok().copied();
+        result
+    }
+
+    /// Run the program and parse its output
+    async fn run(&self) -> Result<IpAddr> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| Error::config("Exec IP source command cannot be empty"))?;
+
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::ip_source(format!("Failed to run '{}': {}", program, e)))?;
+
+        // Dropping the child on timeout kills it
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                Error::ip_source(format!(
+                    "'{}' did not finish within {:?}",
+                    program, self.timeout
+                ))
+            })?
+            .map_err(|e| Error::ip_source(format!("Failed to run '{}': {}", program, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().map(str::trim).find(|line| !line.is_empty());
+            return Err(Error::ip_source(match reason {
+                Some(reason) => format!(
+                    "'{}' failed ({}): {}",
+                    program,
+                    output.status,
+                    output::excerpt(reason)
+                ),
+                None => format!("'{}' failed ({})", program, output.status),
+            }));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        output::parse(&stdout, self.json_pointer.as_deref())
+    }
+
+    /// Record the outcome of a run
+    fn report(&self, error: Option<&Error>) {
+        match error {
+            None => self.health.record_success(),
+            Some(error) => {
+                self.health.record_failure(error);
+            }
+        }
+    }
+
+    fn cached_ip(&self) -> Option<IpAddr> {
+        *self.current_ip.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ExecIpSource {
+    /// Create an exec IP source running `command` (program and arguments)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `command` is empty.
+    pub fn new<I, S>(command: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::builder().command(command).build()
+    }
+
+    /// Create a builder for an exec IP source
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ddns_core::config::ExecSignal;
+    /// use ddns_ip_exec::ExecIpSource;
+    ///
+    /// let source = ExecIpSource::builder()
+    ///     .command(["ip", "-j", "-4", "addr", "show", "dev", "ppp0"])
+    ///     .json_pointer("/0/addr_info/0/local")
+    ///     .signal(ExecSignal::Usr1)
+    ///     .build()?;
+    /// # Ok::<(), ddns_core::Error>(())
+    /// ```
+    pub fn builder() -> ExecIpSourceBuilder {
+        ExecIpSourceBuilder::new()
+    }
+}
+
+/// Builder for [`ExecIpSource`]
+#[derive(Debug, Clone)]
+pub struct ExecIpSourceBuilder {
+    command: Vec<String>,
+    json_pointer: Option<String>,
+    timeout: Duration,
+    signal: Option<ExecSignal>,
+    poll_interval: Duration,
+}
+
+impl ExecIpSourceBuilder {
+    fn new() -> Self {
+        Self {
+            command: Vec::new(),
+            json_pointer: None,
+            timeout: DEFAULT_TIMEOUT,
+            signal: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Program and arguments
+    ///
+    /// The program is looked up in `PATH` unless it is a path.
+    pub fn command<I, S>(mut self, command: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.command = command.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Read the address from JSON output at this RFC 6901 JSON pointer
+    /// (default: the output is the address)
+    pub fn json_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.json_pointer = Some(pointer.into());
+        self
+    }
+
+    /// Time the program may run before it is killed (default: 10s)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run the program immediately when this signal is received
+    pub fn signal(mut self, signal: ExecSignal) -> Self {
+        self.signal = Some(signal);
+        self
+    }
+
+    /// Run interval (default: 60s)
+    pub fn interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Build the IP source
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if no program is configured or the JSON
+    /// pointer is invalid.
+    pub fn build(self) -> Result<ExecIpSource> {
+        if self
+            .command
+            .first()
+            .is_none_or(|program| program.trim().is_empty())
+        {
+            return Err(Error::config("Exec IP source command cannot be empty"));
+        }
+        if let Some(pointer) = &self.json_pointer
+            && !pointer.is_empty()
+            && !pointer.starts_with('/')
+        {
+            return Err(Error::config(format!(
+                "JSON pointer must be empty or start with '/', got '{}'",
+                pointer
+            )));
+        }
+
+        Ok(ExecIpSource {
+            shared: Arc::new(Shared {
+                command: self.command,
+                json_pointer: self.json_pointer,
+                timeout: self.timeout,
+                current_ip: Mutex::new(None),
+                health: watch::Sender::new(IpSourceHealth::Unknown),
+            }),
+            poll_interval: self.poll_interval,
+            signal: self.signal,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl IpSource for ExecIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        if let Some(ip) = self.shared.cached_ip() {
+            return Ok(ip);
+        }
+
+        let result = self.shared.fetch().await;
+        self.shared.report(result.as_ref().err());
+        result
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let shared = self.shared.clone();
+        let poll_interval = self.poll_interval;
+
+        // Listen right away, so that a signal sent after watch() returns is
+        // not lost (or, worse, terminates the process)
+        let mut trigger = self.signal.and_then(Trigger::listen);
+
+        tokio::spawn(async move {
+            tracing::info!(
+                "Starting exec IP monitoring (command={:?}, interval={:?})",
+                shared.command,
+                poll_interval
+            );
+
+            let mut last_known_ip = None;
+
+            loop {
+                let result = tokio::select! {
+                    result = shared.fetch() => result,
+                    _ = tx.closed() => break,
+                };
+                shared.report(result.as_ref().err());
+
+                match result {
+                    Ok(ip) if last_known_ip != Some(ip) => {
+                        tracing::info!("IP changed: {:?} -> {:?}", last_known_ip, ip);
+                        if tx.send(IpChangeEvent::new(ip, last_known_ip)).is_err() {
+                            break;
+                        }
+                        last_known_ip = Some(ip);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to fetch IP: {}", e),
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = trigger::triggered(&mut trigger) => {
+                        tracing::debug!("Signal received, running command");
+                    }
+                    _ = tx.closed() => break,
+                }
+            }
+
+            tracing::debug!("Receiver dropped, stopping monitor");
+        });
+
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.shared.health.subscribe())
+    }
+}
+
+/// Factory for creating exec IP sources
+pub struct ExecFactory;
+
+impl IpSourceFactory for ExecFactory {
+    fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        match config {
+            IpSourceConfig::Exec {
+                command,
+                json_pointer,
+                timeout_secs,
+                signal,
+                interval_secs,
+            } => {
+                let mut builder = ExecIpSource::builder()
+                    .command(command.iter().cloned())
+                    .timeout(Duration::from_secs(*timeout_secs))
+                    .interval(Duration::from_secs(*interval_secs));
+                if let Some(pointer) = json_pointer {
+                    builder = builder.json_pointer(pointer.clone());
+                }
+                if let Some(signal) = signal {
+                    builder = builder.signal(*signal);
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for exec IP source")),
+        }
+    }
+}
+
+/// Register the exec IP source with a registry
+pub fn register(registry: &ProviderRegistry) {
+    registry.register_ip_source("exec", Box::new(ExecFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_creation() {
+        let config = IpSourceConfig::Exec {
+            command: vec!["modem-wan-ip".to_string()],
+            json_pointer: None,
+            timeout_secs: 10,
+            signal: Some(ExecSignal::Usr1),
+            interval_secs: 60,
+        };
+        assert!(ExecFactory.create(&config).is_ok());
+
+        let config = IpSourceConfig::Exec {
+            command: Vec::new(),
+            json_pointer: None,
+            timeout_secs: 10,
+            signal: None,
+            interval_secs: 60,
+        };
+        assert!(ExecFactory.create(&config).is_err());
+    }
+
+    #[test]
+    fn test_invalid_json_pointer() {
+        let result = ExecIpSource::builder()
+            .command(["modem-wan-ip"])
+            .json_pointer("ip")
+            .build();
+        assert!(result.is_err());
+    }
+}
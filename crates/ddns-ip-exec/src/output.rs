@@ -0,0 +1,109 @@
+// # Command Output
+//
+// Extracts the address from a program's standard output. The output is
+// either the address itself (surrounding whitespace is ignored) or JSON
+// holding the address as a string, found by an RFC 6901 JSON pointer.
+//
+// Tools often print addresses in CIDR notation (`ip addr`), so a trailing
+// prefix length is dropped.
+
+use ddns_core::{Error, Result};
+
+use std::net::IpAddr;
+
+/// Longest output excerpt quoted in errors
+const EXCERPT_LEN: usize = 64;
+
+/// Extract the address from `stdout`
+///
+/// # Errors
+///
+/// Returns `Error::IpSource` if the output is not valid JSON (with a
+/// pointer), the pointer does not address a string, or the value is not an
+/// IP address.
+pub(crate) fn parse(stdout: &str, json_pointer: Option<&str>) -> Result<IpAddr> {
+    let value = match json_pointer {
+        None => stdout.trim(),
+        Some(pointer) => {
+            let json: serde_json::Value = serde_json::from_str(stdout).map_err(|e| {
+                Error::ip_source(format!("Command output is not valid JSON: {}", e))
+            })?;
+            return json
+                .pointer(pointer)
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| {
+                    Error::ip_source(format!(
+                        "Command output has no string at JSON pointer '{}'",
+                        pointer
+                    ))
+                })
+                .and_then(|value| address(value.trim()));
+        }
+    };
+    address(value)
+}
+
+/// Parse an address, dropping a trailing prefix length
+fn address(value: &str) -> Result<IpAddr> {
+    ddns_core::net::parse_address(value).ok_or_else(|| {
+        Error::ip_source(format!(
+            "Command output is not an IP address: '{}'",
+            excerpt(value)
+        ))
+    })
+}
+
+/// Shorten `text` for error messages
+pub(crate) fn excerpt(text: &str) -> &str {
+    match text.char_indices().nth(EXCERPT_LEN) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_output() {
+        assert_eq!(
+            parse("203.0.113.5\n", None).unwrap(),
+            IpAddr::from([203, 0, 113, 5])
+        );
+        assert_eq!(
+            parse("  2001:db8::5/64 ", None).unwrap(),
+            "2001:db8::5".parse::<IpAddr>().unwrap()
+        );
+        assert!(parse("", None).is_err());
+        assert!(parse("203.0.113.5\n203.0.113.6\n", None).is_err());
+        assert!(parse("203.0.113.5/", None).is_err());
+    }
+
+    #[test]
+    fn test_json_output() {
+        // Shape of `ip -j addr show dev eth0`
+        let output = r#"[{"ifname":"eth0","addr_info":[{"family":"inet","local":"203.0.113.5","prefixlen":24}]}]"#;
+        assert_eq!(
+            parse(output, Some("/0/addr_info/0/local")).unwrap(),
+            IpAddr::from([203, 0, 113, 5])
+        );
+
+        let message = parse(output, Some("/0/addr_info/0/prefixlen"))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("no string"), "{}", message);
+        assert!(parse("203.0.113.5", Some("")).is_err());
+        assert_eq!(
+            parse(r#""203.0.113.5""#, Some("")).unwrap(),
+            IpAddr::from([203, 0, 113, 5])
+        );
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let long = "x".repeat(100);
+        assert_eq!(excerpt(&long).len(), EXCERPT_LEN);
+        assert_eq!(excerpt("short"), "short");
+    }
+}
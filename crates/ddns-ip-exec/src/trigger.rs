@@ -0,0 +1,62 @@
+// # Signal Triggers
+//
+// Listens for the signal that makes an exec IP source run its program
+// ahead of the interval. Signals only exist on Unix; elsewhere the
+// setting is ignored with a warning.
+
+use ddns_core::config::ExecSignal;
+
+/// Listener for one signal
+pub(crate) struct Trigger {
+    #[cfg(unix)]
+    signal: tokio::signal::unix::Signal,
+}
+
+impl Trigger {
+    /// Start listening for `signal`
+    ///
+    /// Must be called from within a Tokio runtime. From then on the signal
+    /// no longer terminates the process.
+    #[cfg(unix)]
+    pub(crate) fn listen(signal: ExecSignal) -> Option<Self> {
+        use tokio::signal::unix::SignalKind;
+
+        let kind = match signal {
+            ExecSignal::Hup => SignalKind::hangup(),
+            ExecSignal::Usr1 => SignalKind::user_defined1(),
+            ExecSignal::Usr2 => SignalKind::user_defined2(),
+        };
+        match tokio::signal::unix::signal(kind) {
+            Ok(signal) => Some(Self { signal }),
+            Err(e) => {
+                tracing::warn!("Failed to listen for {:?}: {}", signal, e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn listen(signal: ExecSignal) -> Option<Self> {
+        tracing::warn!("Signal {:?} is not supported on this platform", signal);
+        None
+    }
+
+    /// Wait for the next signal
+    #[cfg(unix)]
+    async fn recv(&mut self) {
+        self.signal.recv().await;
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) {
+        std::future::pending().await
+    }
+}
+
+/// Wait for the next signal, or forever without a trigger
+pub(crate) async fn triggered(trigger: &mut Option<Trigger>) {
+    match trigger {
+        Some(trigger) => trigger.recv().await,
+        None => std::future::pending().await,
+    }
+}
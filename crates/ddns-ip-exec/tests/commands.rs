@@ -0,0 +1,137 @@
+//! Exec IP source running real commands
+//!
+//! Commands are small `sh` scripts; addresses that change are read from a
+//! temporary file.
+//!
+//! If this test fails, the address printed by a configured program is not
+//! picked up, a hanging or failing program is not reported, or a change
+//! announced by signal waits for the next interval.
+
+use ddns_core::config::ExecSignal;
+use ddns_core::traits::{IpSource, IpSourceHealth};
+use ddns_ip_exec::ExecIpSource;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio_stream::StreamExt;
+
+fn sh(script: &str) -> [String; 3] {
+    ["sh".to_string(), "-c".to_string(), script.to_string()]
+}
+
+/// A command printing the contents of `path`
+fn cat(path: &Path) -> [String; 2] {
+    ["cat".to_string(), path.display().to_string()]
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn plain_output_is_parsed() {
+    let source = ExecIpSource::new(sh("echo 203.0.113.5")).unwrap();
+    assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+}
+
+#[tokio::test]
+async fn json_output_is_parsed() {
+    let source = ExecIpSource::builder()
+        .command(sh(
+            r#"echo '[{"ifname":"ppp0","addr_info":[{"local":"2001:db8::5","prefixlen":64}]}]'"#,
+        ))
+        .json_pointer("/0/addr_info/0/local")
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip("2001:db8::5"));
+}
+
+#[tokio::test]
+async fn failures_are_reported() {
+    let source = ExecIpSource::new(sh("echo 'modem: not connected' >&2; exit 3")).unwrap();
+    let health = source.health().unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("modem: not connected"), "{}", message);
+    assert!(matches!(
+        *health.borrow(),
+        IpSourceHealth::Failing {
+            consecutive_failures: 1,
+            ..
+        }
+    ));
+
+    let source = ExecIpSource::new(["/nonexistent/modem-wan-ip"]).unwrap();
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("Failed to run"), "{}", message);
+}
+
+#[tokio::test]
+async fn hanging_command_is_killed() {
+    let source = ExecIpSource::builder()
+        .command(["sleep", "30"])
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("did not finish"), "{}", message);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn changes_are_emitted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = ExecIpSource::builder()
+        .command(cat(&path))
+        .interval(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+    let first = events.next().await.unwrap();
+    assert_eq!((first.new_ip, first.previous_ip), (ip("203.0.113.5"), None));
+
+    std::fs::write(&path, "203.0.113.6\n").unwrap();
+    let second = events.next().await.unwrap();
+    assert_eq!(
+        (second.new_ip, second.previous_ip),
+        (ip("203.0.113.6"), Some(ip("203.0.113.5")))
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn signal_runs_command_immediately() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = ExecIpSource::builder()
+        .command(cat(&path))
+        .interval(Duration::from_secs(3600))
+        .signal(ExecSignal::Usr2)
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+    assert_eq!(events.next().await.unwrap().new_ip, ip("203.0.113.5"));
+
+    std::fs::write(&path, "203.0.113.6\n").unwrap();
+    let status = std::process::Command::new("kill")
+        .args(["-USR2", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("signal should run the command before the interval")
+        .unwrap();
+    assert_eq!(event.new_ip, ip("203.0.113.6"));
+}
@@ -0,0 +1,104 @@
+// # File IP Source
+//
+// This crate provides an IP source that reads the address from a file and
+// watches it with inotify.
+//
+// ## Purpose
+//
+// Some hosts learn their public address from a DHCP hook or a PPP `ip-up`
+// script. Having the script write the address to a file is the simplest
+// way to hand it over, and with inotify the daemon reacts as soon as the
+// file changes, without polling.
+//
+// ## Implementation
+//
+// The file holds a single address; surrounding whitespace and a trailing
+// prefix length (`203.0.113.5/32`) are ignored.
+//
+// The parent directory is watched rather than the file itself, so that
+// files replaced atomically (written elsewhere, then renamed over the old
+// one) and files created after startup are picked up. Events for other
+// files in the directory are ignored. Changes are debounced: the file is
+// only read once no further event arrived for the debounce window, so that
+// a partially written file is not read.
+//
+// A missing or unreadable file is reported through `IpSource::health`; the
+// last address stays published until the file holds a new one.
+//
+// ## Platform Support
+//
+// inotify is Linux-specific. On other platforms, the factory returns an
+// error.
+
+#[cfg(target_os = "linux")]
+mod source;
+
+#[cfg(target_os = "linux")]
+pub use source::FileIpSource;
+
+use ddns_core::ProviderRegistry;
+use ddns_core::config::IpSourceConfig;
+use ddns_core::traits::{IpSource, IpSourceFactory};
+use ddns_core::{Error, Result};
+
+/// Default debounce window
+#[cfg(target_os = "linux")]
+const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// Factory for creating file IP sources
+pub struct FileFactory;
+
+#[cfg(target_os = "linux")]
+impl IpSourceFactory for FileFactory {
+    fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        match config {
+            IpSourceConfig::File { path, debounce_ms } => Ok(Box::new(
+                FileIpSource::with_debounce(path, std::time::Duration::from_millis(*debounce_ms))?,
+            )),
+            _ => Err(Error::config("Invalid config for file IP source")),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl IpSourceFactory for FileFactory {
+    fn create(&self, _config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        Err(Error::config("File IP source is only supported on Linux"))
+    }
+}
+
+/// Register the file IP source with a registry
+pub fn register(registry: &ProviderRegistry) {
+    registry.register_ip_source("file", Box::new(FileFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_factory_creation() {
+        let config = IpSourceConfig::File {
+            path: "/run/ddns/wan-ip".to_string(),
+            debounce_ms: 500,
+        };
+        assert!(FileFactory.create(&config).is_ok());
+
+        let config = IpSourceConfig::File {
+            path: "/".to_string(),
+            debounce_ms: 500,
+        };
+        assert!(FileFactory.create(&config).is_err());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "linux"))]
+    fn test_factory_unsupported() {
+        let config = IpSourceConfig::File {
+            path: "/run/ddns/wan-ip".to_string(),
+            debounce_ms: 500,
+        };
+        assert!(FileFactory.create(&config).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+// # File IP Source (inotify)
+//
+// See the crate documentation for the behaviour. All reads of the file go
+// through `Shared::read`, which also updates the health signal.
+
+use crate::DEFAULT_DEBOUNCE_MS;
+
+use ddns_core::traits::{HealthReporter, IpChangeEvent, IpSource, IpSourceHealth};
+use ddns_core::{Error, Result};
+
+use inotify::{EventMask, EventStream, Inotify, WatchMask};
+
+use std::ffi::OsString;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// Buffer for inotify events (each holds at most one file name)
+type EventBuffer = [u8; 4096];
+
+/// IP source reading the address from a watched file
+pub struct FileIpSource {
+    /// State shared with the watch loop
+    shared: Arc<Shared>,
+
+    /// Quiet time after the last change before the file is read
+    debounce: Duration,
+}
+
+/// Path and health of a [`FileIpSource`]
+struct Shared {
+    /// File holding the address
+    path: PathBuf,
+
+    /// Health signal
+    health: watch::Sender<IpSourceHealth>,
+}
+
+impl Shared {
+    /// Read the address from the file and record the outcome
+    async fn read(&self) -> Result<IpAddr> {
+        let result = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| Error::ip_source(format!("Failed to read {}: {}", self.path.display(), e)))
+            .and_then(|contents| parse(&contents, &self.path));
+        self.report(result.as_ref().err());
+        result
+    }
+
+    /// Record the outcome of a read
+    fn report(&self, error: Option<&Error>) {
+        match error {
+            None => self.health.record_success(),
+            Some(error) => {
+                self.health.record_failure(error);
+            }
+        }
+    }
+
+    /// Directory holding the file
+    fn directory(&self) -> &Path {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Start watching the directory holding the file
+    fn events(&self) -> Result<EventStream<EventBuffer>> {
+        let inotify = Inotify::init()
+            .map_err(|e| Error::ip_source(format!("Failed to initialise inotify: {}", e)))?;
+
+        let directory = self.directory();
+        inotify
+            .watches()
+            .add(
+                directory,
+                WatchMask::MODIFY
+                    | WatchMask::CLOSE_WRITE
+                    | WatchMask::CREATE
+                    | WatchMask::DELETE
+                    | WatchMask::MOVED_FROM
+                    | WatchMask::MOVED_TO
+                    | WatchMask::DELETE_SELF
+                    | WatchMask::MOVE_SELF,
+            )
+            .map_err(|e| {
+                Error::ip_source(format!("Failed to watch {}: {}", directory.display(), e))
+            })?;
+
+        inotify
+            .into_event_stream([0u8; 4096])
+            .map_err(|e| Error::ip_source(format!("Failed to read inotify events: {}", e)))
+    }
+}
+
+/// Parse the contents of the file
+fn parse(contents: &str, path: &Path) -> Result<IpAddr> {
+    let value = contents.trim();
+    ddns_core::net::parse_address(value).ok_or_else(|| {
+        Error::ip_source(format!(
+            "{} does not hold an IP address: '{}'",
+            path.display(),
+            value.chars().take(64).collect::<String>()
+        ))
+    })
+}
+
+impl FileIpSource {
+    /// Create a file IP source with the default debounce window (500ms)
+    ///
+    /// The file does not need to exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `path` does not name a file.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::with_debounce(path, Duration::from_millis(DEFAULT_DEBOUNCE_MS))
+    }
+
+    /// Create with custom debounce duration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `path` does not name a file.
+    pub fn with_debounce(path: impl Into<PathBuf>, debounce: Duration) -> Result<Self> {
+        let path = path.into();
+        if path.file_name().is_none() {
+            return Err(Error::config(format!(
+                "File IP source path must name a file, got: {}",
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                path,
+                health: watch::Sender::new(IpSourceHealth::Unknown),
+            }),
+            debounce,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl IpSource for FileIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        self.shared.read().await
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let shared = self.shared.clone();
+        let debounce = self.debounce;
+
+        // Watch right away, so that changes made after watch() returns are
+        // not missed
+        let mut events = match shared.events() {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::error!("{}", e);
+                shared.report(Some(&e));
+                return Box::pin(UnboundedReceiverStream::new(rx));
+            }
+        };
+        let file_name: OsString = shared.path.file_name().unwrap_or_default().to_owned();
+
+        tokio::spawn(async move {
+            tracing::info!(
+                "Starting file IP monitoring (path={}, debounce={:?})",
+                shared.path.display(),
+                debounce
+            );
+
+            let mut last_known_ip = None;
+
+            // Read the file once at startup
+            let mut deadline = Some(Instant::now());
+
+            loop {
+                tokio::select! {
+                    event = events.next() => match event {
+                        Some(Ok(event)) => {
+                            if event
+                                .mask
+                                .intersects(EventMask::DELETE_SELF | EventMask::MOVE_SELF)
+                            {
+                                let error = Error::ip_source(format!(
+                                    "{} was removed or moved",
+                                    shared.directory().display()
+                                ));
+                                tracing::error!("{}, stopping monitor", error);
+                                shared.report(Some(&error));
+                                break;
+                            }
+
+                            // Events may have been dropped; check the file
+                            let overflow = event.mask.contains(EventMask::Q_OVERFLOW);
+                            if overflow || event.name.as_deref() == Some(file_name.as_os_str()) {
+                                deadline = Some(Instant::now() + debounce);
+                            }
+                        }
+                        Some(Err(e)) => {
+                            tracing::error!("inotify error: {}", e);
+                            break;
+                        }
+                        None => break,
+                    },
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                        if deadline.is_some() =>
+                    {
+                        deadline = None;
+
+                        let ip = match shared.read().await {
+                            Ok(ip) => ip,
+                            Err(e) => {
+                                tracing::warn!("{}", e);
+                                continue;
+                            }
+                        };
+                        if last_known_ip == Some(ip) {
+                            continue;
+                        }
+
+                        tracing::info!("IP changed: {:?} -> {:?}", last_known_ip, ip);
+                        if tx.send(IpChangeEvent::new(ip, last_known_ip)).is_err() {
+                            break;
+                        }
+                        last_known_ip = Some(ip);
+                    }
+                    _ = tx.closed() => break,
+                }
+            }
+
+            tracing::info!("File IP monitoring stopped");
+        });
+
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.shared.health.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let path = Path::new("/run/ddns/wan-ip");
+        assert_eq!(
+            parse("203.0.113.5\n", path).unwrap(),
+            IpAddr::from([203, 0, 113, 5])
+        );
+        assert_eq!(
+            parse(" 2001:db8::5/64\n", path).unwrap(),
+            "2001:db8::5".parse::<IpAddr>().unwrap()
+        );
+        assert!(parse("", path).is_err());
+        assert!(parse("203.0.113.5\n203.0.113.6\n", path).is_err());
+    }
+
+    #[test]
+    fn test_directory() {
+        let source = FileIpSource::new("wan-ip").unwrap();
+        assert_eq!(source.shared.directory(), Path::new("."));
+
+        let source = FileIpSource::new("/run/ddns/wan-ip").unwrap();
+        assert_eq!(source.shared.directory(), Path::new("/run/ddns"));
+    }
+}
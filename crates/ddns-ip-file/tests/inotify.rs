@@ -0,0 +1,161 @@
+//! File IP source against files in a temporary directory
+//!
+//! Files are written the way hook scripts write them: in place, or to a
+//! temporary file that is then renamed over the watched one.
+//!
+//! If this test fails, an address written by a DHCP or PPP hook is not
+//! published, is published before the write completed, or other files in
+//! the same directory trigger updates.
+
+#![cfg(target_os = "linux")]
+
+use ddns_core::traits::{IpChangeEvent, IpSource, IpSourceHealth};
+use ddns_ip_file::FileIpSource;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio_stream::{Stream, StreamExt};
+
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+type Events = Pin<Box<dyn Stream<Item = IpChangeEvent> + Send>>;
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+async fn next(events: &mut Events) -> IpChangeEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("change should be emitted")
+        .unwrap()
+}
+
+async fn assert_quiet(events: &mut Events) {
+    assert!(
+        tokio::time::timeout(DEBOUNCE * 6, events.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn current_reads_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5/32\n").unwrap();
+
+    let source = FileIpSource::new(&path).unwrap();
+    assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+}
+
+#[tokio::test]
+async fn missing_file_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let source = FileIpSource::new(dir.path().join("wan-ip")).unwrap();
+    let health = source.health().unwrap();
+
+    assert!(source.current().await.is_err());
+    assert!(matches!(
+        *health.borrow(),
+        IpSourceHealth::Failing {
+            consecutive_failures: 1,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn writes_are_emitted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = FileIpSource::with_debounce(&path, DEBOUNCE).unwrap();
+    let mut events = source.watch();
+    assert_eq!(next(&mut events).await.new_ip, ip("203.0.113.5"));
+
+    std::fs::write(&path, "203.0.113.6\n").unwrap();
+    let event = next(&mut events).await;
+    assert_eq!(
+        (event.new_ip, event.previous_ip),
+        (ip("203.0.113.6"), Some(ip("203.0.113.5")))
+    );
+
+    // Rewriting the same address is not a change
+    std::fs::write(&path, "203.0.113.6\n").unwrap();
+    assert_quiet(&mut events).await;
+}
+
+#[tokio::test]
+async fn renamed_files_are_emitted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = FileIpSource::with_debounce(&path, DEBOUNCE).unwrap();
+    let mut events = source.watch();
+    assert_eq!(next(&mut events).await.new_ip, ip("203.0.113.5"));
+
+    let temporary = dir.path().join("wan-ip.tmp");
+    std::fs::write(&temporary, "2001:db8::5\n").unwrap();
+    std::fs::rename(&temporary, &path).unwrap();
+
+    assert_eq!(next(&mut events).await.new_ip, ip("2001:db8::5"));
+}
+
+#[tokio::test]
+async fn file_created_later_is_emitted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+
+    let source = FileIpSource::with_debounce(&path, DEBOUNCE).unwrap();
+    let health = source.health().unwrap();
+    let mut events = source.watch();
+    assert_quiet(&mut events).await;
+    assert!(matches!(*health.borrow(), IpSourceHealth::Failing { .. }));
+
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+    assert_eq!(next(&mut events).await.new_ip, ip("203.0.113.5"));
+    assert_eq!(*health.borrow(), IpSourceHealth::Healthy);
+}
+
+#[tokio::test]
+async fn bursts_are_debounced() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = FileIpSource::with_debounce(&path, Duration::from_millis(300)).unwrap();
+    let mut events = source.watch();
+    assert_eq!(next(&mut events).await.new_ip, ip("203.0.113.5"));
+
+    // A half-written file followed by the complete one
+    std::fs::write(&path, "203.0.").unwrap();
+    std::fs::write(&path, "203.0.113.7\n").unwrap();
+
+    let event = next(&mut events).await;
+    assert_eq!(
+        (event.new_ip, event.previous_ip),
+        (ip("203.0.113.7"), Some(ip("203.0.113.5")))
+    );
+}
+
+#[tokio::test]
+async fn other_files_are_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wan-ip");
+    std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+    let source = FileIpSource::with_debounce(&path, DEBOUNCE).unwrap();
+    let health = source.health().unwrap();
+    let mut events = source.watch();
+    assert_eq!(next(&mut events).await.new_ip, ip("203.0.113.5"));
+
+    let mut reads = health.clone();
+    reads.mark_unchanged();
+    std::fs::write(dir.path().join("lan-ip"), "192.168.1.2\n").unwrap();
+    assert_quiet(&mut events).await;
+    assert!(!reads.has_changed().unwrap());
+}
@@ -0,0 +1,296 @@
+// # Endpoint Pool
+//
+// Queries a list of HTTP IP services according to an `HttpStrategy` and
+// tracks the health of each service.
+//
+// ## Strategies
+//
+// - `Failover`: endpoints are tried in configured order; the first valid
+//   answer wins.
+// - `RoundRobin`: like `Failover`, but every query starts one endpoint
+//   further down the list, so that the load is spread across services.
+// - `Consensus`: all endpoints are queried concurrently, and an address is
+//   only accepted once `quorum` of them agree on it. Endpoints outvoted by
+//   the quorum count as failed.
+//
+// ## Health Tracking
+//
+// An endpoint that fails `failure_threshold` times in a row is evicted for
+// the eviction period and skipped by later queries. A valid answer resets
+// its failure count. If every endpoint is evicted, all of them are tried
+// again rather than failing without a single request.
+//
+// A query only succeeds if the response status is a success, the
+// configured extractor finds the address text (see `extract.rs`), and it
+// parses as an address of the pool's family.
+
+use crate::extract::Extractor;
+
+use ddns_core::config::{HttpStrategy, IpVersion as ConfigIpVersion};
+use ddns_core::{Error, Result};
+
+use reqwest::header::HeaderMap;
+
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinSet;
+
+/// Health of a single endpoint
+#[derive(Debug, Clone, Copy, Default)]
+struct Health {
+    /// Failed queries since the last valid answer
+    consecutive_failures: u32,
+    /// Skip the endpoint until then
+    evicted_until: Option<Instant>,
+}
+
+/// How the endpoints of a pool are queried
+#[derive(Debug, Clone)]
+pub(crate) struct PoolOptions {
+    pub(crate) strategy: HttpStrategy,
+    /// Consecutive failures before eviction (0 = never evict)
+    pub(crate) failure_threshold: u32,
+    pub(crate) eviction: Duration,
+    pub(crate) extractor: Extractor,
+    /// Extra headers sent with every request
+    pub(crate) headers: HeaderMap,
+}
+
+/// A set of HTTP IP services queried according to a strategy
+pub(crate) struct EndpointPool {
+    urls: Vec<String>,
+    version: Option<ConfigIpVersion>,
+    client: reqwest::Client,
+    options: PoolOptions,
+    health: Mutex<Vec<Health>>,
+    /// Round-robin cursor
+    next: AtomicUsize,
+}
+
+impl EndpointPool {
+    pub(crate) fn new(
+        urls: Vec<String>,
+        version: Option<ConfigIpVersion>,
+        client: reqwest::Client,
+        options: PoolOptions,
+    ) -> Self {
+        Self {
+            health: Mutex::new(vec![Health::default(); urls.len()]),
+            urls,
+            version,
+            client,
+            options,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    /// Address family this pool is restricted to, if any
+    pub(crate) fn version(&self) -> Option<ConfigIpVersion> {
+        self.version
+    }
+
+    pub(crate) fn strategy(&self) -> HttpStrategy {
+        self.options.strategy
+    }
+
+    /// Query the endpoints and return the resulting address
+    pub(crate) async fn resolve(self: &Arc<Self>) -> Result<IpAddr> {
+        let order = self.candidates();
+
+        match self.options.strategy {
+            HttpStrategy::Failover | HttpStrategy::RoundRobin => self.first_success(&order).await,
+            HttpStrategy::Consensus { quorum } => self.consensus(order, quorum).await,
+        }
+    }
+
+    /// Indices of the endpoints to query, in order
+    fn candidates(&self) -> Vec<usize> {
+        let count = self.urls.len();
+        let start = match self.options.strategy {
+            HttpStrategy::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % count,
+            _ => 0,
+        };
+        let order: Vec<usize> = (0..count).map(|i| (start + i) % count).collect();
+
+        let now = Instant::now();
+        let health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let available: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&i| health[i].evicted_until.is_none_or(|until| until <= now))
+            .collect();
+
+        if available.is_empty() {
+            tracing::warn!("All HTTP IP endpoints are evicted, trying all of them");
+            order
+        } else {
+            available
+        }
+    }
+
+    /// Return the first valid answer, trying the endpoints in order
+    async fn first_success(&self, order: &[usize]) -> Result<IpAddr> {
+        let mut errors = Vec::new();
+
+        for &index in order {
+            match self.query(index).await {
+                Ok(ip) => return Ok(ip),
+                Err(e) => {
+                    tracing::warn!("HTTP IP endpoint {} failed: {}", self.urls[index], e);
+                    errors.push(format!("{}: {}", self.urls[index], e));
+                }
+            }
+        }
+
+        Err(Error::provider(
+            "http",
+            format!("All HTTP IP endpoints failed ({})", errors.join("; ")),
+        ))
+    }
+
+    /// Query all endpoints concurrently and return the address `quorum` of
+    /// them agree on
+    async fn consensus(self: &Arc<Self>, order: Vec<usize>, quorum: usize) -> Result<IpAddr> {
+        let mut tasks = JoinSet::new();
+        for index in order {
+            let pool = Arc::clone(self);
+            tasks.spawn(async move { (index, pool.query(index).await) });
+        }
+
+        let mut answers: Vec<(usize, IpAddr)> = Vec::new();
+        let mut errors = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, Ok(ip))) => answers.push((index, ip)),
+                Ok((index, Err(e))) => errors.push(format!("{}: {}", self.urls[index], e)),
+                Err(e) => errors.push(format!("query task failed: {}", e)),
+            }
+        }
+
+        // Count votes per address, in endpoint order so ties are deterministic
+        answers.sort_by_key(|(index, _)| *index);
+        let mut votes: Vec<(IpAddr, usize)> = Vec::new();
+        for (_, ip) in &answers {
+            match votes.iter_mut().find(|(candidate, _)| candidate == ip) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((*ip, 1)),
+            }
+        }
+
+        let winner = votes.iter().filter(|(_, count)| *count >= quorum).fold(
+            None,
+            |best: Option<(IpAddr, usize)>, &(ip, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((ip, count)),
+            },
+        );
+
+        let Some((ip, _)) = winner else {
+            let answers: Vec<String> = votes
+                .iter()
+                .map(|(ip, count)| format!("{} x{}", ip, count))
+                .collect();
+            return Err(Error::provider(
+                "http",
+                format!(
+                    "No consensus among HTTP IP endpoints: {} of {} must agree (answers: [{}]; errors: [{}])",
+                    quorum,
+                    self.urls.len(),
+                    answers.join(", "),
+                    errors.join("; ")
+                ),
+            ));
+        };
+
+        // Endpoints that were outvoted returned a wrong address
+        for (index, answer) in answers {
+            if answer != ip {
+                tracing::warn!(
+                    "HTTP IP endpoint {} disagrees with consensus: {} != {}",
+                    self.urls[index],
+                    answer,
+                    ip
+                );
+                self.record(index, false);
+            }
+        }
+
+        Ok(ip)
+    }
+
+    /// Query a single endpoint and update its health
+    async fn query(&self, index: usize) -> Result<IpAddr> {
+        let result = self.fetch(&self.urls[index]).await;
+        self.record(index, result.is_ok());
+        result
+    }
+
+    /// Record the outcome of a query, evicting the endpoint if it keeps failing
+    fn record(&self, index: usize, success: bool) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = &mut health[index];
+
+        if success {
+            *entry = Health::default();
+            return;
+        }
+
+        entry.consecutive_failures += 1;
+        let threshold = self.options.failure_threshold;
+        if threshold > 0 && entry.consecutive_failures >= threshold {
+            tracing::warn!(
+                "Evicting HTTP IP endpoint {} for {:?} after {} consecutive failures",
+                self.urls[index],
+                self.options.eviction,
+                entry.consecutive_failures
+            );
+            entry.consecutive_failures = 0;
+            entry.evicted_until = Some(Instant::now() + self.options.eviction);
+        }
+    }
+
+    /// Fetch and parse the address returned by a URL
+    async fn fetch(&self, url: &str) -> Result<IpAddr> {
+        let response = self
+            .client
+            .get(url)
+            .headers(self.options.headers.clone())
+            .send()
+            .await
+            .map_err(|e| Error::provider("http", format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(Error::provider(
+                "http",
+                format!("HTTP error: {}", response.status()),
+            ));
+        }
+
+        let ip_text = self.options.extractor.extract(response).await?;
+
+        // Parse IP address
+        let ip: IpAddr = ip_text
+            .parse()
+            .map_err(|_| Error::provider("http", format!("Invalid IP address: {}", ip_text)))?;
+
+        // Filter by IP version if specified
+        match self.version {
+            Some(ConfigIpVersion::V4) if !ip.is_ipv4() => Err(Error::provider(
+                "http",
+                format!("Expected IPv4, got: {}", ip),
+            )),
+            Some(ConfigIpVersion::V6) if !ip.is_ipv6() => Err(Error::provider(
+                "http",
+                format!("Expected IPv6, got: {}", ip),
+            )),
+            _ => Ok(ip),
+        }
+    }
+}
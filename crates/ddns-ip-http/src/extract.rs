@@ -0,0 +1,175 @@
+// # Response Extraction
+//
+// Finds the address in an HTTP response, as configured by `HttpExtractor`.
+//
+// Plain-text services (ipify, icanhazip) return a bare address, but many
+// endpoints wrap it: JSON APIs, router status pages, internal metadata
+// services, or Cloudflare's `/cdn-cgi/trace` (`ip=203.0.113.5` among other
+// `key=value` lines). The extractor returns the text holding the address;
+// parsing it is left to the caller.
+
+use ddns_core::config::HttpExtractor;
+use ddns_core::{Error, Result};
+
+use regex::Regex;
+use reqwest::header::HeaderName;
+
+/// A compiled `HttpExtractor`
+#[derive(Debug, Clone)]
+pub(crate) enum Extractor {
+    Body,
+    JsonPointer(String),
+    Regex(Regex),
+    KeyValue(String),
+    Header(HeaderName),
+}
+
+impl Extractor {
+    /// Compile an extractor configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` for invalid settings, invalid regular
+    /// expressions and invalid header names.
+    pub(crate) fn new(config: &HttpExtractor) -> Result<Self> {
+        config.validate()?;
+
+        Ok(match config {
+            HttpExtractor::Body => Extractor::Body,
+            HttpExtractor::JsonPointer { pointer } => Extractor::JsonPointer(pointer.clone()),
+            HttpExtractor::Regex { pattern } => Extractor::Regex(
+                Regex::new(pattern)
+                    .map_err(|e| Error::config(format!("Invalid HTTP IP source regex: {}", e)))?,
+            ),
+            HttpExtractor::KeyValue { key } => Extractor::KeyValue(key.clone()),
+            HttpExtractor::Header { name } => {
+                Extractor::Header(HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                    Error::config(format!("Invalid HTTP IP source header '{}': {}", name, e))
+                })?)
+            }
+        })
+    }
+
+    /// Extract the address text from a successful response
+    pub(crate) async fn extract(&self, response: reqwest::Response) -> Result<String> {
+        if let Extractor::Header(name) = self {
+            let value = response
+                .headers()
+                .get(name)
+                .ok_or_else(|| Error::provider("http", format!("Missing header: {}", name)))?;
+            return value
+                .to_str()
+                .map(|value| value.trim().to_string())
+                .map_err(|_| Error::provider("http", format!("Invalid header value: {}", name)));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::provider("http", format!("Failed to read response: {}", e)))?;
+
+        self.extract_from_body(&body)
+    }
+
+    /// Extract the address text from a response body
+    fn extract_from_body(&self, body: &str) -> Result<String> {
+        match self {
+            Extractor::Body | Extractor::Header(_) => Ok(body.trim().to_string()),
+            Extractor::JsonPointer(pointer) => {
+                let json: serde_json::Value = serde_json::from_str(body).map_err(|e| {
+                    Error::provider("http", format!("Failed to parse JSON response: {}", e))
+                })?;
+                json.pointer(pointer)
+                    .and_then(serde_json::Value::as_str)
+                    .map(|value| value.trim().to_string())
+                    .ok_or_else(|| {
+                        Error::provider("http", format!("No string at JSON pointer '{}'", pointer))
+                    })
+            }
+            Extractor::Regex(regex) => {
+                let captures = regex.captures(body).ok_or_else(|| {
+                    Error::provider("http", format!("Regex '{}' does not match", regex))
+                })?;
+                let matched = captures.get(1).or_else(|| captures.get(0));
+                Ok(matched.map_or("", |m| m.as_str()).trim().to_string())
+            }
+            Extractor::KeyValue(key) => body
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .find(|(candidate, _)| candidate.trim() == key)
+                .map(|(_, value)| value.trim().to_string())
+                .ok_or_else(|| Error::provider("http", format!("No '{}=' line", key))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(config: HttpExtractor, body: &str) -> Result<String> {
+        Extractor::new(&config).unwrap().extract_from_body(body)
+    }
+
+    #[test]
+    fn test_json_pointer() {
+        let config = HttpExtractor::JsonPointer {
+            pointer: "/data/ip".to_string(),
+        };
+        assert_eq!(
+            extract(config.clone(), r#"{"data": {"ip": "203.0.113.5"}}"#).unwrap(),
+            "203.0.113.5"
+        );
+        assert!(extract(config.clone(), r#"{"data": {"ip": 5}}"#).is_err());
+        assert!(extract(config, "203.0.113.5").is_err());
+    }
+
+    #[test]
+    fn test_regex() {
+        let config = HttpExtractor::Regex {
+            pattern: r"WAN address: ([0-9.]+)".to_string(),
+        };
+        assert_eq!(
+            extract(config.clone(), "<td>WAN address: 203.0.113.5</td>").unwrap(),
+            "203.0.113.5"
+        );
+        assert!(extract(config, "offline").is_err());
+
+        // Without a group, the whole match is used
+        let config = HttpExtractor::Regex {
+            pattern: r"\d+\.\d+\.\d+\.\d+".to_string(),
+        };
+        assert_eq!(
+            extract(config, "Current IP Address: 203.0.113.5").unwrap(),
+            "203.0.113.5"
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        let trace = "fl=123\nh=example.com\nip=203.0.113.5\nts=1700000000.1\n";
+        let config = HttpExtractor::KeyValue {
+            key: "ip".to_string(),
+        };
+        assert_eq!(extract(config.clone(), trace).unwrap(), "203.0.113.5");
+        assert!(extract(config, "fl=123\n").is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        for config in [
+            HttpExtractor::Regex {
+                pattern: "(".to_string(),
+            },
+            HttpExtractor::JsonPointer {
+                pointer: "ip".to_string(),
+            },
+            HttpExtractor::Header {
+                name: "bad header".to_string(),
+            },
+            HttpExtractor::KeyValue { key: String::new() },
+        ] {
+            assert!(Extractor::new(&config).is_err(), "{:?}", config);
+        }
+    }
+}
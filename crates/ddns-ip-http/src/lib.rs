@@ -19,37 +19,115 @@
 //
 // Fetches current IP from external services (e.g., ifconfig.me, icanhazip.com)
 // and polls at a configurable interval for changes.
+//
+// Several services can be configured. They are queried according to an
+// `HttpStrategy` (failover, round-robin or N-of-M consensus), and services
+// that keep failing are temporarily evicted (see `endpoints.rs`).
+//
+// ## Address Families
+//
+// A family-agnostic URL returns whichever family the connection happened to
+// use, which depends on DNS and happy-eyeballs. For dual-stack hosts,
+// separate IPv4 and IPv6 URLs can be configured instead: each family is
+// queried with its own client, bound to `0.0.0.0` or `::`, so requests are
+// forced over that family. The watch loop then emits an independent
+// `IpChangeEvent` per family.
+//
+// ## Response Formats
+//
+// By default the whole body must be the address. Wrapped answers (JSON,
+// `key=value` lines, HTML status pages, response headers) are handled by an
+// `HttpExtractor` (see `extract.rs`). Custom request headers can be added
+// for authenticated internal endpoints.
+//
+// ## Failures
+//
+// `current()` and the watch loop share a single fetch path, which keeps the
+// cached address and the health signal (`IpSource::health`) up to date. A
+// family whose query fails is dropped from the cache, so `current()` never
+// returns an address that could not be confirmed.
+//
+// After a failed poll, the watch loop retries with exponential backoff
+// (`retry_delay`, doubling up to `max_retry_delay`) instead of waiting for
+// the full poll interval. Half of each delay is randomized, so that several
+// daemons behind one outage do not retry in lockstep. The loop ends as soon
+// as the stream returned by `watch()` is dropped, even in the middle of a
+// request or a delay.
+
+mod endpoints;
+mod extract;
+
+use endpoints::{EndpointPool, PoolOptions};
+use extract::Extractor;
 
 use ddns_core::ProviderRegistry;
-use ddns_core::config::IpSourceConfig;
 use ddns_core::config::IpVersion as ConfigIpVersion;
-use ddns_core::traits::{IpChangeEvent, IpSource, IpSourceFactory, IpVersion as TraitsIpVersion};
+use ddns_core::config::{HttpExtractor, HttpStrategy, IpSourceConfig};
+use ddns_core::traits::{
+    HealthReporter, IpChangeEvent, IpSource, IpSourceFactory, IpSourceHealth,
+    IpVersion as TraitsIpVersion,
+};
 use ddns_core::{Error, Result};
 
-use std::net::IpAddr;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use tokio::sync::Mutex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tokio::sync::watch;
 use tokio_stream::Stream;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 /// Default polling interval for HTTP IP source
 const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
 
-/// Default IP check services (for future failover support)
-#[allow(dead_code)]
+/// Default request timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default number of consecutive failures before an endpoint is evicted
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default time an evicted endpoint is skipped
+const DEFAULT_EVICTION: Duration = Duration::from_secs(300);
+
+/// Default delay before the first retry of a failed poll
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Default IP check services, used when no URL is configured
 const DEFAULT_IP_SERVICES: &[&str] = &[
     "https://api.ipify.org",  // 43KB/day free, returns plain text IP
     "https://ifconfig.me/ip", // No rate limit documented
     "https://icanhazip.com",  // No rate limit documented
 ];
 
+/// Address family of an IP address
+fn ip_version(ip: &IpAddr) -> TraitsIpVersion {
+    match ip {
+        IpAddr::V4(_) => TraitsIpVersion::V4,
+        IpAddr::V6(_) => TraitsIpVersion::V6,
+    }
+}
+
+/// Exponential backoff delay before retry number `failures` (starting at 1)
+///
+/// The upper half of the delay is randomized.
+fn backoff(failures: u32, initial: Duration, max: Duration) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    let delay = initial.saturating_mul(1 << exponent).min(max);
+
+    let half = delay / 2;
+    let random = RandomState::new().build_hasher().finish();
+    half + half.mul_f64(random as f64 / u64::MAX as f64)
+}
+
 /// HTTP-based IP source (fallback for non-Linux or CI)
 pub struct HttpIpSource {
-    /// URL to fetch IP from
-    url: String,
+    /// State shared with the watch loop
+    shared: Arc<Shared>,
 
     /// IP version to monitor
     version: Option<ConfigIpVersion>,
@@ -57,11 +135,77 @@ pub struct HttpIpSource {
     /// Polling interval
     poll_interval: Duration,
 
-    /// Current IP address (cached)
-    current_ip: Arc<Mutex<Option<IpAddr>>>,
+    /// Delay before the first retry of a failed poll
+    retry_delay: Duration,
+
+    /// Upper bound for retry delays
+    max_retry_delay: Duration,
+}
+
+/// Endpoints, cache and health of an [`HttpIpSource`]
+struct Shared {
+    /// Services to fetch IP from: a single family-agnostic pool, or one
+    /// pool per address family
+    pools: Vec<Arc<EndpointPool>>,
+
+    /// Current IP address per family (cached)
+    current_ips: Mutex<HashMap<TraitsIpVersion, IpAddr>>,
 
-    /// HTTP client
-    client: reqwest::Client,
+    /// Health signal
+    health: watch::Sender<IpSourceHealth>,
+}
+
+impl Shared {
+    /// Query a pool and update the cached address of its family
+    ///
+    /// This is the only place addresses are fetched.
+    async fn fetch(&self, pool: &Arc<EndpointPool>) -> Result<IpAddr> {
+        let result = pool.resolve().await;
+
+        let mut current_ips = self.current_ips.lock().unwrap_or_else(|e| e.into_inner());
+        match (&result, pool.version()) {
+            (Ok(ip), _) => {
+                current_ips.insert(ip_version(ip), *ip);
+            }
+            // The address of this family can no longer be confirmed
+            (Err(_), Some(ConfigIpVersion::V4)) => {
+                current_ips.remove(&TraitsIpVersion::V4);
+            }
+            (Err(_), Some(ConfigIpVersion::V6)) => {
+                current_ips.remove(&TraitsIpVersion::V6);
+            }
+            (Err(_), _) => current_ips.clear(),
+        }
+
+        result
+    }
+
+    /// Record the outcome of a poll and return the number of consecutive
+    /// failed polls
+    fn report(&self, errors: &[String]) -> u32 {
+        if errors.is_empty() {
+            self.health.record_success();
+            0
+        } else {
+            self.health.record_failure(errors.join("; "))
+        }
+    }
+
+    fn cached_ip_for(&self, version: TraitsIpVersion) -> Option<IpAddr> {
+        self.current_ips
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&version)
+            .copied()
+    }
+
+    fn cached_ip(&self) -> Option<IpAddr> {
+        let current_ips = self.current_ips.lock().unwrap_or_else(|e| e.into_inner());
+        current_ips
+            .get(&TraitsIpVersion::V4)
+            .or_else(|| current_ips.get(&TraitsIpVersion::V6))
+            .copied()
+    }
 }
 
 impl HttpIpSource {
@@ -72,16 +216,10 @@ impl HttpIpSource {
     /// - `url`: URL to fetch IP from (e.g., "https://api.ipify.org")
     /// - `version`: IP version to monitor (None = both)
     pub fn new(url: String, version: Option<ConfigIpVersion>) -> Self {
-        Self {
-            url,
-            version,
-            poll_interval: Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
-            current_ip: Arc::new(Mutex::new(None)),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap_or_default(),
-        }
+        Self::builder()
+            .url(url)
+            .version(version)
+            .into_source(Extractor::Body, HeaderMap::new())
     }
 
     /// Create with custom polling interval
@@ -90,171 +228,571 @@ impl HttpIpSource {
         version: Option<ConfigIpVersion>,
         poll_interval: Duration,
     ) -> Self {
+        Self::builder()
+            .url(url)
+            .version(version)
+            .interval(poll_interval)
+            .into_source(Extractor::Body, HeaderMap::new())
+    }
+
+    /// Create a builder for an HTTP IP source with several URLs
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ddns_core::config::HttpStrategy;
+    /// use ddns_ip_http::HttpIpSource;
+    ///
+    /// let source = HttpIpSource::builder()
+    ///     .url("https://api.ipify.org")
+    ///     .url("https://ifconfig.me/ip")
+    ///     .url("https://icanhazip.com")
+    ///     .strategy(HttpStrategy::Consensus { quorum: 2 })
+    ///     .build()?;
+    ///
+    /// // Dual-stack: each family is queried over a connection of that family
+    /// let source = HttpIpSource::builder()
+    ///     .v4_url("https://api.ipify.org")
+    ///     .v6_url("https://api6.ipify.org")
+    ///     .build()?;
+    /// # Ok::<(), ddns_core::Error>(())
+    /// ```
+    pub fn builder() -> HttpIpSourceBuilder {
+        HttpIpSourceBuilder::new()
+    }
+
+    /// The URLs this source queries
+    pub fn urls(&self) -> Vec<String> {
+        self.shared
+            .pools
+            .iter()
+            .flat_map(|pool| pool.urls().iter().cloned())
+            .collect()
+    }
+
+    /// Fetch current IP from the HTTP services
+    ///
+    /// With one pool per family, the first family that answers wins
+    /// (IPv4 before IPv6).
+    async fn fetch_ip(&self) -> Result<IpAddr> {
+        let mut errors = Vec::new();
+
+        for pool in &self.shared.pools {
+            match self.shared.fetch(pool).await {
+                Ok(ip) => {
+                    self.shared.report(&[]);
+                    return Ok(ip);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        self.shared.report(&errors);
+        Err(Error::provider("http", errors.join("; ")))
+    }
+}
+
+/// Builder for [`HttpIpSource`]
+#[derive(Clone)]
+pub struct HttpIpSourceBuilder {
+    urls: Vec<String>,
+    v4_urls: Vec<String>,
+    v6_urls: Vec<String>,
+    strategy: HttpStrategy,
+    extractor: HttpExtractor,
+    headers: Vec<(String, String)>,
+    version: Option<ConfigIpVersion>,
+    poll_interval: Duration,
+    retry_delay: Duration,
+    max_retry_delay: Option<Duration>,
+    timeout: Duration,
+    client: Option<reqwest::Client>,
+    failure_threshold: u32,
+    eviction: Duration,
+}
+
+impl std::fmt::Debug for HttpIpSourceBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values may carry credentials
+        let header_names: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+
+        f.debug_struct("HttpIpSourceBuilder")
+            .field("urls", &self.urls)
+            .field("v4_urls", &self.v4_urls)
+            .field("v6_urls", &self.v6_urls)
+            .field("strategy", &self.strategy)
+            .field("extractor", &self.extractor)
+            .field("headers", &header_names)
+            .field("version", &self.version)
+            .field("poll_interval", &self.poll_interval)
+            .field("retry_delay", &self.retry_delay)
+            .field("max_retry_delay", &self.max_retry_delay)
+            .field("timeout", &self.timeout)
+            .field("failure_threshold", &self.failure_threshold)
+            .field("eviction", &self.eviction)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpIpSourceBuilder {
+    fn new() -> Self {
         Self {
-            url,
-            version,
-            poll_interval,
-            current_ip: Arc::new(Mutex::new(None)),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap_or_default(),
+            urls: Vec::new(),
+            v4_urls: Vec::new(),
+            v6_urls: Vec::new(),
+            strategy: HttpStrategy::default(),
+            extractor: HttpExtractor::default(),
+            headers: Vec::new(),
+            version: None,
+            poll_interval: Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+            retry_delay: DEFAULT_RETRY_DELAY,
+            max_retry_delay: None,
+            timeout: DEFAULT_TIMEOUT,
+            client: None,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            eviction: DEFAULT_EVICTION,
         }
     }
 
-    /// Fetch current IP from HTTP service
-    async fn fetch_ip(&self) -> Result<IpAddr> {
-        let response = self
-            .client
-            .get(&self.url)
-            .send()
-            .await
-            .map_err(|e| Error::provider("http", format!("Request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(Error::provider(
-                "http",
-                format!("HTTP error: {}", response.status()),
+    /// Add a family-agnostic URL to fetch IP from
+    ///
+    /// Without any URL, the source uses a built-in list of public services.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    /// Add several family-agnostic URLs to fetch IP from
+    pub fn urls<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add a URL to fetch the IPv4 address from, always over IPv4
+    pub fn v4_url(mut self, url: impl Into<String>) -> Self {
+        self.v4_urls.push(url.into());
+        self
+    }
+
+    /// Add several URLs to fetch the IPv4 address from, always over IPv4
+    pub fn v4_urls<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.v4_urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add a URL to fetch the IPv6 address from, always over IPv6
+    pub fn v6_url(mut self, url: impl Into<String>) -> Self {
+        self.v6_urls.push(url.into());
+        self
+    }
+
+    /// Add several URLs to fetch the IPv6 address from, always over IPv6
+    pub fn v6_urls<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.v6_urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// How the URLs are queried (default: failover)
+    pub fn strategy(mut self, strategy: HttpStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Where the address is found in the response (default: whole body)
+    pub fn extractor(mut self, extractor: HttpExtractor) -> Self {
+        self.extractor = extractor;
+        self
+    }
+
+    /// Add a request header, e.g. for authenticated internal endpoints
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add several request headers
+    pub fn headers<I, K, V>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.headers
+            .extend(headers.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// IP version to monitor (None = both)
+    ///
+    /// Family-agnostic URLs of a single-family source are queried over that
+    /// family.
+    pub fn version(mut self, version: Option<ConfigIpVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Polling interval
+    pub fn interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Delay before retrying a failed poll (default: 5s)
+    ///
+    /// The delay doubles with every consecutive failed poll, up to
+    /// [`max_retry_delay`](Self::max_retry_delay).
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Upper bound for retry delays (default: the polling interval)
+    pub fn max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.max_retry_delay = Some(max_retry_delay);
+        self
+    }
+
+    /// Per-request timeout (ignored when a client is injected)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Use a preconfigured HTTP client for family-agnostic URLs
+    ///
+    /// Per-family URLs always use clients bound to their family.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Consecutive failures before a URL is temporarily evicted (0 = never)
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// How long an evicted URL is skipped
+    pub fn eviction(mut self, eviction: Duration) -> Self {
+        self.eviction = eviction;
+        self
+    }
+
+    /// Build the IP source
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if a URL is not an HTTP(S) URL, if
+    /// family-agnostic and per-family URLs are mixed, if per-family URLs
+    /// contradict `version`, if the consensus quorum is not between 1 and
+    /// the number of URLs of each family, or if the extractor or a header is
+    /// invalid.
+    pub fn build(self) -> Result<HttpIpSource> {
+        if let Some(url) = self
+            .urls
+            .iter()
+            .chain(&self.v4_urls)
+            .chain(&self.v6_urls)
+            .find(|url| !url.starts_with("https://") && !url.starts_with("http://"))
+        {
+            return Err(Error::config(format!(
+                "HTTP IP source URL must use HTTP or HTTPS scheme. Got: {}",
+                url
+            )));
+        }
+
+        let per_family = !self.v4_urls.is_empty() || !self.v6_urls.is_empty();
+        if per_family && !self.urls.is_empty() {
+            return Err(Error::config(
+                "HTTP IP source URLs must either all be family-agnostic or all per family",
             ));
         }
 
-        let ip_text = response
-            .text()
-            .await
-            .map_err(|e| Error::provider("http", format!("Failed to read response: {}", e)))?;
-
-        let ip_text = ip_text.trim();
-
-        // Parse IP address
-        let ip: IpAddr = ip_text
-            .parse()
-            .map_err(|_| Error::provider("http", format!("Invalid IP address: {}", ip_text)))?;
-
-        // Filter by IP version if specified
-        if let Some(version) = self.version {
-            match version {
-                ConfigIpVersion::V4 => {
-                    if !ip.is_ipv4() {
-                        return Err(Error::provider(
-                            "http",
-                            format!("Expected IPv4, got: {}", ip),
-                        ));
-                    }
+        match self.version {
+            Some(ConfigIpVersion::V4) if !self.v6_urls.is_empty() => {
+                return Err(Error::config(
+                    "IPv6 URLs configured for an IPv4-only HTTP IP source",
+                ));
+            }
+            Some(ConfigIpVersion::V6) if !self.v4_urls.is_empty() => {
+                return Err(Error::config(
+                    "IPv4 URLs configured for an IPv6-only HTTP IP source",
+                ));
+            }
+            _ => {}
+        }
+
+        let url_count = if per_family {
+            [self.v4_urls.len(), self.v6_urls.len()]
+                .into_iter()
+                .filter(|count| *count > 0)
+                .min()
+                .unwrap_or(0)
+        } else if self.urls.is_empty() {
+            DEFAULT_IP_SERVICES.len()
+        } else {
+            self.urls.len()
+        };
+        if let HttpStrategy::Consensus { quorum } = self.strategy
+            && (quorum == 0 || quorum > url_count)
+        {
+            return Err(Error::config(format!(
+                "HTTP IP source quorum must be between 1 and the number of URLs ({}), got {}",
+                url_count, quorum
+            )));
+        }
+
+        let extractor = Extractor::new(&self.extractor)?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                Error::config(format!("Invalid HTTP IP source header '{}': {}", name, e))
+            })?;
+            let mut value = HeaderValue::from_str(value).map_err(|e| {
+                Error::config(format!(
+                    "Invalid value for HTTP IP source header '{}': {}",
+                    name, e
+                ))
+            })?;
+            value.set_sensitive(true);
+            headers.append(name, value);
+        }
+
+        Ok(self.into_source(extractor, headers))
+    }
+
+    /// Build the IP source from validated parts
+    fn into_source(self, extractor: Extractor, headers: HeaderMap) -> HttpIpSource {
+        let options = PoolOptions {
+            strategy: self.strategy,
+            failure_threshold: self.failure_threshold,
+            eviction: self.eviction,
+            extractor,
+            headers,
+        };
+
+        let pools = if self.v4_urls.is_empty() && self.v6_urls.is_empty() {
+            let urls = if self.urls.is_empty() {
+                DEFAULT_IP_SERVICES
+                    .iter()
+                    .map(|url| url.to_string())
+                    .collect()
+            } else {
+                self.urls.clone()
+            };
+            let client = match (&self.client, self.version) {
+                (Some(client), _) => client.clone(),
+                (None, Some(ConfigIpVersion::V4)) => {
+                    self.pinned_client(Ipv4Addr::UNSPECIFIED.into())
                 }
-                ConfigIpVersion::V6 => {
-                    if !ip.is_ipv6() {
-                        return Err(Error::provider(
-                            "http",
-                            format!("Expected IPv6, got: {}", ip),
-                        ));
-                    }
+                (None, Some(ConfigIpVersion::V6)) => {
+                    self.pinned_client(Ipv6Addr::UNSPECIFIED.into())
                 }
-                ConfigIpVersion::Both => {}
+                (None, _) => self.unpinned_client(),
+            };
+            vec![Self::pool(urls, self.version, client, &options)]
+        } else {
+            let mut pools = Vec::new();
+            if !self.v4_urls.is_empty() {
+                let client = self.pinned_client(Ipv4Addr::UNSPECIFIED.into());
+                pools.push(Self::pool(
+                    self.v4_urls.clone(),
+                    Some(ConfigIpVersion::V4),
+                    client,
+                    &options,
+                ));
+            }
+            if !self.v6_urls.is_empty() {
+                let client = self.pinned_client(Ipv6Addr::UNSPECIFIED.into());
+                pools.push(Self::pool(
+                    self.v6_urls.clone(),
+                    Some(ConfigIpVersion::V6),
+                    client,
+                    &options,
+                ));
             }
+            pools
+        };
+
+        HttpIpSource {
+            shared: Arc::new(Shared {
+                pools,
+                current_ips: Mutex::new(HashMap::new()),
+                health: watch::Sender::new(IpSourceHealth::Unknown),
+            }),
+            version: self.version,
+            poll_interval: self.poll_interval,
+            retry_delay: self.retry_delay,
+            max_retry_delay: self.max_retry_delay.unwrap_or(self.poll_interval),
         }
+    }
+
+    fn pool(
+        urls: Vec<String>,
+        version: Option<ConfigIpVersion>,
+        client: reqwest::Client,
+        options: &PoolOptions,
+    ) -> Arc<EndpointPool> {
+        Arc::new(EndpointPool::new(urls, version, client, options.clone()))
+    }
 
-        Ok(ip)
+    fn unpinned_client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .unwrap_or_default()
+    }
+
+    /// A client whose connections are bound to `local_address`, which
+    /// restricts them to its address family
+    fn pinned_client(&self, local_address: IpAddr) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .local_address(local_address)
+            .build()
+            .unwrap_or_default()
     }
 }
 
 #[async_trait::async_trait]
 impl IpSource for HttpIpSource {
     async fn current(&self) -> Result<IpAddr> {
-        // Return cached IP if available and fresh (< 30 seconds old)
+        // Return cached IP if available, preferring IPv4
         // This reduces unnecessary HTTP requests
-        if let Some(ip) = *self.current_ip.lock().await {
-            // Cache is valid for 30 seconds
+        if let Some(ip) = self.shared.cached_ip() {
+            return Ok(ip);
+        }
+
+        self.fetch_ip().await
+    }
+
+    async fn current_for(&self, version: TraitsIpVersion) -> Result<IpAddr> {
+        if let Some(ip) = self.shared.cached_ip_for(version) {
             return Ok(ip);
         }
 
-        // Fetch fresh IP
-        let ip = self.fetch_ip().await?;
-        *self.current_ip.lock().await = Some(ip);
-        Ok(ip)
+        // Per-family URLs answer for their family only
+        let pool = self.shared.pools.iter().find(|pool| match pool.version() {
+            Some(ConfigIpVersion::V4) => version == TraitsIpVersion::V4,
+            Some(ConfigIpVersion::V6) => version == TraitsIpVersion::V6,
+            _ => false,
+        });
+        let ip = match pool {
+            Some(pool) => {
+                let result = self.shared.fetch(pool).await;
+                match &result {
+                    Ok(_) => self.shared.report(&[]),
+                    Err(e) => self.shared.report(&[e.to_string()]),
+                };
+                result?
+            }
+            None => self.fetch_ip().await?,
+        };
+
+        if ip_version(&ip) == version {
+            Ok(ip)
+        } else {
+            Err(Error::not_found(format!(
+                "HTTP IP source has no {:?} address",
+                version
+            )))
+        }
     }
 
     fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
-        let url = self.url.clone();
-        let version_filter = self.version;
+        let shared = self.shared.clone();
         let poll_interval = self.poll_interval;
-        let current_ip = self.current_ip.clone();
-        let client = self.client.clone();
+        let (retry_delay, max_retry_delay) = (self.retry_delay, self.max_retry_delay);
 
         tokio::spawn(async move {
             tracing::info!(
-                "Starting HTTP IP monitoring (url={}, interval={:?})",
-                url,
+                "Starting HTTP IP monitoring (urls={:?}, strategy={:?}, interval={:?})",
+                shared
+                    .pools
+                    .iter()
+                    .map(|pool| pool.urls())
+                    .collect::<Vec<_>>(),
+                shared.pools.first().map(|pool| pool.strategy()),
                 poll_interval
             );
 
-            let mut last_known_ip: Option<IpAddr> = None;
+            let mut last_known_ips: HashMap<TraitsIpVersion, IpAddr> = HashMap::new();
 
             loop {
-                // Fetch current IP
-                match client.get(&url).send().await {
-                    Ok(response) => {
-                        if response.status().is_success() {
-                            match response.text().await {
-                                Ok(ip_text) => {
-                                    let ip_text = ip_text.trim();
-                                    match ip_text.parse::<IpAddr>() {
-                                        Ok(ip) => {
-                                            // Filter by version if specified
-                                            let acceptable = if let Some(version) = version_filter {
-                                                match version {
-                                                    ConfigIpVersion::V4 => ip.is_ipv4(),
-                                                    ConfigIpVersion::V6 => ip.is_ipv6(),
-                                                    ConfigIpVersion::Both => true,
-                                                }
-                                            } else {
-                                                true
-                                            };
-
-                                            if acceptable && last_known_ip != Some(ip) {
-                                                tracing::info!(
-                                                    "IP changed: {:?} -> {:?}",
-                                                    last_known_ip,
-                                                    ip
-                                                );
-
-                                                let event = IpChangeEvent::new(ip, last_known_ip);
-                                                if tx.send(event).is_err() {
-                                                    tracing::error!(
-                                                        "Receiver dropped, stopping monitor"
-                                                    );
-                                                    break;
-                                                }
-
-                                                last_known_ip = Some(ip);
-                                                *current_ip.lock().await = Some(ip);
-                                            }
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!(
-                                                "Failed to parse IP address '{}': {}",
-                                                ip_text,
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Failed to read response: {}", e);
-                                }
-                            }
-                        } else {
-                            tracing::warn!("HTTP error: {}", response.status());
+                let mut errors = Vec::new();
+
+                // Fetch current IP of each family independently
+                for pool in &shared.pools {
+                    let result = tokio::select! {
+                        result = shared.fetch(pool) => result,
+                        _ = tx.closed() => {
+                            tracing::debug!("Receiver dropped, stopping monitor");
+                            return;
                         }
+                    };
+
+                    let ip = match result {
+                        Ok(ip) => ip,
+                        Err(e) => {
+                            tracing::warn!("Failed to fetch IP: {}", e);
+                            errors.push(e.to_string());
+                            continue;
+                        }
+                    };
+
+                    let version = ip_version(&ip);
+                    let previous_ip = last_known_ips.get(&version).copied();
+                    if previous_ip == Some(ip) {
+                        continue;
                     }
-                    Err(e) => {
-                        tracing::warn!("HTTP request failed: {}", e);
+
+                    tracing::info!("IP changed: {:?} -> {:?}", previous_ip, ip);
+
+                    let event = IpChangeEvent::new(ip, previous_ip);
+                    if tx.send(event).is_err() {
+                        tracing::debug!("Receiver dropped, stopping monitor");
+                        return;
                     }
+
+                    last_known_ips.insert(version, ip);
                 }
 
-                // Wait before next poll
-                tokio::time::sleep(poll_interval).await;
+                // Wait before next poll, backing off while polls keep failing
+                let delay = match shared.report(&errors) {
+                    0 => poll_interval,
+                    failures => {
+                        let delay = backoff(failures, retry_delay, max_retry_delay);
+                        tracing::warn!(
+                            "HTTP IP poll failed {} time(s) in a row, retrying in {:?}",
+                            failures,
+                            delay
+                        );
+                        delay
+                    }
+                };
+
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = tx.closed() => {
+                        tracing::debug!("Receiver dropped, stopping monitor");
+                        return;
+                    }
+                }
             }
         });
 
@@ -269,6 +807,10 @@ impl IpSource for HttpIpSource {
             None => None,
         }
     }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.shared.health.subscribe())
+    }
 }
 
 /// Factory for creating HTTP IP sources
@@ -277,14 +819,29 @@ pub struct HttpFactory;
 impl IpSourceFactory for HttpFactory {
     fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
         match config {
-            IpSourceConfig::Http { url, interval_secs } => {
-                let url = url.clone();
-                let interval = Duration::from_secs(*interval_secs);
-
-                Ok(Box::new(HttpIpSource::with_interval(
-                    url, None, // version filtering can be added later
-                    interval,
-                )))
+            IpSourceConfig::Http {
+                url,
+                urls,
+                v4_urls,
+                v6_urls,
+                strategy,
+                extractor,
+                headers,
+                interval_secs,
+            } => {
+                let source = HttpIpSource::builder()
+                    .urls(Some(url).filter(|url| !url.is_empty()).cloned())
+                    .urls(urls.iter().cloned())
+                    .v4_urls(v4_urls.iter().cloned())
+                    .v6_urls(v6_urls.iter().cloned())
+                    .strategy(*strategy)
+                    .extractor(extractor.clone())
+                    .headers(headers.clone())
+                    // version filtering can be added later
+                    .interval(Duration::from_secs(*interval_secs))
+                    .build()?;
+
+                Ok(Box::new(source))
             }
             _ => Err(Error::config("Invalid config for HTTP IP source")),
         }
@@ -306,10 +863,106 @@ mod tests {
 
         let config = IpSourceConfig::Http {
             url: "https://api.ipify.org".to_string(),
+            urls: vec!["https://icanhazip.com".to_string()],
+            v4_urls: Vec::new(),
+            v6_urls: Vec::new(),
+            strategy: HttpStrategy::Consensus { quorum: 2 },
+            extractor: HttpExtractor::KeyValue {
+                key: "ip".to_string(),
+            },
+            headers: Default::default(),
             interval_secs: 60,
         };
 
         let source = factory.create(&config);
         assert!(source.is_ok());
     }
+
+    #[test]
+    fn test_builder_defaults_to_public_services() {
+        let source = HttpIpSource::builder().build().unwrap();
+        assert_eq!(source.urls(), DEFAULT_IP_SERVICES);
+
+        let source = HttpIpSource::new("https://example.com/ip".to_string(), None);
+        assert_eq!(source.urls(), ["https://example.com/ip"]);
+    }
+
+    #[test]
+    fn test_builder_debug_redacts_header_values() {
+        let builder = HttpIpSource::builder().header("Authorization", "Bearer secret");
+        let debug = format!("{:?}", builder);
+        assert!(debug.contains("Authorization"));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_maximum() {
+        let initial = Duration::from_secs(4);
+        let max = Duration::from_secs(60);
+
+        for (failures, full) in [(1, 4), (2, 8), (3, 16), (4, 32), (5, 60), (40, 60)] {
+            let full = Duration::from_secs(full);
+            let delay = backoff(failures, initial, max);
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "{} failures: {:?} not within [{:?}, {:?}]",
+                failures,
+                delay,
+                full / 2,
+                full
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            HttpIpSource::builder()
+                .url("ftp://example.com")
+                .build()
+                .is_err()
+        );
+
+        for quorum in [0, 3] {
+            let result = HttpIpSource::builder()
+                .urls(["https://a.example", "https://b.example"])
+                .strategy(HttpStrategy::Consensus { quorum })
+                .build();
+            assert!(result.is_err(), "quorum {} should be rejected", quorum);
+        }
+
+        assert!(
+            HttpIpSource::builder()
+                .url("https://a.example")
+                .v4_url("https://b.example")
+                .build()
+                .is_err(),
+            "Family-agnostic and per-family URLs cannot be mixed"
+        );
+        assert!(
+            HttpIpSource::builder()
+                .version(Some(ConfigIpVersion::V4))
+                .v6_url("https://a.example")
+                .build()
+                .is_err()
+        );
+
+        // Each family must reach the quorum on its own
+        assert!(
+            HttpIpSource::builder()
+                .v4_urls(["https://a.example", "https://b.example"])
+                .v6_url("https://c.example")
+                .strategy(HttpStrategy::Consensus { quorum: 2 })
+                .build()
+                .is_err()
+        );
+
+        // The built-in list has three services
+        assert!(
+            HttpIpSource::builder()
+                .strategy(HttpStrategy::Consensus { quorum: 3 })
+                .build()
+                .is_ok()
+        );
+    }
 }
@@ -0,0 +1,129 @@
+//! Per-family HTTP endpoints against local stub servers
+//!
+//! IPv4 and IPv6 stubs listen on 127.0.0.1 and ::1 respectively, so a
+//! request that is not forced over the configured family cannot reach them.
+//!
+//! If this test fails, the family an address is learned for depends on how
+//! the connection happened to be made, or a change of one family is
+//! reported as (or suppressed by) a change of the other.
+
+use ddns_core::traits::{IpSource, IpVersion};
+use ddns_ip_http::HttpIpSource;
+use std::net::{IpAddr, TcpListener};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const V4: &str = "203.0.113.5";
+const V6: &str = "2001:db8::5";
+
+/// A stub IP service listening on `bind` and returning `ip`
+async fn answering(bind: &str, ip: &str) -> MockServer {
+    let server = MockServer::builder()
+        .listener(TcpListener::bind(bind).unwrap())
+        .start()
+        .await;
+    answer(&server, ip).await;
+    server
+}
+
+async fn answer(server: &MockServer, ip: &str) {
+    server.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(ip.to_string()))
+        .mount(server)
+        .await;
+}
+
+fn url(server: &MockServer) -> String {
+    format!("{}/ip", server.uri())
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn each_family_is_watched_independently() {
+    let v4_service = answering("127.0.0.1:0", V4).await;
+    let v6_service = answering("[::1]:0", V6).await;
+
+    let source = HttpIpSource::builder()
+        .v4_url(url(&v4_service))
+        .v6_url(url(&v6_service))
+        .interval(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+
+    let first = events.next().await.unwrap();
+    let second = events.next().await.unwrap();
+    assert_eq!((first.version, first.new_ip), (IpVersion::V4, ip(V4)));
+    assert_eq!((second.version, second.new_ip), (IpVersion::V6, ip(V6)));
+
+    // Only the IPv6 address changes
+    answer(&v6_service, "2001:db8::6").await;
+
+    let change = tokio::time::timeout(Duration::from_secs(2), events.next())
+        .await
+        .expect("IPv6 change is reported")
+        .unwrap();
+    assert_eq!(change.version, IpVersion::V6);
+    assert_eq!(change.previous_ip, Some(ip(V6)));
+    assert_eq!(change.new_ip, ip("2001:db8::6"));
+
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), events.next())
+            .await
+            .is_err(),
+        "The unchanged IPv4 address must not be reported again"
+    );
+}
+
+#[tokio::test]
+async fn requests_are_forced_over_their_family() {
+    let v4_service = answering("127.0.0.1:0", V4).await;
+    let v6_service = answering("[::1]:0", V6).await;
+
+    // Each family's URL points at the other family's listener
+    let source = HttpIpSource::builder()
+        .v4_url(url(&v6_service))
+        .v6_url(url(&v4_service))
+        .build()
+        .unwrap();
+
+    assert!(source.current().await.is_err());
+    assert!(v4_service.received_requests().await.unwrap().is_empty());
+    assert!(v6_service.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn current_prefers_ipv4() {
+    let v4_service = answering("127.0.0.1:0", V4).await;
+    let v6_service = answering("[::1]:0", V6).await;
+
+    let source = HttpIpSource::builder()
+        .v4_url(url(&v4_service))
+        .v6_url(url(&v6_service))
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip(V4));
+    assert!(v6_service.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn answers_of_the_wrong_family_are_rejected() {
+    let v4_service = answering("127.0.0.1:0", V6).await;
+
+    let source = HttpIpSource::builder()
+        .v4_url(url(&v4_service))
+        .build()
+        .unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("Expected IPv4"), "{}", message);
+}
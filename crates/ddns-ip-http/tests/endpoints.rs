@@ -0,0 +1,196 @@
+//! Multi-endpoint HTTP IP source against local stub servers
+//!
+//! Every strategy (failover, round-robin, consensus) is driven through
+//! healthy, failing and lying endpoints, and endpoint eviction is checked
+//! through the number of requests each stub server receives.
+//!
+//! If this test fails, a single broken or poisoned IP service may stall
+//! updates or get its address published.
+
+use ddns_core::config::HttpStrategy;
+use ddns_core::traits::IpSource;
+use ddns_ip_http::HttpIpSource;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IP: &str = "203.0.113.5";
+const WRONG_IP: &str = "198.51.100.66";
+
+/// A stub IP service answering every request with `response`
+async fn service(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+/// A stub IP service returning `ip`
+async fn answering(ip: &str) -> MockServer {
+    service(ResponseTemplate::new(200).set_body_string(format!("{}\n", ip))).await
+}
+
+/// A stub IP service that is down
+async fn failing() -> MockServer {
+    service(ResponseTemplate::new(503)).await
+}
+
+fn url(server: &MockServer) -> String {
+    format!("{}/ip", server.uri())
+}
+
+async fn requests(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap_or_default().len()
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn failover_uses_the_first_healthy_endpoint() {
+    let down = failing().await;
+    let up = answering(IP).await;
+    let spare = answering(WRONG_IP).await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&down), url(&up), url(&spare)])
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip(IP));
+    assert_eq!(requests(&down).await, 1);
+    assert_eq!(requests(&up).await, 1);
+    assert_eq!(requests(&spare).await, 0, "Later endpoints are not queried");
+}
+
+#[tokio::test]
+async fn failover_reports_every_failure() {
+    let first = failing().await;
+    let second = service(ResponseTemplate::new(200).set_body_string("not an ip")).await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&first), url(&second)])
+        .build()
+        .unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(
+        message.contains("All HTTP IP endpoints failed"),
+        "{}",
+        message
+    );
+    assert!(message.contains(&url(&first)), "{}", message);
+    assert!(message.contains("Invalid IP address"), "{}", message);
+}
+
+#[tokio::test]
+async fn round_robin_spreads_queries() {
+    let first = answering(IP).await;
+    let second = answering(IP).await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&first), url(&second)])
+        .strategy(HttpStrategy::RoundRobin)
+        .interval(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    // Each poll starts at the next endpoint
+    let mut events = source.watch();
+    assert_eq!(events.next().await.unwrap().new_ip, ip(IP));
+    tokio::time::sleep(Duration::from_millis(180)).await;
+
+    let (first, second) = (requests(&first).await, requests(&second).await);
+    assert!(first >= 2 && second >= 2, "{} / {}", first, second);
+    assert!(first.abs_diff(second) <= 1, "{} / {}", first, second);
+}
+
+#[tokio::test]
+async fn consensus_outvotes_a_lying_endpoint() {
+    let honest = answering(IP).await;
+    let liar = answering(WRONG_IP).await;
+    let also_honest = answering(IP).await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&liar), url(&honest), url(&also_honest)])
+        .strategy(HttpStrategy::Consensus { quorum: 2 })
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip(IP));
+    assert_eq!(requests(&liar).await, 1, "Every endpoint is queried");
+    assert_eq!(requests(&honest).await, 1);
+    assert_eq!(requests(&also_honest).await, 1);
+}
+
+#[tokio::test]
+async fn consensus_without_quorum_fails() {
+    let first = answering(IP).await;
+    let second = answering(WRONG_IP).await;
+    let third = failing().await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&first), url(&second), url(&third)])
+        .strategy(HttpStrategy::Consensus { quorum: 2 })
+        .build()
+        .unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("No consensus"), "{}", message);
+    assert!(
+        message.contains(IP) && message.contains(WRONG_IP),
+        "{}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn failing_endpoint_is_evicted_and_readmitted() {
+    let down = failing().await;
+    let up = answering(IP).await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&down), url(&up)])
+        .failure_threshold(2)
+        .eviction(Duration::from_millis(300))
+        .interval(Duration::from_millis(20))
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+    assert_eq!(events.next().await.unwrap().new_ip, ip(IP));
+
+    // Two failures evict the endpoint; later polls skip it
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(requests(&down).await, 2);
+    assert!(requests(&up).await >= 4);
+
+    // Once the eviction expires, the endpoint is tried again
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(requests(&down).await > 2);
+}
+
+#[tokio::test]
+async fn all_endpoints_evicted_are_tried_again() {
+    let first = failing().await;
+    let second = failing().await;
+
+    let source = HttpIpSource::builder()
+        .urls([url(&first), url(&second)])
+        .failure_threshold(1)
+        .eviction(Duration::from_secs(60))
+        .build()
+        .unwrap();
+
+    assert!(source.current().await.is_err());
+    assert!(source.current().await.is_err());
+
+    assert_eq!(requests(&first).await, 2);
+    assert_eq!(requests(&second).await, 2);
+}
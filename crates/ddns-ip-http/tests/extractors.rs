@@ -0,0 +1,192 @@
+//! Response extraction and request headers against local stub servers
+//!
+//! Each extractor is driven through a realistic response shape (JSON API,
+//! Cloudflare trace, router status page, response header).
+//!
+//! If this test fails, endpoints that wrap the address in a structured
+//! response cannot be used, or authenticated endpoints reject the source.
+
+use ddns_core::config::HttpExtractor;
+use ddns_core::traits::IpSource;
+use ddns_ip_http::HttpIpSource;
+use std::net::IpAddr;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IP: &str = "203.0.113.5";
+
+async fn service(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Query a stub answering with `response` through `extractor`
+async fn extract(
+    extractor: HttpExtractor,
+    response: ResponseTemplate,
+) -> ddns_core::Result<IpAddr> {
+    let server = service(response).await;
+
+    HttpIpSource::builder()
+        .url(format!("{}/ip", server.uri()))
+        .extractor(extractor)
+        .build()
+        .unwrap()
+        .current()
+        .await
+}
+
+#[tokio::test]
+async fn json_pointer() {
+    let result = extract(
+        HttpExtractor::JsonPointer {
+            pointer: "/client/address".to_string(),
+        },
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "client": { "address": IP, "port": 4711 }
+        })),
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), IP.parse::<IpAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn key_value_trace() {
+    let result = extract(
+        HttpExtractor::KeyValue {
+            key: "ip".to_string(),
+        },
+        ResponseTemplate::new(200).set_body_string(format!(
+            "fl=29f1\nh=example.com\nip={}\nts=1700000000.1\nvisit_scheme=https\n",
+            IP
+        )),
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), IP.parse::<IpAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn regex_on_status_page() {
+    let result = extract(
+        HttpExtractor::Regex {
+            pattern: r"<td>WAN IP</td>\s*<td>([^<]+)</td>".to_string(),
+        },
+        ResponseTemplate::new(200).set_body_string(format!(
+            "<table><tr><td>WAN IP</td> <td>{}</td></tr></table>",
+            IP
+        )),
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), IP.parse::<IpAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn response_header() {
+    let result = extract(
+        HttpExtractor::Header {
+            name: "X-Client-IP".to_string(),
+        },
+        ResponseTemplate::new(204).insert_header("x-client-ip", IP),
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), IP.parse::<IpAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn extraction_failures_are_reported() {
+    let cases = [
+        (
+            HttpExtractor::JsonPointer {
+                pointer: "/ip".to_string(),
+            },
+            ResponseTemplate::new(200).set_body_string("not json"),
+            "Failed to parse JSON",
+        ),
+        (
+            HttpExtractor::KeyValue {
+                key: "ip".to_string(),
+            },
+            ResponseTemplate::new(200).set_body_string("fl=29f1\n"),
+            "No 'ip=' line",
+        ),
+        (
+            HttpExtractor::Header {
+                name: "x-client-ip".to_string(),
+            },
+            ResponseTemplate::new(200),
+            "Missing header",
+        ),
+        (
+            HttpExtractor::JsonPointer {
+                pointer: "/ip".to_string(),
+            },
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "ip": "unknown" })),
+            "Invalid IP address",
+        ),
+    ];
+
+    for (extractor, response, fragment) in cases {
+        let message = extract(extractor.clone(), response)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains(fragment), "{:?}: {}", extractor, message);
+    }
+}
+
+#[tokio::test]
+async fn custom_headers_are_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .and(header("authorization", "Bearer secret"))
+        .and(header("x-tenant", "home"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(IP))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/ip", server.uri());
+
+    let source = HttpIpSource::builder()
+        .url(url.clone())
+        .header("Authorization", "Bearer secret")
+        .header("X-Tenant", "home")
+        .build()
+        .unwrap();
+    assert_eq!(
+        source.current().await.unwrap(),
+        IP.parse::<IpAddr>().unwrap()
+    );
+
+    let unauthenticated = HttpIpSource::builder().url(url).build().unwrap();
+    assert!(unauthenticated.current().await.is_err());
+}
+
+#[test]
+fn invalid_headers_are_rejected() {
+    assert!(
+        HttpIpSource::builder()
+            .header("bad header", "value")
+            .build()
+            .is_err()
+    );
+    assert!(
+        HttpIpSource::builder()
+            .header("x-ok", "line\nbreak")
+            .build()
+            .is_err()
+    );
+}
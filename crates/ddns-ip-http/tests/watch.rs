@@ -0,0 +1,179 @@
+//! Watch loop lifecycle against local stub servers
+//!
+//! Covers retries of failed polls, the health signal, the address cache and
+//! termination of the polling task once the stream is dropped.
+//!
+//! If this test fails, an outage delays updates by a full polling interval,
+//! goes unnoticed, or leaves a polling task running forever.
+
+use ddns_core::traits::{IpSource, IpSourceHealth};
+use ddns_ip_http::HttpIpSource;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IP: &str = "203.0.113.5";
+
+async fn respond(server: &MockServer, response: ResponseTemplate) {
+    server.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+fn answer() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_string(IP)
+}
+
+fn url(server: &MockServer) -> String {
+    format!("{}/ip", server.uri())
+}
+
+async fn requests(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap_or_default().len()
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn failed_polls_are_retried_before_the_interval() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ip"))
+        .respond_with(answer())
+        .mount(&server)
+        .await;
+
+    let source = HttpIpSource::builder()
+        .url(url(&server))
+        .interval(Duration::from_secs(3600))
+        .retry_delay(Duration::from_millis(20))
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+    let event = tokio::time::timeout(Duration::from_secs(2), events.next())
+        .await
+        .expect("The address is fetched once the service recovers")
+        .unwrap();
+    assert_eq!(event.new_ip, ip(IP));
+    assert_eq!(requests(&server).await, 3);
+}
+
+#[tokio::test]
+async fn health_follows_the_polls() {
+    let server = MockServer::start().await;
+    respond(&server, ResponseTemplate::new(503)).await;
+
+    let source = HttpIpSource::builder()
+        .url(url(&server))
+        .retry_delay(Duration::from_millis(20))
+        .build()
+        .unwrap();
+    let mut health = source.health().expect("HTTP source reports its health");
+    assert_eq!(*health.borrow(), IpSourceHealth::Unknown);
+
+    let mut events = source.watch();
+
+    // Every failed poll is counted
+    for expected in 1..=2 {
+        health.changed().await.unwrap();
+        let IpSourceHealth::Failing {
+            consecutive_failures,
+            last_error,
+        } = health.borrow_and_update().clone()
+        else {
+            panic!("Expected a failing source");
+        };
+        assert_eq!(consecutive_failures, expected);
+        assert!(last_error.contains("503"), "{}", last_error);
+    }
+
+    respond(&server, answer()).await;
+    assert_eq!(events.next().await.unwrap().new_ip, ip(IP));
+    health
+        .wait_for(|health| *health == IpSourceHealth::Healthy)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn failures_invalidate_the_cached_address() {
+    let server = MockServer::start().await;
+    respond(&server, answer()).await;
+
+    let source = HttpIpSource::builder()
+        .url(url(&server))
+        .interval(Duration::from_millis(20))
+        .build()
+        .unwrap();
+    assert_eq!(source.current().await.unwrap(), ip(IP));
+
+    respond(&server, ResponseTemplate::new(503)).await;
+    let mut health = source.health().unwrap();
+    let _events = source.watch();
+    health
+        .wait_for(|health| matches!(health, IpSourceHealth::Failing { .. }))
+        .await
+        .unwrap();
+
+    assert!(
+        source.current().await.is_err(),
+        "An address that can no longer be confirmed must not be returned"
+    );
+}
+
+#[tokio::test]
+async fn dropping_the_stream_stops_polling() {
+    let server = MockServer::start().await;
+    respond(&server, answer()).await;
+
+    let source = HttpIpSource::builder()
+        .url(url(&server))
+        .interval(Duration::from_millis(10))
+        .build()
+        .unwrap();
+
+    let mut events = source.watch();
+    events.next().await.unwrap();
+    drop(events);
+
+    // Allow an in-flight poll to complete
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let polled = requests(&server).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(requests(&server).await, polled);
+}
+
+#[tokio::test]
+async fn dropping_the_stream_cancels_a_pending_request() {
+    let server = MockServer::start().await;
+    respond(&server, answer().set_delay(Duration::from_millis(300))).await;
+
+    let source = HttpIpSource::builder()
+        .url(url(&server))
+        .interval(Duration::ZERO)
+        .build()
+        .unwrap();
+
+    let events = source.watch();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    drop(events);
+
+    // Had the request completed, the next poll would start right after
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(requests(&server).await, 1);
+}
@@ -14,6 +14,12 @@
 // This crate only works on Linux due to Netlink being a Linux-specific feature.
 // On non-Linux platforms, the factory returns an error.
 
+#[cfg(target_os = "linux")]
+mod monitor;
+
+#[cfg(target_os = "linux")]
+mod policy;
+
 use ddns_core::config::IpSourceConfig;
 
 #[cfg(target_os = "linux")]
@@ -22,6 +28,17 @@ use ddns_core::config::IpVersion as ConfigIpVersion;
 #[cfg(target_os = "linux")]
 use ddns_core::traits::{IpChangeEvent, IpSource, IpSourceFactory, IpVersion as TraitsIpVersion};
 
+#[cfg(target_os = "linux")]
+use monitor::{
+    AddressTracker, address_dump_request, is_dump_done, parse_address_messages, parse_link_changes,
+};
+
+#[cfg(target_os = "linux")]
+use policy::AddressPolicy;
+
+#[cfg(target_os = "linux")]
+use ddns_core::config::AddressSelection;
+
 #[cfg(not(target_os = "linux"))]
 use ddns_core::traits::{IpSource, IpSourceFactory};
 
@@ -44,6 +61,25 @@ use std::time::{Duration, Instant};
 #[cfg(target_os = "linux")]
 const DEFAULT_DEBOUNCE_MS: u64 = 500;
 
+/// Lower bound for the netlink receive timeout used to flush debounced changes
+#[cfg(target_os = "linux")]
+const MIN_RECV_TIMEOUT_MS: u64 = 100;
+
+/// How long `current()` waits for the kernel to answer an address dump
+#[cfg(target_os = "linux")]
+const DUMP_TIMEOUT_MS: u64 = 2000;
+
+/// Resolve an interface name to its kernel index
+///
+/// Returns 0 (which no interface uses) if the interface does not exist.
+#[cfg(target_os = "linux")]
+fn interface_index(name: &str) -> u32 {
+    match std::ffi::CString::new(name) {
+        Ok(c_name) => unsafe { libc::if_nametoindex(c_name.as_ptr()) },
+        Err(_) => 0,
+    }
+}
+
 /// Netlink-based IP source for Linux
 #[cfg(target_os = "linux")]
 pub struct NetlinkIpSource {
@@ -53,6 +89,9 @@ pub struct NetlinkIpSource {
     /// IP version to monitor
     version: Option<ConfigIpVersion>,
 
+    /// Which addresses may be published and which one is preferred
+    policy: AddressPolicy,
+
     /// Debounce window
     debounce_duration: Duration,
 }
@@ -64,6 +103,7 @@ impl NetlinkIpSource {
         Self {
             interface,
             version,
+            policy: AddressPolicy::default(),
             debounce_duration: Duration::from_millis(DEFAULT_DEBOUNCE_MS),
         }
     }
@@ -77,178 +117,158 @@ impl NetlinkIpSource {
         Self {
             interface,
             version,
+            policy: AddressPolicy::default(),
             debounce_duration,
         }
     }
 
-    /// Check if an IP address should be considered
-    fn should_accept_ip(&self, ip: &IpAddr) -> bool {
-        if ip.is_loopback() || ip.is_unspecified() {
-            return false;
-        }
-
-        if let Some(version) = self.version {
-            match version {
-                ConfigIpVersion::V4 => ip.is_ipv4(),
-                ConfigIpVersion::V6 => ip.is_ipv6(),
-                ConfigIpVersion::Both => true,
-            }
-        } else {
-            true
-        }
+    /// Use a custom address selection policy
+    ///
+    /// Fails if the selection contains invalid CIDRs or an invalid interface identifier.
+    pub fn with_selection(mut self, selection: &AddressSelection) -> Result<Self> {
+        self.policy = AddressPolicy::from_config(selection)?;
+        Ok(self)
     }
 
-    /// Query current IP addresses by reading from /proc/net/if_inet6
-    fn query_addresses_proc(&self) -> Result<Vec<IpAddr>> {
-        use std::fs::read_to_string;
-
-        let content = read_to_string("/proc/net/if_inet6")
-            .map_err(|_| Error::ip_source("Failed to read /proc/net/if_inet6"))?;
-
-        let mut addresses = Vec::new();
-
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 6 {
-                continue;
-            }
-
-            // Parse IPv6 address from /proc/net/if_inet6
-            // Format: addr index prefix_len scope status iface_name
-            let addr_hex = parts[0];
-            let if_name = parts[5];
-
-            // Filter by interface if specified
-            if let Some(ref iface) = self.interface
-                && iface != if_name
-            {
-                continue;
-            }
+    /// Dump the configured addresses on a blocking thread
+    async fn dump(&self) -> Result<AddressTracker> {
+        let tracker = self.tracker();
+        tokio::task::spawn_blocking(move || dump_addresses(tracker))
+            .await
+            .map_err(|e| Error::ip_source(format!("Netlink address dump task failed: {}", e)))?
+    }
 
-            // Parse hex IPv6 address
-            if addr_hex.len() == 32 {
-                let mut addr_bytes = [0u8; 16];
-                for (i, chunk) in (0..32).step_by(8).enumerate() {
-                    let byte_val =
-                        u32::from_str_radix(&addr_hex[chunk..chunk + 8], 16).unwrap_or(0);
-                    addr_bytes[i * 4] = (byte_val >> 24) as u8;
-                    addr_bytes[i * 4 + 1] = (byte_val >> 16) as u8;
-                    addr_bytes[i * 4 + 2] = (byte_val >> 8) as u8;
-                    addr_bytes[i * 4 + 3] = byte_val as u8;
-                }
+    /// Build a tracker with this source's filters and policy
+    ///
+    /// The interface name is resolved to its index here, once per dump.
+    fn tracker(&self) -> AddressTracker {
+        let interface_index = self.interface.as_deref().map(interface_index);
+
+        AddressTracker::new(
+            interface_index,
+            self.version,
+            self.policy.clone(),
+            self.debounce_duration,
+        )
+    }
+}
 
-                let addr = IpAddr::from(addr_bytes);
-                if self.should_accept_ip(&addr) {
-                    addresses.push(addr);
-                }
-            }
+/// Read the currently configured addresses with a one-shot RTM_GETADDR dump
+///
+/// Blocks for up to `DUMP_TIMEOUT_MS` per receive; call it off the async
+/// runtime.
+#[cfg(target_os = "linux")]
+fn dump_addresses(mut tracker: AddressTracker) -> Result<AddressTracker> {
+    use netlink_sys::{Socket, SocketAddr};
+
+    let mut sock = Socket::new(libc::NETLINK_ROUTE as isize)
+        .map_err(|e| Error::ip_source(format!("Failed to create netlink socket: {}", e)))?;
+    set_recv_timeout(&sock, Duration::from_millis(DUMP_TIMEOUT_MS))
+        .map_err(|e| Error::ip_source(format!("Failed to set netlink receive timeout: {}", e)))?;
+
+    sock.bind(&SocketAddr::new(0, 0))
+        .map_err(|e| Error::ip_source(format!("Failed to bind netlink socket: {}", e)))?;
+    sock.send(&address_dump_request(), 0)
+        .map_err(|e| Error::ip_source(format!("Failed to request address dump: {}", e)))?;
+
+    loop {
+        let (buf, _) = sock
+            .recv_from_full()
+            .map_err(|e| Error::ip_source(format!("Failed to read address dump: {}", e)))?;
+
+        for change in parse_address_messages(&buf) {
+            tracker.apply(&change);
         }
 
-        // Also read IPv4 addresses from /proc/net/dev (parse via ifconfig or similar)
-        // For simplicity, we'll use a different approach for IPv4
-        self.query_ipv4_addresses(&mut addresses);
-
-        if addresses.is_empty() {
-            Err(Error::not_found("No suitable IP addresses found"))
-        } else {
-            Ok(addresses)
+        if buf.is_empty() || is_dump_done(&buf) {
+            return Ok(tracker);
         }
     }
+}
 
-    /// Query IPv4 addresses using ioctl
-    fn query_ipv4_addresses(&self, addresses: &mut Vec<IpAddr>) {
-        unsafe {
-            // For each interface, get IPv4 addresses using ioctl
-            let interfaces_to_check: Vec<String> = if let Some(ref iface) = self.interface {
-                vec![iface.clone()]
-            } else {
-                // Read all interfaces from /proc/net/dev
-                match self.read_all_interfaces() {
-                    Ok(ifaces) => ifaces,
-                    Err(_) => return,
-                }
-            };
-
-            for iface_name in interfaces_to_check {
-                let mut ifreq: libc::ifreq = std::mem::zeroed();
-                std::ptr::copy_nonoverlapping(
-                    iface_name.as_bytes().as_ptr() as *const i8,
-                    ifreq.ifr_name.as_mut_ptr(),
-                    iface_name.len().min(libc::IFNAMSIZ - 1),
-                );
-
-                let sock = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
-                if sock < 0 {
-                    continue;
-                }
-
-                // Get IPv4 address
-                let ret = libc::ioctl(sock, libc::SIOCGIFADDR, &ifreq);
-                libc::close(sock);
-
-                if ret == 0 {
-                    // Parse sockaddr
-                    let addr = &ifreq.ifr_ifru.ifru_addr;
-                    if addr.sa_family == libc::AF_INET as u16 {
-                        let sin = &*(addr as *const libc::sockaddr as *const libc::sockaddr_in);
-                        let ip = u32::from_be(sin.sin_addr.s_addr);
-                        let ip_addr: IpAddr = std::net::Ipv4Addr::from(ip).into();
-                        if self.should_accept_ip(&ip_addr) {
-                            addresses.push(ip_addr);
-                        }
-                    }
-                }
-            }
-        }
+/// Set an integer or struct socket option, checking the result
+#[cfg(target_os = "linux")]
+fn set_socket_option<T>(
+    sock: &netlink_sys::Socket,
+    option: libc::c_int,
+    value: &T,
+) -> std::io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            sock.as_raw_fd(),
+            libc::SOL_SOCKET,
+            option,
+            value as *const T as *const libc::c_void,
+            std::mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
     }
+}
 
-    /// Read all interface names from /proc/net/dev
-    fn read_all_interfaces(&self) -> Result<Vec<String>> {
-        use std::fs::read_to_string;
-
-        let content = read_to_string("/proc/net/dev")
-            .map_err(|_| Error::ip_source("Failed to read /proc/net/dev"))?;
-
-        let mut interfaces = Vec::new();
+/// Set SO_RCVTIMEO on a netlink socket
+#[cfg(target_os = "linux")]
+fn set_recv_timeout(sock: &netlink_sys::Socket, timeout: Duration) -> std::io::Result<()> {
+    let timeval = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+    };
+    set_socket_option(sock, libc::SO_RCVTIMEO, &timeval)
+}
 
-        // Skip header lines
-        for line in content.lines().skip(2) {
-            let line = line.trim();
-            if let Some(colon_pos) = line.find(':') {
-                let iface_name = line[..colon_pos].trim();
-                if !iface_name.is_empty() {
-                    interfaces.push(iface_name.to_string());
-                }
-            }
-        }
+/// Resolve the configured interface and point the tracker at it
+///
+/// Called before every (re)dump. If the interface is gone, the tracker drops
+/// its candidates and matches nothing until the interface reappears.
+#[cfg(target_os = "linux")]
+fn resolve_interface(tracker: &mut AddressTracker, name: &str) {
+    let index = interface_index(name);
+    if tracker.interface_index() == Some(index) {
+        return;
+    }
 
-        Ok(interfaces)
+    if index == 0 {
+        tracing::warn!(
+            "Interface {} does not exist; waiting for it to appear",
+            name
+        );
+    } else {
+        tracing::debug!("Interface {} has index {}", name, index);
     }
+    tracker.set_interface_index(Some(index));
 }
 
 #[cfg(target_os = "linux")]
 #[async_trait::async_trait]
 impl IpSource for NetlinkIpSource {
     async fn current(&self) -> Result<IpAddr> {
-        let addresses = self.query_addresses_proc()?;
+        let tracker = self.dump().await?;
 
         // Prefer IPv4 over IPv6 if both are available
-        let addr = addresses
-            .iter()
-            .find(|ip| ip.is_ipv4())
-            .or_else(|| addresses.first())
-            .ok_or_else(|| Error::not_found("No IP addresses found"))?;
+        tracker
+            .selected(TraitsIpVersion::V4)
+            .or_else(|| tracker.selected(TraitsIpVersion::V6))
+            .ok_or_else(|| Error::not_found("No suitable IP addresses found"))
+    }
 
-        Ok(*addr)
+    async fn current_for(&self, version: TraitsIpVersion) -> Result<IpAddr> {
+        self.dump()
+            .await?
+            .selected(version)
+            .ok_or_else(|| Error::not_found(format!("No suitable {:?} address found", version)))
     }
 
     fn watch(&self) -> Pin<Box<dyn tokio_stream::Stream<Item = IpChangeEvent> + Send + 'static>> {
         use netlink_sys::{Socket, SocketAddr};
         use tokio_stream::wrappers::UnboundedReceiverStream;
 
-        let _interface = self.interface.clone();
-        let _version = self.version;
+        let interface = self.interface.clone();
         let debounce_duration = self.debounce_duration;
+        let mut tracker = self.tracker();
 
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -264,85 +284,123 @@ impl IpSource for NetlinkIpSource {
                 }
             };
 
-            // Set receive buffer size
-            let fd = sock.as_raw_fd();
+            // Set receive buffer size; the default only means overruns
+            // (and re-dumps) come sooner
             let bufsize: i32 = 8192 * 8;
-            unsafe {
-                libc::setsockopt(
-                    fd,
-                    libc::SOL_SOCKET,
-                    libc::SO_RCVBUF,
-                    &bufsize as *const i32 as *const libc::c_void,
-                    std::mem::size_of::<i32>() as libc::socklen_t,
-                );
+            if let Err(e) = set_socket_option(&sock, libc::SO_RCVBUF, &bufsize) {
+                tracing::warn!("Failed to set netlink receive buffer size: {}", e);
+            }
+
+            // Wake up periodically even without traffic, so that debounced
+            // changes get flushed and a dropped stream stops the thread
+            if let Err(e) = set_recv_timeout(
+                &sock,
+                debounce_duration.max(Duration::from_millis(MIN_RECV_TIMEOUT_MS)),
+            ) {
+                tracing::error!("Failed to set netlink receive timeout: {}", e);
+                drop(tx);
+                return;
             }
 
-            // Bind to address
-            let addr = SocketAddr::new(
-                0,
-                (libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR) as u32,
-            );
+            // Bind to address; link notifications tell when the configured
+            // interface disappears or is re-created under a new index
+            let mut groups = libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR;
+            if interface.is_some() {
+                groups |= libc::RTMGRP_LINK;
+            }
+            let addr = SocketAddr::new(0, groups as u32);
             if let Err(e) = sock.bind(&addr) {
                 tracing::error!("Failed to bind netlink socket: {}", e);
                 drop(tx);
                 return;
             }
 
-            tracing::info!("Netlink IP monitoring started");
-
-            let _last_ip: Option<IpAddr> = None;
-            let mut last_event = Instant::now() - Duration::from_secs(60);
+            // Replay the existing addresses as RTM_NEWADDR so the tracker
+            // knows what is already configured (and can fail over on removal)
+            if let Some(ref name) = interface {
+                resolve_interface(&mut tracker, name);
+            }
+            if let Err(e) = sock.send(&address_dump_request(), 0) {
+                tracing::error!("Failed to request netlink address dump: {}", e);
+                drop(tx);
+                return;
+            }
 
-            // Receive loop
-            let mut recv_buf = vec![0u8; 8192];
+            tracing::info!("Netlink IP monitoring started");
 
-            loop {
-                match sock.recv(&mut recv_buf, 0) {
-                    Ok(nread) => {
-                        if nread == 0 {
+            'recv: loop {
+                match sock.recv_from_full() {
+                    Ok((buf, _)) => {
+                        if buf.is_empty() {
                             break;
                         }
 
-                        // Parse netlink message header to get message type
-                        // RTM_NEWADDR = 20, RTM_DELADDR = 21
-                        if nread >= 16 {
-                            // nlmsghdr is at least 16 bytes
-                            let msg_type = recv_buf[4];
-
-                            if msg_type == libc::RTM_NEWADDR as u8
-                                || msg_type == libc::RTM_DELADDR as u8
+                        // The interface may disappear, or be created (or
+                        // re-created under a new index) after startup
+                        if let Some(ref name) = interface
+                            && !parse_link_changes(&buf).is_empty()
+                        {
+                            let previous = tracker.interface_index();
+                            resolve_interface(&mut tracker, name);
+
+                            if tracker.interface_index() != previous
+                                && tracker.interface_index() != Some(0)
+                                && let Err(e) = sock.send(&address_dump_request(), 0)
                             {
-                                // For now, trigger a re-query of all IPs on any address change
-                                // This is simpler than parsing the full netlink message
-                                tracing::debug!("Netlink address change event received");
-
-                                let now = Instant::now();
-
-                                // Apply debounce
-                                if now.duration_since(last_event) > debounce_duration {
-                                    // In a real implementation, we would parse the netlink message
-                                    // to extract the new IP address. For now, we'll emit a placeholder
-                                    // to show that the mechanism works.
-
-                                    // TODO: Parse netlink message to extract actual IP
-                                    // For now, just log the event
-                                    tracing::info!(
-                                        "Address change detected (parsing not yet implemented)"
-                                    );
-
-                                    last_event = now;
-                                }
+                                tracing::error!("Failed to request netlink address dump: {}", e);
+                                break;
+                            }
+                        }
+
+                        for change in parse_address_messages(&buf) {
+                            if tracker.apply(&change) {
+                                tracing::debug!(
+                                    "Netlink address {:?}: {} (ifindex {})",
+                                    change.kind,
+                                    change.ip,
+                                    change.interface_index
+                                );
                             }
                         }
                     }
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            std::io::ErrorKind::WouldBlock
+                                | std::io::ErrorKind::TimedOut
+                                | std::io::ErrorKind::Interrupted
+                        ) => {}
+                    Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                        // The kernel dropped notifications; resynchronise
+                        tracing::warn!("Netlink receive buffer overrun, re-reading addresses");
+                        if let Some(ref name) = interface {
+                            resolve_interface(&mut tracker, name);
+                        }
+                        if let Err(e) = sock.send(&address_dump_request(), 0) {
+                            tracing::error!("Failed to request netlink address dump: {}", e);
+                            break;
+                        }
+                    }
                     Err(e) => {
                         tracing::error!("Netlink recv error: {}", e);
                         break;
                     }
                 }
 
-                // Small sleep to prevent tight loop on errors
-                std::thread::sleep(Duration::from_millis(100));
+                if tracker.has_pending() {
+                    for event in tracker.poll(Instant::now()) {
+                        tracing::info!("IP changed: {:?} -> {}", event.previous_ip, event.new_ip);
+
+                        if tx.send(event).is_err() {
+                            break 'recv;
+                        }
+                    }
+                }
+
+                if tx.is_closed() {
+                    tracing::debug!("Receiver dropped, stopping monitor");
+                    break;
+                }
             }
 
             tracing::info!("Netlink IP monitoring stopped");
@@ -368,9 +426,13 @@ pub struct NetlinkFactory;
 impl IpSourceFactory for NetlinkFactory {
     fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
         match config {
-            IpSourceConfig::Netlink { interface, version } => {
-                Ok(Box::new(NetlinkIpSource::new(interface.clone(), *version)))
-            }
+            IpSourceConfig::Netlink {
+                interface,
+                version,
+                selection,
+            } => Ok(Box::new(
+                NetlinkIpSource::new(interface.clone(), *version).with_selection(selection)?,
+            )),
             _ => Err(Error::config("Invalid config for Netlink IP source")),
         }
     }
@@ -394,6 +456,9 @@ pub fn register(registry: &ProviderRegistry) {
 mod tests {
     use super::*;
 
+    #[cfg(target_os = "linux")]
+    use monitor::accepts_ip;
+
     #[test]
     #[cfg(target_os = "linux")]
     fn test_factory_creation() {
@@ -402,12 +467,30 @@ mod tests {
         let config = IpSourceConfig::Netlink {
             interface: Some("eth0".to_string()),
             version: Some(ConfigIpVersion::V4),
+            selection: Default::default(),
         };
 
         let source = factory.create(&config);
         assert!(source.is_ok());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_factory_rejects_invalid_selection() {
+        let factory = NetlinkFactory;
+
+        let config = IpSourceConfig::Netlink {
+            interface: None,
+            version: None,
+            selection: AddressSelection {
+                allow_cidrs: vec!["not-a-cidr".to_string()],
+                ..Default::default()
+            },
+        };
+
+        assert!(factory.create(&config).is_err());
+    }
+
     #[test]
     #[cfg(not(target_os = "linux"))]
     fn test_factory_unsupported() {
@@ -416,6 +499,7 @@ mod tests {
         let config = IpSourceConfig::Netlink {
             interface: None,
             version: None,
+            selection: Default::default(),
         };
 
         let source = factory.create(&config);
@@ -424,36 +508,39 @@ mod tests {
 
     #[test]
     #[cfg(target_os = "linux")]
-    fn test_should_accept_ip() {
-        let source = NetlinkIpSource::new(None, Some(ConfigIpVersion::V4));
+    fn test_accepts_ip() {
+        let version = Some(ConfigIpVersion::V4);
 
         // IPv4 addresses
-        assert!(source.should_accept_ip(&"192.168.1.1".parse().unwrap()));
-        assert!(source.should_accept_ip(&"8.8.8.8".parse().unwrap()));
+        assert!(accepts_ip(version, &"192.168.1.1".parse().unwrap()));
+        assert!(accepts_ip(version, &"8.8.8.8".parse().unwrap()));
 
         // IPv6 addresses
-        assert!(!source.should_accept_ip(&"2001:db8::1".parse().unwrap()));
+        assert!(!accepts_ip(version, &"2001:db8::1".parse().unwrap()));
 
         // Special addresses
-        assert!(!source.should_accept_ip(&"127.0.0.1".parse().unwrap())); // loopback
-        assert!(!source.should_accept_ip(&"0.0.0.0".parse().unwrap())); // unspecified
+        assert!(!accepts_ip(version, &"127.0.0.1".parse().unwrap())); // loopback
+        assert!(!accepts_ip(version, &"0.0.0.0".parse().unwrap())); // unspecified
     }
 
     #[test]
     #[cfg(target_os = "linux")]
-    fn test_should_accept_ip_v6() {
-        let source = NetlinkIpSource::new(None, Some(ConfigIpVersion::V6));
+    fn test_accepts_ip_v6() {
+        let version = Some(ConfigIpVersion::V6);
 
         // IPv4 addresses
-        assert!(!source.should_accept_ip(&"192.168.1.1".parse().unwrap()));
+        assert!(!accepts_ip(version, &"192.168.1.1".parse().unwrap()));
 
         // IPv6 addresses
-        assert!(source.should_accept_ip(&"2001:db8::1".parse().unwrap()));
-        assert!(source.should_accept_ip(&"2001:4860:4860::8888".parse().unwrap()));
+        assert!(accepts_ip(version, &"2001:db8::1".parse().unwrap()));
+        assert!(accepts_ip(
+            version,
+            &"2001:4860:4860::8888".parse().unwrap()
+        ));
 
         // Special addresses
-        assert!(!source.should_accept_ip(&"::1".parse().unwrap())); // loopback
-        assert!(!source.should_accept_ip(&"::".parse().unwrap())); // unspecified
+        assert!(!accepts_ip(version, &"::1".parse().unwrap())); // loopback
+        assert!(!accepts_ip(version, &"::".parse().unwrap())); // unspecified
     }
 
     #[test]
@@ -0,0 +1,717 @@
+// # Netlink Address Monitor
+//
+// Decoding and bookkeeping for RTM_NEWADDR / RTM_DELADDR notifications.
+//
+// The receive thread in `NetlinkIpSource::watch()` owns the socket; this module
+// is deliberately free of I/O so that captured netlink buffers can be fed through
+// the parser and tracker in unit tests without touching the host's interfaces.
+//
+// ## Flow
+//
+// ```text
+// recv() bytes ─► parse_address_messages() ─► AddressTracker::apply() ─► AddressTracker::poll() ─► IpChangeEvent
+// ```
+
+use ddns_core::config::IpVersion as ConfigIpVersion;
+use ddns_core::traits::{IpChangeEvent, IpVersion as TraitsIpVersion};
+
+use netlink_packet_core::{NLM_F_DUMP, NLM_F_REQUEST, NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::nlas::address::Nla;
+use netlink_packet_route::{AddressMessage, RtnlMessage};
+
+use crate::policy::AddressPolicy;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
+
+/// Whether an address was added to or removed from an interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AddressChangeKind {
+    /// RTM_NEWADDR
+    Added,
+    /// RTM_DELADDR
+    Removed,
+}
+
+/// A decoded RTM_NEWADDR / RTM_DELADDR notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AddressChange {
+    /// Added or removed
+    pub kind: AddressChangeKind,
+    /// The interface address (IFA_LOCAL, falling back to IFA_ADDRESS)
+    pub ip: IpAddr,
+    /// Kernel interface index (`ifa_index`)
+    pub interface_index: u32,
+    /// Prefix length (`ifa_prefixlen`)
+    pub prefix_len: u8,
+    /// Address scope (`ifa_scope`, RT_SCOPE_*)
+    pub scope: u8,
+    /// Address flags (IFA_F_*), taken from IFA_FLAGS when present
+    pub flags: u32,
+}
+
+/// Decode every address notification contained in a netlink receive buffer
+///
+/// A single `recv()` may return several netlink messages back to back (dump
+/// responses always do). Messages that are not address notifications, or that
+/// fail to decode, are skipped.
+pub(crate) fn parse_address_messages(buf: &[u8]) -> Vec<AddressChange> {
+    netlink_messages(buf)
+        .into_iter()
+        .filter_map(|message| match message.payload {
+            NetlinkPayload::InnerMessage(RtnlMessage::NewAddress(msg)) => {
+                decode_address(AddressChangeKind::Added, &msg)
+            }
+            NetlinkPayload::InnerMessage(RtnlMessage::DelAddress(msg)) => {
+                decode_address(AddressChangeKind::Removed, &msg)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Interface indexes of every RTM_NEWLINK / RTM_DELLINK in a receive buffer
+pub(crate) fn parse_link_changes(buf: &[u8]) -> Vec<u32> {
+    netlink_messages(buf)
+        .into_iter()
+        .filter_map(|message| match message.payload {
+            NetlinkPayload::InnerMessage(RtnlMessage::NewLink(msg))
+            | NetlinkPayload::InnerMessage(RtnlMessage::DelLink(msg)) => Some(msg.header.index),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check whether a receive buffer contains the NLMSG_DONE terminating a dump
+pub(crate) fn is_dump_done(buf: &[u8]) -> bool {
+    netlink_messages(buf)
+        .iter()
+        .any(|message| matches!(message.payload, NetlinkPayload::Done(_)))
+}
+
+/// Split a receive buffer into its netlink messages
+fn netlink_messages(buf: &[u8]) -> Vec<NetlinkMessage<RtnlMessage>> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+
+    while offset < buf.len() {
+        let message = match NetlinkMessage::<RtnlMessage>::deserialize(&buf[offset..]) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!("Failed to decode netlink message: {}", e);
+                break;
+            }
+        };
+
+        let length = message.header.length as usize;
+        if length == 0 {
+            break;
+        }
+        messages.push(message);
+
+        // Netlink messages are aligned to 4 bytes (NLMSG_ALIGN)
+        offset += (length + 3) & !3;
+    }
+
+    messages
+}
+
+/// Extract the address and attributes from an `ifaddrmsg`
+fn decode_address(kind: AddressChangeKind, msg: &AddressMessage) -> Option<AddressChange> {
+    let mut address = None;
+    let mut local = None;
+    let mut flags = u32::from(msg.header.flags);
+
+    for nla in &msg.nlas {
+        match nla {
+            Nla::Address(bytes) => address = bytes_to_ip(msg.header.family, bytes),
+            Nla::Local(bytes) => local = bytes_to_ip(msg.header.family, bytes),
+            Nla::Flags(value) => flags = *value,
+            _ => {}
+        }
+    }
+
+    // For point-to-point links IFA_ADDRESS is the peer; IFA_LOCAL is ours
+    let ip = local.or(address)?;
+
+    Some(AddressChange {
+        kind,
+        ip,
+        interface_index: msg.header.index,
+        prefix_len: msg.header.prefix_len,
+        scope: msg.header.scope,
+        flags,
+    })
+}
+
+/// Convert raw attribute bytes to an address of the given family
+fn bytes_to_ip(family: u8, bytes: &[u8]) -> Option<IpAddr> {
+    match i32::from(family) {
+        libc::AF_INET => {
+            let octets: [u8; 4] = bytes.try_into().ok()?;
+            Some(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        libc::AF_INET6 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Build an RTM_GETADDR dump request for all address families
+///
+/// Sending this after binding to the multicast groups makes the kernel replay
+/// every existing address as RTM_NEWADDR, so the tracker is seeded through the
+/// same code path as live notifications.
+pub(crate) fn address_dump_request() -> Vec<u8> {
+    let mut message = NetlinkMessage::from(RtnlMessage::GetAddress(AddressMessage::default()));
+    message.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+    message.header.sequence_number = 1;
+    message.finalize();
+
+    let mut buf = vec![0u8; message.buffer_len()];
+    message.serialize(&mut buf);
+    buf
+}
+
+/// Check if an address matches the configured version filter
+///
+/// Loopback and unspecified addresses are never accepted.
+pub(crate) fn accepts_ip(version: Option<ConfigIpVersion>, ip: &IpAddr) -> bool {
+    if ip.is_loopback() || ip.is_unspecified() {
+        return false;
+    }
+
+    match version {
+        Some(ConfigIpVersion::V4) => ip.is_ipv4(),
+        Some(ConfigIpVersion::V6) => ip.is_ipv6(),
+        Some(ConfigIpVersion::Both) | None => true,
+    }
+}
+
+/// Map an address to its family
+fn family_of(ip: &IpAddr) -> TraitsIpVersion {
+    match ip {
+        IpAddr::V4(_) => TraitsIpVersion::V4,
+        IpAddr::V6(_) => TraitsIpVersion::V6,
+    }
+}
+
+/// Tracks the addresses present on the monitored interface(s)
+///
+/// State is kept per address family: the set of candidate addresses (in the
+/// order the kernel reported them) and the address that was last published.
+/// Which candidate is published is decided by the [`AddressPolicy`]; among
+/// equally ranked candidates the published address stays selected for as long
+/// as it exists, so adding a secondary address does not cause churn. When it
+/// is removed (or stops qualifying) the tracker fails over to the next one.
+///
+/// # Debounce
+///
+/// Notifications are always applied immediately, but at most one event per
+/// family is emitted within the debounce window. Anything suppressed is
+/// flushed by a later `poll()` once the window has elapsed (trailing edge),
+/// so the final state after a flap is never lost.
+pub(crate) struct AddressTracker {
+    /// Only accept addresses on this interface index (None = all interfaces)
+    interface_index: Option<u32>,
+
+    /// IP version filter
+    version: Option<ConfigIpVersion>,
+
+    /// Address selection policy
+    policy: AddressPolicy,
+
+    /// Debounce window
+    debounce: Duration,
+
+    /// Candidate addresses per family, in arrival order
+    known: HashMap<TraitsIpVersion, Vec<AddressChange>>,
+
+    /// Last address emitted per family
+    published: HashMap<TraitsIpVersion, IpAddr>,
+
+    /// When an event was last emitted per family
+    last_emitted: HashMap<TraitsIpVersion, Instant>,
+}
+
+impl AddressTracker {
+    /// Create a new tracker
+    pub(crate) fn new(
+        interface_index: Option<u32>,
+        version: Option<ConfigIpVersion>,
+        policy: AddressPolicy,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            interface_index,
+            version,
+            policy,
+            debounce,
+            known: HashMap::new(),
+            published: HashMap::new(),
+            last_emitted: HashMap::new(),
+        }
+    }
+
+    /// The interface index filter (None = all interfaces)
+    pub(crate) fn interface_index(&self) -> Option<u32> {
+        self.interface_index
+    }
+
+    /// Update the interface index filter (interfaces may appear after startup)
+    ///
+    /// Candidates on any other interface are dropped: the interface they were
+    /// seen on is gone or was replaced, and its RTM_DELADDR may never match.
+    pub(crate) fn set_interface_index(&mut self, interface_index: Option<u32>) {
+        self.interface_index = interface_index;
+
+        if let Some(index) = interface_index {
+            for candidates in self.known.values_mut() {
+                candidates.retain(|candidate| candidate.interface_index == index);
+            }
+        }
+    }
+
+    /// Apply a decoded notification to the candidate set
+    ///
+    /// Returns `true` if the notification passed the filters and changed state.
+    pub(crate) fn apply(&mut self, change: &AddressChange) -> bool {
+        if let Some(index) = self.interface_index
+            && index != change.interface_index
+        {
+            return false;
+        }
+
+        if !accepts_ip(self.version, &change.ip) {
+            return false;
+        }
+
+        let candidates = self.known.entry(family_of(&change.ip)).or_default();
+
+        let existing = candidates
+            .iter()
+            .position(|candidate| candidate.ip == change.ip);
+
+        match (change.kind, existing) {
+            (AddressChangeKind::Added, Some(index)) => {
+                // RTM_NEWADDR is also sent when flags change (DAD completes,
+                // address becomes deprecated, ...)
+                if candidates[index] == *change {
+                    return false;
+                }
+                candidates[index] = change.clone();
+            }
+            (AddressChangeKind::Added, None) => candidates.push(change.clone()),
+            (AddressChangeKind::Removed, Some(index)) => {
+                candidates.remove(index);
+            }
+            (AddressChangeKind::Removed, None) => return false,
+        }
+
+        true
+    }
+
+    /// The address that should currently be published for a family
+    pub(crate) fn selected(&self, family: TraitsIpVersion) -> Option<IpAddr> {
+        let candidates = self.known.get(&family)?;
+
+        self.policy
+            .select(candidates, self.published.get(&family).copied())
+    }
+
+    /// Emit events for every family whose desired address changed
+    ///
+    /// Families still inside their debounce window are left pending.
+    pub(crate) fn poll(&mut self, now: Instant) -> Vec<IpChangeEvent> {
+        let mut events = Vec::new();
+
+        for family in [TraitsIpVersion::V4, TraitsIpVersion::V6] {
+            let Some(desired) = self.selected(family) else {
+                // Every address of this family is gone. There is nothing to
+                // publish; keep the last published address so the next one
+                // to appear is reported relative to it.
+                continue;
+            };
+
+            let previous = self.published.get(&family).copied();
+            if previous == Some(desired) {
+                continue;
+            }
+
+            if let Some(last) = self.last_emitted.get(&family)
+                && now.duration_since(*last) < self.debounce
+            {
+                continue;
+            }
+
+            events.push(IpChangeEvent::new(desired, previous));
+            self.published.insert(family, desired);
+            self.last_emitted.insert(family, now);
+        }
+
+        events
+    }
+
+    /// Whether a change is waiting for its debounce window to elapse
+    pub(crate) fn has_pending(&self) -> bool {
+        [TraitsIpVersion::V4, TraitsIpVersion::V6]
+            .into_iter()
+            .any(|family| {
+                self.selected(family)
+                    .is_some_and(|desired| self.published.get(&family) != Some(&desired))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RTM_NEWADDR for 203.0.113.5/24 on ifindex 2 ("eth0"), as captured
+    /// from `ip monitor address` style traffic.
+    const NEWADDR_V4: &[u8] = &[
+        // nlmsghdr: len=60 type=RTM_NEWADDR flags=0 seq=0 pid=0
+        0x3c, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, //
+        // ifaddrmsg: family=AF_INET prefixlen=24 flags=IFA_F_PERMANENT scope=0 index=2
+        0x02, 0x18, 0x80, 0x00, 0x02, 0x00, 0x00, 0x00, //
+        // IFA_ADDRESS 203.0.113.5
+        0x08, 0x00, 0x01, 0x00, 0xcb, 0x00, 0x71, 0x05, //
+        // IFA_LOCAL 203.0.113.5
+        0x08, 0x00, 0x02, 0x00, 0xcb, 0x00, 0x71, 0x05, //
+        // IFA_LABEL "eth0"
+        0x09, 0x00, 0x03, 0x00, 0x65, 0x74, 0x68, 0x30, 0x00, 0x00, 0x00, 0x00, //
+        // IFA_FLAGS IFA_F_PERMANENT
+        0x08, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00,
+    ];
+
+    /// RTM_DELADDR for 203.0.113.5/24 on ifindex 2
+    const DELADDR_V4: &[u8] = &[
+        0x3c, 0x00, 0x00, 0x00, 0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, //
+        0x02, 0x18, 0x80, 0x00, 0x02, 0x00, 0x00, 0x00, //
+        0x08, 0x00, 0x01, 0x00, 0xcb, 0x00, 0x71, 0x05, //
+        0x08, 0x00, 0x02, 0x00, 0xcb, 0x00, 0x71, 0x05, //
+        0x09, 0x00, 0x03, 0x00, 0x65, 0x74, 0x68, 0x30, 0x00, 0x00, 0x00, 0x00, //
+        0x08, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWADDR for 198.51.100.7/24 on ifindex 3 ("wan0"), point-to-point
+    /// style: IFA_ADDRESS carries the peer, IFA_LOCAL our address.
+    const NEWADDR_V4_PTP: &[u8] = &[
+        0x3c, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, //
+        0x02, 0x18, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, //
+        // IFA_ADDRESS 198.51.100.1 (peer)
+        0x08, 0x00, 0x01, 0x00, 0xc6, 0x33, 0x64, 0x01, //
+        // IFA_LOCAL 198.51.100.7
+        0x08, 0x00, 0x02, 0x00, 0xc6, 0x33, 0x64, 0x07, //
+        0x09, 0x00, 0x03, 0x00, 0x77, 0x61, 0x6e, 0x30, 0x00, 0x00, 0x00, 0x00, //
+        0x08, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWADDR for 2001:db8::10/64 on ifindex 2 (IFA_ADDRESS only, like
+    /// the kernel sends for IPv6)
+    const NEWADDR_V6: &[u8] = &[
+        // nlmsghdr: len=52 type=RTM_NEWADDR
+        0x34, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, //
+        // ifaddrmsg: family=AF_INET6 prefixlen=64 flags=0 scope=0 index=2
+        0x0a, 0x40, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, //
+        // IFA_ADDRESS 2001:db8::10
+        0x14, 0x00, 0x01, 0x00, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x10, //
+        // IFA_FLAGS IFA_F_MANAGETEMPADDR | IFA_F_PERMANENT
+        0x08, 0x00, 0x08, 0x00, 0x80, 0x01, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWLINK (not an address message, must be ignored)
+    const NEWLINK: &[u8] = &[
+        0x20, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, //
+        // ifinfomsg: family=0 type=1 index=2 flags=0 change=0
+        0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    fn v4(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn change(kind: AddressChangeKind, ip: &str, interface_index: u32) -> AddressChange {
+        AddressChange {
+            kind,
+            ip: ip.parse().unwrap(),
+            interface_index,
+            prefix_len: 24,
+            scope: 0,
+            flags: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_newaddr_v4() {
+        let changes = parse_address_messages(NEWADDR_V4);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, AddressChangeKind::Added);
+        assert_eq!(changes[0].ip, v4("203.0.113.5"));
+        assert_eq!(changes[0].interface_index, 2);
+        assert_eq!(changes[0].prefix_len, 24);
+        assert_eq!(changes[0].flags, 0x80);
+    }
+
+    #[test]
+    fn test_parse_deladdr_v4() {
+        let changes = parse_address_messages(DELADDR_V4);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, AddressChangeKind::Removed);
+        assert_eq!(changes[0].ip, v4("203.0.113.5"));
+    }
+
+    #[test]
+    fn test_parse_prefers_ifa_local() {
+        let changes = parse_address_messages(NEWADDR_V4_PTP);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].ip, v4("198.51.100.7"));
+        assert_eq!(changes[0].interface_index, 3);
+    }
+
+    #[test]
+    fn test_parse_newaddr_v6() {
+        let changes = parse_address_messages(NEWADDR_V6);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].ip, "2001:db8::10".parse::<IpAddr>().unwrap());
+        assert_eq!(changes[0].prefix_len, 64);
+        assert_eq!(changes[0].flags, 0x180);
+    }
+
+    #[test]
+    fn test_parse_multiple_messages_in_one_buffer() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(NEWLINK);
+        buf.extend_from_slice(NEWADDR_V4);
+        buf.extend_from_slice(NEWADDR_V6);
+
+        let changes = parse_address_messages(&buf);
+
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].ip.is_ipv4());
+        assert!(changes[1].ip.is_ipv6());
+    }
+
+    #[test]
+    fn test_parse_ignores_garbage() {
+        assert!(parse_address_messages(&[]).is_empty());
+        assert!(parse_address_messages(&[0xff; 7]).is_empty());
+        assert!(parse_address_messages(&NEWADDR_V4[..20]).is_empty());
+    }
+
+    #[test]
+    fn test_dump_request_roundtrip() {
+        let buf = address_dump_request();
+        let message = NetlinkMessage::<RtnlMessage>::deserialize(&buf).unwrap();
+
+        assert_eq!(message.header.flags, NLM_F_REQUEST | NLM_F_DUMP);
+        assert!(matches!(
+            message.payload,
+            NetlinkPayload::InnerMessage(RtnlMessage::GetAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_tracker_emits_from_captured_buffers() {
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), Duration::ZERO);
+        let now = Instant::now();
+
+        for change in parse_address_messages(NEWADDR_V4) {
+            tracker.apply(&change);
+        }
+        let events = tracker.poll(now);
+        assert_eq!(events, vec![IpChangeEvent::new(v4("203.0.113.5"), None)]);
+
+        for change in parse_address_messages(NEWADDR_V4_PTP) {
+            tracker.apply(&change);
+        }
+        // Secondary address does not displace the published one
+        assert!(tracker.poll(now).is_empty());
+
+        for change in parse_address_messages(DELADDR_V4) {
+            tracker.apply(&change);
+        }
+        // Removal fails over to the remaining address
+        let events = tracker.poll(now);
+        assert_eq!(
+            events,
+            vec![IpChangeEvent::new(
+                v4("198.51.100.7"),
+                Some(v4("203.0.113.5"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_tracker_tracks_families_independently() {
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), Duration::ZERO);
+
+        for change in parse_address_messages(NEWADDR_V4)
+            .into_iter()
+            .chain(parse_address_messages(NEWADDR_V6))
+        {
+            tracker.apply(&change);
+        }
+
+        let events = tracker.poll(Instant::now());
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].version, TraitsIpVersion::V4);
+        assert_eq!(events[0].previous_ip, None);
+        assert_eq!(events[1].version, TraitsIpVersion::V6);
+        assert_eq!(events[1].previous_ip, None);
+    }
+
+    #[test]
+    fn test_tracker_interface_filter() {
+        let mut tracker =
+            AddressTracker::new(Some(3), None, AddressPolicy::default(), Duration::ZERO);
+
+        assert!(!tracker.apply(&change(AddressChangeKind::Added, "203.0.113.5", 2)));
+        assert!(tracker.apply(&change(AddressChangeKind::Added, "198.51.100.7", 3)));
+
+        let events = tracker.poll(Instant::now());
+        assert_eq!(events, vec![IpChangeEvent::new(v4("198.51.100.7"), None)]);
+    }
+
+    #[test]
+    fn test_parse_link_changes() {
+        assert_eq!(parse_link_changes(NEWLINK), vec![2]);
+        assert!(parse_link_changes(NEWADDR_V4).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_drops_candidates_of_vanished_interface() {
+        let mut tracker =
+            AddressTracker::new(Some(3), None, AddressPolicy::default(), Duration::ZERO);
+        let now = Instant::now();
+
+        tracker.apply(&change(AddressChangeKind::Added, "198.51.100.7", 3));
+        assert_eq!(tracker.poll(now).len(), 1);
+
+        // The interface is gone (no index resolves to 0)
+        tracker.set_interface_index(Some(0));
+        assert_eq!(tracker.selected(TraitsIpVersion::V4), None);
+        assert!(!tracker.has_pending());
+
+        // It is re-created under a new index
+        tracker.set_interface_index(Some(4));
+        assert!(tracker.apply(&change(AddressChangeKind::Added, "198.51.100.9", 4)));
+        assert_eq!(
+            tracker.poll(now),
+            vec![IpChangeEvent::new(
+                v4("198.51.100.9"),
+                Some(v4("198.51.100.7"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_tracker_version_filter() {
+        let mut tracker = AddressTracker::new(
+            None,
+            Some(ConfigIpVersion::V6),
+            AddressPolicy::default(),
+            Duration::ZERO,
+        );
+
+        for change in parse_address_messages(NEWADDR_V4) {
+            assert!(!tracker.apply(&change));
+        }
+        for change in parse_address_messages(NEWADDR_V6) {
+            assert!(tracker.apply(&change));
+        }
+
+        let events = tracker.poll(Instant::now());
+        assert_eq!(events.len(), 1);
+        assert!(events[0].new_ip.is_ipv6());
+    }
+
+    #[test]
+    fn test_tracker_ignores_loopback() {
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), Duration::ZERO);
+
+        assert!(!tracker.apply(&change(AddressChangeKind::Added, "127.0.0.1", 1)));
+        assert!(tracker.poll(Instant::now()).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_removing_last_address_emits_nothing() {
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), Duration::ZERO);
+        let now = Instant::now();
+
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.5", 2));
+        assert_eq!(tracker.poll(now).len(), 1);
+
+        tracker.apply(&change(AddressChangeKind::Removed, "203.0.113.5", 2));
+        assert!(tracker.poll(now).is_empty());
+        assert!(!tracker.has_pending());
+
+        // The next address is reported relative to the last published one
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.9", 2));
+        let events = tracker.poll(now);
+        assert_eq!(
+            events,
+            vec![IpChangeEvent::new(
+                v4("203.0.113.9"),
+                Some(v4("203.0.113.5"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_tracker_debounce_is_trailing_edge() {
+        let debounce = Duration::from_millis(500);
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), debounce);
+        let start = Instant::now();
+
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.5", 2));
+        assert_eq!(tracker.poll(start).len(), 1);
+
+        // Flap inside the debounce window: nothing is emitted yet
+        tracker.apply(&change(AddressChangeKind::Removed, "203.0.113.5", 2));
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.6", 2));
+        assert!(tracker.poll(start + Duration::from_millis(100)).is_empty());
+        assert!(tracker.has_pending());
+
+        // Once the window elapses the final state is flushed
+        let events = tracker.poll(start + debounce);
+        assert_eq!(
+            events,
+            vec![IpChangeEvent::new(
+                v4("203.0.113.6"),
+                Some(v4("203.0.113.5"))
+            )]
+        );
+        assert!(!tracker.has_pending());
+    }
+
+    #[test]
+    fn test_tracker_flap_back_to_published_is_silent() {
+        let debounce = Duration::from_millis(500);
+        let mut tracker = AddressTracker::new(None, None, AddressPolicy::default(), debounce);
+        let start = Instant::now();
+
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.5", 2));
+        assert_eq!(tracker.poll(start).len(), 1);
+
+        tracker.apply(&change(AddressChangeKind::Removed, "203.0.113.5", 2));
+        tracker.apply(&change(AddressChangeKind::Added, "203.0.113.5", 2));
+
+        assert!(!tracker.has_pending());
+        assert!(tracker.poll(start + debounce).is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+// # Address Selection Policy
+//
+// Decides which of an interface's addresses may be published, and which one
+// is preferred when several qualify.
+//
+// The same policy is used by `NetlinkIpSource::current()` (on a one-shot
+// address dump) and by the `watch()` thread (on live notifications), so both
+// always agree on the selected address.
+//
+// ## Rules
+//
+// Rejected outright:
+// - Tentative or DAD-failed addresses (not usable yet / at all)
+// - Non-global scope or non-globally-routable addresses (if `global_only`)
+// - IPv6 temporary addresses (unless `allow_temporary`)
+// - IPv6 deprecated addresses (unless `allow_deprecated`)
+// - Addresses outside `allow_cidrs` (if set) or inside `deny_cidrs`
+//
+// Ranking of the remaining candidates (best first):
+// 1. Matches the configured interface identifier
+// 2. Modified EUI-64 interface identifier (if `prefer_eui64`)
+// 3. Not deprecated
+// 4. Not temporary
+// 5. Arrival order
+
+use crate::monitor::AddressChange;
+
+use ddns_core::config::AddressSelection;
+use ddns_core::net::Cidr;
+use ddns_core::{Error, Result};
+
+use netlink_packet_route::constants::{
+    IFA_F_DADFAILED, IFA_F_DEPRECATED, IFA_F_TEMPORARY, IFA_F_TENTATIVE, RT_SCOPE_UNIVERSE,
+};
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Compiled form of [`AddressSelection`]
+#[derive(Debug, Clone)]
+pub(crate) struct AddressPolicy {
+    global_only: bool,
+    allow_temporary: bool,
+    allow_deprecated: bool,
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+    prefer_eui64: bool,
+    /// Lower 64 bits of the preferred IPv6 address
+    interface_id: Option<u64>,
+}
+
+impl AddressPolicy {
+    /// Build a policy from configuration
+    pub(crate) fn from_config(selection: &AddressSelection) -> Result<Self> {
+        let allow = selection
+            .allow_cidrs
+            .iter()
+            .map(|cidr| Cidr::parse(cidr))
+            .collect::<Result<Vec<_>>>()?;
+        let deny = selection
+            .deny_cidrs
+            .iter()
+            .map(|cidr| Cidr::parse(cidr))
+            .collect::<Result<Vec<_>>>()?;
+
+        let interface_id = match selection.interface_id {
+            Some(ref id) => {
+                let id: Ipv6Addr = id
+                    .parse()
+                    .map_err(|_| Error::config(format!("Invalid interface identifier '{}'", id)))?;
+                Some(u128::from(id) as u64)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            global_only: selection.global_only,
+            allow_temporary: selection.allow_temporary,
+            allow_deprecated: selection.allow_deprecated,
+            allow,
+            deny,
+            prefer_eui64: selection.prefer_eui64,
+            interface_id,
+        })
+    }
+
+    /// Check whether a candidate may be published at all
+    pub(crate) fn accepts(&self, candidate: &AddressChange) -> bool {
+        if candidate.flags & (IFA_F_TENTATIVE | IFA_F_DADFAILED) != 0 {
+            return false;
+        }
+
+        if self.global_only && (candidate.scope != RT_SCOPE_UNIVERSE || !is_global(&candidate.ip)) {
+            return false;
+        }
+
+        // IFA_F_TEMPORARY shares its value with IFA_F_SECONDARY, which is
+        // meaningless for selection on IPv4
+        if candidate.ip.is_ipv6() {
+            if !self.allow_temporary && is_temporary(candidate) {
+                return false;
+            }
+            if !self.allow_deprecated && is_deprecated(candidate) {
+                return false;
+            }
+        }
+
+        if self.deny.iter().any(|cidr| cidr.contains(&candidate.ip)) {
+            return false;
+        }
+
+        self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(&candidate.ip))
+    }
+
+    /// Preference rank of an accepted candidate (higher is better)
+    fn rank(&self, candidate: &AddressChange) -> (bool, bool, bool, bool) {
+        let IpAddr::V6(ip) = candidate.ip else {
+            return (false, false, true, true);
+        };
+
+        let identifier = u128::from(ip) as u64;
+
+        (
+            self.interface_id == Some(identifier),
+            self.prefer_eui64 && is_eui64(&ip),
+            !is_deprecated(candidate),
+            !is_temporary(candidate),
+        )
+    }
+
+    /// Pick the address to publish from a family's candidates
+    ///
+    /// `current` is the address published so far; it is kept when it is still
+    /// among the best-ranked candidates to avoid needless DNS updates.
+    pub(crate) fn select(
+        &self,
+        candidates: &[AddressChange],
+        current: Option<IpAddr>,
+    ) -> Option<IpAddr> {
+        let accepted: Vec<&AddressChange> = candidates
+            .iter()
+            .filter(|candidate| self.accepts(candidate))
+            .collect();
+
+        let best = accepted
+            .iter()
+            .map(|candidate| self.rank(candidate))
+            .max()?;
+        let mut best_candidates = accepted
+            .iter()
+            .filter(|candidate| self.rank(candidate) == best)
+            .map(|candidate| candidate.ip);
+
+        match current {
+            Some(current) if best_candidates.clone().any(|ip| ip == current) => Some(current),
+            _ => best_candidates.next(),
+        }
+    }
+}
+
+impl Default for AddressPolicy {
+    fn default() -> Self {
+        Self::from_config(&AddressSelection::default()).expect("default address selection is valid")
+    }
+}
+
+fn is_temporary(candidate: &AddressChange) -> bool {
+    candidate.flags & IFA_F_TEMPORARY != 0
+}
+
+fn is_deprecated(candidate: &AddressChange) -> bool {
+    candidate.flags & IFA_F_DEPRECATED != 0
+}
+
+/// Modified EUI-64 identifiers carry 0xfffe in the middle (RFC 4291 §2.5.1)
+fn is_eui64(ip: &Ipv6Addr) -> bool {
+    let octets = ip.octets();
+    octets[11] == 0xff && octets[12] == 0xfe
+}
+
+/// Check whether an address is globally routable
+fn is_global(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_global_v4(ip),
+        // Global unicast is 2000::/3; this excludes link-local, ULA,
+        // site-local, multicast, loopback and the IPv4-mapped ranges
+        IpAddr::V6(ip) => ip.segments()[0] & 0xe000 == 0x2000,
+    }
+}
+
+fn is_global_v4(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // "This network" 0.0.0.0/8
+        || octets[0] == 0
+        // Shared address space (CGNAT) 100.64.0.0/10
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        // IETF protocol assignments 192.0.0.0/24
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // Reserved 240.0.0.0/4
+        || octets[0] >= 240)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::AddressChangeKind;
+
+    fn candidate(ip: &str, scope: u8, flags: u32) -> AddressChange {
+        AddressChange {
+            kind: AddressChangeKind::Added,
+            ip: ip.parse().unwrap(),
+            interface_index: 2,
+            prefix_len: 64,
+            scope,
+            flags,
+        }
+    }
+
+    fn global(ip: &str) -> AddressChange {
+        candidate(ip, RT_SCOPE_UNIVERSE, 0)
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_default_rejects_non_global() {
+        let policy = AddressPolicy::default();
+
+        for addr in [
+            "10.0.0.5",
+            "172.16.4.1",
+            "192.168.1.10",
+            "100.64.0.1",
+            "169.254.1.1",
+            "fe80::1",
+            "fd00::1",
+            "fec0::1",
+            "::ffff:203.0.113.5",
+        ] {
+            assert!(
+                !policy.accepts(&global(addr)),
+                "{} should be rejected",
+                addr
+            );
+        }
+
+        assert!(policy.accepts(&global("8.8.8.8")));
+        assert!(policy.accepts(&global("2606:4700::1111")));
+    }
+
+    #[test]
+    fn test_default_rejects_non_universe_scope() {
+        let policy = AddressPolicy::default();
+        let link_scope = 253;
+
+        assert!(!policy.accepts(&candidate("2606:4700::1111", link_scope, 0)));
+    }
+
+    #[test]
+    fn test_global_only_disabled_accepts_private() {
+        let policy = AddressPolicy::from_config(&AddressSelection {
+            global_only: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(policy.accepts(&global("192.168.1.10")));
+        assert!(policy.accepts(&candidate("fe80::1", 253, 0)));
+    }
+
+    #[test]
+    fn test_rejects_tentative_and_dadfailed() {
+        let policy = AddressPolicy::default();
+
+        assert!(!policy.accepts(&candidate("2606:4700::1", 0, IFA_F_TENTATIVE)));
+        assert!(!policy.accepts(&candidate("2606:4700::1", 0, IFA_F_DADFAILED)));
+    }
+
+    #[test]
+    fn test_temporary_and_deprecated_rejected_by_default() {
+        let policy = AddressPolicy::default();
+
+        assert!(!policy.accepts(&candidate("2606:4700::2", 0, IFA_F_TEMPORARY)));
+        assert!(!policy.accepts(&candidate("2606:4700::3", 0, IFA_F_DEPRECATED)));
+
+        // IFA_F_SECONDARY has the same value as IFA_F_TEMPORARY
+        assert!(policy.accepts(&candidate("8.8.8.8", 0, IFA_F_TEMPORARY)));
+    }
+
+    #[test]
+    fn test_stable_preferred_over_temporary_and_deprecated() {
+        let policy = AddressPolicy::from_config(&AddressSelection {
+            allow_temporary: true,
+            allow_deprecated: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let candidates = vec![
+            candidate("2606:4700::2", 0, IFA_F_TEMPORARY),
+            candidate("2606:4700::3", 0, IFA_F_DEPRECATED),
+            candidate("2606:4700::4", 0, 0),
+        ];
+        assert_eq!(policy.select(&candidates, None), Some(ip("2606:4700::4")));
+
+        // Only temporary and deprecated left: deprecated ranks lowest
+        assert_eq!(
+            policy.select(&candidates[..2], None),
+            Some(ip("2606:4700::2"))
+        );
+    }
+
+    #[test]
+    fn test_cidr_allow_and_deny() {
+        let policy = AddressPolicy::from_config(&AddressSelection {
+            allow_cidrs: vec!["2606:4700::/32".to_string()],
+            deny_cidrs: vec!["2606:4700:1::/48".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(policy.accepts(&global("2606:4700::1")));
+        assert!(!policy.accepts(&global("2606:4700:1::1")));
+        assert!(!policy.accepts(&global("2a00:1450::1")));
+        assert!(!policy.accepts(&global("8.8.8.8")));
+    }
+
+    #[test]
+    fn test_invalid_cidr_rejected() {
+        for cidr in ["10.0.0.0", "10.0.0.0/33", "fe80::/129", "nonsense/8"] {
+            let selection = AddressSelection {
+                deny_cidrs: vec![cidr.to_string()],
+                ..Default::default()
+            };
+            assert!(AddressPolicy::from_config(&selection).is_err(), "{}", cidr);
+        }
+    }
+
+    #[test]
+    fn test_prefer_eui64() {
+        let policy = AddressPolicy::from_config(&AddressSelection {
+            prefer_eui64: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let candidates = vec![
+            global("2606:4700::1234"),
+            global("2606:4700::211:22ff:fe33:4455"),
+        ];
+        assert_eq!(
+            policy.select(&candidates, Some(ip("2606:4700::1234"))),
+            Some(ip("2606:4700::211:22ff:fe33:4455"))
+        );
+    }
+
+    #[test]
+    fn test_prefer_interface_id() {
+        let policy = AddressPolicy::from_config(&AddressSelection {
+            interface_id: Some("::1234".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let candidates = vec![global("2606:4700::abcd"), global("2606:4700::1234")];
+        assert_eq!(
+            policy.select(&candidates, None),
+            Some(ip("2606:4700::1234"))
+        );
+    }
+
+    #[test]
+    fn test_select_keeps_current_among_equals() {
+        let policy = AddressPolicy::default();
+        let candidates = vec![global("8.8.8.8"), global("1.1.1.1")];
+
+        assert_eq!(policy.select(&candidates, None), Some(ip("8.8.8.8")));
+        assert_eq!(
+            policy.select(&candidates, Some(ip("1.1.1.1"))),
+            Some(ip("1.1.1.1"))
+        );
+    }
+
+    #[test]
+    fn test_select_nothing_acceptable() {
+        let policy = AddressPolicy::default();
+        let candidates = vec![global("192.168.1.10"), global("fe80::1")];
+
+        assert_eq!(policy.select(&candidates, None), None);
+    }
+}
@@ -0,0 +1,564 @@
+// # STUN IP Source
+//
+// This crate provides an IP source that learns the public address from
+// STUN servers (RFC 5389).
+//
+// ## Purpose
+//
+// A STUN Binding Request is a single small UDP datagram, and the answer
+// carries the address the request was seen from (XOR-MAPPED-ADDRESS). That
+// makes STUN a lightweight alternative to HTTP IP services: no HTTP client,
+// no TLS, no response parsing, which matters on small devices.
+//
+// ## Architecture
+//
+// Servers are tried in configured order; the first valid answer wins. Each
+// address family is queried over a socket of that family, so the answer is
+// the public address of that family. With both families enabled, each one
+// is tracked independently and the watch loop emits a separate
+// `IpChangeEvent` per family.
+//
+// Requests are retransmitted with a doubling timeout, as STUN over UDP
+// requires (RFC 5389, section 7.2.1), but with fewer attempts. Responses
+// that do not match the request's transaction ID are ignored.
+//
+// STUN servers do not push changes, so they are polled at a configurable
+// interval. Like the HTTP source, this source reports its health
+// (`IpSource::health`) and stops polling as soon as the stream returned by
+// `watch()` is dropped.
+//
+// ## Trust
+//
+// A STUN server can lie about the address just like an HTTP service. Only
+// use servers you trust, e.g. your own or a large public provider.
+
+mod message;
+
+use ddns_core::ProviderRegistry;
+use ddns_core::config::{IpSourceConfig, IpVersion as ConfigIpVersion};
+use ddns_core::traits::{
+    HealthReporter, IpChangeEvent, IpSource, IpSourceFactory, IpSourceHealth,
+    IpVersion as TraitsIpVersion,
+};
+use ddns_core::{Error, Result};
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Default STUN port
+pub const DEFAULT_STUN_PORT: u16 = 3478;
+
+/// Default polling interval
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default retransmission timeout of the first request
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Requests sent to a server before moving on to the next one
+const ATTEMPTS: u32 = 3;
+
+/// Address family of an IP address
+fn ip_version(ip: &IpAddr) -> TraitsIpVersion {
+    match ip {
+        IpAddr::V4(_) => TraitsIpVersion::V4,
+        IpAddr::V6(_) => TraitsIpVersion::V6,
+    }
+}
+
+/// Split `host[:port]` into host and port
+///
+/// IPv6 literals need brackets to carry a port (`[2001:db8::1]:3478`).
+fn split_server(server: &str) -> (&str, u16) {
+    if server.parse::<IpAddr>().is_ok() {
+        return (server, DEFAULT_STUN_PORT);
+    }
+    if let Some((host, port)) = server.rsplit_once(':')
+        && let Ok(port) = port.parse()
+    {
+        return (host.trim_start_matches('[').trim_end_matches(']'), port);
+    }
+    let host = server
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(server);
+    (host, DEFAULT_STUN_PORT)
+}
+
+/// STUN-based IP source
+pub struct StunIpSource {
+    /// State shared with the watch loop
+    shared: Arc<Shared>,
+
+    /// IP version to monitor
+    version: Option<ConfigIpVersion>,
+
+    /// Polling interval
+    poll_interval: Duration,
+}
+
+/// Servers, cache and health of a [`StunIpSource`]
+struct Shared {
+    /// Servers as `host[:port]`, in priority order
+    servers: Vec<String>,
+
+    /// Families to query, IPv4 first
+    families: Vec<TraitsIpVersion>,
+
+    /// Retransmission timeout of the first request
+    timeout: Duration,
+
+    /// Current IP address per family (cached)
+    current_ips: Mutex<HashMap<TraitsIpVersion, IpAddr>>,
+
+    /// Health signal
+    health: watch::Sender<IpSourceHealth>,
+}
+
+impl Shared {
+    /// Ask the servers for the public address of `family` and update the
+    /// cache
+    ///
+    /// This is the only place addresses are fetched.
+    async fn fetch(&self, family: TraitsIpVersion) -> Result<IpAddr> {
+        let mut errors = Vec::new();
+        let mut result = None;
+
+        'servers: for server in &self.servers {
+            let (host, port) = split_server(server);
+            let addresses = match tokio::net::lookup_host((host, port)).await {
+                Ok(addresses) => addresses.filter(|address| ip_version(&address.ip()) == family),
+                Err(e) => {
+                    errors.push(format!("{}: {}", server, e));
+                    continue;
+                }
+            };
+
+            let mut resolved = false;
+            for address in addresses {
+                resolved = true;
+                match self.query(address).await {
+                    Ok(ip) => {
+                        result = Some(ip);
+                        break 'servers;
+                    }
+                    Err(e) => {
+                        tracing::warn!("STUN server {} ({}) failed: {}", server, address, e);
+                        errors.push(format!("{}: {}", server, e));
+                    }
+                }
+            }
+            if !resolved {
+                errors.push(format!("{}: no {:?} address", server, family));
+            }
+        }
+
+        let mut current_ips = self.current_ips.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Some(ip) => {
+                current_ips.insert(family, ip);
+                Ok(ip)
+            }
+            None => {
+                // The address of this family can no longer be confirmed
+                current_ips.remove(&family);
+                Err(Error::ip_source(format!(
+                    "All STUN servers failed ({})",
+                    errors.join("; ")
+                )))
+            }
+        }
+    }
+
+    /// Send a Binding Request to `server` and return the mapped address
+    async fn query(&self, server: SocketAddr) -> Result<IpAddr> {
+        let bind: SocketAddr = match server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(bind)
+            .await
+            .map_err(|e| Error::ip_source(format!("Failed to open STUN socket: {}", e)))?;
+        socket
+            .connect(server)
+            .await
+            .map_err(|e| Error::ip_source(format!("Failed to reach STUN server: {}", e)))?;
+
+        let transaction_id = message::transaction_id()?;
+        let request = message::binding_request(&transaction_id);
+
+        let mut timeout = self.timeout;
+        let mut buffer = [0u8; 576];
+        for _ in 0..ATTEMPTS {
+            socket
+                .send(&request)
+                .await
+                .map_err(|e| Error::ip_source(format!("Failed to send STUN request: {}", e)))?;
+
+            let response = tokio::time::timeout(timeout, async {
+                loop {
+                    let len = socket.recv(&mut buffer).await?;
+                    if message::is_response_to(&buffer[..len], &transaction_id) {
+                        return Ok::<_, std::io::Error>(len);
+                    }
+                }
+            })
+            .await;
+
+            match response {
+                Ok(Ok(len)) => {
+                    let ip = message::mapped_address(&buffer[..len], &transaction_id)?;
+                    if ip_version(&ip) != ip_version(&server.ip()) {
+                        return Err(Error::ip_source(format!(
+                            "STUN server mapped the request to an address of another family: {}",
+                            ip
+                        )));
+                    }
+                    return Ok(ip);
+                }
+                Ok(Err(e)) => {
+                    return Err(Error::ip_source(format!("STUN request failed: {}", e)));
+                }
+                Err(_) => timeout *= 2,
+            }
+        }
+
+        Err(Error::ip_source("STUN server did not answer"))
+    }
+
+    /// Record the outcome of a poll
+    fn report(&self, errors: &[String]) {
+        if errors.is_empty() {
+            self.health.record_success();
+        } else {
+            self.health.record_failure(errors.join("; "));
+        }
+    }
+
+    fn cached_ip(&self) -> Option<IpAddr> {
+        let current_ips = self.current_ips.lock().unwrap_or_else(|e| e.into_inner());
+        self.families
+            .iter()
+            .find_map(|family| current_ips.get(family))
+            .copied()
+    }
+
+    fn cached_ip_for(&self, family: TraitsIpVersion) -> Option<IpAddr> {
+        self.current_ips
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&family)
+            .copied()
+    }
+}
+
+impl StunIpSource {
+    /// Create a STUN IP source querying `servers` in order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `servers` is empty.
+    pub fn new<I, S>(servers: I, version: Option<ConfigIpVersion>) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::builder().servers(servers).version(version).build()
+    }
+
+    /// Create a builder for a STUN IP source
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ddns_core::config::IpVersion;
+    /// use ddns_ip_stun::StunIpSource;
+    ///
+    /// let source = StunIpSource::builder()
+    ///     .server("stun.l.google.com:19302")
+    ///     .server("stun.cloudflare.com")
+    ///     .version(Some(IpVersion::V4))
+    ///     .build()?;
+    /// # Ok::<(), ddns_core::Error>(())
+    /// ```
+    pub fn builder() -> StunIpSourceBuilder {
+        StunIpSourceBuilder::new()
+    }
+}
+
+/// Builder for [`StunIpSource`]
+#[derive(Debug, Clone)]
+pub struct StunIpSourceBuilder {
+    servers: Vec<String>,
+    version: Option<ConfigIpVersion>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl StunIpSourceBuilder {
+    fn new() -> Self {
+        Self {
+            servers: Vec::new(),
+            version: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Add a server as `host[:port]` (default port 3478)
+    pub fn server(mut self, server: impl Into<String>) -> Self {
+        self.servers.push(server.into());
+        self
+    }
+
+    /// Add several servers
+    pub fn servers<I, S>(mut self, servers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.servers.extend(servers.into_iter().map(Into::into));
+        self
+    }
+
+    /// IP version to monitor (None = both)
+    pub fn version(mut self, version: Option<ConfigIpVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Polling interval (default: 60s)
+    pub fn interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Retransmission timeout of the first request (default: 500ms)
+    ///
+    /// The timeout doubles with every retransmission.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Build the IP source
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if no server is configured.
+    pub fn build(self) -> Result<StunIpSource> {
+        if self.servers.is_empty() {
+            return Err(Error::config("STUN IP source needs at least one server"));
+        }
+        if let Some(server) = self.servers.iter().find(|server| server.trim().is_empty()) {
+            return Err(Error::config(format!("Invalid STUN server: '{}'", server)));
+        }
+
+        let families = match self.version {
+            Some(ConfigIpVersion::V4) => vec![TraitsIpVersion::V4],
+            Some(ConfigIpVersion::V6) => vec![TraitsIpVersion::V6],
+            Some(ConfigIpVersion::Both) | None => vec![TraitsIpVersion::V4, TraitsIpVersion::V6],
+        };
+
+        Ok(StunIpSource {
+            shared: Arc::new(Shared {
+                servers: self.servers,
+                families,
+                timeout: self.timeout,
+                current_ips: Mutex::new(HashMap::new()),
+                health: watch::Sender::new(IpSourceHealth::Unknown),
+            }),
+            version: self.version,
+            poll_interval: self.poll_interval,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl IpSource for StunIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        // Return cached IP if available, preferring IPv4
+        if let Some(ip) = self.shared.cached_ip() {
+            return Ok(ip);
+        }
+
+        // The first family that answers wins
+        let mut errors = Vec::new();
+        for family in &self.shared.families {
+            match self.shared.fetch(*family).await {
+                Ok(ip) => {
+                    self.shared.report(&[]);
+                    return Ok(ip);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        self.shared.report(&errors);
+        Err(Error::ip_source(errors.join("; ")))
+    }
+
+    async fn current_for(&self, version: TraitsIpVersion) -> Result<IpAddr> {
+        if !self.shared.families.contains(&version) {
+            return Err(Error::not_found(format!(
+                "STUN IP source does not query {:?} addresses",
+                version
+            )));
+        }
+
+        if let Some(ip) = self.shared.cached_ip_for(version) {
+            return Ok(ip);
+        }
+
+        let result = self.shared.fetch(version).await;
+        match &result {
+            Ok(_) => self.shared.report(&[]),
+            Err(e) => self.shared.report(&[e.to_string()]),
+        }
+        result
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let shared = self.shared.clone();
+        let poll_interval = self.poll_interval;
+
+        tokio::spawn(async move {
+            tracing::info!(
+                "Starting STUN IP monitoring (servers={:?}, families={:?}, interval={:?})",
+                shared.servers,
+                shared.families,
+                poll_interval
+            );
+
+            let mut last_known_ips: HashMap<TraitsIpVersion, IpAddr> = HashMap::new();
+
+            'poll: loop {
+                let mut errors = Vec::new();
+
+                // Query each family independently
+                for family in &shared.families {
+                    let result = tokio::select! {
+                        result = shared.fetch(*family) => result,
+                        _ = tx.closed() => break 'poll,
+                    };
+
+                    let ip = match result {
+                        Ok(ip) => ip,
+                        Err(e) => {
+                            tracing::warn!("Failed to fetch {:?} address: {}", family, e);
+                            errors.push(e.to_string());
+                            continue;
+                        }
+                    };
+
+                    let previous_ip = last_known_ips.get(family).copied();
+                    if previous_ip == Some(ip) {
+                        continue;
+                    }
+
+                    tracing::info!("IP changed: {:?} -> {:?}", previous_ip, ip);
+                    if tx.send(IpChangeEvent::new(ip, previous_ip)).is_err() {
+                        break 'poll;
+                    }
+                    last_known_ips.insert(*family, ip);
+                }
+
+                shared.report(&errors);
+
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = tx.closed() => break,
+                }
+            }
+
+            tracing::debug!("Receiver dropped, stopping monitor");
+        });
+
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn version(&self) -> Option<TraitsIpVersion> {
+        match self.version {
+            Some(ConfigIpVersion::V4) => Some(TraitsIpVersion::V4),
+            Some(ConfigIpVersion::V6) => Some(TraitsIpVersion::V6),
+            Some(ConfigIpVersion::Both) | None => None,
+        }
+    }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.shared.health.subscribe())
+    }
+}
+
+/// Factory for creating STUN IP sources
+pub struct StunFactory;
+
+impl IpSourceFactory for StunFactory {
+    fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        match config {
+            IpSourceConfig::Stun {
+                servers,
+                version,
+                interval_secs,
+            } => {
+                let source = StunIpSource::builder()
+                    .servers(servers.iter().cloned())
+                    .version(*version)
+                    .interval(Duration::from_secs(*interval_secs))
+                    .build()?;
+
+                Ok(Box::new(source))
+            }
+            _ => Err(Error::config("Invalid config for STUN IP source")),
+        }
+    }
+}
+
+/// Register the STUN IP source with a registry
+pub fn register(registry: &ProviderRegistry) {
+    registry.register_ip_source("stun", Box::new(StunFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_creation() {
+        let config = IpSourceConfig::Stun {
+            servers: vec!["stun.l.google.com:19302".to_string()],
+            version: Some(ConfigIpVersion::V4),
+            interval_secs: 60,
+        };
+        assert!(StunFactory.create(&config).is_ok());
+
+        let config = IpSourceConfig::Stun {
+            servers: Vec::new(),
+            version: None,
+            interval_secs: 60,
+        };
+        assert!(StunFactory.create(&config).is_err());
+    }
+
+    #[test]
+    fn test_split_server() {
+        assert_eq!(
+            split_server("stun.l.google.com:19302"),
+            ("stun.l.google.com", 19302)
+        );
+        assert_eq!(split_server("stun.example.com"), ("stun.example.com", 3478));
+        assert_eq!(split_server("192.0.2.1:5349"), ("192.0.2.1", 5349));
+        assert_eq!(split_server("2001:db8::1"), ("2001:db8::1", 3478));
+        assert_eq!(split_server("[2001:db8::1]:5349"), ("2001:db8::1", 5349));
+        assert_eq!(split_server("[2001:db8::1]"), ("2001:db8::1", 3478));
+    }
+}
@@ -0,0 +1,275 @@
+// # STUN Messages
+//
+// Encoding of Binding Requests and decoding of Binding Responses
+// (RFC 5389, section 6 and 15).
+//
+// ## Format
+//
+// ```text
+//  0                   1                   2                   3
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |0 0|     STUN Message Type     |         Message Length        |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                         Magic Cookie                          |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                     Transaction ID (96 bits)                  |
+// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+// |                  Attributes (type, length, value)             |
+// ```
+//
+// The mapped address is read from XOR-MAPPED-ADDRESS. Servers that predate
+// RFC 5389 only send MAPPED-ADDRESS, which is used as a fallback.
+
+use ddns_core::{Error, Result};
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Fixed value in every RFC 5389 message
+const MAGIC_COOKIE: u32 = 0x2112_A442;
+
+/// Size of the message header
+const HEADER_LEN: usize = 20;
+
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const BINDING_ERROR: u16 = 0x0111;
+
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_ERROR_CODE: u16 = 0x0009;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+
+const FAMILY_IPV4: u8 = 0x01;
+const FAMILY_IPV6: u8 = 0x02;
+
+/// A 96-bit transaction ID
+pub(crate) type TransactionId = [u8; 12];
+
+/// Generate a random transaction ID
+///
+/// Drawn from the operating system's CSPRNG: an off-path attacker who can
+/// guess the ID can forge the mapped address.
+pub(crate) fn transaction_id() -> Result<TransactionId> {
+    let mut id = [0u8; 12];
+    getrandom::fill(&mut id).map_err(|e| {
+        Error::ip_source(format!("Failed to generate a STUN transaction ID: {}", e))
+    })?;
+    Ok(id)
+}
+
+/// Encode a Binding Request without attributes
+pub(crate) fn binding_request(transaction_id: &TransactionId) -> [u8; HEADER_LEN] {
+    let mut request = [0u8; HEADER_LEN];
+    request[0..2].copy_from_slice(&BINDING_REQUEST.to_be_bytes());
+    // Message length 0
+    request[4..8].copy_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    request[8..20].copy_from_slice(transaction_id);
+    request
+}
+
+/// Whether `message` is a response to the request with `transaction_id`
+///
+/// Anything else (stray or late datagrams) should be ignored.
+pub(crate) fn is_response_to(message: &[u8], transaction_id: &TransactionId) -> bool {
+    message.len() >= HEADER_LEN
+        && message[4..8] == MAGIC_COOKIE.to_be_bytes()
+        && message[8..20] == *transaction_id
+}
+
+/// Decode the mapped address from a Binding Response
+///
+/// # Errors
+///
+/// Returns `Error::IpSource` for error responses, malformed messages and
+/// responses without a mapped address.
+pub(crate) fn mapped_address(message: &[u8], transaction_id: &TransactionId) -> Result<IpAddr> {
+    if !is_response_to(message, transaction_id) {
+        return Err(Error::ip_source("Not a response to this STUN request"));
+    }
+
+    let message_type = u16::from_be_bytes([message[0], message[1]]);
+    let length = u16::from_be_bytes([message[2], message[3]]) as usize;
+    let attributes = message
+        .get(HEADER_LEN..HEADER_LEN + length)
+        .ok_or_else(|| Error::ip_source("Truncated STUN response"))?;
+
+    let mut mapped = None;
+    let mut xor_mapped = None;
+    let mut error = None;
+    for (attribute_type, value) in Attributes(attributes) {
+        match attribute_type {
+            ATTR_XOR_MAPPED_ADDRESS => xor_mapped = Some(value),
+            ATTR_MAPPED_ADDRESS => mapped = Some(value),
+            ATTR_ERROR_CODE => error = Some(value),
+            _ => {}
+        }
+    }
+
+    match message_type {
+        BINDING_SUCCESS => {}
+        BINDING_ERROR => {
+            return Err(Error::ip_source(format!(
+                "STUN server returned an error: {}",
+                error.map_or_else(|| "unknown".to_string(), describe_error)
+            )));
+        }
+        other => {
+            return Err(Error::ip_source(format!(
+                "Unexpected STUN message type {:#06x}",
+                other
+            )));
+        }
+    }
+
+    if let Some(value) = xor_mapped {
+        return decode_address(value, Some(transaction_id));
+    }
+    if let Some(value) = mapped {
+        return decode_address(value, None);
+    }
+    Err(Error::ip_source("STUN response contains no mapped address"))
+}
+
+/// Decode a (XOR-)MAPPED-ADDRESS value; `transaction_id` is given for
+/// XOR-MAPPED-ADDRESS
+fn decode_address(value: &[u8], transaction_id: Option<&TransactionId>) -> Result<IpAddr> {
+    let malformed = || Error::ip_source("Malformed STUN mapped address");
+
+    let family = *value.get(1).ok_or_else(malformed)?;
+    let address = value.get(4..).ok_or_else(malformed)?;
+
+    // The address is XORed with the magic cookie followed by the
+    // transaction ID
+    let mut mask = [0u8; 16];
+    if let Some(transaction_id) = transaction_id {
+        mask[..4].copy_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        mask[4..].copy_from_slice(transaction_id);
+    }
+
+    match family {
+        FAMILY_IPV4 => {
+            let bytes: [u8; 4] = address.get(..4).ok_or_else(malformed)?.try_into().unwrap();
+            let octets: [u8; 4] = std::array::from_fn(|i| bytes[i] ^ mask[i]);
+            Ok(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        FAMILY_IPV6 => {
+            let bytes: [u8; 16] = address.get(..16).ok_or_else(malformed)?.try_into().unwrap();
+            let octets: [u8; 16] = std::array::from_fn(|i| bytes[i] ^ mask[i]);
+            Ok(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        other => Err(Error::ip_source(format!(
+            "Unknown STUN address family {:#04x}",
+            other
+        ))),
+    }
+}
+
+/// Format an ERROR-CODE value as `<code> <reason>`
+fn describe_error(value: &[u8]) -> String {
+    match value {
+        [_, _, class, number, reason @ ..] => format!(
+            "{} {}",
+            u16::from(class & 0x07) * 100 + u16::from(*number),
+            String::from_utf8_lossy(reason)
+        ),
+        _ => "malformed error code".to_string(),
+    }
+}
+
+/// Iterator over the (type, value) pairs of an attribute list
+struct Attributes<'a>(&'a [u8]);
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = self.0.get(..4)?;
+        let attribute_type = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let value = self.0.get(4..4 + length)?;
+
+        // Values are padded to a multiple of four bytes
+        let padded = (4 + length).next_multiple_of(4);
+        self.0 = self.0.get(padded..).unwrap_or_default();
+
+        Some((attribute_type, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sample Binding Response with an XOR-mapped IPv4 address
+    /// (RFC 5769, section 2.2)
+    const RFC5769_IPV4_RESPONSE: [u8; 80] = [
+        0x01, 0x01, 0x00, 0x3c, 0x21, 0x12, 0xa4, 0x42, 0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6,
+        0x86, 0xfa, 0x87, 0xdf, 0xae, 0x80, 0x22, 0x00, 0x0b, 0x74, 0x65, 0x73, 0x74, 0x20, 0x76,
+        0x65, 0x63, 0x74, 0x6f, 0x72, 0x20, 0x00, 0x20, 0x00, 0x08, 0x00, 0x01, 0xa1, 0x47, 0xe1,
+        0x12, 0xa6, 0x43, 0x00, 0x08, 0x00, 0x14, 0x2b, 0x91, 0xf5, 0x99, 0xfd, 0x9e, 0x90, 0xc3,
+        0x8c, 0x74, 0x89, 0xf9, 0x2a, 0xf9, 0xba, 0x53, 0xf0, 0x6b, 0xe7, 0xd7, 0x80, 0x28, 0x00,
+        0x04, 0xc0, 0x7d, 0x4c, 0x96,
+    ];
+
+    const RFC5769_TRANSACTION_ID: TransactionId = [
+        0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6, 0x86, 0xfa, 0x87, 0xdf, 0xae,
+    ];
+
+    #[test]
+    fn test_rfc5769_ipv4_response() {
+        assert_eq!(
+            mapped_address(&RFC5769_IPV4_RESPONSE, &RFC5769_TRANSACTION_ID).unwrap(),
+            IpAddr::from([192, 0, 2, 1])
+        );
+    }
+
+    #[test]
+    fn test_response_to_other_transaction_is_rejected() {
+        let mut other = RFC5769_TRANSACTION_ID;
+        other[0] ^= 1;
+        assert!(!is_response_to(&RFC5769_IPV4_RESPONSE, &other));
+        assert!(mapped_address(&RFC5769_IPV4_RESPONSE, &other).is_err());
+    }
+
+    #[test]
+    fn test_binding_request() {
+        let id = transaction_id().unwrap();
+        let request = binding_request(&id);
+        assert_eq!(&request[..4], &[0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(&request[4..8], &MAGIC_COOKIE.to_be_bytes());
+        assert_eq!(&request[8..], &id);
+        assert_ne!(
+            id,
+            transaction_id().unwrap(),
+            "Transaction IDs must be random"
+        );
+    }
+
+    #[test]
+    fn test_error_response() {
+        let id = RFC5769_TRANSACTION_ID;
+        let mut response = vec![0x01, 0x11, 0x00, 0x10];
+        response.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        response.extend_from_slice(&id);
+        // ERROR-CODE 420 "Unknown", padded
+        response.extend_from_slice(&[0x00, 0x09, 0x00, 0x0b, 0, 0, 4, 20]);
+        response.extend_from_slice(b"Unknown\0");
+
+        let message = mapped_address(&response, &id).unwrap_err().to_string();
+        assert!(message.contains("420 Unknown"), "{}", message);
+    }
+
+    #[test]
+    fn test_mapped_address_fallback() {
+        let id = RFC5769_TRANSACTION_ID;
+        let mut response = vec![0x01, 0x01, 0x00, 0x0c];
+        response.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        response.extend_from_slice(&id);
+        response.extend_from_slice(&[0x00, 0x01, 0x00, 0x08, 0, FAMILY_IPV4, 0x0d, 0x96]);
+        response.extend_from_slice(&[203, 0, 113, 5]);
+
+        assert_eq!(
+            mapped_address(&response, &id).unwrap(),
+            IpAddr::from([203, 0, 113, 5])
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! STUN IP source against local STUN responders
+//!
+//! Responders listen on 127.0.0.1 and ::1 and answer Binding Requests with
+//! a configured XOR-MAPPED-ADDRESS, standing in for the public address a
+//! real server would observe.
+//!
+//! If this test fails, the public address cannot be learned over STUN, or
+//! an address of the wrong family or from a stray datagram is published.
+
+use ddns_core::config::IpVersion as ConfigIpVersion;
+use ddns_core::traits::{IpSource, IpSourceHealth, IpVersion};
+use ddns_ip_stun::StunIpSource;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio_stream::StreamExt;
+
+const MAGIC_COOKIE: [u8; 4] = [0x21, 0x12, 0xa4, 0x42];
+
+/// How a responder answers
+#[derive(Clone, Copy)]
+enum Answer {
+    /// A success response mapping the request to this address
+    Mapped(IpAddr),
+    /// A stray response first, then the real one
+    StrayFirst(IpAddr),
+    /// An error response
+    Error(u16),
+}
+
+/// Encode a response to the request with `transaction_id`
+fn response(transaction_id: &[u8], answer: Answer) -> Vec<u8> {
+    let (message_type, attribute_type, value) = match answer {
+        Answer::Mapped(ip) | Answer::StrayFirst(ip) => {
+            let mut mask = MAGIC_COOKIE.to_vec();
+            mask.extend_from_slice(transaction_id);
+            let (family, octets) = match ip {
+                IpAddr::V4(ip) => (1, ip.octets().to_vec()),
+                IpAddr::V6(ip) => (2, ip.octets().to_vec()),
+            };
+            let mut value = vec![0, family, 0x11 ^ 0x21, 0x22 ^ 0x12];
+            value.extend(octets.iter().zip(&mask).map(|(byte, mask)| byte ^ mask));
+            (0x0101u16, 0x0020u16, value)
+        }
+        Answer::Error(code) => {
+            let mut value = vec![0, 0, (code / 100) as u8, (code % 100) as u8];
+            value.extend_from_slice(b"Try Later");
+            (0x0111, 0x0009, value)
+        }
+    };
+
+    let padded = value.len().next_multiple_of(4);
+    let mut message = message_type.to_be_bytes().to_vec();
+    message.extend_from_slice(&((4 + padded) as u16).to_be_bytes());
+    message.extend_from_slice(&MAGIC_COOKIE);
+    message.extend_from_slice(transaction_id);
+    message.extend_from_slice(&attribute_type.to_be_bytes());
+    message.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    message.extend_from_slice(&value);
+    message.resize(message.len() + padded - value.len(), 0);
+    message
+}
+
+/// A STUN responder bound to `bind`
+async fn responder(bind: &str, answer: Answer) -> SocketAddr {
+    let socket = UdpSocket::bind(bind).await.unwrap();
+    let address = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 576];
+        while let Ok((len, from)) = socket.recv_from(&mut buffer).await {
+            let request = &buffer[..len];
+            if len < 20 || request[..2] != [0x00, 0x01] || request[4..8] != MAGIC_COOKIE {
+                continue;
+            }
+            let transaction_id = request[8..20].to_vec();
+
+            if let Answer::StrayFirst(_) = answer {
+                let mut stray_id = transaction_id.clone();
+                stray_id[0] ^= 0xff;
+                let stray = response(&stray_id, Answer::Mapped(ip("198.51.100.66")));
+                let _ = socket.send_to(&stray, from).await;
+            }
+            let _ = socket
+                .send_to(&response(&transaction_id, answer), from)
+                .await;
+        }
+    });
+
+    address
+}
+
+/// A socket that never answers
+async fn silent() -> (UdpSocket, SocketAddr) {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap();
+    (socket, address)
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn ipv4_address_is_learned() {
+    let server = responder("127.0.0.1:0", Answer::Mapped(ip("203.0.113.5"))).await;
+
+    let source = StunIpSource::builder()
+        .server(server.to_string())
+        .version(Some(ConfigIpVersion::V4))
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+}
+
+#[tokio::test]
+async fn ipv6_address_is_learned() {
+    let server = responder("[::1]:0", Answer::Mapped(ip("2001:db8::5"))).await;
+
+    let source = StunIpSource::builder()
+        .server(server.to_string())
+        .version(Some(ConfigIpVersion::V6))
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip("2001:db8::5"));
+}
+
+#[tokio::test]
+async fn each_family_is_watched() {
+    let v4_server = responder("127.0.0.1:0", Answer::Mapped(ip("203.0.113.5"))).await;
+    let v6_server = responder("[::1]:0", Answer::Mapped(ip("2001:db8::5"))).await;
+
+    // Each server only resolves to one family
+    let source = StunIpSource::builder()
+        .servers([v4_server.to_string(), v6_server.to_string()])
+        .interval(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    assert_eq!(source.version(), None);
+
+    let mut events = source.watch();
+    let first = events.next().await.unwrap();
+    let second = events.next().await.unwrap();
+    assert_eq!(
+        (first.version, first.new_ip),
+        (IpVersion::V4, ip("203.0.113.5"))
+    );
+    assert_eq!(
+        (second.version, second.new_ip),
+        (IpVersion::V6, ip("2001:db8::5"))
+    );
+
+    // Unchanged addresses are not reported again
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), events.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn unresponsive_server_is_skipped() {
+    let (_socket, unresponsive) = silent().await;
+    let server = responder("127.0.0.1:0", Answer::Mapped(ip("203.0.113.5"))).await;
+
+    let source = StunIpSource::builder()
+        .servers([unresponsive.to_string(), server.to_string()])
+        .version(Some(ConfigIpVersion::V4))
+        .timeout(Duration::from_millis(20))
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+}
+
+#[tokio::test]
+async fn stray_responses_are_ignored() {
+    let server = responder("127.0.0.1:0", Answer::StrayFirst(ip("203.0.113.5"))).await;
+
+    let source = StunIpSource::builder()
+        .server(server.to_string())
+        .version(Some(ConfigIpVersion::V4))
+        .build()
+        .unwrap();
+
+    assert_eq!(source.current().await.unwrap(), ip("203.0.113.5"));
+}
+
+#[tokio::test]
+async fn wrong_family_is_rejected() {
+    let server = responder("127.0.0.1:0", Answer::Mapped(ip("2001:db8::5"))).await;
+
+    let source = StunIpSource::builder()
+        .server(server.to_string())
+        .version(Some(ConfigIpVersion::V4))
+        .build()
+        .unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("another family"), "{}", message);
+}
+
+#[tokio::test]
+async fn errors_are_reported() {
+    let server = responder("127.0.0.1:0", Answer::Error(500)).await;
+    let (_socket, unresponsive) = silent().await;
+
+    let source = StunIpSource::builder()
+        .servers([server.to_string(), unresponsive.to_string()])
+        .version(Some(ConfigIpVersion::V4))
+        .timeout(Duration::from_millis(20))
+        .build()
+        .unwrap();
+    let health = source.health().unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("500 Try Later"), "{}", message);
+    assert!(message.contains("did not answer"), "{}", message);
+    assert!(matches!(
+        *health.borrow(),
+        IpSourceHealth::Failing {
+            consecutive_failures: 1,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,288 @@
+// # Internet Gateway Device Control
+//
+// Reads the device description of a gateway to find its WAN connection
+// service, and calls `GetExternalIPAddress` on it over SOAP.
+//
+// ## Services
+//
+// The external address is exposed by `WANIPConnection` (cable, fiber, DSL
+// with the modem doing PPP) or `WANPPPConnection` (PPPoE gateways). The
+// first of these found in the description is used, in document order.
+//
+// ## XML Handling
+//
+// Device descriptions and SOAP responses are small and flat, and only a
+// few element texts are needed, so they are scanned rather than parsed
+// with a full XML parser. Namespace prefixes on element names are ignored.
+
+use ddns_core::{Error, Result};
+
+use std::net::Ipv4Addr;
+
+use reqwest::Url;
+
+/// Service types that expose `GetExternalIPAddress`
+const WAN_SERVICES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// The WAN connection service of a gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WanService {
+    /// Service type, needed for the SOAP action
+    pub(crate) service_type: String,
+    /// Absolute URL of the control endpoint
+    pub(crate) control_url: String,
+}
+
+/// Fetch a device description and find its WAN connection service
+pub(crate) async fn wan_service(client: &reqwest::Client, location: &str) -> Result<WanService> {
+    let response =
+        client.get(location).send().await.map_err(|e| {
+            Error::ip_source(format!("Failed to fetch UPnP device description: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(Error::ip_source(format!(
+            "Failed to fetch UPnP device description: HTTP {}",
+            response.status()
+        )));
+    }
+    let description = response
+        .text()
+        .await
+        .map_err(|e| Error::ip_source(format!("Failed to read UPnP device description: {}", e)))?;
+
+    parse_description(location, &description)
+}
+
+/// Find the WAN connection service in a device description
+fn parse_description(location: &str, description: &str) -> Result<WanService> {
+    let invalid_url =
+        |e| Error::ip_source(format!("Invalid URL in UPnP device description: {}", e));
+
+    // Relative URLs are relative to URLBase (UPnP 1.0) or the description
+    let base = match element_text(description, "URLBase") {
+        Some(base) if !base.is_empty() => Url::parse(base).map_err(invalid_url)?,
+        _ => Url::parse(location).map_err(invalid_url)?,
+    };
+
+    let services: Vec<(&str, &str)> = elements(description, "service")
+        .filter_map(|service| {
+            Some((
+                element_text(service, "serviceType")?,
+                element_text(service, "controlURL")?,
+            ))
+        })
+        .collect();
+
+    let (service_type, control_url) = services
+        .iter()
+        .find(|(service_type, _)| WAN_SERVICES.contains(service_type))
+        .ok_or_else(|| {
+            Error::ip_source("UPnP gateway has no WANIPConnection or WANPPPConnection service")
+        })?;
+
+    Ok(WanService {
+        service_type: service_type.to_string(),
+        control_url: base.join(control_url).map_err(invalid_url)?.to_string(),
+    })
+}
+
+/// Call `GetExternalIPAddress` on a WAN connection service
+pub(crate) async fn external_ip(
+    client: &reqwest::Client,
+    service: &WanService,
+) -> Result<Ipv4Addr> {
+    let body = format!(
+        "<?xml version=\"1.0\"?>\r\n\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:GetExternalIPAddress xmlns:u=\"{}\"></u:GetExternalIPAddress></s:Body>\
+         </s:Envelope>",
+        service.service_type
+    );
+
+    let response = client
+        .post(&service.control_url)
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header(
+            "SOAPAction",
+            format!("\"{}#GetExternalIPAddress\"", service.service_type),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| Error::ip_source(format!("UPnP GetExternalIPAddress failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| Error::ip_source(format!("Failed to read UPnP response: {}", e)))?;
+
+    parse_external_ip(status, &text)
+}
+
+/// Read the address from a `GetExternalIPAddress` response
+fn parse_external_ip(status: reqwest::StatusCode, response: &str) -> Result<Ipv4Addr> {
+    if !status.is_success() {
+        // UPnP errors are SOAP faults with a code and description
+        let detail = match (
+            element_text(response, "errorCode"),
+            element_text(response, "errorDescription"),
+        ) {
+            (Some(code), Some(description)) => format!("{} {}", code, description),
+            (Some(code), None) => code.to_string(),
+            _ => format!("HTTP {}", status),
+        };
+        return Err(Error::ip_source(format!(
+            "UPnP GetExternalIPAddress failed: {}",
+            detail
+        )));
+    }
+
+    let text = element_text(response, "NewExternalIPAddress")
+        .ok_or_else(|| Error::ip_source("UPnP response does not contain NewExternalIPAddress"))?;
+
+    // An empty address means the WAN connection is down
+    if text.is_empty() {
+        return Err(Error::ip_source("UPnP gateway has no external address"));
+    }
+
+    text.parse().map_err(|_| {
+        Error::ip_source(format!(
+            "Invalid external address from UPnP gateway: {}",
+            text
+        ))
+    })
+}
+
+/// Contents of every `<name>` element, ignoring namespace prefixes
+fn elements<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        let (start, end) = find_element(rest, name)?;
+        let content = &rest[start..end];
+        rest = &rest[end..];
+        Some(content)
+    })
+}
+
+/// Trimmed text of the first `<name>` element
+fn element_text<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    find_element(xml, name).map(|(start, end)| xml[start..end].trim())
+}
+
+/// Byte range of the content of the first `<name>` (or `<prefix:name>`)
+/// element
+fn find_element(xml: &str, name: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    while let Some(position) = xml[offset..].find('<') {
+        let tag_start = offset + position + 1;
+        let tag_end = tag_start + xml[tag_start..].find('>')?;
+        let tag = &xml[tag_start..tag_end];
+        offset = tag_end;
+
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name != name || tag.ends_with('/') {
+            continue;
+        }
+
+        // Find the matching closing tag
+        let content_start = tag_end + 1;
+        let closing = format!("</{}>", tag_name);
+        let content_end = content_start + xml[content_start..].find(&closing)?;
+        return Some((content_start, content_end));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL>
+      </service>
+    </serviceList>
+    <deviceList>
+      <device>
+        <deviceType>urn:schemas-upnp-org:device:WANConnectionDevice:1</deviceType>
+        <serviceList>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+            <controlURL>/ctl/IPConn</controlURL>
+          </service>
+        </serviceList>
+      </device>
+    </deviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn test_parse_description() {
+        let service =
+            parse_description("http://192.168.1.1:5000/rootDesc.xml", DESCRIPTION).unwrap();
+        assert_eq!(
+            service,
+            WanService {
+                service_type: "urn:schemas-upnp-org:service:WANIPConnection:1".to_string(),
+                control_url: "http://192.168.1.1:5000/ctl/IPConn".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_description_uses_url_base() {
+        let description = DESCRIPTION.replace(
+            "<device>",
+            "<URLBase>http://192.168.1.1:49000/</URLBase><device>",
+        );
+        let service = parse_description("http://192.168.1.1:5000/desc.xml", &description).unwrap();
+        assert_eq!(service.control_url, "http://192.168.1.1:49000/ctl/IPConn");
+    }
+
+    #[test]
+    fn test_parse_description_without_wan_service() {
+        let description = DESCRIPTION.replace("WANIPConnection", "WANCommonInterfaceConfig");
+        assert!(parse_description("http://192.168.1.1/desc.xml", &description).is_err());
+    }
+
+    #[test]
+    fn test_parse_external_ip() {
+        let response = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+<s:Body><u:GetExternalIPAddressResponse xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1">
+<NewExternalIPAddress>203.0.113.5</NewExternalIPAddress>
+</u:GetExternalIPAddressResponse></s:Body></s:Envelope>"#;
+        assert_eq!(
+            parse_external_ip(reqwest::StatusCode::OK, response).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 5)
+        );
+
+        let down = response.replace("203.0.113.5", "");
+        assert!(parse_external_ip(reqwest::StatusCode::OK, &down).is_err());
+    }
+
+    #[test]
+    fn test_parse_soap_fault() {
+        let fault = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+<s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring>
+<detail><UPnPError xmlns="urn:schemas-upnp-org:control-1-0">
+<errorCode>501</errorCode><errorDescription>Action Failed</errorDescription>
+</UPnPError></detail></s:Fault></s:Body></s:Envelope>"#;
+        let message = parse_external_ip(reqwest::StatusCode::INTERNAL_SERVER_ERROR, fault)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("501 Action Failed"), "{}", message);
+    }
+}
@@ -0,0 +1,448 @@
+// # UPnP IP Source
+//
+// This crate provides an IP source that asks the home gateway for its WAN
+// address.
+//
+// ## Purpose
+//
+// Behind NAT, the host only sees its private LAN address, so netlink cannot
+// provide the public address. Third-party HTTP services can, but leak the
+// address to a third party and are rate limited. The gateway itself knows
+// its external address and can be asked over the LAN:
+//
+// - **UPnP IGD**: the gateway is discovered via SSDP (see `ssdp.rs`), and
+//   `GetExternalIPAddress` is called on its WAN connection service (see
+//   `igd.rs`).
+// - **NAT-PMP** (optional): if UPnP fails and a gateway address is
+//   configured, the external address is requested via NAT-PMP, which PCP
+//   gateways answer as well (see `natpmp.rs`).
+//
+// ## Architecture
+//
+// Gateways do not push address changes to clients that are not subscribed
+// to UPnP events, so the gateway is polled at a configurable interval. The
+// discovered WAN service is cached; it is discovered again after a failed
+// request, e.g. when the gateway rebooted and changed its port.
+//
+// Only IPv4 is supported: IGD exposes the IPv4 WAN address. An address in a
+// private or shared (CGNAT) range means the gateway is itself behind NAT;
+// it is rejected rather than published.
+//
+// Like the HTTP source, this source reports its health (`IpSource::health`)
+// and stops polling as soon as the stream returned by `watch()` is dropped.
+
+mod igd;
+mod natpmp;
+mod ssdp;
+
+pub use natpmp::NAT_PMP_PORT;
+
+use igd::WanService;
+
+use ddns_core::ProviderRegistry;
+use ddns_core::config::IpSourceConfig;
+use ddns_core::traits::{
+    HealthReporter, IpChangeEvent, IpSource, IpSourceFactory, IpSourceHealth,
+    IpVersion as TraitsIpVersion,
+};
+use ddns_core::{Error, Result};
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Default polling interval
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default time to wait for SSDP responses
+const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default timeout of HTTP requests to the gateway
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether an external address can be published
+///
+/// Private, shared (RFC 6598) and other non-routable addresses mean the
+/// gateway is behind another NAT.
+fn is_public(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    let shared = a == 100 && (64..128).contains(&b);
+
+    !(ip.is_private()
+        || shared
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast())
+}
+
+/// UPnP-IGD IP source, with NAT-PMP as an optional fallback
+pub struct UpnpIpSource {
+    /// State shared with the watch loop
+    shared: Arc<Shared>,
+
+    /// Polling interval
+    poll_interval: Duration,
+}
+
+/// Gateway access, cache and health of an [`UpnpIpSource`]
+struct Shared {
+    client: reqwest::Client,
+
+    /// Device description URL; discovered via SSDP if `None`
+    location: Option<String>,
+
+    /// Where SSDP searches are sent
+    ssdp_target: SocketAddr,
+
+    /// Time to wait for SSDP responses
+    discovery_timeout: Duration,
+
+    /// NAT-PMP gateway to ask if UPnP fails
+    nat_pmp_gateway: Option<SocketAddr>,
+
+    /// WAN connection service found by the last discovery
+    service: tokio::sync::Mutex<Option<WanService>>,
+
+    /// Last external address (cached)
+    current_ip: Mutex<Option<IpAddr>>,
+
+    /// Health signal
+    health: watch::Sender<IpSourceHealth>,
+}
+
+impl Shared {
+    /// Ask the gateway for its external address and update the cache and
+    /// the health signal
+    ///
+    /// This is the only place addresses are fetched.
+    async fn fetch(&self) -> Result<IpAddr> {
+        let result = match (self.query_upnp().await, self.nat_pmp_gateway) {
+            (Ok(ip), _) => Ok(ip),
+            (Err(upnp_error), Some(gateway)) => {
+                tracing::debug!("UPnP failed, trying NAT-PMP: {}", upnp_error);
+                natpmp::external_ip(gateway)
+                    .await
+                    .map_err(|e| Error::ip_source(format!("{}; NAT-PMP: {}", upnp_error, e)))
+            }
+            (Err(upnp_error), None) => Err(upnp_error),
+        }
+        .and_then(|ip| {
+            if is_public(ip) {
+                Ok(IpAddr::V4(ip))
+            } else {
+                Err(Error::ip_source(format!(
+                    "Gateway reports a non-public external address {} \
+                     (is it behind another NAT?)",
+                    ip
+                )))
+            }
+        });
+
+        *self.current_ip.lock().unwrap_or_else(|e| e.into_inner()) = result.as_ref().ok().copied();
+        self.report(result.as_ref().err());
+        result
+    }
+
+    /// Query the external address over UPnP, discovering the gateway first
+    /// if needed
+    async fn query_upnp(&self) -> Result<Ipv4Addr> {
+        let mut cached = self.service.lock().await;
+
+        let service = match cached.as_ref() {
+            Some(service) => service.clone(),
+            None => {
+                let location = match &self.location {
+                    Some(location) => location.clone(),
+                    None => ssdp::discover(self.ssdp_target, self.discovery_timeout).await?,
+                };
+                let service = igd::wan_service(&self.client, &location).await?;
+                tracing::info!(
+                    "Using UPnP gateway service {} at {}",
+                    service.service_type,
+                    service.control_url
+                );
+                cached.insert(service).clone()
+            }
+        };
+
+        let result = igd::external_ip(&self.client, &service).await;
+        if result.is_err() {
+            // The gateway may have restarted on a different port
+            *cached = None;
+        }
+        result
+    }
+
+    /// Record the outcome of a query
+    fn report(&self, error: Option<&Error>) {
+        match error {
+            None => self.health.record_success(),
+            Some(error) => {
+                self.health.record_failure(error);
+            }
+        }
+    }
+}
+
+impl UpnpIpSource {
+    /// Create a UPnP IP source that discovers the gateway via SSDP
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Create a builder for a UPnP IP source
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ddns_ip_upnp::{NAT_PMP_PORT, UpnpIpSource};
+    /// use std::net::Ipv4Addr;
+    /// use std::time::Duration;
+    ///
+    /// let source = UpnpIpSource::builder()
+    ///     .nat_pmp_gateway((Ipv4Addr::new(192, 168, 1, 1), NAT_PMP_PORT).into())
+    ///     .interval(Duration::from_secs(300))
+    ///     .build();
+    /// ```
+    pub fn builder() -> UpnpIpSourceBuilder {
+        UpnpIpSourceBuilder::new()
+    }
+}
+
+impl Default for UpnpIpSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for [`UpnpIpSource`]
+#[derive(Debug, Clone)]
+pub struct UpnpIpSourceBuilder {
+    location: Option<String>,
+    ssdp_target: SocketAddr,
+    discovery_timeout: Duration,
+    nat_pmp_gateway: Option<SocketAddr>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl UpnpIpSourceBuilder {
+    fn new() -> Self {
+        Self {
+            location: None,
+            ssdp_target: ssdp::SSDP_MULTICAST,
+            discovery_timeout: DEFAULT_DISCOVERY_TIMEOUT,
+            nat_pmp_gateway: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Use the device description at `location` instead of discovering
+    /// the gateway (e.g. `http://192.168.1.1:5000/rootDesc.xml`)
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Send SSDP searches to `target` instead of the multicast group
+    ///
+    /// Mostly useful for tests, or to query a known gateway directly.
+    pub fn ssdp_target(mut self, target: SocketAddr) -> Self {
+        self.ssdp_target = target;
+        self
+    }
+
+    /// Time to wait for SSDP responses (default: 3s)
+    pub fn discovery_timeout(mut self, timeout: Duration) -> Self {
+        self.discovery_timeout = timeout;
+        self
+    }
+
+    /// Ask `gateway` via NAT-PMP if UPnP fails
+    ///
+    /// The gateway normally listens on [`NAT_PMP_PORT`].
+    pub fn nat_pmp_gateway(mut self, gateway: SocketAddr) -> Self {
+        self.nat_pmp_gateway = Some(gateway);
+        self
+    }
+
+    /// Polling interval (default: 60s)
+    pub fn interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Timeout of HTTP requests to the gateway (default: 5s)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Build the IP source
+    pub fn build(self) -> UpnpIpSource {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .unwrap_or_default();
+
+        UpnpIpSource {
+            shared: Arc::new(Shared {
+                client,
+                location: self.location,
+                ssdp_target: self.ssdp_target,
+                discovery_timeout: self.discovery_timeout,
+                nat_pmp_gateway: self.nat_pmp_gateway,
+                service: tokio::sync::Mutex::new(None),
+                current_ip: Mutex::new(None),
+                health: watch::Sender::new(IpSourceHealth::Unknown),
+            }),
+            poll_interval: self.poll_interval,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl IpSource for UpnpIpSource {
+    async fn current(&self) -> Result<IpAddr> {
+        let cached = *self
+            .shared
+            .current_ip
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match cached {
+            Some(ip) => Ok(ip),
+            None => self.shared.fetch().await,
+        }
+    }
+
+    fn watch(&self) -> Pin<Box<dyn Stream<Item = IpChangeEvent> + Send + 'static>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let shared = self.shared.clone();
+        let poll_interval = self.poll_interval;
+
+        tokio::spawn(async move {
+            tracing::info!(
+                "Starting UPnP IP monitoring (location={:?}, nat_pmp={:?}, interval={:?})",
+                shared.location,
+                shared.nat_pmp_gateway,
+                poll_interval
+            );
+
+            let mut last_known_ip = None;
+
+            loop {
+                let result = tokio::select! {
+                    result = shared.fetch() => result,
+                    _ = tx.closed() => break,
+                };
+
+                match result {
+                    Ok(ip) if last_known_ip != Some(ip) => {
+                        tracing::info!("IP changed: {:?} -> {:?}", last_known_ip, ip);
+                        if tx.send(IpChangeEvent::new(ip, last_known_ip)).is_err() {
+                            break;
+                        }
+                        last_known_ip = Some(ip);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to query gateway: {}", e),
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = tx.closed() => break,
+                }
+            }
+
+            tracing::debug!("Receiver dropped, stopping monitor");
+        });
+
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn version(&self) -> Option<TraitsIpVersion> {
+        Some(TraitsIpVersion::V4)
+    }
+
+    fn health(&self) -> Option<watch::Receiver<IpSourceHealth>> {
+        Some(self.shared.health.subscribe())
+    }
+}
+
+/// Factory for creating UPnP IP sources
+pub struct UpnpFactory;
+
+impl IpSourceFactory for UpnpFactory {
+    fn create(&self, config: &IpSourceConfig) -> Result<Box<dyn IpSource>> {
+        match config {
+            IpSourceConfig::Upnp {
+                location,
+                nat_pmp_gateway,
+                interval_secs,
+            } => {
+                let mut builder =
+                    UpnpIpSource::builder().interval(Duration::from_secs(*interval_secs));
+                if let Some(location) = location {
+                    builder = builder.location(location.clone());
+                }
+                if let Some(gateway) = nat_pmp_gateway {
+                    builder = builder.nat_pmp_gateway(SocketAddr::new(*gateway, NAT_PMP_PORT));
+                }
+
+                Ok(Box::new(builder.build()))
+            }
+            _ => Err(Error::config("Invalid config for UPnP IP source")),
+        }
+    }
+}
+
+/// Register the UPnP IP source with a registry
+pub fn register(registry: &ProviderRegistry) {
+    registry.register_ip_source("upnp", Box::new(UpnpFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_creation() {
+        let config = IpSourceConfig::Upnp {
+            location: Some("http://192.168.1.1:5000/rootDesc.xml".to_string()),
+            nat_pmp_gateway: Some(IpAddr::from([192, 168, 1, 1])),
+            interval_secs: 60,
+        };
+        assert!(UpnpFactory.create(&config).is_ok());
+
+        let config = IpSourceConfig::Netlink {
+            interface: None,
+            version: None,
+            selection: Default::default(),
+        };
+        assert!(UpnpFactory.create(&config).is_err());
+    }
+
+    #[test]
+    fn test_is_public() {
+        assert!(is_public(Ipv4Addr::new(203, 0, 113, 5)));
+        assert!(is_public(Ipv4Addr::new(100, 128, 0, 1)));
+
+        for ip in [
+            Ipv4Addr::new(192, 168, 0, 2),
+            Ipv4Addr::new(10, 1, 2, 3),
+            Ipv4Addr::new(172, 20, 0, 1),
+            Ipv4Addr::new(100, 64, 0, 1),
+            Ipv4Addr::UNSPECIFIED,
+        ] {
+            assert!(!is_public(ip), "{}", ip);
+        }
+    }
+}
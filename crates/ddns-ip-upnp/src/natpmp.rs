@@ -0,0 +1,142 @@
+// # NAT-PMP
+//
+// Asks a gateway for its external address with the NAT-PMP "external
+// address" request (RFC 6886, opcode 0). PCP gateways (RFC 6887) are
+// expected to answer NAT-PMP requests as well, as required for backwards
+// compatibility, so PCP is not spoken separately.
+//
+// NAT-PMP has no discovery: the gateway address must be configured.
+// Requests are sent over UDP to port 5351 and retried with a doubling
+// timeout starting at 250ms, as in the RFC (but with fewer attempts).
+
+use ddns_core::{Error, Result};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+/// Port NAT-PMP gateways listen on
+pub const NAT_PMP_PORT: u16 = 5351;
+
+/// Timeout of the first attempt
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Number of attempts before giving up
+const ATTEMPTS: u32 = 4;
+
+/// Ask `gateway` for its external address
+pub(crate) async fn external_ip(gateway: SocketAddr) -> Result<Ipv4Addr> {
+    let bind: SocketAddr = match gateway {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind)
+        .await
+        .map_err(|e| Error::ip_source(format!("Failed to open NAT-PMP socket: {}", e)))?;
+    socket
+        .connect(gateway)
+        .await
+        .map_err(|e| Error::ip_source(format!("Failed to reach NAT-PMP gateway: {}", e)))?;
+
+    let mut timeout = INITIAL_TIMEOUT;
+    let mut buffer = [0u8; 16];
+    for _ in 0..ATTEMPTS {
+        // Version 0, opcode 0 (external address)
+        socket
+            .send(&[0, 0])
+            .await
+            .map_err(|e| Error::ip_source(format!("Failed to send NAT-PMP request: {}", e)))?;
+
+        match tokio::time::timeout(timeout, socket.recv(&mut buffer)).await {
+            Ok(Ok(len)) => return parse_response(&buffer[..len]),
+            Ok(Err(e)) => {
+                return Err(Error::ip_source(format!(
+                    "NAT-PMP request to {} failed: {}",
+                    gateway, e
+                )));
+            }
+            Err(_) => timeout *= 2,
+        }
+    }
+
+    Err(Error::ip_source(format!(
+        "NAT-PMP gateway {} did not answer",
+        gateway
+    )))
+}
+
+/// Parse an external address response
+fn parse_response(response: &[u8]) -> Result<Ipv4Addr> {
+    let [
+        version,
+        opcode,
+        result_high,
+        result_low,
+        _,
+        _,
+        _,
+        _,
+        a,
+        b,
+        c,
+        d,
+        ..,
+    ] = *response
+    else {
+        return Err(Error::ip_source(format!(
+            "Truncated NAT-PMP response ({} bytes)",
+            response.len()
+        )));
+    };
+
+    if version != 0 || opcode != 128 {
+        return Err(Error::ip_source(format!(
+            "Unexpected NAT-PMP response (version {}, opcode {})",
+            version, opcode
+        )));
+    }
+
+    match u16::from_be_bytes([result_high, result_low]) {
+        0 => Ok(Ipv4Addr::new(a, b, c, d)),
+        code => Err(Error::ip_source(format!(
+            "NAT-PMP gateway refused the request: {}",
+            result_description(code)
+        ))),
+    }
+}
+
+fn result_description(code: u16) -> String {
+    match code {
+        1 => "unsupported version".to_string(),
+        2 => "not authorized".to_string(),
+        3 => "network failure".to_string(),
+        4 => "out of resources".to_string(),
+        5 => "unsupported opcode".to_string(),
+        code => format!("result code {}", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let response = [0, 128, 0, 0, 0, 0, 0x1c, 0x20, 203, 0, 113, 5];
+        assert_eq!(
+            parse_response(&response).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 5)
+        );
+    }
+
+    #[test]
+    fn test_parse_error_response() {
+        let refused = [0, 128, 0, 3, 0, 0, 0x1c, 0x20, 0, 0, 0, 0];
+        let message = parse_response(&refused).unwrap_err().to_string();
+        assert!(message.contains("network failure"), "{}", message);
+
+        assert!(parse_response(&[0, 128, 0, 0]).is_err());
+        assert!(parse_response(&[2, 128, 0, 0, 0, 0, 0, 0, 203, 0, 113, 5]).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+// # SSDP Discovery
+//
+// Finds an Internet Gateway Device by multicasting an `M-SEARCH` request to
+// 239.255.255.250:1900 and reading the `LOCATION` header (the URL of the
+// device description) of the first matching response.
+//
+// Both IGD versions are searched for: IGD:2 devices do not always answer
+// searches for IGD:1.
+//
+// Any host on the network can answer a multicast search, so a response is
+// only trusted if its `LOCATION` points back at the host that sent it.
+// Otherwise a single spoofed datagram could send the source to a
+// description on an arbitrary server.
+
+use ddns_core::{Error, Result};
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+/// The SSDP multicast group and port
+pub(crate) const SSDP_MULTICAST: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900));
+
+/// Device types searched for
+const SEARCH_TARGETS: &[&str] = &[
+    "urn:schemas-upnp-org:device:InternetGatewayDevice:1",
+    "urn:schemas-upnp-org:device:InternetGatewayDevice:2",
+];
+
+/// Discover a gateway and return the URL of its device description
+///
+/// # Errors
+///
+/// Returns `Error::IpSource` if no gateway answers within `timeout`.
+pub(crate) async fn discover(target: SocketAddr, timeout: Duration) -> Result<String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| Error::ip_source(format!("Failed to open SSDP socket: {}", e)))?;
+    // Stay within the local network
+    let _ = socket.set_multicast_ttl_v4(2);
+
+    // Devices answer within MX seconds
+    let mx = timeout.as_secs().clamp(1, 5);
+    for search_target in SEARCH_TARGETS {
+        let request = format!(
+            "M-SEARCH * HTTP/1.1\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             MAN: \"ssdp:discover\"\r\n\
+             MX: {}\r\n\
+             ST: {}\r\n\r\n",
+            mx, search_target
+        );
+        socket
+            .send_to(request.as_bytes(), target)
+            .await
+            .map_err(|e| Error::ip_source(format!("Failed to send SSDP search: {}", e)))?;
+    }
+
+    let mut buffer = [0u8; 2048];
+    let search = async {
+        loop {
+            let (len, from) = socket.recv_from(&mut buffer).await?;
+            match parse_response(&String::from_utf8_lossy(&buffer[..len])) {
+                Some(location) if served_by(&location, from.ip()) => {
+                    tracing::debug!("Found UPnP gateway at {} ({})", location, from);
+                    return Ok::<_, std::io::Error>(location);
+                }
+                Some(location) => tracing::warn!(
+                    "Ignoring SSDP response from {} pointing to another host: {}",
+                    from,
+                    location
+                ),
+                None => tracing::trace!("Ignoring SSDP response from {}", from),
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, search).await {
+        Ok(Ok(location)) => Ok(location),
+        Ok(Err(e)) => Err(Error::ip_source(format!("SSDP discovery failed: {}", e))),
+        Err(_) => Err(Error::ip_source(format!(
+            "No UPnP gateway answered within {:?}",
+            timeout
+        ))),
+    }
+}
+
+/// Return the `LOCATION` of a successful response from a gateway device
+fn parse_response(response: &str) -> Option<String> {
+    let mut lines = response.lines();
+    if !lines.next()?.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+
+    let mut location = None;
+    let mut is_gateway = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "location" => location = Some(value.to_string()),
+            "st" => is_gateway = value.contains(":device:InternetGatewayDevice:"),
+            _ => {}
+        }
+    }
+
+    location.filter(|_| is_gateway)
+}
+
+/// Check that a `LOCATION` URL names the responding host by address
+fn served_by(location: &str, from: IpAddr) -> bool {
+    let Ok(url) = reqwest::Url::parse(location) else {
+        return false;
+    };
+    url.host_str()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+        .and_then(|host| host.parse::<IpAddr>().ok())
+        == Some(from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let response = "HTTP/1.1 200 OK\r\n\
+            CACHE-CONTROL: max-age=120\r\n\
+            ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+            USN: uuid:1234::urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+            Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            parse_response(response).as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+    }
+
+    #[test]
+    fn test_parse_response_ignores_other_devices() {
+        let printer = "HTTP/1.1 200 OK\r\n\
+            ST: urn:schemas-upnp-org:device:Printer:1\r\n\
+            LOCATION: http://192.168.1.20/desc.xml\r\n\r\n";
+        assert_eq!(parse_response(printer), None);
+
+        let notify = "NOTIFY * HTTP/1.1\r\n\
+            ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+            LOCATION: http://192.168.1.1/desc.xml\r\n\r\n";
+        assert_eq!(parse_response(notify), None);
+    }
+
+    #[test]
+    fn test_location_must_point_at_the_responder() {
+        let gateway: IpAddr = "192.168.1.1".parse().unwrap();
+
+        assert!(served_by("http://192.168.1.1:5000/rootDesc.xml", gateway));
+        assert!(!served_by("http://203.0.113.9:5000/rootDesc.xml", gateway));
+        assert!(!served_by("http://router.lan/rootDesc.xml", gateway));
+        assert!(!served_by("not a url", gateway));
+        assert!(served_by(
+            "http://[fe80::1]:5000/desc.xml",
+            "fe80::1".parse().unwrap()
+        ));
+    }
+}
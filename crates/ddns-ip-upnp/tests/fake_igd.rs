@@ -0,0 +1,251 @@
+//! UPnP IP source against a local fake gateway
+//!
+//! The fake gateway answers SSDP searches on a local UDP port, serves its
+//! device description and `GetExternalIPAddress` from a stub HTTP server,
+//! and optionally answers NAT-PMP requests on another UDP port.
+//!
+//! If this test fails, the WAN address of a home gateway cannot be found,
+//! or a broken or double-NATed gateway gets its address published.
+
+use ddns_core::traits::{IpSource, IpSourceHealth, IpVersion};
+use ddns_ip_upnp::UpnpIpSource;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio_stream::StreamExt;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const WAN_IP: &str = "203.0.113.5";
+
+const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <deviceList>
+      <device>
+        <deviceType>urn:schemas-upnp-org:device:WANConnectionDevice:1</deviceType>
+        <serviceList>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+            <controlURL>/ctl/IPConn</controlURL>
+          </service>
+        </serviceList>
+      </device>
+    </deviceList>
+  </device>
+</root>"#;
+
+fn soap_response(ip: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_string(format!(
+        "<?xml version=\"1.0\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\"><s:Body>\
+         <u:GetExternalIPAddressResponse xmlns:u=\"urn:schemas-upnp-org:service:WANIPConnection:1\">\
+         <NewExternalIPAddress>{}</NewExternalIPAddress>\
+         </u:GetExternalIPAddressResponse></s:Body></s:Envelope>",
+        ip
+    ))
+}
+
+/// The HTTP side of a fake gateway, reporting `wan_ip`
+async fn gateway(wan_ip: &str) -> MockServer {
+    let server = MockServer::start().await;
+    serve(&server, wan_ip).await;
+    server
+}
+
+/// Serve the device description and report `wan_ip`
+async fn serve(server: &MockServer, wan_ip: &str) {
+    server.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/rootDesc.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(DESCRIPTION))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ctl/IPConn"))
+        .and(header(
+            "soapaction",
+            "\"urn:schemas-upnp-org:service:WANIPConnection:1#GetExternalIPAddress\"",
+        ))
+        .respond_with(soap_response(wan_ip))
+        .mount(server)
+        .await;
+}
+
+fn location(server: &MockServer) -> String {
+    format!("{}/rootDesc.xml", server.uri())
+}
+
+/// An SSDP responder announcing `location`
+async fn ssdp_responder(location: String) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 1024];
+        while let Ok((len, from)) = socket.recv_from(&mut buffer).await {
+            let request = String::from_utf8_lossy(&buffer[..len]);
+            if !request.starts_with("M-SEARCH") {
+                continue;
+            }
+            let Some(st) = request
+                .lines()
+                .find_map(|line| line.strip_prefix("ST: "))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\nST: {}\r\n\
+                 USN: uuid:fake::{}\r\nLOCATION: {}\r\n\r\n",
+                st, st, location
+            );
+            let _ = socket.send_to(response.as_bytes(), from).await;
+        }
+    });
+
+    address
+}
+
+/// A NAT-PMP responder reporting `wan_ip`
+async fn nat_pmp_responder(wan_ip: [u8; 4]) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 16];
+        while let Ok((len, from)) = socket.recv_from(&mut buffer).await {
+            if buffer[..len] != [0, 0] {
+                continue;
+            }
+            let mut response = vec![0, 128, 0, 0, 0, 0, 0x1c, 0x20];
+            response.extend_from_slice(&wan_ip);
+            let _ = socket.send_to(&response, from).await;
+        }
+    });
+
+    address
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn gateway_is_discovered_and_queried() {
+    let gateway = gateway(WAN_IP).await;
+    let ssdp = ssdp_responder(location(&gateway)).await;
+
+    let source = UpnpIpSource::builder().ssdp_target(ssdp).build();
+
+    assert_eq!(source.current().await.unwrap(), ip(WAN_IP));
+    assert_eq!(source.version(), Some(IpVersion::V4));
+}
+
+#[tokio::test]
+async fn configured_location_skips_discovery() {
+    let gateway = gateway(WAN_IP).await;
+
+    // Nothing answers at this SSDP target
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let source = UpnpIpSource::builder()
+        .location(location(&gateway))
+        .ssdp_target(silent.local_addr().unwrap())
+        .discovery_timeout(Duration::from_millis(100))
+        .build();
+
+    assert_eq!(source.current().await.unwrap(), ip(WAN_IP));
+}
+
+#[tokio::test]
+async fn missing_gateway_is_reported() {
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let source = UpnpIpSource::builder()
+        .ssdp_target(silent.local_addr().unwrap())
+        .discovery_timeout(Duration::from_millis(100))
+        .build();
+    let health = source.health().unwrap();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("No UPnP gateway answered"), "{}", message);
+    assert!(matches!(
+        *health.borrow(),
+        IpSourceHealth::Failing {
+            consecutive_failures: 1,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn soap_faults_are_reported() {
+    let gateway = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rootDesc.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(DESCRIPTION))
+        .mount(&gateway)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_string(
+            "<s:Envelope><s:Body><s:Fault><detail><UPnPError>\
+             <errorCode>501</errorCode><errorDescription>Action Failed</errorDescription>\
+             </UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+        ))
+        .mount(&gateway)
+        .await;
+
+    let source = UpnpIpSource::builder().location(location(&gateway)).build();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("501 Action Failed"), "{}", message);
+}
+
+#[tokio::test]
+async fn double_nat_address_is_rejected() {
+    let gateway = gateway("100.64.12.34").await;
+
+    let source = UpnpIpSource::builder().location(location(&gateway)).build();
+
+    let message = source.current().await.unwrap_err().to_string();
+    assert!(message.contains("non-public"), "{}", message);
+}
+
+#[tokio::test]
+async fn nat_pmp_is_used_when_upnp_fails() {
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let nat_pmp = nat_pmp_responder([198, 51, 100, 7]).await;
+
+    let source = UpnpIpSource::builder()
+        .ssdp_target(silent.local_addr().unwrap())
+        .discovery_timeout(Duration::from_millis(100))
+        .nat_pmp_gateway(nat_pmp)
+        .build();
+
+    assert_eq!(source.current().await.unwrap(), ip("198.51.100.7"));
+}
+
+#[tokio::test]
+async fn wan_address_changes_are_watched() {
+    let gateway = gateway(WAN_IP).await;
+    let ssdp = ssdp_responder(location(&gateway)).await;
+
+    let source = UpnpIpSource::builder()
+        .ssdp_target(ssdp)
+        .interval(Duration::from_millis(50))
+        .build();
+
+    let mut events = source.watch();
+    let first = events.next().await.unwrap();
+    assert_eq!(first.new_ip, ip(WAN_IP));
+
+    // The ISP assigns a new address
+    serve(&gateway, "203.0.113.77").await;
+
+    let change = tokio::time::timeout(Duration::from_secs(2), events.next())
+        .await
+        .expect("Address change is reported")
+        .unwrap();
+    assert_eq!(change.previous_ip, Some(ip(WAN_IP)));
+    assert_eq!(change.new_ip, ip("203.0.113.77"));
+}
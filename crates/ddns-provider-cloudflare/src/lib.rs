@@ -6,18 +6,27 @@
 //
 // **This is a PRODUCTION-READY implementation** with:
 //
-// - ✅ Makes one HTTP request per engine event (as required by architectural constraints)
+// - ✅ Single-shot per engine event: two GETs to find and read the record,
+//   then a PUT only if the IP differs (or a POST instead when the record is
+//   missing and `create_if_missing` is set); a zone cache miss adds one
+//   `GET /zones` per name suffix
 // - ✅ Full error propagation to engine (engine handles retries, backoff, rate limiting)
-// - ✅ HTTP timeout configured (30 seconds)
+// - ✅ HTTP timeout configured (30 seconds, overridable via the builder)
+// - ✅ Configurable API base URL and injectable HTTP client (for proxies and offline tests)
 // - ✅ Specific error handling for HTTP status codes (403, 404, 409, 429, 5xx)
 // - ✅ Dry-run mode for safe testing
 // - ✅ Idempotency checking (no PUT if IP unchanged)
 // - ✅ Both A and AAAA record support
-// - ✅ Zone auto-discovery and explicit zone ID
+// - ✅ Zone auto-discovery (longest matching suffix, so delegated subzones work),
+//   explicit zone ID, and per-record zone overrides
+// - ✅ Optional creation of missing records (`create_if_missing`)
+// - ✅ Record lookup (`get_record`) with Cloudflare metadata (proxied, comment, tags)
 // - ❌ NO retry logic (intentionally omitted - owned by DdnsEngine)
 // - ❌ NO backoff logic (intentionally omitted - owned by DdnsEngine)
 // - ❌ NO rate limiting (intentionally omitted - owned by DdnsEngine)
-// - ❌ NO caching (intentionally omitted - state owned by StateStore)
+// - ❌ NO caching of DNS state (intentionally omitted - state owned by StateStore);
+//   only zone lookups are cached, since zone IDs are account metadata that
+//   rarely change and would otherwise cost up to one request per label
 // - ❌ NO background tasks (intentionally omitted - violates shutdown determinism)
 //
 // ## Architectural Constraints (Per AI_CONTRACT.md)
@@ -51,16 +60,21 @@
 //
 // - Cloudflare API v4: https://developers.cloudflare.com/api/
 // - Update DNS Record: PUT `/zones/:zone_id/dns_records/:record_id`
+// - Create DNS Record: POST `/zones/:zone_id/dns_records`
 // - List DNS Records: GET `/zones/:zone_id/dns_records?name=...&type=...`
-// - List Zones: GET `/zones?name=...`
+//   (also used by `get_record`; a missing record maps to `Error::NotFound`)
+// - List Zones: GET `/zones?name=...` (exact name match, one request per suffix)
 
 use async_trait::async_trait;
 use ddns_core::config::ProviderConfig;
-use ddns_core::traits::{DnsProvider, DnsProviderFactory, RecordMetadata, UpdateResult};
+use ddns_core::dns::{normalize_name, record_type, zone_candidates};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
 use ddns_core::{Error, Result};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::net::IpAddr;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Cloudflare API base URL
 const CLOUDFLARE_API_BASE: &str = "https://api.cloudflare.com/client/v4";
@@ -68,6 +82,9 @@ const CLOUDFLARE_API_BASE: &str = "https://api.cloudflare.com/client/v4";
 /// Default HTTP timeout for API requests (30 seconds)
 const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Default lifetime of cached zone lookups (1 hour)
+const DEFAULT_ZONE_CACHE_TTL: Duration = Duration::from_secs(3600);
+
 /// Cloudflare DNS provider
 ///
 /// # Trust Level: Untrusted
@@ -95,6 +112,9 @@ impl std::fmt::Debug for CloudflareProvider {
             .field("zone_id", &self.zone_id)
             .field("account_id", &self.account_id)
             .field("dry_run", &self.dry_run)
+            .field("api_base", &self.api_base)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("zone_overrides", &self.zone_overrides)
             .finish()
     }
 }
@@ -115,6 +135,41 @@ pub struct CloudflareProvider {
 
     /// Dry-run mode: if true, perform GET requests but skip PUT updates
     dry_run: bool,
+
+    /// API base URL
+    api_base: String,
+
+    /// Settings for creating missing records (None = missing records are an error)
+    create_if_missing: Option<NewRecordSettings>,
+
+    /// Zone IDs for specific record names, bypassing zone lookup
+    zone_overrides: HashMap<String, String>,
+
+    /// Zone lookup results by zone name
+    zone_cache: Mutex<HashMap<String, CachedZone>>,
+
+    /// How long zone lookup results are cached (zero disables the cache)
+    zone_cache_ttl: Duration,
+}
+
+/// A cached zone lookup result
+struct CachedZone {
+    /// The zone ID, or None if the name is not a zone
+    zone_id: Option<String>,
+    expires_at: Instant,
+}
+
+/// Settings for records created by the provider
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewRecordSettings {
+    /// TTL in seconds (None = automatic)
+    pub ttl: Option<u32>,
+
+    /// Whether the record is proxied through Cloudflare
+    pub proxied: bool,
+
+    /// Comment attached to the record
+    pub comment: Option<String>,
 }
 
 impl CloudflareProvider {
@@ -155,9 +210,42 @@ impl CloudflareProvider {
             account_id,
             client,
             dry_run,
+            api_base: CLOUDFLARE_API_BASE.to_string(),
+            create_if_missing: None,
+            zone_overrides: HashMap::new(),
+            zone_cache: Mutex::new(HashMap::new()),
+            zone_cache_ttl: DEFAULT_ZONE_CACHE_TTL,
         }
     }
 
+    /// Start building a provider with a custom API base URL, timeout or HTTP client
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_cloudflare::CloudflareProvider;
+    /// use std::time::Duration;
+    ///
+    /// let provider = CloudflareProvider::builder("token")
+    ///     .zone_id("023e105f4ecef8ad9ca31a8372d0c353")
+    ///     .api_base_url("http://127.0.0.1:8080/client/v4")
+    ///     .timeout(Duration::from_secs(5))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_token: impl Into<String>) -> CloudflareProviderBuilder {
+        CloudflareProviderBuilder::new(api_token)
+    }
+
+    /// Create missing A/AAAA records instead of failing with `NotFound`
+    ///
+    /// Created records use the given TTL, proxied flag and comment; existing
+    /// records are only ever updated, never re-created.
+    pub fn with_create_if_missing(mut self, settings: NewRecordSettings) -> Self {
+        self.create_if_missing = Some(settings);
+        self
+    }
+
     /// Create a new Cloudflare provider (production/live mode)
     ///
     /// This is a convenience method that creates a provider in live mode.
@@ -182,57 +270,126 @@ impl CloudflareProvider {
         Self::new(api_token, zone_id, account_id, true)
     }
 
-    /// Get the zone ID for a domain
+    /// Get the zone ID for a record name
+    ///
+    /// Resolution order:
+    /// 1. A per-record zone override (see [`CloudflareProviderBuilder::zone_override`])
+    /// 2. The pre-configured zone ID
+    /// 3. A lookup of each suffix of the record name, longest first, so that
+    ///    delegated subzones (`lab.example.com`) win over their parent zone
     ///
-    /// If zone_id is set, returns it directly. Otherwise, queries Cloudflare API
-    /// to find the zone ID for the given domain.
+    /// Lookup results (including suffixes that are not zones) are cached for
+    /// the zone cache TTL.
     ///
     /// # Parameters
     ///
-    /// - `domain`: The domain name
+    /// - `domain`: The record name
     ///
     /// # Returns
     ///
     /// - `Ok(String)`: The zone ID
-    /// - `Err(Error)`: If zone lookup fails
+    /// - `Err(Error::NotFound)`: If no suffix of the name is a zone in the account
+    /// - `Err(Error)`: If a zone lookup fails
     ///
     /// # API Call
     ///
     /// ```http
+    /// GET /zones?name=sub.example.com
     /// GET /zones?name=example.com
     /// Authorization: Bearer <token>
     /// ```
     async fn get_zone_id(&self, domain: &str) -> Result<String> {
+        let domain = normalize_name(domain);
+
+        if let Some(zone_id) = self.zone_overrides.get(&domain) {
+            tracing::debug!("Using zone ID override for {}", domain);
+            return Ok(zone_id.clone());
+        }
+
         // If zone_id is pre-configured, use it
         if let Some(ref zone_id) = self.zone_id {
             tracing::debug!("Using pre-configured zone ID");
             return Ok(zone_id.to_string());
         }
 
-        // Extract the root domain from the record name
-        // For "sub.example.com", we need "example.com"
-        let parts: Vec<&str> = domain.split('.').collect();
-        if parts.len() < 2 {
+        let suffixes = zone_candidates(&domain);
+        if suffixes.is_empty() {
             return Err(Error::config(format!("Invalid domain name: {}", domain)));
         }
 
-        // Use the last two parts for the zone
-        // For "sub.example.com" -> "example.com"
-        // For "deep.nested.example.co.uk" -> "example.co.uk" (not perfect, but works for most cases)
-        let zone_name = if parts.len() >= 3 && parts[parts.len() - 2].len() <= 3 {
-            // Handle TLDs like .co.uk, .com.au
-            format!("{}.{}", parts[parts.len() - 3], parts[parts.len() - 2])
-        } else {
-            format!("{}.{}", parts[parts.len() - 2], parts[parts.len() - 1])
-        };
+        for suffix in &suffixes {
+            let zone_id = match self.cached_zone(suffix) {
+                Some(cached) => cached,
+                None => {
+                    let zone_id = self.lookup_zone(suffix).await?;
+                    self.cache_zone(suffix, zone_id.clone());
+                    zone_id
+                }
+            };
+
+            if let Some(zone_id) = zone_id {
+                tracing::debug!(
+                    "Found zone ID for {}: {} (zone: {})",
+                    domain,
+                    zone_id,
+                    suffix
+                );
+                return Ok(zone_id);
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No Cloudflare zone found for {} (tried: {})",
+            domain,
+            suffixes.join(", ")
+        )))
+    }
+
+    /// Get a cached zone lookup result for a suffix, if it has not expired
+    ///
+    /// `Some(None)` means the suffix is known not to be a zone.
+    fn cached_zone(&self, suffix: &str) -> Option<Option<String>> {
+        let cache = self.zone_cache.lock().unwrap();
+        cache
+            .get(suffix)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.zone_id.clone())
+    }
+
+    /// Cache a zone lookup result for a suffix
+    fn cache_zone(&self, suffix: &str, zone_id: Option<String>) {
+        if self.zone_cache_ttl.is_zero() {
+            return;
+        }
+
+        let mut cache = self.zone_cache.lock().unwrap();
+        let now = Instant::now();
+        cache.retain(|_, entry| entry.expires_at > now);
+        cache.insert(
+            suffix.to_string(),
+            CachedZone {
+                zone_id,
+                expires_at: now + self.zone_cache_ttl,
+            },
+        );
+    }
 
-        tracing::debug!("Looking up zone ID for domain: {}", zone_name);
+    /// Look up the zone with exactly this name
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(String))`: The zone ID
+    /// - `Ok(None)`: The name is not a zone in the account
+    /// - `Err(Error)`: If the lookup fails
+    async fn lookup_zone(&self, zone_name: &str) -> Result<Option<String>> {
+        tracing::debug!("Looking up zone ID for: {}", zone_name);
 
         // Make API request to list zones
-        let url = format!("{}/zones?name={}", CLOUDFLARE_API_BASE, zone_name);
+        let url = format!("{}/zones", self.api_base);
         let response = self
             .client
             .get(&url)
+            .query(&[("name", zone_name)])
             .bearer_auth(&self.api_token)
             .header("Content-Type", "application/json")
             .send()
@@ -260,8 +417,8 @@ impl CloudflareProvider {
                     ))
                 }
                 404 => {
-                    // Zone not found
-                    Err(Error::not_found(format!("Zone not found: {}", zone_name)))
+                    // Not a zone; the caller moves on to the next suffix
+                    Ok(None)
                 }
                 429 => {
                     // Rate limit
@@ -306,9 +463,15 @@ impl CloudflareProvider {
             )
         })?;
 
-        let zone = zones
-            .first()
-            .ok_or_else(|| Error::not_found(format!("Zone not found: {}", zone_name)))?;
+        // The name filter is an exact match, but don't trust a zone whose
+        // name says otherwise
+        let Some(zone) = zones.iter().find(|zone| {
+            zone["name"]
+                .as_str()
+                .is_some_and(|name| name.eq_ignore_ascii_case(zone_name))
+        }) else {
+            return Ok(None);
+        };
 
         let zone_id = zone["id"].as_str().ok_or_else(|| {
             Error::provider(
@@ -317,8 +480,7 @@ impl CloudflareProvider {
             )
         })?;
 
-        tracing::debug!("Found zone ID: {}", zone_id);
-        Ok(zone_id.to_string())
+        Ok(Some(zone_id.to_string()))
     }
 
     /// Get the DNS record ID for a record name
@@ -333,6 +495,37 @@ impl CloudflareProvider {
     ///
     /// - `Ok(String)`: The record ID
     /// - `Err(Error)`: If record lookup fails
+    async fn get_record_id(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+    ) -> Result<String> {
+        let record = self.find_record(zone_id, record_name, record_type).await?;
+
+        let record_id = record["id"].as_str().ok_or_else(|| {
+            Error::provider(
+                "cloudflare",
+                "Invalid response format: record.id is not a string",
+            )
+        })?;
+
+        tracing::debug!("Found record ID: {}", record_id);
+        Ok(record_id.to_string())
+    }
+
+    /// Look up a DNS record by name and type
+    ///
+    /// # Parameters
+    ///
+    /// - `zone_id`: The zone ID
+    /// - `record_name`: The DNS record name
+    /// - `record_type`: The DNS record type (A or AAAA)
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Value)`: The record object as returned by the API
+    /// - `Err(Error)`: If record lookup fails
     ///
     /// # API Call
     ///
@@ -340,26 +533,20 @@ impl CloudflareProvider {
     /// GET /zones/:zone_id/dns_records?name=example.com&type=A
     /// Authorization: Bearer <token>
     /// ```
-    async fn get_record_id(
+    async fn find_record(
         &self,
         zone_id: &str,
         record_name: &str,
         record_type: &str,
-    ) -> Result<String> {
-        tracing::debug!(
-            "Looking up record ID: {} (type: {})",
-            record_name,
-            record_type
-        );
+    ) -> Result<Value> {
+        tracing::debug!("Looking up record: {} (type: {})", record_name, record_type);
 
-        let url = format!(
-            "{}/zones/{}/dns_records?name={}&type={}",
-            CLOUDFLARE_API_BASE, zone_id, record_name, record_type
-        );
+        let url = format!("{}/zones/{}/dns_records", self.api_base, zone_id);
 
         let response = self
             .client
             .get(&url)
+            .query(&[("name", record_name), ("type", record_type)])
             .bearer_auth(&self.api_token)
             .header("Content-Type", "application/json")
             .send()
@@ -417,22 +604,298 @@ impl CloudflareProvider {
             )
         })?;
 
-        let record = records.first().ok_or_else(|| {
+        records.first().cloned().ok_or_else(|| {
             Error::not_found(format!(
                 "DNS record not found: {} (type: {})",
                 record_name, record_type
             ))
-        })?;
+        })
+    }
 
-        let record_id = record["id"].as_str().ok_or_else(|| {
-            Error::provider(
-                "cloudflare",
-                "Invalid response format: record.id is not a string",
-            )
-        })?;
+    /// Create a new DNS record
+    ///
+    /// # Parameters
+    ///
+    /// - `zone_id`: The zone ID
+    /// - `record_name`: The DNS record name
+    /// - `record_type`: The DNS record type (A or AAAA)
+    /// - `ip`: The record content
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(UpdateResult::Created)`: The record was created
+    /// - `Err(Error)`: If creation fails
+    ///
+    /// # API Call
+    ///
+    /// ```http
+    /// POST /zones/:zone_id/dns_records
+    /// {
+    ///   "type": "A",
+    ///   "name": "sub.example.com",
+    ///   "content": "1.2.3.4",
+    ///   "ttl": 1,
+    ///   "proxied": false,
+    ///   "comment": "..."
+    /// }
+    /// ```
+    async fn create_record(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+        ip: IpAddr,
+    ) -> Result<UpdateResult> {
+        let settings = self.create_if_missing.clone().unwrap_or_default();
 
-        tracing::debug!("Found record ID: {}", record_id);
-        Ok(record_id.to_string())
+        let mut payload = serde_json::json!({
+            "type": record_type,
+            "name": record_name,
+            "content": ip.to_string(),
+            // 1 means "automatic" in the Cloudflare API
+            "ttl": settings.ttl.unwrap_or(1),
+            "proxied": settings.proxied,
+        });
+        if let Some(comment) = settings.comment {
+            payload["comment"] = Value::String(comment);
+        }
+
+        let url = format!("{}/zones/{}/dns_records", self.api_base, zone_id);
+
+        if self.dry_run {
+            tracing::info!(
+                "[DRY-RUN] Would send POST request to {} with payload: {}",
+                url,
+                payload
+            );
+            return Ok(UpdateResult::Created { new_ip: ip });
+        }
+
+        tracing::info!(
+            "Creating DNS record: {} -> {} ({})",
+            record_name,
+            ip,
+            record_type
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.api_token)
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| Error::provider("cloudflare", format!("HTTP request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unable to read error response".to_string());
+
+            return match status.as_u16() {
+                401 | 403 => Err(Error::provider(
+                    "cloudflare",
+                    format!(
+                        "Authentication failed: Invalid API token or insufficient permissions. Status: {}",
+                        status
+                    ),
+                )),
+                429 => Err(Error::provider(
+                    "cloudflare",
+                    format!(
+                        "Rate limit exceeded. Please retry later. Status: {}",
+                        status
+                    ),
+                )),
+                500..=599 => Err(Error::provider(
+                    "cloudflare",
+                    format!(
+                        "Cloudflare server error (transient): {} - {}",
+                        status, error_text
+                    ),
+                )),
+                _ => Err(Error::provider(
+                    "cloudflare",
+                    format!("Failed to create record: {} - {}", status, error_text),
+                )),
+            };
+        }
+
+        tracing::info!("DNS record created successfully: {} -> {}", record_name, ip);
+        Ok(UpdateResult::Created { new_ip: ip })
+    }
+}
+
+/// Builder for [`CloudflareProvider`]
+///
+/// Unlike [`CloudflareProvider::new`], [`build`](Self::build) reports an
+/// empty token or an unusable HTTP client as an error instead of panicking.
+pub struct CloudflareProviderBuilder {
+    api_token: String,
+    zone_id: Option<String>,
+    account_id: Option<String>,
+    dry_run: bool,
+    api_base_url: Option<String>,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    create_if_missing: Option<NewRecordSettings>,
+    zone_overrides: HashMap<String, String>,
+    zone_cache_ttl: Duration,
+}
+
+impl std::fmt::Debug for CloudflareProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudflareProviderBuilder")
+            .field("api_token", &"<REDACTED>")
+            .field("zone_id", &self.zone_id)
+            .field("account_id", &self.account_id)
+            .field("dry_run", &self.dry_run)
+            .field("api_base_url", &self.api_base_url)
+            .field("timeout", &self.timeout)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("zone_overrides", &self.zone_overrides)
+            .field("zone_cache_ttl", &self.zone_cache_ttl)
+            .finish()
+    }
+}
+
+impl CloudflareProviderBuilder {
+    /// Create a builder for the given API token
+    pub fn new(api_token: impl Into<String>) -> Self {
+        Self {
+            api_token: api_token.into(),
+            zone_id: None,
+            account_id: None,
+            dry_run: false,
+            api_base_url: None,
+            timeout: None,
+            client: None,
+            create_if_missing: None,
+            zone_overrides: HashMap::new(),
+            zone_cache_ttl: DEFAULT_ZONE_CACHE_TTL,
+        }
+    }
+
+    /// Use a pre-configured zone ID instead of looking it up
+    pub fn zone_id(mut self, zone_id: impl Into<String>) -> Self {
+        self.zone_id = Some(zone_id.into());
+        self
+    }
+
+    /// Set the account ID
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    /// Perform GET requests but skip PUT/POST requests
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send API requests to this base URL instead of the public Cloudflare API
+    ///
+    /// A trailing slash is ignored.
+    pub fn api_base_url(mut self, api_base_url: impl Into<String>) -> Self {
+        self.api_base_url = Some(api_base_url.into());
+        self
+    }
+
+    /// HTTP timeout for API requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy and TLS settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Create missing A/AAAA records (see [`CloudflareProvider::with_create_if_missing`])
+    pub fn create_if_missing(mut self, settings: NewRecordSettings) -> Self {
+        self.create_if_missing = Some(settings);
+        self
+    }
+
+    /// Use this zone ID for one record name, bypassing zone lookup
+    ///
+    /// Overrides take precedence over [`zone_id`](Self::zone_id).
+    pub fn zone_override(
+        mut self,
+        record_name: impl Into<String>,
+        zone_id: impl Into<String>,
+    ) -> Self {
+        let record_name = record_name.into();
+        self.zone_overrides
+            .insert(normalize_name(&record_name), zone_id.into());
+        self
+    }
+
+    /// How long zone lookup results are cached (default: 1 hour)
+    ///
+    /// A zero duration disables the cache.
+    pub fn zone_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.zone_cache_ttl = ttl;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the API token is empty
+    /// - `Error::Config` if the API base URL is not an http(s) URL
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<CloudflareProvider> {
+        if self.api_token.is_empty() {
+            return Err(Error::config("Cloudflare API token cannot be empty"));
+        }
+
+        let api_base = match self.api_base_url {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(url) => {
+                return Err(Error::config(format!(
+                    "Cloudflare API base URL must start with http:// or https://, got {}",
+                    url
+                )));
+            }
+            None => CLOUDFLARE_API_BASE.to_string(),
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .build()
+                .map_err(|e| {
+                    Error::provider("cloudflare", format!("Failed to build HTTP client: {}", e))
+                })?,
+        };
+
+        Ok(CloudflareProvider {
+            api_token: self.api_token,
+            zone_id: self.zone_id,
+            account_id: self.account_id,
+            client,
+            dry_run: self.dry_run,
+            api_base,
+            create_if_missing: self.create_if_missing,
+            zone_overrides: self.zone_overrides,
+            zone_cache: Mutex::new(HashMap::new()),
+            zone_cache_ttl: self.zone_cache_ttl,
+        })
     }
 }
 
@@ -441,11 +904,17 @@ impl DnsProvider for CloudflareProvider {
     /// Update a DNS record with a new IP address
     ///
     /// This implementation:
-    /// - Makes ONE HTTP request per engine event (GET to check, PUT if needed)
+    /// - Resolves the zone from the zone ID, a per-record override or the
+    ///   zone cache; on a cache miss it looks up each suffix of the name,
+    ///   longest first, with one `GET /zones` per suffix
+    /// - Looks the record ID up by name and type and reads the record, then
+    ///   sends a PUT only if the IP differs; a missing record is created with
+    ///   a POST instead if `create_if_missing` is set
     /// - Returns full error propagation (no retry, no backoff - owned by engine)
     /// - Never logs the API token
     /// - Never spawns background tasks
-    /// - Never caches state (owned by StateStore)
+    /// - Never caches record state (owned by StateStore); only zone IDs are
+    ///   cached
     /// - In dry-run mode, logs intended changes without making them
     ///
     /// # Parameters
@@ -461,6 +930,12 @@ impl DnsProvider for CloudflareProvider {
     /// # API Calls
     ///
     /// ```http
+    /// # Zone discovery on a cache miss, one request per suffix
+    /// GET /zones?name=:suffix
+    ///
+    /// # Find the record ID
+    /// GET /zones/:zone_id/dns_records?name=:record_name&type=A|AAAA
+    ///
     /// # Get current record
     /// GET /zones/:zone_id/dns_records/:record_id
     ///
@@ -470,13 +945,17 @@ impl DnsProvider for CloudflareProvider {
     ///   "content": "1.2.3.4",
     ///   "type": "A" or "AAAA"
     /// }
+    ///
+    /// # Create if missing and `create_if_missing` is set (skipped in dry-run mode)
+    /// POST /zones/:zone_id/dns_records
     /// ```
     async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
         // Determine record type based on IP address
-        let record_type = match new_ip {
-            IpAddr::V4(_) => "A",
-            IpAddr::V6(_) => "AAAA",
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
         };
+        let record_type = record_type(version);
 
         tracing::info!(
             "Updating Cloudflare DNS record: {} -> {} ({}) [mode: {}]",
@@ -489,15 +968,21 @@ impl DnsProvider for CloudflareProvider {
         // Step 1: Get zone ID
         let zone_id = self.get_zone_id(record_name).await?;
 
-        // Step 2: Get record ID
-        let record_id = self
-            .get_record_id(&zone_id, record_name, record_type)
-            .await?;
+        // Step 2: Get record ID (or create the record if allowed)
+        let record_id = match self.get_record_id(&zone_id, record_name, record_type).await {
+            Ok(record_id) => record_id,
+            Err(Error::NotFound(_)) if self.create_if_missing.is_some() => {
+                return self
+                    .create_record(&zone_id, record_name, record_type, new_ip)
+                    .await;
+            }
+            Err(e) => return Err(e),
+        };
 
         // Step 3: Get current record to check if IP matches
         let get_url = format!(
             "{}/zones/{}/dns_records/{}",
-            CLOUDFLARE_API_BASE, zone_id, record_id
+            self.api_base, zone_id, record_id
         );
 
         let get_response = self
@@ -674,10 +1159,33 @@ impl DnsProvider for CloudflareProvider {
         })
     }
 
-    async fn get_record(&self, _record_name: &str) -> Result<RecordMetadata> {
-        // TODO: Implement actual API call
-        // GET /zones/:zone_id/dns_records?name=example.com
-        Err(Error::not_found("get_record not implemented"))
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// # API Calls
+    ///
+    /// ```http
+    /// GET /zones?name=example.com
+    /// GET /zones/:zone_id/dns_records?name=sub.example.com&type=A
+    /// ```
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let record_type = record_type(version);
+
+        let zone_id = self.get_zone_id(record_name).await?;
+        let record = self.find_record(&zone_id, record_name, record_type).await?;
+
+        parse_record_metadata(record_name, &record)
     }
 
     fn supports_record(&self, record_name: &str) -> bool {
@@ -691,6 +1199,51 @@ impl DnsProvider for CloudflareProvider {
     }
 }
 
+/// Convert a Cloudflare DNS record object into [`RecordMetadata`]
+///
+/// Cloudflare-specific fields are exposed in `extra`:
+/// `type`, `zone_id`, `proxied`, `comment`, `tags` and `modified_on`.
+/// A TTL of 1 means "automatic" in the Cloudflare API and is reported as `None`.
+fn parse_record_metadata(record_name: &str, record: &Value) -> Result<RecordMetadata> {
+    let id = record["id"].as_str().ok_or_else(|| {
+        Error::provider(
+            "cloudflare",
+            "Invalid response format: record.id is not a string",
+        )
+    })?;
+
+    let ip: IpAddr = record["content"]
+        .as_str()
+        .ok_or_else(|| {
+            Error::provider(
+                "cloudflare",
+                "Invalid response format: content is not a string",
+            )
+        })?
+        .parse()
+        .map_err(|e| Error::provider("cloudflare", format!("Invalid IP in response: {}", e)))?;
+
+    let ttl = record["ttl"]
+        .as_u64()
+        .filter(|&ttl| ttl != 1)
+        .map(|ttl| ttl as u32);
+
+    Ok(RecordMetadata {
+        id: id.to_string(),
+        name: record["name"].as_str().unwrap_or(record_name).to_string(),
+        ip,
+        ttl,
+        extra: serde_json::json!({
+            "type": record["type"],
+            "zone_id": record["zone_id"],
+            "proxied": record["proxied"].as_bool().unwrap_or(false),
+            "comment": record["comment"],
+            "tags": record["tags"].as_array().cloned().unwrap_or_default(),
+            "modified_on": record["modified_on"],
+        }),
+    })
+}
+
 /// Factory for creating Cloudflare providers
 pub struct CloudflareFactory;
 
@@ -701,6 +1254,12 @@ impl DnsProviderFactory for CloudflareFactory {
                 api_token,
                 zone_id,
                 account_id,
+                create_if_missing,
+                ttl,
+                proxied,
+                comment,
+                api_base_url,
+                zone_overrides,
             } => {
                 if api_token.is_empty() {
                     return Err(Error::config("Cloudflare API token is required"));
@@ -718,12 +1277,29 @@ impl DnsProviderFactory for CloudflareFactory {
                     );
                 }
 
-                Ok(Box::new(CloudflareProvider::new(
-                    api_token.clone(),
-                    zone_id.clone(),
-                    account_id.clone(),
-                    dry_run,
-                )))
+                let mut builder = CloudflareProvider::builder(api_token.clone()).dry_run(dry_run);
+
+                if let Some(zone_id) = zone_id {
+                    builder = builder.zone_id(zone_id.clone());
+                }
+                if let Some(account_id) = account_id {
+                    builder = builder.account_id(account_id.clone());
+                }
+                if let Some(api_base_url) = api_base_url {
+                    builder = builder.api_base_url(api_base_url.clone());
+                }
+                for (record_name, zone_id) in zone_overrides {
+                    builder = builder.zone_override(record_name.clone(), zone_id.clone());
+                }
+                if *create_if_missing {
+                    builder = builder.create_if_missing(NewRecordSettings {
+                        ttl: *ttl,
+                        proxied: *proxied,
+                        comment: comment.clone(),
+                    });
+                }
+
+                Ok(Box::new(builder.build()?))
             }
             _ => Err(Error::config("Invalid config for Cloudflare provider")),
         }
@@ -759,6 +1335,12 @@ mod tests {
             api_token: "test_token".to_string(),
             zone_id: Some("test_zone".to_string()),
             account_id: None,
+            create_if_missing: false,
+            ttl: None,
+            proxied: false,
+            comment: None,
+            api_base_url: None,
+            zone_overrides: Default::default(),
         };
 
         let provider = factory.create(&config);
@@ -773,6 +1355,12 @@ mod tests {
             api_token: "".to_string(),
             zone_id: None,
             account_id: None,
+            create_if_missing: false,
+            ttl: None,
+            proxied: false,
+            comment: None,
+            api_base_url: None,
+            zone_overrides: Default::default(),
         };
 
         let provider = factory.create(&config);
@@ -785,6 +1373,34 @@ mod tests {
         CloudflareProvider::new("", None, None, false);
     }
 
+    #[test]
+    fn test_builder() {
+        let provider = CloudflareProvider::builder("token")
+            .zone_id("zone1")
+            .dry_run(true)
+            .api_base_url("http://127.0.0.1:8080/client/v4/")
+            .build()
+            .unwrap();
+
+        assert_eq!(provider.zone_id, Some("zone1".to_string()));
+        assert!(provider.dry_run);
+        assert_eq!(provider.api_base, "http://127.0.0.1:8080/client/v4");
+
+        let default = CloudflareProvider::builder("token").build().unwrap();
+        assert_eq!(default.api_base, CLOUDFLARE_API_BASE);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(CloudflareProvider::builder("").build().is_err());
+        assert!(
+            CloudflareProvider::builder("token")
+                .api_base_url("api.example.com")
+                .build()
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_dry_run_mode() {
         let provider_dry = CloudflareProvider::new_dry_run("token", None, None);
@@ -839,6 +1455,239 @@ mod tests {
         assert!(debug_str.contains("CloudflareProvider"));
     }
 
+    #[test]
+    fn test_parse_record_metadata() {
+        let record = serde_json::json!({
+            "id": "372e67954025e0ba6aaa6d586b9e0b59",
+            "zone_id": "023e105f4ecef8ad9ca31a8372d0c353",
+            "name": "home.example.com",
+            "type": "AAAA",
+            "content": "2001:db8::5",
+            "proxied": true,
+            "ttl": 300,
+            "comment": "managed by ddnsd",
+            "tags": ["owner:ddns"],
+            "modified_on": "2024-01-01T05:20:00.12345Z"
+        });
+
+        let metadata = parse_record_metadata("home.example.com", &record).unwrap();
+
+        assert_eq!(metadata.id, "372e67954025e0ba6aaa6d586b9e0b59");
+        assert_eq!(metadata.name, "home.example.com");
+        assert_eq!(metadata.ip, "2001:db8::5".parse::<IpAddr>().unwrap());
+        assert_eq!(metadata.ttl, Some(300));
+        assert_eq!(metadata.extra["type"], "AAAA");
+        assert_eq!(
+            metadata.extra["zone_id"],
+            "023e105f4ecef8ad9ca31a8372d0c353"
+        );
+        assert_eq!(metadata.extra["proxied"], true);
+        assert_eq!(metadata.extra["comment"], "managed by ddnsd");
+        assert_eq!(metadata.extra["tags"], serde_json::json!(["owner:ddns"]));
+    }
+
+    #[test]
+    fn test_parse_record_metadata_defaults() {
+        // Automatic TTL, no comment, no tags
+        let record = serde_json::json!({
+            "id": "abc",
+            "content": "203.0.113.5",
+            "ttl": 1
+        });
+
+        let metadata = parse_record_metadata("example.com", &record).unwrap();
+
+        assert_eq!(metadata.name, "example.com");
+        assert_eq!(metadata.ttl, None);
+        assert_eq!(metadata.extra["proxied"], false);
+        assert!(metadata.extra["comment"].is_null());
+        assert_eq!(metadata.extra["tags"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_parse_record_metadata_invalid() {
+        let missing_id = serde_json::json!({ "content": "203.0.113.5" });
+        assert!(parse_record_metadata("example.com", &missing_id).is_err());
+
+        let bad_content = serde_json::json!({ "id": "abc", "content": "example.net" });
+        assert!(parse_record_metadata("example.com", &bad_content).is_err());
+    }
+
+    /// Provider pointed at a local mock server, with a pre-configured zone
+    fn mock_provider(server: &wiremock::MockServer) -> CloudflareProvider {
+        CloudflareProvider::builder("test_token")
+            .zone_id("zone1")
+            .api_base_url(server.uri())
+            .build()
+            .unwrap()
+    }
+
+    /// Mock an A/AAAA record listing
+    async fn mock_record_list(server: &wiremock::MockServer, record_type: &str, result: Value) {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, ResponseTemplate};
+
+        Mock::given(method("GET"))
+            .and(path("/zones/zone1/dns_records"))
+            .and(query_param("type", record_type))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "success": true, "result": result })),
+            )
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_create_if_missing_creates_record() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_record_list(&server, "A", serde_json::json!([])).await;
+
+        Mock::given(method("POST"))
+            .and(path("/zones/zone1/dns_records"))
+            .and(body_partial_json(serde_json::json!({
+                "type": "A",
+                "name": "new.example.com",
+                "content": "203.0.113.5",
+                "ttl": 120,
+                "proxied": true,
+                "comment": "managed by ddnsd"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "result": { "id": "rec1", "content": "203.0.113.5" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = mock_provider(&server).with_create_if_missing(NewRecordSettings {
+            ttl: Some(120),
+            proxied: true,
+            comment: Some("managed by ddnsd".to_string()),
+        });
+
+        let ip: IpAddr = "203.0.113.5".parse().unwrap();
+        let result = provider.update_record("new.example.com", ip).await.unwrap();
+
+        assert_eq!(result, UpdateResult::Created { new_ip: ip });
+    }
+
+    #[tokio::test]
+    async fn test_create_if_missing_uses_automatic_ttl() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_record_list(&server, "AAAA", serde_json::json!([])).await;
+
+        Mock::given(method("POST"))
+            .and(path("/zones/zone1/dns_records"))
+            .and(body_partial_json(serde_json::json!({
+                "type": "AAAA",
+                "content": "2001:db8::5",
+                "ttl": 1,
+                "proxied": false
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "success": true })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = mock_provider(&server).with_create_if_missing(NewRecordSettings::default());
+
+        let ip: IpAddr = "2001:db8::5".parse().unwrap();
+        let result = provider.update_record("new.example.com", ip).await.unwrap();
+
+        assert_eq!(result, UpdateResult::Created { new_ip: ip });
+    }
+
+    #[tokio::test]
+    async fn test_missing_record_without_create_is_not_found() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_record_list(&server, "A", serde_json::json!([])).await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let provider = mock_provider(&server);
+
+        let result = provider
+            .update_record("new.example.com", "203.0.113.5".parse().unwrap())
+            .await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_if_missing_keeps_existing_record() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_record_list(
+            &server,
+            "A",
+            serde_json::json!([{ "id": "rec1", "content": "203.0.113.5" }]),
+        )
+        .await;
+
+        Mock::given(method("GET"))
+            .and(path("/zones/zone1/dns_records/rec1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "result": { "id": "rec1", "content": "203.0.113.5" }
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let provider = mock_provider(&server).with_create_if_missing(NewRecordSettings::default());
+
+        let ip: IpAddr = "203.0.113.5".parse().unwrap();
+        let result = provider.update_record("new.example.com", ip).await.unwrap();
+
+        assert_eq!(result, UpdateResult::Unchanged { current_ip: ip });
+    }
+
+    #[tokio::test]
+    async fn test_create_failure_is_propagated() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_record_list(&server, "A", serde_json::json!([])).await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("invalid content"))
+            .mount(&server)
+            .await;
+
+        let provider = mock_provider(&server).with_create_if_missing(NewRecordSettings::default());
+
+        let result = provider
+            .update_record("new.example.com", "203.0.113.5".parse().unwrap())
+            .await;
+
+        assert!(matches!(result, Err(Error::Provider { .. })));
+    }
+
     #[test]
     fn test_http_timeout_configured() {
         // Test that HTTP client is configured with timeout
@@ -0,0 +1,373 @@
+//! Cloudflare API error mapping against a local stub server
+//!
+//! An update looks up the zone and the record, reads the record and PUTs
+//! the new address. Each call is failed in turn while the calls before it
+//! succeed. Cloudflare reports every failure as a provider error, whose
+//! message says whether it is an authentication failure, a rate limit or a
+//! transient server error; the `errors` of other client errors are passed
+//! on.
+
+mod common;
+
+use common::{failure, provider, success};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_cloudflare::{CloudflareProvider, NewRecordSettings};
+use ddns_test_support::{ApiCall, Expect, assert_maps_to, fail_at};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// An apex record, so that zone resolution takes a single lookup
+const RECORD_NAME: &str = "example.com";
+
+/// The API calls made by `update_record`, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    /// GET /zones?name=...
+    ListZones,
+    /// GET /zones/:zone_id/dns_records?name=...&type=...
+    ListRecords,
+    /// GET /zones/:zone_id/dns_records/:record_id
+    GetRecord,
+    /// PUT /zones/:zone_id/dns_records/:record_id
+    UpdateRecord,
+}
+
+const CALLS: [Call; 4] = [
+    Call::ListZones,
+    Call::ListRecords,
+    Call::GetRecord,
+    Call::UpdateRecord,
+];
+
+impl ApiCall for Call {
+    fn method(self) -> &'static str {
+        match self {
+            Call::UpdateRecord => "PUT",
+            _ => "GET",
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Call::ListZones => "/zones",
+            Call::ListRecords => "/zones/zone1/dns_records",
+            Call::GetRecord | Call::UpdateRecord => "/zones/zone1/dns_records/rec1",
+        }
+    }
+
+    fn success(self) -> ResponseTemplate {
+        success(match self {
+            Call::ListZones => json!([{ "id": "zone1", "name": "example.com" }]),
+            Call::ListRecords => {
+                json!([{ "id": "rec1", "name": RECORD_NAME, "content": "198.51.100.1" }])
+            }
+            Call::GetRecord => json!({ "id": "rec1", "content": "198.51.100.1" }),
+            Call::UpdateRecord => json!({ "id": "rec1", "content": "203.0.113.5" }),
+        })
+    }
+}
+
+/// Run `update_record` with `failing` answered by `response`
+async fn update_failing_at(
+    failing: Call,
+    response: ResponseTemplate,
+) -> ddns_core::Result<UpdateResult> {
+    let server = fail_at(&CALLS, failing, response).await;
+    provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+}
+
+#[tokio::test]
+async fn update_reads_the_record_before_replacing_it() {
+    let server = MockServer::start().await;
+    for call in CALLS {
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(call.success())
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let result = provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some("198.51.100.1".parse().unwrap()),
+            new_ip: "203.0.113.5".parse().unwrap(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn status_codes_map_to_error_kinds() {
+    for call in CALLS {
+        let cases = [
+            (
+                failure(401, 10000, "Authentication error"),
+                Expect::Provider("Authentication failed"),
+            ),
+            (
+                failure(403, 9109, "Invalid access token"),
+                Expect::Provider("Authentication failed"),
+            ),
+            (
+                failure(
+                    429,
+                    971,
+                    "Please wait and consider throttling your request speed",
+                ),
+                Expect::Provider("Rate limit exceeded"),
+            ),
+            (
+                failure(502, 10013, "Bad gateway"),
+                Expect::Provider("server error (transient)"),
+            ),
+            (
+                failure(400, 1004, "DNS Validation Error"),
+                Expect::Provider("DNS Validation Error"),
+            ),
+        ];
+        for (response, expect) in cases {
+            assert_maps_to(
+                update_failing_at(call, response).await,
+                "cloudflare",
+                expect,
+                &format!("{:?} -> {:?}", call, expect),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn missing_zone_or_record_is_not_found() {
+    for call in [Call::ListZones, Call::ListRecords, Call::GetRecord] {
+        assert_maps_to(
+            update_failing_at(call, failure(404, 81044, "Record does not exist.")).await,
+            "cloudflare",
+            Expect::NotFound,
+            &format!("{:?} -> 404", call),
+        );
+    }
+
+    for call in [Call::ListZones, Call::ListRecords] {
+        assert_maps_to(
+            update_failing_at(call, success(json!([]))).await,
+            "cloudflare",
+            Expect::NotFound,
+            &format!("{:?} -> empty result", call),
+        );
+    }
+}
+
+#[tokio::test]
+async fn record_deleted_before_the_put_fails_the_update() {
+    // The record was just looked up, so a 404 is not a lookup miss
+    assert_maps_to(
+        update_failing_at(
+            Call::UpdateRecord,
+            failure(404, 81044, "Record does not exist."),
+        )
+        .await,
+        "cloudflare",
+        Expect::Provider("Failed to update record"),
+        "UpdateRecord -> 404",
+    );
+}
+
+#[tokio::test]
+async fn conflicting_record_fails_the_put() {
+    assert_maps_to(
+        update_failing_at(
+            Call::UpdateRecord,
+            failure(
+                409,
+                81053,
+                "An A, AAAA, or CNAME record with that host already exists.",
+            ),
+        )
+        .await,
+        "cloudflare",
+        Expect::Provider("Conflict"),
+        "UpdateRecord -> 409",
+    );
+}
+
+#[tokio::test]
+async fn unreadable_responses_fail_the_update() {
+    // The PUT response body is not inspected
+    for call in [Call::ListZones, Call::ListRecords, Call::GetRecord] {
+        assert_maps_to(
+            update_failing_at(
+                call,
+                ResponseTemplate::new(200).set_body_string("{\"result\": [}"),
+            )
+            .await,
+            "cloudflare",
+            Expect::Provider("Failed to parse response"),
+            &format!("{:?} -> malformed JSON", call),
+        );
+        assert_maps_to(
+            update_failing_at(call, success(json!(null))).await,
+            "cloudflare",
+            Expect::Provider("Invalid response format"),
+            &format!("{:?} -> null result", call),
+        );
+    }
+}
+
+#[tokio::test]
+async fn record_content_that_is_not_an_address_fails_the_update() {
+    assert_maps_to(
+        update_failing_at(
+            Call::GetRecord,
+            success(json!({ "id": "rec1", "content": "home.example.net" })),
+        )
+        .await,
+        "cloudflare",
+        Expect::Provider("Invalid IP in response"),
+        "GetRecord -> hostname content",
+    );
+}
+
+#[tokio::test]
+async fn create_failures() {
+    let cases = [
+        (401, Expect::Provider("Authentication failed")),
+        (403, Expect::Provider("Authentication failed")),
+        (404, Expect::Provider("Failed to create record")),
+        (429, Expect::Provider("Rate limit exceeded")),
+        (503, Expect::Provider("server error (transient)")),
+    ];
+
+    for (status, expect) in cases {
+        let server = MockServer::start().await;
+        for (call, response) in [
+            (Call::ListZones, Call::ListZones.success()),
+            (Call::ListRecords, success(json!([]))),
+        ] {
+            Mock::given(method(call.method()))
+                .and(path(call.path()))
+                .respond_with(response)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/zones/zone1/dns_records"))
+            .respond_with(failure(status, 1004, "DNS Validation Error"))
+            .mount(&server)
+            .await;
+
+        let provider = CloudflareProvider::builder("test_token")
+            .api_base_url(server.uri())
+            .create_if_missing(NewRecordSettings::default())
+            .build()
+            .unwrap();
+
+        assert_maps_to(
+            provider
+                .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+                .await,
+            "cloudflare",
+            expect,
+            &format!("CreateRecord -> {}", status),
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_record_maps_errors() {
+    for (status, expect) in [
+        (401, Expect::Provider("Authentication failed")),
+        (404, Expect::NotFound),
+        (429, Expect::Provider("Rate limit exceeded")),
+        (500, Expect::Provider("server error (transient)")),
+    ] {
+        let server = fail_at(
+            &CALLS,
+            Call::ListRecords,
+            failure(status, 1000, "lookup rejected"),
+        )
+        .await;
+
+        let result = provider(&server)
+            .get_record_for(RECORD_NAME, IpVersion::V4)
+            .await;
+        assert_maps_to(
+            result,
+            "cloudflare",
+            expect,
+            &format!("get_record -> {}", status),
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_zone_lookup_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListZones.path()))
+        .respond_with(Call::ListZones.success().set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let provider = CloudflareProvider::builder("test_token")
+        .api_base_url(server.uri())
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let result = provider
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_maps_to(
+        result,
+        "cloudflare",
+        Expect::Provider("HTTP request failed"),
+        "ListZones -> timeout",
+    );
+}
+
+#[tokio::test]
+async fn injected_client_is_used() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListZones.path()))
+        .and(wiremock::matchers::header("user-agent", "ddns-test"))
+        .respond_with(Call::ListZones.success())
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListRecords.path()))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::builder()
+        .user_agent("ddns-test")
+        .build()
+        .unwrap();
+    let provider = CloudflareProvider::builder("test_token")
+        .api_base_url(server.uri())
+        .client(client)
+        .build()
+        .unwrap();
+
+    // The zone lookup only matches with the custom user agent
+    assert!(matches!(
+        provider.get_record_for(RECORD_NAME, IpVersion::V4).await,
+        Err(Error::NotFound(_))
+    ));
+}
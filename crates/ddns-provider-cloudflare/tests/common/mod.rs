@@ -0,0 +1,30 @@
+//! Stub server fixtures shared by the Cloudflare API tests
+
+#![allow(dead_code)]
+
+use ddns_provider_cloudflare::CloudflareProvider;
+use serde_json::{Value, json};
+use wiremock::{MockServer, ResponseTemplate};
+
+/// A successful API response with `result` in the Cloudflare envelope
+pub fn success(result: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "success": true, "result": result }))
+}
+
+/// A failed API response listing one error in the Cloudflare envelope
+pub fn failure(status: u16, code: u32, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({
+        "success": false,
+        "errors": [{ "code": code, "message": message }],
+        "messages": [],
+        "result": null,
+    }))
+}
+
+/// A provider with default settings talking to the stub server
+pub fn provider(server: &MockServer) -> CloudflareProvider {
+    CloudflareProvider::builder("test_token")
+        .api_base_url(server.uri())
+        .build()
+        .expect("provider builds")
+}
@@ -0,0 +1,187 @@
+//! Cloudflare zone resolution against a local stub server
+//!
+//! Zones are resolved by querying each suffix of the record name, longest
+//! first. Only a zone whose name matches counts. Lookups are cached, and
+//! per-record overrides bypass them entirely.
+
+mod common;
+
+use common::{provider, success};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion};
+use ddns_provider_cloudflare::CloudflareProvider;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+/// Answer zone lookups for `zone_name` with `zone_id`, expecting `calls` lookups
+async fn mock_zone(server: &MockServer, zone_name: &str, zone_id: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", zone_name))
+        .respond_with(success(json!([{ "id": zone_id, "name": zone_name }])))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer zone lookups for `zone_name` with an empty result
+async fn mock_not_a_zone(server: &MockServer, zone_name: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", zone_name))
+        .respond_with(success(json!([])))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer A record lookups in `zone_id`
+async fn mock_records(server: &MockServer, zone_id: &str, record_name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/zones/{}/dns_records", zone_id)))
+        .and(query_param("name", record_name))
+        .respond_with(success(json!([
+            { "id": "rec1", "name": record_name, "content": "203.0.113.5" }
+        ])))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn delegated_subzone_wins_over_parent() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "host.lab.example.com", 1).await;
+    mock_zone(&server, "lab.example.com", "lab-zone", 1).await;
+    mock_zone(&server, "example.com", "parent-zone", 0).await;
+    mock_records(&server, "lab-zone", "host.lab.example.com").await;
+
+    let record = provider(&server)
+        .get_record_for("host.lab.example.com", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "rec1");
+}
+
+#[tokio::test]
+async fn short_second_level_names_are_not_mistaken_for_public_suffixes() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "a.abc.net", 1).await;
+    mock_zone(&server, "abc.net", "abc-zone", 1).await;
+    mock_records(&server, "abc-zone", "a.abc.net").await;
+
+    assert!(
+        provider(&server)
+            .get_record_for("a.abc.net", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn lookups_are_cached() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "www.example.com", 1).await;
+    mock_not_a_zone(&server, "home.example.com", 1).await;
+    mock_zone(&server, "example.com", "zone1", 1).await;
+    mock_records(&server, "zone1", "www.example.com").await;
+    mock_records(&server, "zone1", "home.example.com").await;
+
+    let provider = provider(&server);
+    for record_name in ["www.example.com", "www.example.com", "home.example.com"] {
+        provider
+            .get_record_for(record_name, IpVersion::V4)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn cache_can_be_disabled() {
+    let server = MockServer::start().await;
+    mock_zone(&server, "example.com", "zone1", 2).await;
+    mock_records(&server, "zone1", "example.com").await;
+
+    let provider = CloudflareProvider::builder("test_token")
+        .api_base_url(server.uri())
+        .zone_cache_ttl(Duration::ZERO)
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        provider
+            .get_record_for("example.com", IpVersion::V4)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn override_bypasses_lookup() {
+    let server = MockServer::start().await;
+    mock_zone(&server, "example.com", "zone1", 0).await;
+    mock_records(&server, "override-zone", "home.example.com").await;
+
+    let provider = CloudflareProvider::builder("test_token")
+        .api_base_url(server.uri())
+        .zone_id("zone1")
+        .zone_override("Home.Example.com.", "override-zone")
+        .build()
+        .unwrap();
+
+    assert!(
+        provider
+            .get_record_for("home.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn unknown_zone_is_not_found() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "home.example.org", 1).await;
+    mock_not_a_zone(&server, "example.org", 1).await;
+
+    let result = provider(&server)
+        .get_record_for("home.example.org", IpVersion::V4)
+        .await;
+
+    match result {
+        Err(Error::NotFound(message)) => {
+            assert!(
+                message.contains("home.example.org, example.org"),
+                "{}",
+                message
+            )
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn zone_without_matching_name_is_ignored() {
+    let server = MockServer::start().await;
+    // A filter the server ignored: nameless or foreign zones are not ours
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "home.example.com"))
+        .respond_with(success(json!([
+            { "id": "nameless" },
+            { "id": "other", "name": "example.net" }
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mock_zone(&server, "example.com", "zone-parent", 1).await;
+    mock_records(&server, "zone-parent", "home.example.com").await;
+
+    let record = provider(&server)
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "rec1");
+}
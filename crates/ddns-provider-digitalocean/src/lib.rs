@@ -0,0 +1,788 @@
+// # DigitalOcean DNS Provider
+//
+// This crate provides a DNS provider that updates records of domains hosted
+// on DigitalOcean DNS through the DigitalOcean API v2.
+//
+// ## Implementation Status
+//
+// - ✅ Domain from configuration, or looked up by name (longest matching
+//   suffix of the record name)
+// - ✅ One request to read the records of the name and type, one PUT if the
+//   address differs
+// - ✅ Optional creation of missing records (`create_if_missing`)
+// - ✅ Record lookup (`get_record`); a missing record maps to `Error::NotFound`
+// - ✅ Status codes map to `Error::Authentication` (401, 403),
+//   `Error::NotFound` (404) and `Error::RateLimited` (429)
+// - ✅ Configurable API base URL and injectable HTTP client (for offline tests)
+// - ✅ Dry-run mode (reads are sent, changes are not)
+// - ✅ Both A and AAAA record support
+// - ❌ NO retry logic (owned by DdnsEngine)
+// - ❌ NO caching of DNS state or domains (state owned by StateStore)
+// - ❌ NO background tasks (violates shutdown determinism)
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated, stateless and single-shot. It
+// only talks to the configured API base URL.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The API token NEVER appears in logs
+// - The API token MUST be provided via environment variables only
+// - Provider MUST fail fast if the token is empty
+// - The token only needs the `domain:read` and `domain:update` scopes
+//   (`domain:create` as well for `create_if_missing`)
+//
+// ## API Reference
+//
+// - DigitalOcean API v2: https://docs.digitalocean.com/reference/api/
+// - Retrieve a Domain: GET `/domains/:domain` (one request per suffix)
+// - List Domain Records: GET `/domains/:domain/records?type=...&name=...`
+// - Update a Domain Record: PUT `/domains/:domain/records/:record_id`
+// - Create a Domain Record: POST `/domains/:domain/records`
+//
+// Record names are relative to the domain (`@` for the domain apex), except
+// for the `name` filter of the list call, which takes the full name.
+
+use async_trait::async_trait;
+use ddns_core::config::ProviderConfig;
+use ddns_core::dns::{normalize_name, record_type, relative_name, zone_candidates};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use reqwest::{Method, StatusCode, Url};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// DigitalOcean API base URL
+pub const DIGITALOCEAN_API_BASE: &str = "https://api.digitalocean.com/v2";
+
+/// Records requested per page; a name has far fewer A or AAAA records
+const PER_PAGE: &str = "200";
+
+/// Default HTTP timeout for API requests (30 seconds)
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// DigitalOcean DNS provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated, stateless, and single-shot. All coordination
+/// (retries, backoff, scheduling) is owned by `DdnsEngine`.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API token.
+pub struct DigitalOceanProvider {
+    /// API token, sent as a bearer token
+    api_token: String,
+
+    /// Domain of all records (None = looked up per record)
+    domain: Option<String>,
+
+    /// Create missing records instead of failing
+    create_if_missing: bool,
+
+    /// TTL of created records (None = DigitalOcean's default of 1800)
+    ttl: Option<u32>,
+
+    /// API base URL without a trailing slash
+    api_base: String,
+
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Dry-run mode: if true, send reads but skip changes
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for DigitalOceanProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DigitalOceanProvider")
+            .field("api_token", &"<REDACTED>")
+            .field("domain", &self.domain)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("ttl", &self.ttl)
+            .field("api_base", &self.api_base)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// An A or AAAA domain record
+#[derive(Debug)]
+struct Record {
+    id: u64,
+    data: String,
+    ttl: Option<u32>,
+}
+
+impl DigitalOceanProvider {
+    /// Start building a provider
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_digitalocean::DigitalOceanProvider;
+    ///
+    /// let provider = DigitalOceanProvider::builder("api-token")
+    ///     .domain("example.com")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_token: impl Into<String>) -> DigitalOceanProviderBuilder {
+        DigitalOceanProviderBuilder::new(api_token)
+    }
+
+    /// URL of an API path with query parameters
+    fn url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}{}", self.api_base, path))
+            .map_err(|e| Error::config(format!("Invalid DigitalOcean API base URL: {}", e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    /// Send a request and return the parsed response body
+    ///
+    /// `action` names the API call in error messages.
+    async fn send(
+        &self,
+        action: &str,
+        method: Method,
+        url: Url,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .bearer_auth(&self.api_token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::provider("digitalocean", format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            Error::provider(
+                "digitalocean",
+                format!("Failed to read {} response: {}", action, e),
+            )
+        })?;
+
+        if !status.is_success() {
+            return Err(api_error(action, status, &text));
+        }
+        serde_json::from_str(&text).map_err(|e| {
+            Error::provider(
+                "digitalocean",
+                format!("Invalid {} response: {}", action, e),
+            )
+        })
+    }
+
+    /// Get the domain of a record name
+    ///
+    /// Resolution order:
+    /// 1. The configured domain
+    /// 2. The domain named like the longest suffix of the record name
+    ///    (one request per suffix)
+    async fn domain_for(&self, record_name: &str) -> Result<String> {
+        let name = normalize_name(record_name);
+
+        if let Some(domain) = &self.domain {
+            if relative_name(&name, domain).is_none() {
+                return Err(Error::config(format!(
+                    "{} is not in the DigitalOcean domain {}",
+                    record_name, domain
+                )));
+            }
+            return Ok(domain.clone());
+        }
+
+        for candidate in zone_candidates(&name) {
+            let url = self.url(&format!("/domains/{}", candidate), &[])?;
+            match self.send("get domain", Method::GET, url, None).await {
+                Ok(_) => {
+                    tracing::debug!("Found domain for {}: {}", name, candidate);
+                    return Ok(candidate);
+                }
+                // Not a domain of the account; try the next suffix
+                Err(Error::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No DigitalOcean domain found for {}",
+            record_name
+        )))
+    }
+
+    /// All `record_type` records of `record_name`
+    async fn records(
+        &self,
+        domain: &str,
+        record_name: &str,
+        record_type: &str,
+    ) -> Result<Vec<Record>> {
+        let name = normalize_name(record_name);
+        let relative = relative_name(&name, domain).unwrap_or_default();
+
+        let url = self.url(
+            &format!("/domains/{}/records", domain),
+            &[
+                ("type", record_type),
+                ("name", &name),
+                ("per_page", PER_PAGE),
+            ],
+        )?;
+        let body = self.send("list records", Method::GET, url, None).await?;
+
+        // The filters are applied here as well, so a server that ignores
+        // them cannot make the wrong record look like a match
+        Ok(body["domain_records"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|record| {
+                record["type"].as_str() == Some(record_type)
+                    && record["name"]
+                        .as_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(&relative))
+            })
+            .filter_map(|record| {
+                Some(Record {
+                    id: record["id"].as_u64()?,
+                    data: record["data"].as_str()?.to_string(),
+                    ttl: record["ttl"].as_u64().and_then(|ttl| ttl.try_into().ok()),
+                })
+            })
+            .collect())
+    }
+
+    /// Create a record in a domain
+    async fn create_record(
+        &self,
+        domain: &str,
+        record_name: &str,
+        record_type: &str,
+        ip: IpAddr,
+    ) -> Result<UpdateResult> {
+        let relative = relative_name(&normalize_name(record_name), domain).unwrap_or_default();
+        let mut payload = serde_json::json!({
+            "type": record_type,
+            "name": relative,
+            "data": ip.to_string(),
+        });
+        if let Some(ttl) = self.ttl {
+            payload["ttl"] = ttl.into();
+        }
+
+        tracing::info!(
+            "{} {} record: {} -> {} (domain: {}) [mode: {}]",
+            if self.dry_run {
+                "Would create"
+            } else {
+                "Creating"
+            },
+            record_type,
+            record_name,
+            ip,
+            domain,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if !self.dry_run {
+            let url = self.url(&format!("/domains/{}/records", domain), &[])?;
+            self.send("create record", Method::POST, url, Some(&payload))
+                .await?;
+        }
+
+        Ok(UpdateResult::Created { new_ip: ip })
+    }
+}
+
+/// Map an error response onto an error kind
+///
+/// Error bodies are `{"id": "not_found", "message": ...}`.
+fn api_error(action: &str, status: StatusCode, body: &str) -> Error {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|json| json["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+
+    match status.as_u16() {
+        401 | 403 => Error::auth(format!(
+            "DigitalOcean {} rejected: {} {}; check the API token and its scopes",
+            action, status, message
+        )),
+        404 => Error::not_found(format!("DigitalOcean {}: {}", action, message)),
+        429 => Error::rate_limited(format!("DigitalOcean {} rate limited: {}", action, message)),
+        500..=599 => Error::provider(
+            "digitalocean",
+            format!(
+                "DigitalOcean server error (transient) in {}: {} - {}",
+                action, status, message
+            ),
+        ),
+        _ => Error::provider(
+            "digitalocean",
+            format!("{} failed: {} - {}", action, status, message),
+        ),
+    }
+}
+
+/// Builder for [`DigitalOceanProvider`]
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API token.
+pub struct DigitalOceanProviderBuilder {
+    api_token: String,
+    domain: Option<String>,
+    create_if_missing: bool,
+    ttl: Option<u32>,
+    api_base_url: Option<String>,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for DigitalOceanProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DigitalOceanProviderBuilder")
+            .field("api_token", &"<REDACTED>")
+            .field("domain", &self.domain)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("ttl", &self.ttl)
+            .field("api_base_url", &self.api_base_url)
+            .field("timeout", &self.timeout)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl DigitalOceanProviderBuilder {
+    /// Create a builder with an API token
+    pub fn new(api_token: impl Into<String>) -> Self {
+        Self {
+            api_token: api_token.into(),
+            domain: None,
+            create_if_missing: false,
+            ttl: None,
+            api_base_url: None,
+            timeout: None,
+            client: None,
+            dry_run: false,
+        }
+    }
+
+    /// Use this domain for all records instead of looking it up
+    ///
+    /// A trailing dot is optional.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Create missing A/AAAA records instead of returning `Error::NotFound`
+    pub fn create_if_missing(mut self, create_if_missing: bool) -> Self {
+        self.create_if_missing = create_if_missing;
+        self
+    }
+
+    /// TTL of created records (default: 1800 seconds, minimum: 30)
+    ///
+    /// Existing records keep their TTL.
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Send API requests to this base URL instead of the public API
+    ///
+    /// A trailing slash is ignored.
+    pub fn api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.api_base_url = Some(url.into());
+        self
+    }
+
+    /// HTTP timeout for API requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy and TLS settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send reads but skip changes
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the API token or domain is empty
+    /// - `Error::Config` if the TTL is below 30 seconds
+    /// - `Error::Config` if the API base URL is not an http(s) URL
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<DigitalOceanProvider> {
+        if self.api_token.is_empty() {
+            return Err(Error::config("DigitalOcean API token cannot be empty"));
+        }
+
+        let domain = match self.domain {
+            Some(domain) => {
+                let domain = normalize_name(domain.trim());
+                if domain.is_empty() {
+                    return Err(Error::config("DigitalOcean domain cannot be empty"));
+                }
+                Some(domain)
+            }
+            None => None,
+        };
+
+        if let Some(ttl) = self.ttl
+            && ttl < 30
+        {
+            return Err(Error::config(format!(
+                "DigitalOcean TTL must be at least 30 seconds, got {}",
+                ttl
+            )));
+        }
+
+        let api_base = match self.api_base_url {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(url) => {
+                return Err(Error::config(format!(
+                    "DigitalOcean API base URL must start with http:// or https://, got {}",
+                    url
+                )));
+            }
+            None => DIGITALOCEAN_API_BASE.to_string(),
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .build()
+                .map_err(|e| {
+                    Error::provider(
+                        "digitalocean",
+                        format!("Failed to build HTTP client: {}", e),
+                    )
+                })?,
+        };
+
+        Ok(DigitalOceanProvider {
+            api_token: self.api_token,
+            domain,
+            create_if_missing: self.create_if_missing,
+            ttl: self.ttl,
+            api_base,
+            client,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for DigitalOceanProvider {
+    /// Set the A or AAAA record of a name to the new address
+    ///
+    /// If the name has several records of the type, the first one is
+    /// updated. Missing records are created with `create_if_missing`.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
+        };
+        let record_type = record_type(version);
+
+        let domain = self.domain_for(record_name).await?;
+        let records = self.records(&domain, record_name, record_type).await?;
+
+        let Some(record) = records.into_iter().next() else {
+            if self.create_if_missing {
+                return self
+                    .create_record(&domain, record_name, record_type, new_ip)
+                    .await;
+            }
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        let current_ip: Option<IpAddr> = record.data.parse().ok();
+        if current_ip == Some(new_ip) {
+            tracing::info!(
+                "DNS record already has correct IP: {} -> {}",
+                record_name,
+                new_ip
+            );
+            return Ok(UpdateResult::Unchanged { current_ip: new_ip });
+        }
+
+        tracing::info!(
+            "{} {} record: {} -> {} (was: {}, domain: {}) [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            record_type,
+            record_name,
+            new_ip,
+            record.data,
+            domain,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        let result = UpdateResult::Updated {
+            previous_ip: current_ip,
+            new_ip,
+        };
+        if self.dry_run {
+            return Ok(result);
+        }
+
+        let payload = serde_json::json!({
+            "type": record_type,
+            "data": new_ip.to_string(),
+        });
+        let url = self.url(&format!("/domains/{}/records/{}", domain, record.id), &[])?;
+        self.send("update record", Method::PUT, url, Some(&payload))
+            .await?;
+
+        tracing::info!(
+            "DNS record updated successfully: {} -> {}",
+            record_name,
+            new_ip
+        );
+        Ok(result)
+    }
+
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// If the name has several records of the type, the first one is
+    /// returned.
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let record_type = record_type(version);
+        let domain = self.domain_for(record_name).await?;
+        let records = self.records(&domain, record_name, record_type).await?;
+
+        let Some((record, ip)) = records.into_iter().find_map(|record| {
+            let ip = record.data.parse().ok()?;
+            Some((record, ip))
+        }) else {
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        Ok(RecordMetadata {
+            id: record.id.to_string(),
+            name: record_name.to_string(),
+            ip,
+            ttl: record.ttl,
+            extra: serde_json::json!({
+                "domain": domain,
+            }),
+        })
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        record_name.contains('.') && record_name.len() <= 253
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "digitalocean"
+    }
+}
+
+/// Factory for creating DigitalOcean providers
+pub struct DigitalOceanFactory;
+
+impl DnsProviderFactory for DigitalOceanFactory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::DigitalOcean {
+                api_token,
+                domain,
+                create_if_missing,
+                ttl,
+                api_base_url,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "DigitalOcean provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = DigitalOceanProvider::builder(api_token.clone())
+                    .create_if_missing(*create_if_missing)
+                    .dry_run(dry_run);
+                if let Some(domain) = domain {
+                    builder = builder.domain(domain.clone());
+                }
+                if let Some(ttl) = ttl {
+                    builder = builder.ttl(*ttl);
+                }
+                if let Some(url) = api_base_url {
+                    builder = builder.api_base_url(url.clone());
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for DigitalOcean provider")),
+        }
+    }
+}
+
+/// Register the DigitalOcean provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_digitalocean::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("digitalocean", Box::new(DigitalOceanFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(api_token: &str) -> ProviderConfig {
+        ProviderConfig::DigitalOcean {
+            api_token: api_token.to_string(),
+            domain: Some("example.com".to_string()),
+            create_if_missing: true,
+            ttl: Some(60),
+            api_base_url: Some("http://127.0.0.1:8080".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(DigitalOceanFactory.create(&config("token")).is_ok());
+        assert!(DigitalOceanFactory.create(&config("")).is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            DigitalOceanProvider::builder("token")
+                .domain(".")
+                .build()
+                .is_err()
+        );
+        assert!(
+            DigitalOceanProvider::builder("token")
+                .ttl(10)
+                .build()
+                .is_err()
+        );
+        assert!(
+            DigitalOceanProvider::builder("token")
+                .api_base_url("api.digitalocean.com")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_api_token_not_exposed_in_debug() {
+        let builder = DigitalOceanProvider::builder("secret-token");
+        assert!(!format!("{:?}", builder).contains("secret-token"));
+
+        let provider = builder.build().unwrap();
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("secret-token"));
+    }
+
+    #[test]
+    fn test_api_error_mapping() {
+        assert!(matches!(
+            api_error(
+                "list records",
+                StatusCode::UNAUTHORIZED,
+                r#"{"id": "unauthorized", "message": "Unable to authenticate you"}"#
+            ),
+            Error::Authentication(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "get domain",
+                StatusCode::NOT_FOUND,
+                r#"{"id": "not_found", "message": "The resource you were accessing could not be found."}"#
+            ),
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "update record",
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"id": "too_many_requests", "message": "API Rate limit exceeded."}"#
+            ),
+            Error::RateLimited(_)
+        ));
+
+        let error = api_error(
+            "create record",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"id": "unprocessable_entity", "message": "Data needs to be a valid IP address"}"#,
+        );
+        assert!(matches!(error, Error::Provider { .. }));
+        assert!(error.to_string().contains("valid IP address"));
+    }
+}
@@ -0,0 +1,325 @@
+//! DigitalOcean API error mapping against a local stub server
+//!
+//! An update looks up the domain, lists the records of the name and type
+//! and PUTs the new address. Each call is failed in turn while the calls
+//! before it succeed. DigitalOcean sends an error `id` and explains it in
+//! `message`, which must reach the error. A token without write scope is
+//! only rejected at the PUT, with a 403.
+
+mod common;
+
+use common::{api_error, domain, not_found, provider, records};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_test_support::{ApiCall, Expect, assert_maps_to, fail_at};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The domain apex, so that domain lookup takes a single request
+const RECORD_NAME: &str = "example.com";
+
+/// The API calls made by `update_record`, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    /// GET /domains/:domain
+    GetDomain,
+    /// GET /domains/:domain/records?type=...&name=...
+    ListRecords,
+    /// PUT /domains/:domain/records/:record_id
+    UpdateRecord,
+}
+
+const CALLS: [Call; 3] = [Call::GetDomain, Call::ListRecords, Call::UpdateRecord];
+
+impl Call {
+    /// The action named in error messages
+    fn action(self) -> &'static str {
+        match self {
+            Call::GetDomain => "get domain",
+            Call::ListRecords => "list records",
+            Call::UpdateRecord => "update record",
+        }
+    }
+}
+
+impl ApiCall for Call {
+    fn method(self) -> &'static str {
+        match self {
+            Call::UpdateRecord => "PUT",
+            _ => "GET",
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Call::GetDomain => "/domains/example.com",
+            Call::ListRecords => "/domains/example.com/records",
+            Call::UpdateRecord => "/domains/example.com/records/11",
+        }
+    }
+
+    fn success(self) -> ResponseTemplate {
+        match self {
+            Call::GetDomain => domain("example.com"),
+            Call::ListRecords => records(&[(11, "A", "@", "198.51.100.1")]),
+            Call::UpdateRecord => {
+                ResponseTemplate::new(200).set_body_json(json!({ "domain_record": { "id": 11 } }))
+            }
+        }
+    }
+}
+
+/// Run `update_record` with `failing` answered by `response`
+async fn update_failing_at(
+    failing: Call,
+    response: ResponseTemplate,
+) -> ddns_core::Result<UpdateResult> {
+    let server = fail_at(&CALLS, failing, response).await;
+    provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+}
+
+#[tokio::test]
+async fn update_puts_after_listing_the_records() {
+    let server = MockServer::start().await;
+    for call in CALLS {
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(call.success())
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let result = provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some("198.51.100.1".parse().unwrap()),
+            new_ip: "203.0.113.5".parse().unwrap(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn status_codes_map_to_error_kinds() {
+    // A missing domain ends the lookup as well: the apex has no parent to try
+    for call in CALLS {
+        let cases = [
+            (
+                api_error(401, "Unauthorized", "Unable to authenticate you"),
+                Expect::Authentication,
+            ),
+            (not_found(), Expect::NotFound),
+            (
+                api_error(429, "too_many_requests", "API Rate limit exceeded."),
+                Expect::RateLimited,
+            ),
+            (
+                api_error(500, "server_error", "Unexpected server-side error"),
+                Expect::Provider("server error (transient)"),
+            ),
+            (
+                ResponseTemplate::new(503).set_body_string("upstream unavailable"),
+                Expect::Provider("server error (transient)"),
+            ),
+        ];
+        for (response, expect) in cases {
+            assert_maps_to(
+                update_failing_at(call, response).await,
+                "digitalocean",
+                expect,
+                &format!("{:?} -> {:?}", call, expect),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn read_only_token_is_rejected_at_the_put() {
+    assert_maps_to(
+        update_failing_at(
+            Call::UpdateRecord,
+            api_error(
+                403,
+                "Forbidden",
+                "You are not authorized to perform this operation",
+            ),
+        )
+        .await,
+        "digitalocean",
+        Expect::Authentication,
+        "UpdateRecord -> 403",
+    );
+}
+
+#[tokio::test]
+async fn rejected_update_passes_on_the_api_message() {
+    assert_maps_to(
+        update_failing_at(
+            Call::UpdateRecord,
+            api_error(
+                422,
+                "unprocessable_entity",
+                "Data needs to be a valid IP address",
+            ),
+        )
+        .await,
+        "digitalocean",
+        Expect::Provider("Data needs to be a valid IP address"),
+        "UpdateRecord -> 422",
+    );
+}
+
+#[tokio::test]
+async fn unreadable_responses_fail_the_update() {
+    for call in CALLS {
+        let expected = format!("Invalid {} response", call.action());
+        let result = update_failing_at(
+            call,
+            ResponseTemplate::new(200).set_body_string("{\"domain_records\": [}"),
+        )
+        .await;
+
+        match result {
+            Err(Error::Provider { message, .. }) => {
+                assert!(message.contains(&expected), "{:?}: {}", call, message)
+            }
+            other => panic!("{:?}: expected a provider error, got {:?}", call, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn create_failures() {
+    let cases = [
+        (
+            api_error(401, "Unauthorized", "Unable to authenticate you"),
+            Expect::Authentication,
+        ),
+        (
+            api_error(
+                403,
+                "Forbidden",
+                "You are not authorized to perform this operation",
+            ),
+            Expect::Authentication,
+        ),
+        (not_found(), Expect::NotFound),
+        (
+            api_error(429, "too_many_requests", "API Rate limit exceeded."),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(
+                422,
+                "unprocessable_entity",
+                "Name Only valid hostname characters are allowed.",
+            ),
+            Expect::Provider("Only valid hostname characters"),
+        ),
+        (
+            ResponseTemplate::new(503),
+            Expect::Provider("server error (transient)"),
+        ),
+    ];
+
+    for (response, expect) in cases {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(Call::GetDomain.path()))
+            .respond_with(Call::GetDomain.success())
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListRecords.path()))
+            .respond_with(records(&[]))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(Call::ListRecords.path()))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+
+        let provider = common::builder(&server)
+            .create_if_missing(true)
+            .build()
+            .unwrap();
+
+        assert_maps_to(
+            provider
+                .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+                .await,
+            "digitalocean",
+            expect,
+            &format!("CreateRecord -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_record_maps_errors() {
+    for (response, expect) in [
+        (
+            api_error(401, "Unauthorized", "Unable to authenticate you"),
+            Expect::Authentication,
+        ),
+        (not_found(), Expect::NotFound),
+        (
+            api_error(429, "too_many_requests", "API Rate limit exceeded."),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(500, "server_error", "Unexpected server-side error"),
+            Expect::Provider("server error (transient)"),
+        ),
+    ] {
+        let server = fail_at(&CALLS, Call::ListRecords, response).await;
+
+        let result = provider(&server)
+            .get_record_for(RECORD_NAME, IpVersion::V4)
+            .await;
+        assert_maps_to(
+            result,
+            "digitalocean",
+            expect,
+            &format!("get_record -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_domain_lookup_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::GetDomain.path()))
+        .respond_with(Call::GetDomain.success().set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let provider = common::builder(&server)
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let result = provider
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_maps_to(
+        result,
+        "digitalocean",
+        Expect::Provider("HTTP request failed"),
+        "GetDomain -> timeout",
+    );
+}
@@ -0,0 +1,51 @@
+//! Stub server fixtures shared by the DigitalOcean API tests
+
+#![allow(dead_code)]
+
+use ddns_provider_digitalocean::{DigitalOceanProvider, DigitalOceanProviderBuilder};
+use serde_json::json;
+use wiremock::{MockServer, ResponseTemplate};
+
+/// A builder with the test API token talking to the stub server
+pub fn builder(server: &MockServer) -> DigitalOceanProviderBuilder {
+    DigitalOceanProvider::builder("api-token").api_base_url(server.uri())
+}
+
+/// A provider with default settings talking to the stub server
+pub fn provider(server: &MockServer) -> DigitalOceanProvider {
+    builder(server).build().expect("provider builds")
+}
+
+/// The domain `name` of the account
+pub fn domain(name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "domain": { "name": name, "ttl": 1800 } }))
+}
+
+/// A record listing with records as (id, type, name, data), all on one page
+pub fn records(records: &[(u64, &str, &str, &str)]) -> ResponseTemplate {
+    let records: Vec<_> = records
+        .iter()
+        .map(|(id, record_type, name, data)| {
+            json!({ "id": id, "type": record_type, "name": name, "data": data, "ttl": 300 })
+        })
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({
+        "domain_records": records,
+        "links": {},
+        "meta": { "total": records.len() },
+    }))
+}
+
+/// An error as sent by the DigitalOcean API, with its `id` and `message`
+pub fn api_error(status: u16, id: &str, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "id": id, "message": message }))
+}
+
+/// The 404 the API sends for a missing domain or record
+pub fn not_found() -> ResponseTemplate {
+    api_error(
+        404,
+        "not_found",
+        "The resource you were accessing could not be found.",
+    )
+}
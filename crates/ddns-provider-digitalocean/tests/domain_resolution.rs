@@ -0,0 +1,111 @@
+//! DigitalOcean domain resolution against a local stub server
+//!
+//! Without a configured domain, the domain of a record is found by
+//! retrieving each suffix of its name as a domain, longest first. A
+//! configured domain is used as is, and names outside it are rejected
+//! before any request is sent. A domain the account does not have is
+//! answered with a 404.
+
+mod common;
+
+use common::{builder, domain, not_found, provider, records};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+/// Answer lookups of `domain`, expecting `calls` of them
+async fn mock_domain(server: &MockServer, name: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path(format!("/domains/{}", name)))
+        .and(header("authorization", "Bearer api-token"))
+        .respond_with(domain(name))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer lookups of `domain` as a domain the account does not have
+async fn mock_not_a_domain(server: &MockServer, domain: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path(format!("/domains/{}", domain)))
+        .respond_with(not_found())
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer A record listings of `record_name` in `domain`
+async fn mock_records(server: &MockServer, domain: &str, record_name: &str, relative: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/domains/{}/records", domain)))
+        .and(query_param("type", "A"))
+        .and(query_param("name", record_name))
+        .respond_with(records(&[(11, "A", relative, "203.0.113.5")]))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn delegated_subdomain_wins_over_parent() {
+    let server = MockServer::start().await;
+    mock_not_a_domain(&server, "host.lab.example.com", 1).await;
+    mock_domain(&server, "lab.example.com", 1).await;
+    mock_domain(&server, "example.com", 0).await;
+    mock_records(&server, "lab.example.com", "host.lab.example.com", "host").await;
+
+    let record = provider(&server)
+        .get_record_for("Host.Lab.Example.com.", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "11");
+    assert_eq!(record.extra["domain"], "lab.example.com");
+}
+
+#[tokio::test]
+async fn configured_domain_skips_lookup() {
+    let server = MockServer::start().await;
+    mock_domain(&server, "example.com", 0).await;
+    mock_records(&server, "example.com", "home.example.com", "home").await;
+
+    let provider = builder(&server).domain("example.com").build().unwrap();
+
+    assert!(
+        provider
+            .get_record_for("home.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn name_outside_configured_domain_is_rejected() {
+    let server = MockServer::start().await;
+
+    let provider = builder(&server).domain("example.com").build().unwrap();
+    let result = provider
+        .update_record("home.example.net", "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(matches!(result, Err(Error::Config(_))), "{:?}", result);
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn unknown_domain_is_not_found() {
+    let server = MockServer::start().await;
+    mock_not_a_domain(&server, "home.example.org", 1).await;
+    mock_not_a_domain(&server, "example.org", 1).await;
+
+    let result = provider(&server)
+        .get_record_for("home.example.org", IpVersion::V4)
+        .await;
+
+    match result {
+        Err(Error::NotFound(message)) => {
+            assert!(message.contains("home.example.org"), "{}", message)
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
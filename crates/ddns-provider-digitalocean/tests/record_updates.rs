@@ -0,0 +1,217 @@
+//! DigitalOcean record updates against a local stub server
+//!
+//! An update reads the records of the name and type and sends a PUT only if
+//! the address differs. Missing records are created when enabled, dry runs
+//! send no changes, and records of other names in the listing are skipped.
+//! The listing is filtered by name, so its first page holds every record.
+
+mod common;
+
+use common::records;
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_digitalocean::{DigitalOceanProvider, DigitalOceanProviderBuilder};
+use serde_json::json;
+use std::net::IpAddr;
+use wiremock::matchers::{body_json, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RECORDS_PATH: &str = "/domains/example.com/records";
+
+/// Answer record listings of `record_type` with (id, type, name, data)
+async fn mock_records(server: &MockServer, record_type: &str, listed: &[(u64, &str, &str, &str)]) {
+    Mock::given(method("GET"))
+        .and(path(RECORDS_PATH))
+        .and(query_param("type", record_type))
+        .respond_with(records(listed))
+        .mount(server)
+        .await;
+}
+
+/// Expect `calls` requests with `http_method` to the records of the domain
+async fn expect_writes(server: &MockServer, http_method: &str, calls: u64) {
+    Mock::given(method(http_method))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "domain_record": {} })))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+fn builder(server: &MockServer) -> DigitalOceanProviderBuilder {
+    common::builder(server).domain("example.com")
+}
+
+fn provider(server: &MockServer) -> DigitalOceanProvider {
+    builder(server).build().unwrap()
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn changed_address_is_written() {
+    let server = MockServer::start().await;
+    mock_records(&server, "A", &[(11, "A", "home", "192.0.2.1")]).await;
+    Mock::given(method("PUT"))
+        .and(path(format!("{}/11", RECORDS_PATH)))
+        .and(body_json(json!({ "type": "A", "data": "203.0.113.5" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "domain_record": {} })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some(ip("192.0.2.1")),
+            new_ip: ip("203.0.113.5"),
+        }
+    );
+}
+
+#[tokio::test]
+async fn unchanged_address_sends_no_update() {
+    let server = MockServer::start().await;
+    mock_records(&server, "AAAA", &[(12, "AAAA", "home", "2001:db8::5")]).await;
+    expect_writes(&server, "PUT", 0).await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("2001:db8::5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Unchanged {
+            current_ip: ip("2001:db8::5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn records_are_listed_in_one_filtered_page() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(RECORDS_PATH))
+        .and(query_param("type", "A"))
+        .and(query_param("name", "home.example.com"))
+        .and(query_param("per_page", "200"))
+        .respond_with(records(&[(11, "A", "home", "203.0.113.5")]))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // No further page is requested
+    Mock::given(method("GET"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Unchanged { .. }));
+}
+
+#[tokio::test]
+async fn records_of_other_names_are_skipped() {
+    // A server that ignores the filters returns every record of the domain
+    let server = MockServer::start().await;
+    mock_records(
+        &server,
+        "A",
+        &[
+            (10, "A", "@", "192.0.2.10"),
+            (11, "AAAA", "home", "2001:db8::1"),
+        ],
+    )
+    .await;
+    expect_writes(&server, "PUT", 0).await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await;
+
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn missing_record_is_created_at_the_apex() {
+    let server = MockServer::start().await;
+    mock_records(&server, "AAAA", &[]).await;
+    Mock::given(method("POST"))
+        .and(path(RECORDS_PATH))
+        .and(body_json(json!({
+            "type": "AAAA",
+            "name": "@",
+            "data": "2001:db8::5",
+            "ttl": 60,
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "domain_record": {} })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server)
+        .create_if_missing(true)
+        .ttl(60)
+        .build()
+        .unwrap();
+    let result = provider
+        .update_record("example.com", ip("2001:db8::5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("2001:db8::5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn dry_run_sends_no_change() {
+    let server = MockServer::start().await;
+    mock_records(&server, "A", &[]).await;
+    expect_writes(&server, "POST", 0).await;
+
+    let provider = builder(&server)
+        .create_if_missing(true)
+        .dry_run(true)
+        .build()
+        .unwrap();
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Created { .. }));
+}
+
+#[tokio::test]
+async fn get_record_falls_back_to_aaaa() {
+    let server = MockServer::start().await;
+    mock_records(&server, "A", &[]).await;
+    mock_records(&server, "AAAA", &[(12, "AAAA", "home", "2001:db8::1")]).await;
+
+    let provider = provider(&server);
+    let record = provider.get_record("home.example.com").await.unwrap();
+    assert_eq!(record.id, "12");
+    assert_eq!(record.ip, ip("2001:db8::1"));
+    assert_eq!(record.ttl, Some(300));
+
+    let result = provider
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
@@ -0,0 +1,737 @@
+// # dyndns2 DNS Provider
+//
+// This crate provides a DNS provider for the dyndns2 update protocol
+// (`GET /nic/update?hostname=...&myip=...` with basic authentication and
+// plain-text responses), as spoken by DynDNS, No-IP, many registrars and
+// most routers.
+//
+// ## Implementation Status
+//
+// - ✅ One request per update; `good` maps to `Updated`, `nochg` to `Unchanged`
+// - ✅ Several hostnames per request (extra hostnames per record), with one
+//   response line per hostname
+// - ✅ Return codes map to `Error::Authentication` (`badauth`),
+//   `Error::NotFound` (`nohost`), `Error::RateLimited` (`abuse`, `911`,
+//   `dnserr`) and `Error::Config`/`Error::InvalidInput` (`badagent`,
+//   `!donator`, `numhost`, `notfqdn`)
+// - ✅ Mandatory back-off: see "Holds" below
+// - ✅ Dry-run mode (no requests are sent)
+// - ✅ Both IPv4 and IPv6 addresses (`myip`)
+// - ❌ NO record lookup: the protocol cannot read records, so `get_record`
+//   returns `Error::Unsupported` and drift checks skip these records
+// - ❌ NO retry logic (owned by DdnsEngine)
+// - ❌ NO background tasks (violates shutdown determinism)
+//
+// ## Holds
+//
+// The protocol requires clients to stop sending after certain responses;
+// servers block clients that keep retrying. The provider therefore
+// remembers these responses and fails further updates locally, without a
+// request, returning the same error kind:
+//
+// - `911`, `dnserr`: all updates for 30 minutes
+// - `badauth`, `badagent`, `!donator`, `numhost`: all updates until restart
+// - `nohost`, `notfqdn`, `abuse`: updates of that hostname until restart
+//
+// This is the only state kept between requests. It is an exception to the
+// "no caching beyond a single request" rule in the same way as the
+// Cloudflare zone cache: it never describes DNS records, and without it the
+// engine's retries would get the account blocked.
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated and single-shot. It only talks
+// to the configured update URL.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The password NEVER appears in logs
+// - The password MUST be provided via environment variables only
+// - The update URL should be https://; basic authentication sends the
+//   password in clear text otherwise
+//
+// ## Protocol Reference
+//
+// - dyndns2: https://help.dyn.com/remote-access-api/ (perform update,
+//   return codes)
+
+mod response;
+
+use async_trait::async_trait;
+use ddns_core::config::ProviderConfig;
+use ddns_core::dns::normalize_name;
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use response::Status;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// User agent sent with every request, in the "company - device - version"
+/// form the protocol asks for
+const USER_AGENT: &str = concat!("ddns-lab - ddnsd - ", env!("CARGO_PKG_VERSION"));
+
+/// Default HTTP timeout for update requests (30 seconds)
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long updates are held after `911` or `dnserr` (30 minutes)
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// dyndns2 protocol provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated and single-shot. All coordination (retries,
+/// backoff, scheduling) is owned by `DdnsEngine`; the holds the protocol
+/// mandates only stop requests the server would punish.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the password.
+pub struct Dyndns2Provider {
+    /// Update URL
+    update_url: String,
+
+    /// Username for basic authentication
+    username: String,
+
+    /// Password for basic authentication
+    password: String,
+
+    /// Hostnames sent along with a record, keyed by normalized record name
+    extra_hostnames: HashMap<String, Vec<String>>,
+
+    /// How long updates are held after `911` or `dnserr`
+    backoff: Duration,
+
+    /// Responses that stop further requests
+    holds: Mutex<Holds>,
+
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Dry-run mode: if true, send no requests
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for Dyndns2Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dyndns2Provider")
+            .field("update_url", &self.update_url)
+            .field("username", &self.username)
+            .field("password", &"<REDACTED>")
+            .field("extra_hostnames", &self.extra_hostnames)
+            .field("backoff", &self.backoff)
+            .field("holds", &self.holds)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// Responses that stop further requests
+#[derive(Debug, Default)]
+struct Holds {
+    /// Hold on all requests
+    all: Option<Hold>,
+    /// Holds on single hostnames, keyed by normalized hostname
+    hosts: HashMap<String, Hold>,
+}
+
+/// A response that stops further requests
+#[derive(Debug, Clone)]
+struct Hold {
+    /// The response
+    status: Status,
+    /// End of the hold (None = until restart)
+    until: Option<Instant>,
+}
+
+impl Hold {
+    fn is_active(&self, now: Instant) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+
+    /// Error for an update not sent because of this hold
+    fn error(&self, hostname: &str, now: Instant) -> Error {
+        let note = match self.until {
+            Some(until) => format!(
+                "not sending updates for another {}s",
+                until.saturating_duration_since(now).as_secs()
+            ),
+            None => "not sending updates until restart".to_string(),
+        };
+        status_error(&self.status, hostname, &note)
+    }
+}
+
+impl Holds {
+    /// The active hold on updates of `hostname`, if any
+    fn get(&self, hostname: &str, now: Instant) -> Option<&Hold> {
+        self.all
+            .iter()
+            .chain(self.hosts.get(hostname))
+            .find(|hold| hold.is_active(now))
+    }
+
+    /// Remember a response that stops further requests
+    fn record(&mut self, hostname: &str, status: &Status, backoff: Duration, now: Instant) {
+        let until = match status {
+            Status::DnsError | Status::ServerError => Some(now + backoff),
+            Status::BadAuth
+            | Status::BadAgent
+            | Status::NotDonator
+            | Status::NumHost
+            | Status::NotFqdn
+            | Status::NoHost
+            | Status::Abuse => None,
+            Status::Good(_) | Status::NoChange(_) | Status::Unknown(_) => return,
+        };
+        let hold = Hold {
+            status: status.clone(),
+            until,
+        };
+
+        if status.is_global() {
+            self.all = Some(hold);
+        } else {
+            self.hosts.insert(hostname.to_string(), hold);
+        }
+    }
+}
+
+/// Error for a response status other than `good` or `nochg`
+fn status_error(status: &Status, hostname: &str, note: &str) -> Error {
+    let code = status.code();
+    match status {
+        Status::BadAuth => Error::auth(format!(
+            "dyndns2 server rejected the username or password ({}); {}",
+            code, note
+        )),
+        Status::NoHost => Error::not_found(format!(
+            "Hostname {} does not exist in this account ({}); {}",
+            hostname, code, note
+        )),
+        Status::NotFqdn => Error::invalid_input(format!(
+            "Hostname {} is not a fully-qualified domain name ({}); {}",
+            hostname, code, note
+        )),
+        Status::Abuse => Error::rate_limited(format!(
+            "Hostname {} is blocked for update abuse ({}); {}",
+            hostname, code, note
+        )),
+        Status::DnsError | Status::ServerError => {
+            Error::rate_limited(format!("dyndns2 server problem ({}); {}", code, note))
+        }
+        Status::BadAgent => Error::config(format!(
+            "dyndns2 server blocked the user agent ({}); {}",
+            code, note
+        )),
+        Status::NotDonator => Error::config(format!(
+            "dyndns2 update needs a paid account ({}); {}",
+            code, note
+        )),
+        Status::NumHost => Error::config(format!(
+            "Too many hostnames in one dyndns2 request ({}); {}",
+            code, note
+        )),
+        Status::Good(_) | Status::NoChange(_) | Status::Unknown(_) => Error::provider(
+            "dyndns2",
+            format!("Unexpected response for {}: {}", hostname, code),
+        ),
+    }
+}
+
+impl Dyndns2Provider {
+    /// Start building a provider
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_dyndns2::Dyndns2Provider;
+    ///
+    /// let provider = Dyndns2Provider::builder(
+    ///     "https://dynupdate.no-ip.com/nic/update",
+    ///     "user",
+    ///     "password",
+    /// )
+    /// .extra_hostnames("home.example.com", ["vpn.example.com"])
+    /// .build()
+    /// .unwrap();
+    /// ```
+    pub fn builder(
+        update_url: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Dyndns2ProviderBuilder {
+        Dyndns2ProviderBuilder::new(update_url, username, password)
+    }
+
+    fn holds(&self) -> std::sync::MutexGuard<'_, Holds> {
+        self.holds.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Send one update request and return its response body
+    async fn send(&self, hostnames: &[String], ip: IpAddr) -> Result<String> {
+        let response = self
+            .client
+            .get(&self.update_url)
+            .query(&[("hostname", hostnames.join(",")), ("myip", ip.to_string())])
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await
+            .map_err(|e| Error::provider("dyndns2", format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::provider("dyndns2", format!("Failed to read response: {}", e)))?;
+
+        // Return codes come with 200 OK; some servers answer badauth with 401
+        match status.as_u16() {
+            200 => Ok(body),
+            401 | 403 => Ok("badauth".to_string()),
+            429 => Err(Error::rate_limited(format!(
+                "dyndns2 server rate limit exceeded: {}",
+                status
+            ))),
+            500..=599 => Err(Error::provider(
+                "dyndns2",
+                format!("dyndns2 server error (transient): {}", status),
+            )),
+            _ => Err(Error::provider(
+                "dyndns2",
+                format!("Update failed: {} - {}", status, body.trim()),
+            )),
+        }
+    }
+}
+
+/// Builder for [`Dyndns2Provider`]
+pub struct Dyndns2ProviderBuilder {
+    update_url: String,
+    username: String,
+    password: String,
+    extra_hostnames: HashMap<String, Vec<String>>,
+    backoff: Duration,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for Dyndns2ProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dyndns2ProviderBuilder")
+            .field("update_url", &self.update_url)
+            .field("username", &self.username)
+            .field("password", &"<REDACTED>")
+            .field("extra_hostnames", &self.extra_hostnames)
+            .field("backoff", &self.backoff)
+            .field("timeout", &self.timeout)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl Dyndns2ProviderBuilder {
+    /// Create a builder for an update URL and account
+    pub fn new(
+        update_url: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            update_url: update_url.into(),
+            username: username.into(),
+            password: password.into(),
+            extra_hostnames: HashMap::new(),
+            backoff: DEFAULT_BACKOFF,
+            timeout: None,
+            client: None,
+            dry_run: false,
+        }
+    }
+
+    /// Update these hostnames in the same request as `record_name`
+    pub fn extra_hostnames<I, S>(mut self, record_name: &str, hostnames: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extra_hostnames
+            .entry(normalize_name(record_name))
+            .or_default()
+            .extend(hostnames.into_iter().map(Into::into));
+        self
+    }
+
+    /// How long updates are held after `911` or `dnserr` (default: 30 minutes)
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// HTTP timeout for update requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy, TLS and user agent
+    /// settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send no requests
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the update URL is not an http(s) URL
+    /// - `Error::Config` if the username or password is empty
+    /// - `Error::Config` if an extra hostname is empty or contains a comma
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<Dyndns2Provider> {
+        if !self.update_url.starts_with("http://") && !self.update_url.starts_with("https://") {
+            return Err(Error::config(format!(
+                "dyndns2 update URL must start with http:// or https://, got {}",
+                self.update_url
+            )));
+        }
+        if self.username.is_empty() || self.password.is_empty() {
+            return Err(Error::config(
+                "dyndns2 username and password cannot be empty",
+            ));
+        }
+
+        let mut extra_hostnames = HashMap::new();
+        for (record_name, hostnames) in self.extra_hostnames {
+            let hostnames: Vec<String> =
+                hostnames.iter().map(|h| normalize_name(h.trim())).collect();
+            if hostnames.iter().any(|h| h.is_empty() || h.contains(',')) {
+                return Err(Error::config(
+                    "dyndns2 extra hostnames cannot be empty or contain commas",
+                ));
+            }
+            extra_hostnames.insert(record_name, hostnames);
+        }
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .user_agent(USER_AGENT)
+                .build()
+                .map_err(|e| {
+                    Error::provider("dyndns2", format!("Failed to build HTTP client: {}", e))
+                })?,
+        };
+
+        Ok(Dyndns2Provider {
+            update_url: self.update_url,
+            username: self.username,
+            password: self.password,
+            extra_hostnames,
+            backoff: self.backoff,
+            holds: Mutex::new(Holds::default()),
+            client,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for Dyndns2Provider {
+    /// Point a hostname (and its extra hostnames) at the new address
+    ///
+    /// The result is that of `record_name`; failures of extra hostnames are
+    /// logged and held like any other, but do not fail the update.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let primary = normalize_name(record_name);
+        let now = Instant::now();
+
+        // Hostnames the protocol allows us to send now
+        let hostnames: Vec<String> = {
+            let holds = self.holds();
+            if let Some(hold) = holds.get(&primary, now) {
+                return Err(hold.error(record_name, now));
+            }
+
+            let extras = self.extra_hostnames.get(&primary).into_iter().flatten();
+            std::iter::once(&primary)
+                .chain(extras.filter(|hostname| {
+                    let held = holds.get(hostname, now).is_some();
+                    if held {
+                        tracing::debug!("Not sending update for held hostname {}", hostname);
+                    }
+                    !held
+                }))
+                .cloned()
+                .collect()
+        };
+
+        tracing::info!(
+            "{} {} -> {} [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            hostnames.join(","),
+            new_ip,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if self.dry_run {
+            return Ok(UpdateResult::Updated {
+                previous_ip: None,
+                new_ip,
+            });
+        }
+
+        let body = self.send(&hostnames, new_ip).await?;
+        let statuses = response::parse(&body, hostnames.len());
+
+        {
+            let mut holds = self.holds();
+            let now = Instant::now();
+            for (hostname, status) in hostnames.iter().zip(&statuses) {
+                holds.record(hostname, status, self.backoff, now);
+            }
+        }
+
+        for (hostname, status) in hostnames.iter().zip(&statuses).skip(1) {
+            if !matches!(status, Status::Good(_) | Status::NoChange(_)) {
+                tracing::warn!(
+                    "Update of extra hostname {} failed: {}",
+                    hostname,
+                    status.code()
+                );
+            }
+        }
+
+        match &statuses[0] {
+            Status::Good(ip) => {
+                if let Some(ip) = ip.filter(|ip| *ip != new_ip) {
+                    tracing::warn!(
+                        "dyndns2 server set {} to {} instead of {}",
+                        record_name,
+                        ip,
+                        new_ip
+                    );
+                }
+                tracing::info!(
+                    "DNS record updated successfully: {} -> {}",
+                    record_name,
+                    new_ip
+                );
+                Ok(UpdateResult::Updated {
+                    previous_ip: None,
+                    new_ip,
+                })
+            }
+            Status::NoChange(_) => {
+                tracing::info!(
+                    "DNS record already has correct IP: {} -> {}",
+                    record_name,
+                    new_ip
+                );
+                Ok(UpdateResult::Unchanged { current_ip: new_ip })
+            }
+            status => {
+                let now = Instant::now();
+                match self.holds().get(&primary, now) {
+                    Some(hold) => Err(hold.error(record_name, now)),
+                    None => Err(status_error(status, record_name, "")),
+                }
+            }
+        }
+    }
+
+    /// Not supported: the dyndns2 protocol cannot read records
+    ///
+    /// Returns `Error::Unsupported`, so the engine skips drift checks of
+    /// these records instead of reporting them as failed.
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        Err(Error::unsupported(format!(
+            "Cannot read {}: the dyndns2 protocol has no lookup",
+            record_name
+        )))
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        record_name.contains('.') && !record_name.contains(',') && record_name.len() <= 253
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "dyndns2"
+    }
+}
+
+/// Factory for creating dyndns2 providers
+pub struct Dyndns2Factory;
+
+impl DnsProviderFactory for Dyndns2Factory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::Dyndns2 {
+                update_url,
+                username,
+                password,
+                extra_hostnames,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "dyndns2 provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = Dyndns2Provider::builder(
+                    update_url.clone(),
+                    username.clone(),
+                    password.clone(),
+                )
+                .dry_run(dry_run);
+
+                for (record_name, hostnames) in extra_hostnames {
+                    builder = builder.extra_hostnames(record_name, hostnames.iter().cloned());
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for dyndns2 provider")),
+        }
+    }
+}
+
+/// Register the dyndns2 provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_dyndns2::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("dyndns2", Box::new(Dyndns2Factory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(password: &str) -> ProviderConfig {
+        ProviderConfig::Dyndns2 {
+            update_url: "https://members.dyndns.org/nic/update".to_string(),
+            username: "user".to_string(),
+            password: password.to_string(),
+            extra_hostnames: HashMap::from([(
+                "home.example.com".to_string(),
+                vec!["vpn.example.com".to_string()],
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(Dyndns2Factory.create(&config("password")).is_ok());
+        assert!(Dyndns2Factory.create(&config("")).is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            Dyndns2Provider::builder("members.dyndns.org/nic/update", "user", "password")
+                .build()
+                .is_err()
+        );
+        assert!(
+            Dyndns2Provider::builder("https://members.dyndns.org/nic/update", "user", "password")
+                .extra_hostnames("home.example.com", ["a.example.com,b.example.com"])
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_password_not_exposed_in_debug() {
+        let builder = Dyndns2Provider::builder(
+            "https://members.dyndns.org/nic/update",
+            "user",
+            "password-value",
+        );
+        assert!(!format!("{:?}", builder).contains("password-value"));
+
+        let debug = format!("{:?}", builder.build().unwrap());
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("password-value"));
+    }
+
+    #[test]
+    fn test_holds() {
+        let now = Instant::now();
+        let backoff = Duration::from_secs(60);
+        let mut holds = Holds::default();
+
+        holds.record("a.example.com", &Status::NoHost, backoff, now);
+        assert!(holds.get("a.example.com", now).is_some());
+        assert!(holds.get("b.example.com", now).is_none());
+
+        holds.record("b.example.com", &Status::ServerError, backoff, now);
+        assert!(holds.get("c.example.com", now).is_some());
+        assert!(holds.get("c.example.com", now + backoff).is_none());
+        assert!(holds.get("a.example.com", now + backoff).is_some());
+
+        let later = now + backoff;
+        assert!(matches!(
+            holds
+                .get("a.example.com", later)
+                .unwrap()
+                .error("a.example.com", later),
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            holds
+                .get("a.example.com", now)
+                .unwrap()
+                .error("a.example.com", now),
+            Error::RateLimited(_)
+        ));
+    }
+
+    #[test]
+    fn test_supports_record() {
+        let provider =
+            Dyndns2Provider::builder("https://members.dyndns.org/nic/update", "user", "password")
+                .build()
+                .unwrap();
+        assert!(provider.supports_record("home.example.com"));
+        assert!(!provider.supports_record("localhost"));
+        assert!(!provider.supports_record("a.example.com,b.example.com"));
+        assert_eq!(provider.provider_name(), "dyndns2");
+    }
+}
@@ -0,0 +1,166 @@
+// # Update Responses
+//
+// The body of an update response has one line per hostname of the request,
+// in the same order, each starting with a return code. Errors that concern
+// the whole request (`badauth`, `911`, ...) may be returned as a single
+// line regardless of the number of hostnames.
+
+use std::net::IpAddr;
+
+/// Return code of one response line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Status {
+    /// `good [ip]`: the hostname now has the address
+    Good(Option<IpAddr>),
+    /// `nochg [ip]`: the hostname already had the address
+    NoChange(Option<IpAddr>),
+    /// `badauth`: wrong username or password
+    BadAuth,
+    /// `badagent`: the user agent is blocked
+    BadAgent,
+    /// `!donator`: the request needs a paid account
+    NotDonator,
+    /// `notfqdn`: the hostname is not a fully-qualified domain name
+    NotFqdn,
+    /// `nohost`: the hostname does not exist in this account
+    NoHost,
+    /// `numhost`: too many hostnames in one request
+    NumHost,
+    /// `abuse`: the hostname is blocked for update abuse
+    Abuse,
+    /// `dnserr`: the server had a DNS error
+    DnsError,
+    /// `911`: the server has a problem or is down for maintenance
+    ServerError,
+    /// Any other line (e.g. `conflict A`), verbatim
+    Unknown(String),
+}
+
+impl Status {
+    /// Whether this status concerns the whole request rather than one
+    /// hostname
+    pub(crate) fn is_global(&self) -> bool {
+        matches!(
+            self,
+            Status::BadAuth
+                | Status::BadAgent
+                | Status::NotDonator
+                | Status::NumHost
+                | Status::DnsError
+                | Status::ServerError
+        )
+    }
+
+    /// The return code as sent by the server
+    pub(crate) fn code(&self) -> &str {
+        match self {
+            Status::Good(_) => "good",
+            Status::NoChange(_) => "nochg",
+            Status::BadAuth => "badauth",
+            Status::BadAgent => "badagent",
+            Status::NotDonator => "!donator",
+            Status::NotFqdn => "notfqdn",
+            Status::NoHost => "nohost",
+            Status::NumHost => "numhost",
+            Status::Abuse => "abuse",
+            Status::DnsError => "dnserr",
+            Status::ServerError => "911",
+            Status::Unknown(line) => line,
+        }
+    }
+}
+
+/// Parse one response line
+pub(crate) fn parse_line(line: &str) -> Status {
+    let line = line.trim();
+    let (code, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let ip = rest.trim().parse().ok();
+
+    match code {
+        "good" => Status::Good(ip),
+        "nochg" => Status::NoChange(ip),
+        "badauth" => Status::BadAuth,
+        "badagent" => Status::BadAgent,
+        "!donator" => Status::NotDonator,
+        "notfqdn" => Status::NotFqdn,
+        "nohost" => Status::NoHost,
+        "numhost" => Status::NumHost,
+        "abuse" => Status::Abuse,
+        "dnserr" => Status::DnsError,
+        "911" => Status::ServerError,
+        _ => Status::Unknown(line.to_string()),
+    }
+}
+
+/// Status of each of `count` hostnames from a response body
+///
+/// A single global status applies to every hostname. Hostnames without a
+/// line of their own get an `Unknown` status.
+pub(crate) fn parse(body: &str, count: usize) -> Vec<Status> {
+    let mut statuses: Vec<Status> = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_line)
+        .collect();
+
+    if let [status] = statuses.as_slice()
+        && status.is_global()
+    {
+        return vec![status.clone(); count];
+    }
+
+    statuses.resize(count, Status::Unknown("(no response line)".to_string()));
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("good 192.0.2.1"),
+            Status::Good(Some("192.0.2.1".parse().unwrap()))
+        );
+        assert_eq!(
+            parse_line("nochg 2001:db8::1\r"),
+            Status::NoChange(Some("2001:db8::1".parse().unwrap()))
+        );
+        assert_eq!(parse_line("good"), Status::Good(None));
+        assert_eq!(parse_line("911"), Status::ServerError);
+        assert_eq!(parse_line("!donator"), Status::NotDonator);
+        assert_eq!(
+            parse_line("conflict A"),
+            Status::Unknown("conflict A".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_one_line_per_hostname() {
+        assert_eq!(
+            parse("good 192.0.2.1\nnohost\n", 2),
+            [
+                Status::Good(Some("192.0.2.1".parse().unwrap())),
+                Status::NoHost
+            ]
+        );
+        assert_eq!(
+            parse("nochg 192.0.2.1", 2),
+            [
+                Status::NoChange(Some("192.0.2.1".parse().unwrap())),
+                Status::Unknown("(no response line)".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_global_status() {
+        assert_eq!(parse("badauth", 3), vec![Status::BadAuth; 3]);
+        assert_eq!(parse("911\n", 2), vec![Status::ServerError; 2]);
+        assert_eq!(
+            parse("abuse", 2)[1],
+            Status::Unknown("(no response line)".to_string())
+        );
+    }
+}
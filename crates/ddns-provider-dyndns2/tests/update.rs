@@ -0,0 +1,284 @@
+//! dyndns2 updates against a local stub server
+//!
+//! Checks the request format (query, basic auth, user agent), the mapping
+//! of return codes onto update results and errors, and that the mandatory
+//! back-off after `911`, `abuse` and `badauth` stops further requests.
+
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, UpdateResult};
+use ddns_provider_dyndns2::{Dyndns2Provider, Dyndns2ProviderBuilder};
+use std::net::IpAddr;
+use std::time::Duration;
+use wiremock::matchers::{header, header_regex, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IP: &str = "203.0.113.5";
+
+fn builder(server: &MockServer) -> Dyndns2ProviderBuilder {
+    Dyndns2Provider::builder(format!("{}/nic/update", server.uri()), "user", "pass")
+        .timeout(Duration::from_secs(5))
+}
+
+fn ip() -> IpAddr {
+    IP.parse().unwrap()
+}
+
+async fn respond(server: &MockServer, body: &str) {
+    Mock::given(method("GET"))
+        .and(path("/nic/update"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(server)
+        .await;
+}
+
+async fn request_count(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn test_good_is_updated() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/nic/update"))
+        .and(query_param("hostname", "home.example.com"))
+        .and(query_param("myip", IP))
+        // "user:pass"
+        .and(header("authorization", "Basic dXNlcjpwYXNz"))
+        .and(header_regex("user-agent", "^ddns-lab - ddnsd - "))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("good {}\n", IP)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server).build().unwrap();
+    let result = provider
+        .update_record("Home.Example.com.", ip())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: None,
+            new_ip: ip()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_nochg_is_unchanged() {
+    let server = MockServer::start().await;
+    respond(&server, &format!("nochg {}", IP)).await;
+
+    let provider = builder(&server).build().unwrap();
+    let result = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+
+    assert_eq!(result, UpdateResult::Unchanged { current_ip: ip() });
+}
+
+#[tokio::test]
+async fn test_extra_hostnames_in_one_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/nic/update"))
+        .and(query_param(
+            "hostname",
+            "home.example.com,vpn.example.com,old.example.com",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(format!("good {0}\nnochg {0}\nnohost\n", IP)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/nic/update"))
+        .and(query_param("hostname", "home.example.com,vpn.example.com"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(format!("nochg {0}\nnochg {0}", IP)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server)
+        .extra_hostnames("home.example.com", ["vpn.example.com", "old.example.com"])
+        .build()
+        .unwrap();
+
+    // An extra hostname failing does not fail the record
+    let result = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+    assert!(matches!(result, UpdateResult::Updated { .. }));
+
+    // The nohost hostname is no longer sent
+    let result = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+    assert_eq!(result, UpdateResult::Unchanged { current_ip: ip() });
+}
+
+#[tokio::test]
+async fn test_badauth_stops_all_updates() {
+    let server = MockServer::start().await;
+    respond(&server, "badauth").await;
+
+    let provider = builder(&server).build().unwrap();
+    let error = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Authentication(_)), "{:?}", error);
+
+    // Held without a request, for every hostname
+    let error = provider
+        .update_record("other.example.com", ip())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Authentication(_)), "{:?}", error);
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn test_unauthorized_status_is_badauth() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server).build().unwrap();
+    let error = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Authentication(_)), "{:?}", error);
+}
+
+#[tokio::test]
+async fn test_nohost_and_abuse_stop_that_hostname() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("hostname", "missing.example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("nohost"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("hostname", "blocked.example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("abuse"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("hostname", "home.example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("good {}", IP)))
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server).build().unwrap();
+
+    for _ in 0..2 {
+        let error = provider
+            .update_record("missing.example.com", ip())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::NotFound(_)), "{:?}", error);
+
+        let error = provider
+            .update_record("blocked.example.com", ip())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::RateLimited(_)), "{:?}", error);
+    }
+    assert_eq!(request_count(&server).await, 2);
+
+    // Other hostnames are not affected
+    provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_911_backs_off() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("911"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    respond(&server, &format!("good {}", IP)).await;
+
+    let provider = builder(&server)
+        .backoff(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let error = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::RateLimited(_)), "{:?}", error);
+
+    let error = provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::RateLimited(_)), "{:?}", error);
+    assert_eq!(request_count(&server).await, 1);
+
+    // Requests resume once the back-off has passed
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+    assert_eq!(request_count(&server).await, 2);
+}
+
+#[tokio::test]
+async fn test_unknown_response_is_provider_error() {
+    let server = MockServer::start().await;
+    respond(&server, "conflict A").await;
+
+    let provider = builder(&server).build().unwrap();
+    for _ in 0..2 {
+        let error = provider
+            .update_record("home.example.com", ip())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Provider { .. }), "{:?}", error);
+        assert!(error.to_string().contains("conflict A"), "{}", error);
+    }
+
+    // Not a hold: both attempts reach the server
+    assert_eq!(request_count(&server).await, 2);
+}
+
+#[tokio::test]
+async fn test_dry_run_sends_nothing() {
+    let server = MockServer::start().await;
+    respond(&server, "good").await;
+
+    let provider = builder(&server).dry_run(true).build().unwrap();
+    provider
+        .update_record("home.example.com", ip())
+        .await
+        .unwrap();
+    assert_eq!(request_count(&server).await, 0);
+}
+
+#[tokio::test]
+async fn test_get_record_is_unsupported() {
+    let server = MockServer::start().await;
+    let provider = builder(&server).build().unwrap();
+
+    let error = provider.get_record("home.example.com").await.unwrap_err();
+    assert!(matches!(error, Error::Unsupported(_)), "{:?}", error);
+}
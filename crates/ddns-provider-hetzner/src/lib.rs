@@ -0,0 +1,810 @@
+// # Hetzner DNS Provider
+//
+// This crate provides a DNS provider that updates records in Hetzner DNS
+// zones through the Hetzner DNS API.
+//
+// ## Implementation Status
+//
+// - ✅ Zone from configuration, or looked up by name (longest matching
+//   suffix of the record name)
+// - ✅ Record lookup by listing the zone's records (one request per page of
+//   100 records), then one PUT if the address differs
+// - ✅ Optional creation of missing records (`create_if_missing`)
+// - ✅ Record lookup (`get_record`); a missing record maps to `Error::NotFound`
+// - ✅ Status codes map to `Error::Authentication` (401, 403),
+//   `Error::NotFound` (404) and `Error::RateLimited` (429)
+// - ✅ Configurable API base URL and injectable HTTP client (for offline tests)
+// - ✅ Dry-run mode (reads are sent, changes are not)
+// - ✅ Both A and AAAA record support
+// - ❌ NO retry logic (owned by DdnsEngine)
+// - ❌ NO caching of DNS state or zones (state owned by StateStore)
+// - ❌ NO background tasks (violates shutdown determinism)
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated, stateless and single-shot. It
+// only talks to the configured API base URL.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The API token NEVER appears in logs
+// - The API token MUST be provided via environment variables only
+// - Provider MUST fail fast if the token is empty
+//
+// ## API Reference
+//
+// - Hetzner DNS API: https://dns.hetzner.com/api-docs
+// - Get Zone: GET `/zones/:zone_id`
+// - List Zones: GET `/zones?name=...` (exact name match, one request per suffix)
+// - List Records: GET `/records?zone_id=...&page=...`
+// - Update Record: PUT `/records/:record_id`
+// - Create Record: POST `/records`
+//
+// Record names are relative to the zone (`@` for the zone apex).
+
+use async_trait::async_trait;
+use ddns_core::config::ProviderConfig;
+use ddns_core::dns::{normalize_name, record_type, relative_name, zone_candidates};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use reqwest::{Method, StatusCode, Url};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Hetzner DNS API base URL
+pub const HETZNER_API_BASE: &str = "https://dns.hetzner.com/api/v1";
+
+/// Default HTTP timeout for API requests (30 seconds)
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hetzner DNS provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated, stateless, and single-shot. All coordination
+/// (retries, backoff, scheduling) is owned by `DdnsEngine`.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API token.
+pub struct HetznerProvider {
+    /// API token, sent in the `Auth-API-Token` header
+    api_token: String,
+
+    /// Zone of all records (None = looked up per record)
+    zone_id: Option<String>,
+
+    /// Create missing records instead of failing
+    create_if_missing: bool,
+
+    /// TTL of created records (None = the zone's default)
+    ttl: Option<u32>,
+
+    /// API base URL without a trailing slash
+    api_base: String,
+
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Dry-run mode: if true, send reads but skip changes
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for HetznerProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HetznerProvider")
+            .field("api_token", &"<REDACTED>")
+            .field("zone_id", &self.zone_id)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("ttl", &self.ttl)
+            .field("api_base", &self.api_base)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// A zone: its ID and name
+#[derive(Debug)]
+struct Zone {
+    id: String,
+    /// Lowercase, without trailing dot
+    name: String,
+}
+
+/// An A or AAAA record
+#[derive(Debug)]
+struct Record {
+    id: String,
+    /// Name relative to the zone, as returned by the API
+    name: String,
+    value: String,
+    /// TTL (None = the zone's default)
+    ttl: Option<u32>,
+}
+
+impl HetznerProvider {
+    /// Start building a provider
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_hetzner::HetznerProvider;
+    ///
+    /// let provider = HetznerProvider::builder("api-token")
+    ///     .create_if_missing(true)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_token: impl Into<String>) -> HetznerProviderBuilder {
+        HetznerProviderBuilder::new(api_token)
+    }
+
+    /// URL of an API path with query parameters
+    fn url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}{}", self.api_base, path))
+            .map_err(|e| Error::config(format!("Invalid Hetzner API base URL: {}", e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    /// Send a request and return the parsed response body
+    ///
+    /// `action` names the API call in error messages.
+    async fn send(
+        &self,
+        action: &str,
+        method: Method,
+        url: Url,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("Auth-API-Token", &self.api_token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::provider("hetzner", format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            Error::provider(
+                "hetzner",
+                format!("Failed to read {} response: {}", action, e),
+            )
+        })?;
+
+        if !status.is_success() {
+            return Err(api_error(action, status, &text));
+        }
+        serde_json::from_str(&text)
+            .map_err(|e| Error::provider("hetzner", format!("Invalid {} response: {}", action, e)))
+    }
+
+    /// Get the zone of a record name
+    ///
+    /// Resolution order:
+    /// 1. The configured zone ID (one request for the zone's name)
+    /// 2. The zone named like the longest suffix of the record name
+    ///    (one request per suffix)
+    async fn zone_for(&self, record_name: &str) -> Result<Zone> {
+        let name = normalize_name(record_name);
+
+        if let Some(zone_id) = &self.zone_id {
+            let url = self.url(&format!("/zones/{}", zone_id), &[])?;
+            let body = self.send("get zone", Method::GET, url, None).await?;
+            let zone = parse_zone(&body["zone"])
+                .ok_or_else(|| Error::provider("hetzner", "Invalid get zone response"))?;
+            if relative_name(&name, &zone.name).is_none() {
+                return Err(Error::config(format!(
+                    "{} is not in the Hetzner zone {}",
+                    record_name, zone.name
+                )));
+            }
+            return Ok(zone);
+        }
+
+        for candidate in zone_candidates(&name) {
+            if let Some(zone) = self.find_zone(&candidate).await? {
+                tracing::debug!("Found zone for {}: {} ({})", name, zone.name, zone.id);
+                return Ok(zone);
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No Hetzner zone found for {}",
+            record_name
+        )))
+    }
+
+    /// The zone named `zone`, if the account has one
+    async fn find_zone(&self, zone: &str) -> Result<Option<Zone>> {
+        let url = self.url("/zones", &[("name", zone)])?;
+        let body = match self.send("list zones", Method::GET, url, None).await {
+            Ok(body) => body,
+            // Returned when no zone has the name
+            Err(Error::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        Ok(body["zones"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(parse_zone)
+            .find(|candidate| candidate.name == zone))
+    }
+
+    /// All `record_type` records of `record_name`
+    async fn records(
+        &self,
+        zone: &Zone,
+        record_name: &str,
+        record_type: &str,
+    ) -> Result<Vec<Record>> {
+        let relative =
+            relative_name(&normalize_name(record_name), &zone.name).ok_or_else(|| {
+                Error::config(format!("{} is not in the zone {}", record_name, zone.name))
+            })?;
+
+        let mut records = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = self.url(
+                "/records",
+                &[("zone_id", &zone.id), ("page", &page.to_string())],
+            )?;
+            let body = self.send("list records", Method::GET, url, None).await?;
+
+            records.extend(
+                body["records"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|record| {
+                        record["type"].as_str() == Some(record_type)
+                            && record["name"]
+                                .as_str()
+                                .is_some_and(|name| name.eq_ignore_ascii_case(&relative))
+                    })
+                    .filter_map(|record| {
+                        Some(Record {
+                            id: record["id"].as_str()?.to_string(),
+                            name: record["name"].as_str()?.to_string(),
+                            value: record["value"].as_str()?.to_string(),
+                            ttl: record["ttl"].as_u64().and_then(|ttl| ttl.try_into().ok()),
+                        })
+                    }),
+            );
+
+            let last_page = body["meta"]["pagination"]["last_page"]
+                .as_u64()
+                .unwrap_or(1);
+            if page >= last_page {
+                return Ok(records);
+            }
+            page += 1;
+        }
+    }
+
+    /// Create a record in a zone
+    async fn create_record(
+        &self,
+        zone: &Zone,
+        record_name: &str,
+        record_type: &str,
+        ip: IpAddr,
+    ) -> Result<UpdateResult> {
+        let relative = relative_name(&normalize_name(record_name), &zone.name).unwrap_or_default();
+        let mut payload = serde_json::json!({
+            "zone_id": zone.id,
+            "type": record_type,
+            "name": relative,
+            "value": ip.to_string(),
+        });
+        if let Some(ttl) = self.ttl {
+            payload["ttl"] = ttl.into();
+        }
+
+        tracing::info!(
+            "{} {} record: {} -> {} (zone: {}) [mode: {}]",
+            if self.dry_run {
+                "Would create"
+            } else {
+                "Creating"
+            },
+            record_type,
+            record_name,
+            ip,
+            zone.name,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if !self.dry_run {
+            let url = self.url("/records", &[])?;
+            self.send("create record", Method::POST, url, Some(&payload))
+                .await?;
+        }
+
+        Ok(UpdateResult::Created { new_ip: ip })
+    }
+}
+
+/// Zone of a `zone` object
+fn parse_zone(zone: &Value) -> Option<Zone> {
+    Some(Zone {
+        id: zone["id"].as_str()?.to_string(),
+        name: normalize_name(zone["name"].as_str()?),
+    })
+}
+
+/// Map an error response onto an error kind
+///
+/// Error bodies are `{"error": {"message": ..., "code": ...}}` or
+/// `{"message": ...}`.
+fn api_error(action: &str, status: StatusCode, body: &str) -> Error {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|json| {
+            json["error"]["message"]
+                .as_str()
+                .or_else(|| json["message"].as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().to_string());
+
+    match status.as_u16() {
+        401 | 403 => Error::auth(format!(
+            "Hetzner {} rejected: {} {}; check the API token",
+            action, status, message
+        )),
+        404 => Error::not_found(format!("Hetzner {}: {}", action, message)),
+        429 => Error::rate_limited(format!("Hetzner {} rate limited: {}", action, message)),
+        500..=599 => Error::provider(
+            "hetzner",
+            format!(
+                "Hetzner server error (transient) in {}: {} - {}",
+                action, status, message
+            ),
+        ),
+        _ => Error::provider(
+            "hetzner",
+            format!("{} failed: {} - {}", action, status, message),
+        ),
+    }
+}
+
+/// Builder for [`HetznerProvider`]
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API token.
+pub struct HetznerProviderBuilder {
+    api_token: String,
+    zone_id: Option<String>,
+    create_if_missing: bool,
+    ttl: Option<u32>,
+    api_base_url: Option<String>,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for HetznerProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HetznerProviderBuilder")
+            .field("api_token", &"<REDACTED>")
+            .field("zone_id", &self.zone_id)
+            .field("create_if_missing", &self.create_if_missing)
+            .field("ttl", &self.ttl)
+            .field("api_base_url", &self.api_base_url)
+            .field("timeout", &self.timeout)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl HetznerProviderBuilder {
+    /// Create a builder with an API token
+    pub fn new(api_token: impl Into<String>) -> Self {
+        Self {
+            api_token: api_token.into(),
+            zone_id: None,
+            create_if_missing: false,
+            ttl: None,
+            api_base_url: None,
+            timeout: None,
+            client: None,
+            dry_run: false,
+        }
+    }
+
+    /// Use this zone for all records instead of looking it up
+    pub fn zone_id(mut self, zone_id: impl Into<String>) -> Self {
+        self.zone_id = Some(zone_id.into());
+        self
+    }
+
+    /// Create missing A/AAAA records instead of returning `Error::NotFound`
+    pub fn create_if_missing(mut self, create_if_missing: bool) -> Self {
+        self.create_if_missing = create_if_missing;
+        self
+    }
+
+    /// TTL of created records (default: the zone's default TTL)
+    ///
+    /// Existing records keep their TTL.
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Send API requests to this base URL instead of the public API
+    ///
+    /// A trailing slash is ignored.
+    pub fn api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.api_base_url = Some(url.into());
+        self
+    }
+
+    /// HTTP timeout for API requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy and TLS settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send reads but skip changes
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the API token or zone ID is empty
+    /// - `Error::Config` if the API base URL is not an http(s) URL
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<HetznerProvider> {
+        if self.api_token.is_empty() {
+            return Err(Error::config("Hetzner API token cannot be empty"));
+        }
+
+        let zone_id = match self.zone_id {
+            Some(id) if id.trim().is_empty() => {
+                return Err(Error::config("Hetzner zone ID cannot be empty"));
+            }
+            zone_id => zone_id.map(|id| id.trim().to_string()),
+        };
+
+        let api_base = match self.api_base_url {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(url) => {
+                return Err(Error::config(format!(
+                    "Hetzner API base URL must start with http:// or https://, got {}",
+                    url
+                )));
+            }
+            None => HETZNER_API_BASE.to_string(),
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .build()
+                .map_err(|e| {
+                    Error::provider("hetzner", format!("Failed to build HTTP client: {}", e))
+                })?,
+        };
+
+        Ok(HetznerProvider {
+            api_token: self.api_token,
+            zone_id,
+            create_if_missing: self.create_if_missing,
+            ttl: self.ttl,
+            api_base,
+            client,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for HetznerProvider {
+    /// Set the A or AAAA record of a name to the new address
+    ///
+    /// If the name has several records of the type, the first one is
+    /// updated. Missing records are created with `create_if_missing`.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
+        };
+        let record_type = record_type(version);
+
+        let zone = self.zone_for(record_name).await?;
+        let records = self.records(&zone, record_name, record_type).await?;
+
+        let Some(record) = records.into_iter().next() else {
+            if self.create_if_missing {
+                return self
+                    .create_record(&zone, record_name, record_type, new_ip)
+                    .await;
+            }
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        let current_ip: Option<IpAddr> = record.value.parse().ok();
+        if current_ip == Some(new_ip) {
+            tracing::info!(
+                "DNS record already has correct IP: {} -> {}",
+                record_name,
+                new_ip
+            );
+            return Ok(UpdateResult::Unchanged { current_ip: new_ip });
+        }
+
+        tracing::info!(
+            "{} {} record: {} -> {} (was: {}, zone: {}) [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            record_type,
+            record_name,
+            new_ip,
+            record.value,
+            zone.name,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        let result = UpdateResult::Updated {
+            previous_ip: current_ip,
+            new_ip,
+        };
+        if self.dry_run {
+            return Ok(result);
+        }
+
+        // PUT replaces the whole record; an absent TTL keeps the zone default
+        let mut payload = serde_json::json!({
+            "zone_id": zone.id,
+            "type": record_type,
+            "name": record.name,
+            "value": new_ip.to_string(),
+        });
+        if let Some(ttl) = record.ttl {
+            payload["ttl"] = ttl.into();
+        }
+
+        let url = self.url(&format!("/records/{}", record.id), &[])?;
+        self.send("update record", Method::PUT, url, Some(&payload))
+            .await?;
+
+        tracing::info!(
+            "DNS record updated successfully: {} -> {}",
+            record_name,
+            new_ip
+        );
+        Ok(result)
+    }
+
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// If the name has several records of the type, the first one is
+    /// returned.
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let record_type = record_type(version);
+        let zone = self.zone_for(record_name).await?;
+        let records = self.records(&zone, record_name, record_type).await?;
+
+        let Some((record, ip)) = records.into_iter().find_map(|record| {
+            let ip = record.value.parse().ok()?;
+            Some((record, ip))
+        }) else {
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        Ok(RecordMetadata {
+            id: record.id,
+            name: record_name.to_string(),
+            ip,
+            ttl: record.ttl,
+            extra: serde_json::json!({
+                "zone_id": zone.id,
+                "zone": zone.name,
+            }),
+        })
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        record_name.contains('.') && record_name.len() <= 253
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "hetzner"
+    }
+}
+
+/// Factory for creating Hetzner providers
+pub struct HetznerFactory;
+
+impl DnsProviderFactory for HetznerFactory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::Hetzner {
+                api_token,
+                zone_id,
+                create_if_missing,
+                ttl,
+                api_base_url,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "Hetzner provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = HetznerProvider::builder(api_token.clone())
+                    .create_if_missing(*create_if_missing)
+                    .dry_run(dry_run);
+                if let Some(zone_id) = zone_id {
+                    builder = builder.zone_id(zone_id.clone());
+                }
+                if let Some(ttl) = ttl {
+                    builder = builder.ttl(*ttl);
+                }
+                if let Some(url) = api_base_url {
+                    builder = builder.api_base_url(url.clone());
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for Hetzner provider")),
+        }
+    }
+}
+
+/// Register the Hetzner provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_hetzner::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("hetzner", Box::new(HetznerFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(api_token: &str) -> ProviderConfig {
+        ProviderConfig::Hetzner {
+            api_token: api_token.to_string(),
+            zone_id: Some("zone1".to_string()),
+            create_if_missing: true,
+            ttl: Some(60),
+            api_base_url: Some("http://127.0.0.1:8080".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(HetznerFactory.create(&config("token")).is_ok());
+        assert!(HetznerFactory.create(&config("")).is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            HetznerProvider::builder("token")
+                .zone_id(" ")
+                .build()
+                .is_err()
+        );
+        assert!(
+            HetznerProvider::builder("token")
+                .api_base_url("dns.hetzner.com")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_api_token_not_exposed_in_debug() {
+        let builder = HetznerProvider::builder("secret-token");
+        assert!(!format!("{:?}", builder).contains("secret-token"));
+
+        let provider = builder.build().unwrap();
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("secret-token"));
+    }
+
+    #[test]
+    fn test_api_error_mapping() {
+        assert!(matches!(
+            api_error(
+                "list records",
+                StatusCode::UNAUTHORIZED,
+                r#"{"message": "Invalid authentication credentials"}"#
+            ),
+            Error::Authentication(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "get zone",
+                StatusCode::NOT_FOUND,
+                r#"{"error": {"message": "zone not found", "code": 404}}"#
+            ),
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            api_error("update record", StatusCode::TOO_MANY_REQUESTS, ""),
+            Error::RateLimited(_)
+        ));
+
+        let error = api_error(
+            "create record",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"error": {"message": "invalid value", "code": 422}}"#,
+        );
+        assert!(matches!(error, Error::Provider { .. }));
+        assert!(error.to_string().contains("invalid value"));
+    }
+}
@@ -0,0 +1,352 @@
+//! Hetzner DNS API error mapping against a local stub server
+//!
+//! An update searches the zone, pages through its records and PUTs the
+//! new address. Each call is failed in turn while the calls before it
+//! succeed. The API nests its error message as `error.message`, while the
+//! gateway in front of it answers authentication failures and rate limits
+//! with a bare `message`; either must end up in the error. A page that
+//! fails part way through the listing fails the update.
+
+mod common;
+
+use common::{api_error, gateway_error, provider, records_page, zones};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_test_support::{ApiCall, Expect, assert_maps_to, fail_at};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The zone apex, so that zone search takes a single request
+const RECORD_NAME: &str = "example.com";
+
+/// The API calls made by `update_record`, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    /// GET /zones?name=...
+    ListZones,
+    /// GET /records?zone_id=...&page=...
+    ListRecords,
+    /// PUT /records/:record_id
+    UpdateRecord,
+}
+
+const CALLS: [Call; 3] = [Call::ListZones, Call::ListRecords, Call::UpdateRecord];
+
+impl Call {
+    /// The action named in error messages
+    fn action(self) -> &'static str {
+        match self {
+            Call::ListZones => "list zones",
+            Call::ListRecords => "list records",
+            Call::UpdateRecord => "update record",
+        }
+    }
+}
+
+impl ApiCall for Call {
+    fn method(self) -> &'static str {
+        match self {
+            Call::UpdateRecord => "PUT",
+            _ => "GET",
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Call::ListZones => "/zones",
+            Call::ListRecords => "/records",
+            Call::UpdateRecord => "/records/r1",
+        }
+    }
+
+    fn success(self) -> ResponseTemplate {
+        match self {
+            Call::ListZones => zones(&[("zone1", "example.com")]),
+            Call::ListRecords => {
+                records_page("zone1", &[("r1", "A", "@", "198.51.100.1", None)], 1, 1)
+            }
+            Call::UpdateRecord => {
+                ResponseTemplate::new(200).set_body_json(json!({ "record": { "id": "r1" } }))
+            }
+        }
+    }
+}
+
+/// Run `update_record` with `failing` answered by `response`
+async fn update_failing_at(
+    failing: Call,
+    response: ResponseTemplate,
+) -> ddns_core::Result<UpdateResult> {
+    let server = fail_at(&CALLS, failing, response).await;
+    provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+}
+
+#[tokio::test]
+async fn update_puts_after_listing_the_records() {
+    let server = MockServer::start().await;
+    for call in CALLS {
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(call.success())
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let result = provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some("198.51.100.1".parse().unwrap()),
+            new_ip: "203.0.113.5".parse().unwrap(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn status_codes_map_to_error_kinds() {
+    for call in CALLS {
+        let cases = [
+            (
+                gateway_error(401, "Invalid authentication credentials"),
+                Expect::Authentication,
+            ),
+            (
+                api_error(403, "insufficient permissions"),
+                Expect::Authentication,
+            ),
+            // A zone search answered with 404 means no zone has the name
+            (api_error(404, "zone not found"), Expect::NotFound),
+            (
+                gateway_error(429, "API rate limit exceeded"),
+                Expect::RateLimited,
+            ),
+            (
+                ResponseTemplate::new(502).set_body_string("Bad Gateway"),
+                Expect::Provider("server error (transient)"),
+            ),
+            (
+                api_error(500, "internal server error"),
+                Expect::Provider("server error (transient)"),
+            ),
+        ];
+        for (response, expect) in cases {
+            assert_maps_to(
+                update_failing_at(call, response).await,
+                "hetzner",
+                expect,
+                &format!("{:?} -> {:?}", call, expect),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn rejected_update_passes_on_the_api_message() {
+    assert_maps_to(
+        update_failing_at(
+            Call::UpdateRecord,
+            api_error(422, "invalid A record: 203.0.113.5.1"),
+        )
+        .await,
+        "hetzner",
+        Expect::Provider("invalid A record"),
+        "UpdateRecord -> 422",
+    );
+}
+
+#[tokio::test]
+async fn failure_on_a_later_page_fails_the_update() {
+    for (response, expect) in [
+        (
+            gateway_error(429, "API rate limit exceeded"),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(500, "internal server error"),
+            Expect::Provider("server error (transient)"),
+        ),
+    ] {
+        // The record is on the first page, but the listing is incomplete
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListZones.path()))
+            .respond_with(Call::ListZones.success())
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListRecords.path()))
+            .and(query_param("page", "1"))
+            .respond_with(records_page(
+                "zone1",
+                &[("r1", "A", "@", "198.51.100.1", None)],
+                1,
+                2,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListRecords.path()))
+            .and(query_param("page", "2"))
+            .respond_with(response)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .respond_with(Call::UpdateRecord.success())
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        assert_maps_to(
+            provider(&server)
+                .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+                .await,
+            "hetzner",
+            expect,
+            &format!("ListRecords page 2 -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn unreadable_responses_fail_the_update() {
+    for call in CALLS {
+        let expected = format!("Invalid {} response", call.action());
+        let result = update_failing_at(
+            call,
+            ResponseTemplate::new(200).set_body_string("{\"records\": [}"),
+        )
+        .await;
+
+        match result {
+            Err(Error::Provider { message, .. }) => {
+                assert!(message.contains(&expected), "{:?}: {}", call, message)
+            }
+            other => panic!("{:?}: expected a provider error, got {:?}", call, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn create_failures() {
+    let cases = [
+        (
+            gateway_error(401, "Invalid authentication credentials"),
+            Expect::Authentication,
+        ),
+        (api_error(404, "zone not found"), Expect::NotFound),
+        (
+            gateway_error(429, "API rate limit exceeded"),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(422, "record already exists"),
+            Expect::Provider("record already exists"),
+        ),
+        (
+            ResponseTemplate::new(503),
+            Expect::Provider("server error (transient)"),
+        ),
+    ];
+
+    for (response, expect) in cases {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListZones.path()))
+            .respond_with(Call::ListZones.success())
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(Call::ListRecords.path()))
+            .respond_with(records_page("zone1", &[], 1, 1))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+
+        let provider = common::builder(&server)
+            .create_if_missing(true)
+            .build()
+            .unwrap();
+
+        assert_maps_to(
+            provider
+                .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+                .await,
+            "hetzner",
+            expect,
+            &format!("CreateRecord -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_record_maps_errors() {
+    for (response, expect) in [
+        (
+            gateway_error(401, "Invalid authentication credentials"),
+            Expect::Authentication,
+        ),
+        (api_error(404, "zone not found"), Expect::NotFound),
+        (
+            gateway_error(429, "API rate limit exceeded"),
+            Expect::RateLimited,
+        ),
+        (
+            ResponseTemplate::new(500),
+            Expect::Provider("server error (transient)"),
+        ),
+    ] {
+        let server = fail_at(&CALLS, Call::ListRecords, response).await;
+
+        let result = provider(&server)
+            .get_record_for(RECORD_NAME, IpVersion::V4)
+            .await;
+        assert_maps_to(
+            result,
+            "hetzner",
+            expect,
+            &format!("get_record -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_zone_search_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListZones.path()))
+        .respond_with(Call::ListZones.success().set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let provider = common::builder(&server)
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let result = provider
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_maps_to(
+        result,
+        "hetzner",
+        Expect::Provider("HTTP request failed"),
+        "ListZones -> timeout",
+    );
+}
@@ -0,0 +1,88 @@
+//! Stub server fixtures shared by the Hetzner DNS API tests
+
+#![allow(dead_code)]
+
+use ddns_provider_hetzner::{HetznerProvider, HetznerProviderBuilder};
+use serde_json::{Value, json};
+use wiremock::{MockServer, ResponseTemplate};
+
+/// A record as (id, type, name, value, ttl)
+pub type Record<'a> = (&'a str, &'a str, &'a str, &'a str, Option<u32>);
+
+/// A builder with the test API token talking to the stub server
+pub fn builder(server: &MockServer) -> HetznerProviderBuilder {
+    HetznerProvider::builder("api-token").api_base_url(server.uri())
+}
+
+/// A provider with default settings talking to the stub server
+pub fn provider(server: &MockServer) -> HetznerProvider {
+    builder(server).build().expect("provider builds")
+}
+
+/// A zone search result with zones as (id, name)
+pub fn zones(zones: &[(&str, &str)]) -> ResponseTemplate {
+    let zones: Vec<_> = zones
+        .iter()
+        .map(|(id, name)| json!({ "id": id, "name": name, "ttl": 86400 }))
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({
+        "zones": zones,
+        "meta": { "pagination": pagination(1, 1) },
+    }))
+}
+
+/// The zone of a zone ID
+pub fn zone(id: &str, name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "zone": { "id": id, "name": name } }))
+}
+
+/// Page `page` of `last_page` of the records of zone `zone_id`
+pub fn records_page(
+    zone_id: &str,
+    records: &[Record<'_>],
+    page: u64,
+    last_page: u64,
+) -> ResponseTemplate {
+    let records: Vec<_> = records
+        .iter()
+        .map(|(id, record_type, name, value, ttl)| {
+            let mut record = json!({
+                "id": id,
+                "type": record_type,
+                "name": name,
+                "value": value,
+                "zone_id": zone_id,
+            });
+            if let Some(ttl) = ttl {
+                record["ttl"] = json!(ttl);
+            }
+            record
+        })
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({
+        "records": records,
+        "meta": { "pagination": pagination(page, last_page) },
+    }))
+}
+
+/// An error in the nested form of the zone and record endpoints
+pub fn api_error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .set_body_json(json!({ "error": { "message": message, "code": status } }))
+}
+
+/// An error in the bare form of the API gateway, which answers
+/// authentication failures and rate limits before the API sees them
+pub fn gateway_error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "message": message }))
+}
+
+fn pagination(page: u64, last_page: u64) -> Value {
+    json!({
+        "page": page,
+        "per_page": 100,
+        "previous_page": page.saturating_sub(1),
+        "next_page": if page < last_page { page + 1 } else { 0 },
+        "last_page": last_page,
+    })
+}
@@ -0,0 +1,209 @@
+//! Hetzner DNS record updates against a local stub server
+//!
+//! Hetzner has no record filter, so an update pages through the records of
+//! the zone and picks the one of the name and type. Since a PUT replaces the
+//! whole record, it carries the record's name and TTL along with the new
+//! address. Missing records are created when enabled; dry runs write nothing.
+
+mod common;
+
+use common::{Record, records_page, zone};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_hetzner::{HetznerProvider, HetznerProviderBuilder};
+use serde_json::json;
+use std::net::IpAddr;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Answer the zone of the configured zone ID
+async fn mock_zone(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/zones/zone1"))
+        .respond_with(zone("zone1", "example.com"))
+        .mount(server)
+        .await;
+}
+
+/// Answer page `page` of `last_page` of the zone's records
+async fn mock_records_page(server: &MockServer, records: &[Record<'_>], page: u64, last_page: u64) {
+    Mock::given(method("GET"))
+        .and(path("/records"))
+        .and(query_param("zone_id", "zone1"))
+        .and(query_param("page", page.to_string()))
+        .respond_with(records_page("zone1", records, page, last_page))
+        .mount(server)
+        .await;
+}
+
+/// Expect `calls` requests with `http_method`
+async fn expect_writes(server: &MockServer, http_method: &str, calls: u64) {
+    Mock::given(method(http_method))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "record": {} })))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+fn builder(server: &MockServer) -> HetznerProviderBuilder {
+    common::builder(server).zone_id("zone1")
+}
+
+fn provider(server: &MockServer) -> HetznerProvider {
+    builder(server).build().unwrap()
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn record_on_a_later_page_is_updated_with_its_ttl() {
+    let server = MockServer::start().await;
+    mock_zone(&server).await;
+    mock_records_page(
+        &server,
+        &[
+            ("r1", "A", "@", "192.0.2.10", None),
+            ("r2", "AAAA", "home", "2001:db8::1", None),
+        ],
+        1,
+        2,
+    )
+    .await;
+    mock_records_page(&server, &[("r3", "A", "Home", "192.0.2.1", Some(60))], 2, 2).await;
+    Mock::given(method("PUT"))
+        .and(path("/records/r3"))
+        .and(header("auth-api-token", "api-token"))
+        .and(body_json(json!({
+            "zone_id": "zone1",
+            "type": "A",
+            "name": "Home",
+            "value": "203.0.113.5",
+            "ttl": 60,
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "record": {} })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some(ip("192.0.2.1")),
+            new_ip: ip("203.0.113.5"),
+        }
+    );
+}
+
+#[tokio::test]
+async fn unchanged_address_sends_no_update() {
+    let server = MockServer::start().await;
+    mock_zone(&server).await;
+    mock_records_page(
+        &server,
+        &[("r1", "AAAA", "home", "2001:db8::5", None)],
+        1,
+        1,
+    )
+    .await;
+    expect_writes(&server, "PUT", 0).await;
+
+    let result = provider(&server)
+        .update_record("home.example.com", ip("2001:db8::5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Unchanged {
+            current_ip: ip("2001:db8::5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_record_is_not_found_unless_created() {
+    let server = MockServer::start().await;
+    mock_zone(&server).await;
+    mock_records_page(&server, &[], 1, 1).await;
+    Mock::given(method("POST"))
+        .and(path("/records"))
+        .and(body_json(json!({
+            "zone_id": "zone1",
+            "type": "A",
+            "name": "@",
+            "value": "203.0.113.5",
+            "ttl": 120,
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "record": {} })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = provider(&server)
+        .update_record("example.com", ip("203.0.113.5"))
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+
+    let provider = builder(&server)
+        .create_if_missing(true)
+        .ttl(120)
+        .build()
+        .unwrap();
+    let result = provider
+        .update_record("example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("203.0.113.5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn dry_run_sends_no_change() {
+    let server = MockServer::start().await;
+    mock_zone(&server).await;
+    mock_records_page(&server, &[("r1", "A", "home", "192.0.2.1", None)], 1, 1).await;
+    expect_writes(&server, "PUT", 0).await;
+
+    let provider = builder(&server).dry_run(true).build().unwrap();
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Updated { .. }));
+}
+
+#[tokio::test]
+async fn get_record_falls_back_to_aaaa() {
+    let server = MockServer::start().await;
+    mock_zone(&server).await;
+    mock_records_page(
+        &server,
+        &[("r2", "AAAA", "home", "2001:db8::1", Some(300))],
+        1,
+        1,
+    )
+    .await;
+
+    let provider = provider(&server);
+    let record = provider.get_record("home.example.com").await.unwrap();
+    assert_eq!(record.id, "r2");
+    assert_eq!(record.ip, ip("2001:db8::1"));
+    assert_eq!(record.ttl, Some(300));
+
+    let result = provider
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
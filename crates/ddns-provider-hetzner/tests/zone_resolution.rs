@@ -0,0 +1,175 @@
+//! Hetzner DNS zone resolution against a local stub server
+//!
+//! Without a configured zone ID, zones are searched by exact name for each
+//! suffix of the record name, longest first. A configured zone ID costs one
+//! request for the zone's name, which then bounds the names it accepts.
+//! Hetzner answers a search for a name no zone has with a 404.
+
+mod common;
+
+use common::{api_error, builder, gateway_error, provider, records_page, zone, zones};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+/// Answer searches for `zone_name` with `zone_id`, expecting `calls` of them
+async fn mock_zone(server: &MockServer, zone_name: &str, zone_id: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", zone_name))
+        .and(header("auth-api-token", "api-token"))
+        .respond_with(zones(&[(zone_id, zone_name)]))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer searches for `zone_name` the way Hetzner does for unknown names
+async fn mock_not_a_zone(server: &MockServer, zone_name: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", zone_name))
+        .respond_with(api_error(404, "zone not found"))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer the zone of a configured zone ID
+async fn mock_zone_by_id(server: &MockServer, zone_id: &str, zone_name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/zones/{}", zone_id)))
+        .respond_with(zone(zone_id, zone_name))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+/// Answer record listings of `zone_id` with one A record named `relative`
+async fn mock_records(server: &MockServer, zone_id: &str, relative: &str) {
+    Mock::given(method("GET"))
+        .and(path("/records"))
+        .and(query_param("zone_id", zone_id))
+        .respond_with(records_page(
+            zone_id,
+            &[("r1", "A", relative, "203.0.113.5", None)],
+            1,
+            1,
+        ))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn delegated_subzone_wins_over_parent() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "host.lab.example.com", 1).await;
+    mock_zone(&server, "lab.example.com", "lab-zone", 1).await;
+    mock_zone(&server, "example.com", "parent-zone", 0).await;
+    mock_records(&server, "lab-zone", "host").await;
+
+    let record = provider(&server)
+        .get_record_for("Host.Lab.Example.com.", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "r1");
+    assert_eq!(record.extra["zone_id"], "lab-zone");
+}
+
+#[tokio::test]
+async fn zone_with_another_name_is_ignored() {
+    // A search the server answered with a zone of a different name
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/zones"))
+        .and(query_param("name", "home.example.com"))
+        .respond_with(zones(&[("other", "example.net")]))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mock_zone(&server, "example.com", "zone1", 1).await;
+    mock_records(&server, "zone1", "home").await;
+
+    let record = provider(&server)
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.extra["zone_id"], "zone1");
+}
+
+#[tokio::test]
+async fn configured_zone_id_skips_search() {
+    let server = MockServer::start().await;
+    mock_zone(&server, "example.com", "zone1", 0).await;
+    mock_zone_by_id(&server, "zone1", "example.com").await;
+    mock_records(&server, "zone1", "home").await;
+
+    let provider = builder(&server).zone_id("zone1").build().unwrap();
+
+    assert!(
+        provider
+            .get_record_for("home.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn name_outside_configured_zone_is_rejected() {
+    let server = MockServer::start().await;
+    mock_zone_by_id(&server, "zone1", "example.com").await;
+
+    let provider = builder(&server).zone_id("zone1").build().unwrap();
+    let result = provider
+        .update_record("home.example.net", "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(matches!(result, Err(Error::Config(_))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn rejected_configured_zone_is_an_authentication_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/zones/zone1"))
+        .respond_with(gateway_error(401, "Invalid authentication credentials"))
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server).zone_id("zone1").build().unwrap();
+    let result = provider
+        .update_record("home.example.com", "203.0.113.5".parse().unwrap())
+        .await;
+
+    match result {
+        Err(Error::Authentication(message)) => {
+            assert!(
+                message.contains("Invalid authentication credentials"),
+                "{}",
+                message
+            )
+        }
+        other => panic!("expected Authentication, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn unknown_zone_is_not_found() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "home.example.org", 1).await;
+    mock_not_a_zone(&server, "example.org", 1).await;
+
+    let result = provider(&server)
+        .get_record_for("home.example.org", IpVersion::V4)
+        .await;
+
+    match result {
+        Err(Error::NotFound(message)) => {
+            assert!(message.contains("home.example.org"), "{}", message)
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
@@ -0,0 +1,896 @@
+// # PowerDNS DNS Provider
+//
+// This crate provides a DNS provider that updates records on a PowerDNS
+// Authoritative server through its built-in HTTP API.
+//
+// ## Implementation Status
+//
+// - ✅ Zone from configuration, or looked up by name (longest matching
+//   suffix of the record name)
+// - ✅ One request to read the current RRset, one PATCH if it differs
+// - ✅ Missing RRsets are created by the same PATCH (`changetype: REPLACE`)
+// - ✅ Optional rectify and NOTIFY after a change (failures are logged, not
+//   returned: the change itself has been applied)
+// - ✅ Configurable server ID and injectable HTTP client (for offline tests)
+// - ✅ Dry-run mode (reads are sent, changes are not)
+// - ✅ Both A and AAAA record support
+// - ❌ NO retry logic (owned by DdnsEngine)
+// - ❌ NO caching of DNS state or zones (state owned by StateStore)
+// - ❌ NO background tasks (violates shutdown determinism)
+// - ❌ NO zone or SOA serial management: the serial is increased by the
+//   server according to the zone's `SOA-EDIT-API` setting
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated, stateless and single-shot. It
+// only talks to the configured API URL.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The API key NEVER appears in logs
+// - The API key MUST be provided via environment variables only
+// - The API URL should be https:// (e.g. behind a reverse proxy) unless the
+//   API is only reachable on a trusted network; the key is sent in clear
+//   text otherwise
+//
+// ## API Reference
+//
+// - PowerDNS Authoritative HTTP API: https://doc.powerdns.com/authoritative/http-api/
+// - List zones by name: GET `/api/v1/servers/:server/zones?zone=...`
+//   (one request per suffix)
+// - Read an RRset: GET `/api/v1/servers/:server/zones/:zone?rrset_name=...&rrset_type=...`
+// - Change RRsets: PATCH `/api/v1/servers/:server/zones/:zone`
+// - Rectify: PUT `/api/v1/servers/:server/zones/:zone/rectify`
+// - NOTIFY secondaries: PUT `/api/v1/servers/:server/zones/:zone/notify`
+
+use async_trait::async_trait;
+use ddns_core::config::ProviderConfig;
+use ddns_core::dns::{normalize_name, record_type, zone_candidates};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use reqwest::{Method, StatusCode, Url};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Server ID of the local server (the only one PowerDNS Authoritative has)
+pub const DEFAULT_SERVER_ID: &str = "localhost";
+
+/// Default TTL of created records (5 minutes)
+const DEFAULT_TTL: u32 = 300;
+
+/// Default HTTP timeout for API requests (30 seconds)
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// PowerDNS Authoritative DNS provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated, stateless, and single-shot. All coordination
+/// (retries, backoff, scheduling) is owned by `DdnsEngine`.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API key.
+pub struct PowerDnsProvider {
+    /// API URL (e.g. `http://127.0.0.1:8081`)
+    api_url: Url,
+
+    /// API key, sent in the `X-API-Key` header
+    api_key: String,
+
+    /// Server ID in API paths
+    server_id: String,
+
+    /// Zone of all records (None = looked up per record)
+    zone: Option<String>,
+
+    /// TTL of created records
+    ttl: u32,
+
+    /// Rectify the zone after a change
+    rectify: bool,
+
+    /// Send a NOTIFY to the secondaries after a change
+    notify: bool,
+
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Dry-run mode: if true, send reads but skip changes
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for PowerDnsProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PowerDnsProvider")
+            .field("api_url", &self.api_url.as_str())
+            .field("api_key", &"<REDACTED>")
+            .field("server_id", &self.server_id)
+            .field("zone", &self.zone)
+            .field("ttl", &self.ttl)
+            .field("rectify", &self.rectify)
+            .field("notify", &self.notify)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// Current state of an A or AAAA RRset
+#[derive(Debug)]
+struct RecordSet {
+    /// TTL
+    ttl: Option<u32>,
+    /// Contents of the records that are not disabled
+    values: Vec<String>,
+}
+
+impl RecordSet {
+    fn addresses(&self) -> Vec<IpAddr> {
+        self.values
+            .iter()
+            .filter_map(|value| value.parse().ok())
+            .collect()
+    }
+}
+
+impl PowerDnsProvider {
+    /// Start building a provider for the API at `api_url`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_powerdns::PowerDnsProvider;
+    ///
+    /// let provider = PowerDnsProvider::builder("http://127.0.0.1:8081", "api-key")
+    ///     .zone("example.com")
+    ///     .notify(true)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(
+        api_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> PowerDnsProviderBuilder {
+        PowerDnsProviderBuilder::new(api_url, api_key)
+    }
+
+    /// URL of the zones collection, or of a zone and its sub-resources
+    ///
+    /// Zone IDs are sent as path segments, so IDs with escaped characters
+    /// (`=2F`) are passed on unchanged.
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("API URL is an http(s) URL")
+            .pop_if_empty()
+            .extend(["api", "v1", "servers", &self.server_id, "zones"])
+            .extend(segments);
+        url
+    }
+
+    /// Send a request and return the response body
+    ///
+    /// `action` names the API call in error messages.
+    async fn send(
+        &self,
+        action: &str,
+        method: Method,
+        url: Url,
+        body: Option<&Value>,
+    ) -> Result<String> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("X-API-Key", &self.api_key);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::provider("powerdns", format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            Error::provider(
+                "powerdns",
+                format!("Failed to read {} response: {}", action, e),
+            )
+        })?;
+
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(api_error(action, status, &text))
+        }
+    }
+
+    /// Get the zone ID of a record name
+    ///
+    /// Resolution order:
+    /// 1. The configured zone
+    /// 2. The zone named like the longest suffix of the record name
+    ///    (one request per suffix)
+    async fn zone_for(&self, record_name: &str) -> Result<String> {
+        let name = normalize_name(record_name);
+
+        if let Some(zone) = &self.zone {
+            if name != *zone && !name.ends_with(&format!(".{}", zone)) {
+                return Err(Error::config(format!(
+                    "{} is not in the PowerDNS zone {}",
+                    record_name, zone
+                )));
+            }
+            return Ok(format!("{}.", zone));
+        }
+
+        for candidate in zone_candidates(&name) {
+            if let Some(id) = self.find_zone(&candidate).await? {
+                tracing::debug!("Found zone for {}: {}", name, id);
+                return Ok(id);
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No PowerDNS zone found for {}",
+            record_name
+        )))
+    }
+
+    /// ID of the zone named `zone`, if the server has one
+    async fn find_zone(&self, zone: &str) -> Result<Option<String>> {
+        let mut url = self.url(&[]);
+        url.query_pairs_mut()
+            .append_pair("zone", &format!("{}.", zone));
+        let body = self.send("list zones", Method::GET, url, None).await?;
+
+        let zones: Value = parse_json("list zones", &body)?;
+        let id = zones
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|candidate| {
+                candidate["name"]
+                    .as_str()
+                    .is_some_and(|name| normalize_name(name) == zone)
+            })
+            .find_map(|candidate| candidate["id"].as_str())
+            .map(str::to_string);
+        Ok(id)
+    }
+
+    /// Get the `record_type` RRset of `record_name`
+    ///
+    /// Returns `None` if the RRset does not exist or all its records are
+    /// disabled.
+    async fn record_set(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+    ) -> Result<Option<RecordSet>> {
+        let name = normalize_name(record_name);
+
+        // Servers before 4.8 ignore the filter and return the whole zone,
+        // so the RRsets are filtered here as well
+        let mut url = self.url(&[zone_id]);
+        url.query_pairs_mut()
+            .append_pair("rrset_name", &format!("{}.", name))
+            .append_pair("rrset_type", record_type);
+        let body = self.send("read zone", Method::GET, url, None).await?;
+
+        let zone: Value = parse_json("read zone", &body)?;
+        let Some(rrset) = zone["rrsets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|rrset| {
+                rrset["type"].as_str() == Some(record_type)
+                    && rrset["name"]
+                        .as_str()
+                        .is_some_and(|rrset_name| normalize_name(rrset_name) == name)
+            })
+        else {
+            return Ok(None);
+        };
+
+        let values: Vec<String> = rrset["records"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|record| !record["disabled"].as_bool().unwrap_or(false))
+            .filter_map(|record| record["content"].as_str())
+            .map(str::to_string)
+            .collect();
+        if values.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(RecordSet {
+            ttl: rrset["ttl"].as_u64().and_then(|ttl| ttl.try_into().ok()),
+            values,
+        }))
+    }
+
+    /// Replace an RRset with a single address, creating it if needed
+    async fn replace(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+        ttl: u32,
+        ip: IpAddr,
+    ) -> Result<()> {
+        let body = serde_json::json!({
+            "rrsets": [{
+                "name": format!("{}.", normalize_name(record_name)),
+                "type": record_type,
+                "ttl": ttl,
+                "changetype": "REPLACE",
+                "records": [{
+                    "content": ip.to_string(),
+                    "disabled": false,
+                }],
+            }],
+        });
+
+        let url = self.url(&[zone_id]);
+        self.send("change RRsets", Method::PATCH, url, Some(&body))
+            .await?;
+        Ok(())
+    }
+
+    /// Run the rectify and NOTIFY steps that are enabled
+    ///
+    /// Failures are logged rather than returned: the record has already
+    /// been changed, and a retry would find it unchanged.
+    async fn after_change(&self, zone_id: &str) {
+        if self.rectify {
+            let url = self.url(&[zone_id, "rectify"]);
+            match self.send("rectify", Method::PUT, url, None).await {
+                Ok(_) => tracing::debug!("Rectified zone {}", zone_id),
+                Err(e) => tracing::warn!("Failed to rectify zone {}: {}", zone_id, e),
+            }
+        }
+
+        if self.notify {
+            let url = self.url(&[zone_id, "notify"]);
+            match self.send("notify", Method::PUT, url, None).await {
+                Ok(_) => tracing::debug!("Queued NOTIFY for zone {}", zone_id),
+                Err(e) => tracing::warn!("Failed to notify secondaries of zone {}: {}", zone_id, e),
+            }
+        }
+    }
+}
+
+/// Parse a JSON response body
+fn parse_json(action: &str, body: &str) -> Result<Value> {
+    serde_json::from_str(body)
+        .map_err(|e| Error::provider("powerdns", format!("Invalid {} response: {}", action, e)))
+}
+
+/// Map an error response onto an error kind
+///
+/// Error bodies are JSON objects with an `error` message, except for
+/// authentication failures, which are plain text.
+fn api_error(action: &str, status: StatusCode, body: &str) -> Error {
+    let message = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|json| json["error"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+
+    match status.as_u16() {
+        401 | 403 => Error::auth(format!(
+            "PowerDNS {} rejected: {} {}; check the API key",
+            action, status, message
+        )),
+        404 => Error::not_found(format!("PowerDNS {}: {}", action, message)),
+        429 => Error::rate_limited(format!("PowerDNS {} rate limited: {}", action, message)),
+        500..=599 => Error::provider(
+            "powerdns",
+            format!(
+                "PowerDNS server error (transient) in {}: {} - {}",
+                action, status, message
+            ),
+        ),
+        _ => Error::provider(
+            "powerdns",
+            format!("{} failed: {} - {}", action, status, message),
+        ),
+    }
+}
+
+/// Builder for [`PowerDnsProvider`]
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the API key.
+pub struct PowerDnsProviderBuilder {
+    api_url: String,
+    api_key: String,
+    server_id: String,
+    zone: Option<String>,
+    ttl: u32,
+    rectify: bool,
+    notify: bool,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for PowerDnsProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PowerDnsProviderBuilder")
+            .field("api_url", &self.api_url)
+            .field("api_key", &"<REDACTED>")
+            .field("server_id", &self.server_id)
+            .field("zone", &self.zone)
+            .field("ttl", &self.ttl)
+            .field("rectify", &self.rectify)
+            .field("notify", &self.notify)
+            .field("timeout", &self.timeout)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl PowerDnsProviderBuilder {
+    /// Create a builder for the API at `api_url`
+    pub fn new(api_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            api_key: api_key.into(),
+            server_id: DEFAULT_SERVER_ID.to_string(),
+            zone: None,
+            ttl: DEFAULT_TTL,
+            rectify: false,
+            notify: false,
+            timeout: None,
+            client: None,
+            dry_run: false,
+        }
+    }
+
+    /// Server ID in API paths (default: "localhost")
+    pub fn server_id(mut self, server_id: impl Into<String>) -> Self {
+        self.server_id = server_id.into();
+        self
+    }
+
+    /// Use this zone for all records instead of looking it up
+    ///
+    /// A trailing dot is optional.
+    pub fn zone(mut self, zone: impl Into<String>) -> Self {
+        self.zone = Some(zone.into());
+        self
+    }
+
+    /// TTL of created records (default: 300 seconds)
+    ///
+    /// Existing records keep their TTL.
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Rectify the zone after a change
+    ///
+    /// Only needed for DNSSEC zones on servers with `default-api-rectify`
+    /// turned off.
+    pub fn rectify(mut self, rectify: bool) -> Self {
+        self.rectify = rectify;
+        self
+    }
+
+    /// Send a NOTIFY to the zone's secondaries after a change
+    pub fn notify(mut self, notify: bool) -> Self {
+        self.notify = notify;
+        self
+    }
+
+    /// HTTP timeout for API requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy and TLS settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send reads but skip changes
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the API URL is not an http(s) URL
+    /// - `Error::Config` if the API key, server ID or zone is empty
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<PowerDnsProvider> {
+        if !self.api_url.starts_with("http://") && !self.api_url.starts_with("https://") {
+            return Err(Error::config(format!(
+                "PowerDNS API URL must start with http:// or https://, got {}",
+                self.api_url
+            )));
+        }
+        let api_url = Url::parse(&self.api_url)
+            .map_err(|e| Error::config(format!("Invalid PowerDNS API URL: {}", e)))?;
+
+        if self.api_key.is_empty() {
+            return Err(Error::config("PowerDNS API key cannot be empty"));
+        }
+        if self.server_id.trim().is_empty() {
+            return Err(Error::config("PowerDNS server ID cannot be empty"));
+        }
+
+        let zone = match self.zone {
+            Some(zone) => {
+                let zone = normalize_name(zone.trim());
+                if zone.is_empty() {
+                    return Err(Error::config("PowerDNS zone cannot be empty"));
+                }
+                Some(zone)
+            }
+            None => None,
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .build()
+                .map_err(|e| {
+                    Error::provider("powerdns", format!("Failed to build HTTP client: {}", e))
+                })?,
+        };
+
+        Ok(PowerDnsProvider {
+            api_url,
+            api_key: self.api_key,
+            server_id: self.server_id,
+            zone,
+            ttl: self.ttl,
+            rectify: self.rectify,
+            notify: self.notify,
+            client,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for PowerDnsProvider {
+    /// Set the A or AAAA RRset of a name to the new address
+    ///
+    /// Reads the RRset first and only sends a PATCH if it does not already
+    /// consist of exactly the new address.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
+        };
+        let record_type = record_type(version);
+
+        let zone_id = self.zone_for(record_name).await?;
+        let current = self.record_set(&zone_id, record_name, record_type).await?;
+
+        let addresses = current
+            .as_ref()
+            .map(RecordSet::addresses)
+            .unwrap_or_default();
+        if addresses == [new_ip] {
+            tracing::info!(
+                "DNS record already has correct IP: {} -> {}",
+                record_name,
+                new_ip
+            );
+            return Ok(UpdateResult::Unchanged { current_ip: new_ip });
+        }
+
+        let result = match addresses.first() {
+            Some(previous_ip) => UpdateResult::Updated {
+                previous_ip: Some(*previous_ip),
+                new_ip,
+            },
+            None => UpdateResult::Created { new_ip },
+        };
+
+        tracing::info!(
+            "{} {} record: {} -> {} (was: {:?}, zone: {}) [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            record_type,
+            record_name,
+            new_ip,
+            addresses,
+            zone_id,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if self.dry_run {
+            return Ok(result);
+        }
+
+        let ttl = current
+            .as_ref()
+            .and_then(|current| current.ttl)
+            .unwrap_or(self.ttl);
+        self.replace(&zone_id, record_name, record_type, ttl, new_ip)
+            .await?;
+        self.after_change(&zone_id).await;
+
+        tracing::info!(
+            "DNS record updated successfully: {} -> {}",
+            record_name,
+            new_ip
+        );
+        Ok(result)
+    }
+
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// If the RRset holds several addresses, the first one is returned and
+    /// all of them are listed in `extra.values`. Disabled records are
+    /// ignored.
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let record_type = record_type(version);
+        let zone_id = self.zone_for(record_name).await?;
+        let record_set = self.record_set(&zone_id, record_name, record_type).await?;
+
+        let Some((record_set, ip)) = record_set.and_then(|record_set| {
+            let ip = *record_set.addresses().first()?;
+            Some((record_set, ip))
+        }) else {
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        Ok(RecordMetadata {
+            id: format!(
+                "{}/{}./{}",
+                zone_id,
+                normalize_name(record_name),
+                record_type
+            ),
+            name: record_name.to_string(),
+            ip,
+            ttl: record_set.ttl,
+            extra: serde_json::json!({
+                "zone": zone_id,
+                "values": record_set.values,
+            }),
+        })
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        record_name.contains('.') && record_name.len() <= 253
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "powerdns"
+    }
+}
+
+/// Factory for creating PowerDNS providers
+pub struct PowerDnsFactory;
+
+impl DnsProviderFactory for PowerDnsFactory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::PowerDns {
+                api_url,
+                api_key,
+                server_id,
+                zone,
+                ttl,
+                rectify,
+                notify,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "PowerDNS provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = PowerDnsProvider::builder(api_url.clone(), api_key.clone())
+                    .server_id(server_id.clone())
+                    .ttl(*ttl)
+                    .rectify(*rectify)
+                    .notify(*notify)
+                    .dry_run(dry_run);
+                if let Some(zone) = zone {
+                    builder = builder.zone(zone.clone());
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for PowerDNS provider")),
+        }
+    }
+}
+
+/// Register the PowerDNS provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_powerdns::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("powerdns", Box::new(PowerDnsFactory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(api_key: &str) -> ProviderConfig {
+        ProviderConfig::PowerDns {
+            api_url: "http://127.0.0.1:8081".to_string(),
+            api_key: api_key.to_string(),
+            server_id: "localhost".to_string(),
+            zone: Some("example.com".to_string()),
+            ttl: 60,
+            rectify: false,
+            notify: true,
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(PowerDnsFactory.create(&config("key")).is_ok());
+        assert!(PowerDnsFactory.create(&config("")).is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            PowerDnsProvider::builder("127.0.0.1:8081", "key")
+                .build()
+                .is_err()
+        );
+        assert!(
+            PowerDnsProvider::builder("http://127.0.0.1:8081", "key")
+                .server_id(" ")
+                .build()
+                .is_err()
+        );
+        assert!(
+            PowerDnsProvider::builder("http://127.0.0.1:8081", "key")
+                .zone(".")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_urls() {
+        let provider = PowerDnsProvider::builder("http://127.0.0.1:8081/", "key")
+            .build()
+            .unwrap();
+        assert_eq!(
+            provider.url(&[]).as_str(),
+            "http://127.0.0.1:8081/api/v1/servers/localhost/zones"
+        );
+        assert_eq!(
+            provider.url(&["example.com.", "notify"]).as_str(),
+            "http://127.0.0.1:8081/api/v1/servers/localhost/zones/example.com./notify"
+        );
+
+        // Behind a reverse proxy
+        let provider = PowerDnsProvider::builder("https://dns.example.com/pdns", "key")
+            .build()
+            .unwrap();
+        assert_eq!(
+            provider.url(&["example.com."]).as_str(),
+            "https://dns.example.com/pdns/api/v1/servers/localhost/zones/example.com."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_configured_zone() {
+        let provider = PowerDnsProvider::builder("http://127.0.0.1:8081", "key")
+            .zone("Example.com.")
+            .build()
+            .unwrap();
+        assert_eq!(
+            provider.zone_for("home.example.com").await.unwrap(),
+            "example.com."
+        );
+        assert_eq!(
+            provider.zone_for("example.com.").await.unwrap(),
+            "example.com."
+        );
+        assert!(matches!(
+            provider.zone_for("home.notexample.com").await,
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_api_key_not_exposed_in_debug() {
+        let builder = PowerDnsProvider::builder("http://127.0.0.1:8081", "secret-key");
+        assert!(!format!("{:?}", builder).contains("secret-key"));
+
+        let provider = builder.build().unwrap();
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("secret-key"));
+    }
+
+    #[test]
+    fn test_api_error_mapping() {
+        assert!(matches!(
+            api_error("read zone", StatusCode::UNAUTHORIZED, "Unauthorized"),
+            Error::Authentication(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "read zone",
+                StatusCode::NOT_FOUND,
+                r#"{"error": "Could not find domain 'example.com.'"}"#
+            ),
+            Error::NotFound(_)
+        ));
+
+        let error = api_error(
+            "change RRsets",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"error": "RRset home.example.com. IN A: Conflicts with pre-existing RRset"}"#,
+        );
+        assert!(matches!(error, Error::Provider { .. }));
+        assert!(
+            error
+                .to_string()
+                .contains("Conflicts with pre-existing RRset")
+        );
+
+        assert!(matches!(
+            api_error("read zone", StatusCode::SERVICE_UNAVAILABLE, ""),
+            Error::Provider { .. }
+        ));
+    }
+}
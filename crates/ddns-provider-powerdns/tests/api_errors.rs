@@ -0,0 +1,239 @@
+//! PowerDNS API error mapping against a local stub server
+//!
+//! An update searches the zone, reads the RRset and sends a PATCH. Each
+//! call is failed in turn while the calls before it succeed. The API
+//! explains most errors in an `error` field, but its web server rejects a
+//! wrong API key with a plain text body; both map by status. A PATCH the
+//! zone cannot take is refused with a 422 whose explanation is passed on.
+
+mod common;
+
+use common::{api_error, provider, zone, zones};
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_test_support::{ApiCall, Expect, assert_maps_to, fail_at};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The zone apex, so that zone search takes a single request
+const RECORD_NAME: &str = "example.com";
+
+/// The API calls made by `update_record`, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    /// GET /api/v1/servers/:server/zones?zone=...
+    ListZones,
+    /// GET /api/v1/servers/:server/zones/:zone?rrset_name=...&rrset_type=...
+    ReadZone,
+    /// PATCH /api/v1/servers/:server/zones/:zone
+    ChangeRrsets,
+}
+
+const CALLS: [Call; 3] = [Call::ListZones, Call::ReadZone, Call::ChangeRrsets];
+
+impl ApiCall for Call {
+    fn method(self) -> &'static str {
+        match self {
+            Call::ChangeRrsets => "PATCH",
+            _ => "GET",
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Call::ListZones => "/api/v1/servers/localhost/zones",
+            Call::ReadZone | Call::ChangeRrsets => "/api/v1/servers/localhost/zones/example.com.",
+        }
+    }
+
+    fn success(self) -> ResponseTemplate {
+        match self {
+            Call::ListZones => zones(&[("example.com.", "example.com.")]),
+            Call::ReadZone => zone(
+                "example.com.",
+                &[("example.com.", "A", 60, &[("198.51.100.1", false)])],
+            ),
+            Call::ChangeRrsets => ResponseTemplate::new(204),
+        }
+    }
+}
+
+/// Run `update_record` with `failing` answered by `response`
+async fn update_failing_at(
+    failing: Call,
+    response: ResponseTemplate,
+) -> ddns_core::Result<UpdateResult> {
+    let server = fail_at(&CALLS, failing, response).await;
+    provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+}
+
+#[tokio::test]
+async fn update_patches_after_reading_the_rrset() {
+    let server = MockServer::start().await;
+    for call in CALLS {
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(call.success())
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let result = provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some("198.51.100.1".parse().unwrap()),
+            new_ip: "203.0.113.5".parse().unwrap(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn status_codes_map_to_error_kinds() {
+    for call in CALLS {
+        let cases = [
+            (
+                ResponseTemplate::new(401)
+                    .insert_header("content-type", "text/plain; charset=utf-8")
+                    .set_body_string("Unauthorized"),
+                Expect::Authentication,
+            ),
+            (
+                ResponseTemplate::new(403).set_body_string("Forbidden"),
+                Expect::Authentication,
+            ),
+            (
+                api_error(404, "Could not find domain 'example.com.'"),
+                Expect::NotFound,
+            ),
+            (
+                // Returned by a rate limiting proxy in front of the API
+                ResponseTemplate::new(429).set_body_string("Too Many Requests"),
+                Expect::RateLimited,
+            ),
+            (
+                api_error(
+                    500,
+                    "Backend reported permanent error which prevented lookup",
+                ),
+                Expect::Provider("server error (transient)"),
+            ),
+            (
+                ResponseTemplate::new(502),
+                Expect::Provider("server error (transient)"),
+            ),
+        ];
+        for (response, expect) in cases {
+            assert_maps_to(
+                update_failing_at(call, response).await,
+                "powerdns",
+                expect,
+                &format!("{:?} -> {:?}", call, expect),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn rejected_patch_passes_on_the_api_error() {
+    for error in [
+        "RRset example.com. IN A: Conflicts with pre-existing RRset",
+        "Record example.com./A '203.0.113.5': Not in expected format (parsed as '203.0.113.5')",
+    ] {
+        assert_maps_to(
+            update_failing_at(Call::ChangeRrsets, api_error(422, error)).await,
+            "powerdns",
+            Expect::Provider(error),
+            "ChangeRrsets -> 422",
+        );
+    }
+}
+
+#[tokio::test]
+async fn unreadable_responses_fail_the_update() {
+    // The PATCH answers 204 without a body, which is not inspected
+    for (call, expected) in [
+        (Call::ListZones, "Invalid list zones response"),
+        (Call::ReadZone, "Invalid read zone response"),
+    ] {
+        assert_maps_to(
+            update_failing_at(
+                call,
+                ResponseTemplate::new(200).set_body_string("{\"rrsets\": [}"),
+            )
+            .await,
+            "powerdns",
+            Expect::Provider(expected),
+            &format!("{:?} -> malformed JSON", call),
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_record_maps_errors() {
+    for (response, expect) in [
+        (
+            ResponseTemplate::new(401).set_body_string("Unauthorized"),
+            Expect::Authentication,
+        ),
+        (
+            api_error(404, "Could not find domain 'example.com.'"),
+            Expect::NotFound,
+        ),
+        (
+            ResponseTemplate::new(429).set_body_string("Too Many Requests"),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(500, "Backend error"),
+            Expect::Provider("server error (transient)"),
+        ),
+    ] {
+        let server = fail_at(&CALLS, Call::ReadZone, response).await;
+
+        let result = provider(&server)
+            .get_record_for(RECORD_NAME, IpVersion::V4)
+            .await;
+        assert_maps_to(
+            result,
+            "powerdns",
+            expect,
+            &format!("get_record -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_zone_search_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListZones.path()))
+        .respond_with(Call::ListZones.success().set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let provider = common::builder(&server)
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let result = provider
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_maps_to(
+        result,
+        "powerdns",
+        Expect::Provider("HTTP request failed"),
+        "ListZones -> timeout",
+    );
+}
@@ -0,0 +1,54 @@
+//! Stub server fixtures shared by the PowerDNS API tests
+
+#![allow(dead_code)]
+
+use ddns_provider_powerdns::{PowerDnsProvider, PowerDnsProviderBuilder};
+use serde_json::json;
+use wiremock::{MockServer, ResponseTemplate};
+
+/// Name, type, TTL and records (content, disabled) of an RRset
+pub type RecordSet<'a> = (&'a str, &'a str, u32, &'a [(&'a str, bool)]);
+
+/// A builder with the test API key talking to the stub server
+pub fn builder(server: &MockServer) -> PowerDnsProviderBuilder {
+    PowerDnsProvider::builder(server.uri(), "api-key")
+}
+
+/// A provider with default settings talking to the stub server
+pub fn provider(server: &MockServer) -> PowerDnsProvider {
+    builder(server).build().expect("provider builds")
+}
+
+/// A zone list with zones as (id, name)
+pub fn zones(zones: &[(&str, &str)]) -> ResponseTemplate {
+    let zones: Vec<_> = zones
+        .iter()
+        .map(|(id, name)| json!({ "id": id, "name": name, "kind": "Native" }))
+        .collect();
+    ResponseTemplate::new(200).set_body_json(zones)
+}
+
+/// The zone with ID `zone_id`, holding `rrsets`
+pub fn zone(zone_id: &str, rrsets: &[RecordSet<'_>]) -> ResponseTemplate {
+    let rrsets: Vec<_> = rrsets
+        .iter()
+        .map(|(name, record_type, ttl, records)| {
+            let records: Vec<_> = records
+                .iter()
+                .map(|(content, disabled)| json!({ "content": content, "disabled": disabled }))
+                .collect();
+            json!({ "name": name, "type": record_type, "ttl": ttl, "records": records })
+        })
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({
+        "id": zone_id,
+        "name": zone_id,
+        "kind": "Native",
+        "rrsets": rrsets,
+    }))
+}
+
+/// An error as explained by the PowerDNS API in its `error` field
+pub fn api_error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "error": message }))
+}
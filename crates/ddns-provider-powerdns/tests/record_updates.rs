@@ -0,0 +1,308 @@
+//! PowerDNS record updates against a local stub server
+//!
+//! An update reads the RRset of the name and type and, if its enabled
+//! addresses differ, replaces it with one PATCH that keeps its TTL. A
+//! missing or fully disabled RRset is created by the same PATCH. Rectify
+//! and NOTIFY follow a change when enabled, and their failures are logged
+//! rather than returned.
+
+mod common;
+
+use common::{RecordSet, api_error, zone};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_powerdns::PowerDnsProviderBuilder;
+use serde_json::json;
+use std::net::IpAddr;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ZONE_PATH: &str = "/api/v1/servers/localhost/zones/example.com.";
+
+/// Answer RRset reads of the zone with `rrsets`
+async fn mock_zone(server: &MockServer, rrsets: &[RecordSet<'_>]) {
+    Mock::given(method("GET"))
+        .and(path(ZONE_PATH))
+        .respond_with(zone("example.com.", rrsets))
+        .mount(server)
+        .await;
+}
+
+/// Expect `calls` PATCHes replacing the RRset with a single address
+async fn expect_replace(
+    server: &MockServer,
+    record_type: &str,
+    ttl: u32,
+    content: &str,
+    calls: u64,
+) {
+    Mock::given(method("PATCH"))
+        .and(path(ZONE_PATH))
+        .and(header("x-api-key", "api-key"))
+        .and(body_json(json!({
+            "rrsets": [{
+                "name": "home.example.com.",
+                "type": record_type,
+                "ttl": ttl,
+                "changetype": "REPLACE",
+                "records": [{ "content": content, "disabled": false }],
+            }],
+        })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+fn builder(server: &MockServer) -> PowerDnsProviderBuilder {
+    common::builder(server).zone("example.com")
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn changed_address_replaces_the_rrset_and_keeps_its_ttl() {
+    let server = MockServer::start().await;
+    mock_zone(
+        &server,
+        &[(
+            "home.example.com.",
+            "A",
+            60,
+            &[("192.0.2.1", false), ("192.0.2.2", false)],
+        )],
+    )
+    .await;
+    expect_replace(&server, "A", 60, "203.0.113.5", 1).await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some(ip("192.0.2.1")),
+            new_ip: ip("203.0.113.5"),
+        }
+    );
+}
+
+#[tokio::test]
+async fn unchanged_address_sends_no_change() {
+    let server = MockServer::start().await;
+    mock_zone(
+        &server,
+        &[("home.example.com.", "AAAA", 60, &[("2001:db8::5", false)])],
+    )
+    .await;
+    expect_replace(&server, "AAAA", 60, "2001:db8::5", 0).await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("2001:db8::5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Unchanged {
+            current_ip: ip("2001:db8::5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_rrset_is_created_with_the_configured_ttl() {
+    let server = MockServer::start().await;
+    // Only the AAAA RRset exists
+    Mock::given(method("GET"))
+        .and(path(ZONE_PATH))
+        .and(query_param("rrset_name", "home.example.com."))
+        .and(query_param("rrset_type", "A"))
+        .respond_with(zone(
+            "example.com.",
+            &[("home.example.com.", "AAAA", 60, &[("2001:db8::1", false)])],
+        ))
+        .mount(&server)
+        .await;
+    expect_replace(&server, "A", 120, "203.0.113.5", 1).await;
+
+    let result = builder(&server)
+        .ttl(120)
+        .build()
+        .unwrap()
+        .update_record("Home.Example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("203.0.113.5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn disabled_records_are_replaced() {
+    let server = MockServer::start().await;
+    mock_zone(
+        &server,
+        &[("home.example.com.", "A", 60, &[("203.0.113.5", true)])],
+    )
+    .await;
+    // An RRset without enabled records counts as missing, TTL included
+    expect_replace(&server, "A", 120, "203.0.113.5", 1).await;
+
+    let result = builder(&server)
+        .ttl(120)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Created { .. }));
+}
+
+#[tokio::test]
+async fn whole_zone_response_is_filtered() {
+    // Servers before 4.8 ignore rrset_name/rrset_type and return every RRset
+    let server = MockServer::start().await;
+    mock_zone(
+        &server,
+        &[
+            ("example.com.", "A", 60, &[("192.0.2.10", false)]),
+            ("home.example.com.", "AAAA", 60, &[("2001:db8::1", false)]),
+            ("home.example.com.", "A", 60, &[("203.0.113.5", false)]),
+        ],
+    )
+    .await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Unchanged { .. }));
+}
+
+#[tokio::test]
+async fn dry_run_only_reads() {
+    let server = MockServer::start().await;
+    mock_zone(&server, &[]).await;
+
+    let provider = builder(&server).notify(true).dry_run(true).build().unwrap();
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Created { .. }));
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method.as_str(), "GET");
+}
+
+#[tokio::test]
+async fn rectify_and_notify_follow_a_change() {
+    let server = MockServer::start().await;
+    mock_zone(&server, &[]).await;
+    expect_replace(&server, "A", 60, "203.0.113.5", 1).await;
+    Mock::given(method("PUT"))
+        .and(path(format!("{}/rectify", ZONE_PATH)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "result": "Rectified" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path(format!("{}/notify", ZONE_PATH)))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "result": "Notification queued" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    builder(&server)
+        .ttl(60)
+        .rectify(true)
+        .notify(true)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn failed_notify_does_not_fail_the_update() {
+    let server = MockServer::start().await;
+    mock_zone(&server, &[]).await;
+    expect_replace(&server, "A", 60, "203.0.113.5", 1).await;
+    Mock::given(method("PUT"))
+        .and(path(format!("{}/notify", ZONE_PATH)))
+        .respond_with(api_error(
+            422,
+            "Domain 'example.com.' is not primary or secondary",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = builder(&server)
+        .ttl(60)
+        .notify(true)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Created { .. }));
+}
+
+#[tokio::test]
+async fn get_record_falls_back_to_aaaa() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(ZONE_PATH))
+        .and(query_param("rrset_type", "A"))
+        .respond_with(zone("example.com.", &[]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(ZONE_PATH))
+        .and(query_param("rrset_type", "AAAA"))
+        .respond_with(zone(
+            "example.com.",
+            &[(
+                "home.example.com.",
+                "AAAA",
+                60,
+                &[("2001:db8::1", true), ("2001:db8::2", false)],
+            )],
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = builder(&server).build().unwrap();
+    let record = provider.get_record("home.example.com").await.unwrap();
+    assert_eq!(record.ip, ip("2001:db8::2"));
+    assert_eq!(record.ttl, Some(60));
+    assert_eq!(record.extra["values"], json!(["2001:db8::2"]));
+
+    let result = provider
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
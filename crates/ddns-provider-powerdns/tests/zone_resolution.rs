@@ -0,0 +1,140 @@
+//! PowerDNS zone resolution against a local stub server
+//!
+//! Without a configured zone, the server's zones are listed by name for
+//! each suffix of the record name, longest first, and the ID of the first
+//! exact match is used. A configured zone is used as is, and names outside
+//! it are rejected before any request is sent.
+
+mod common;
+
+use common::{builder, provider, zone, zones};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+const ZONES_PATH: &str = "/api/v1/servers/localhost/zones";
+
+/// Answer searches for `zone_name` with a zone of ID `zone_id`, expecting
+/// `calls` of them
+async fn mock_zone(server: &MockServer, zone_name: &str, zone_id: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path(ZONES_PATH))
+        .and(query_param("zone", format!("{}.", zone_name)))
+        .and(header("x-api-key", "api-key"))
+        .respond_with(zones(&[(zone_id, &format!("{}.", zone_name))]))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer searches for `zone_name` with an empty list
+async fn mock_not_a_zone(server: &MockServer, zone_name: &str, calls: u64) {
+    Mock::given(method("GET"))
+        .and(path(ZONES_PATH))
+        .and(query_param("zone", format!("{}.", zone_name)))
+        .respond_with(zones(&[]))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer A RRset reads of `record_name` in the zone with ID `zone_id`
+async fn mock_rrset(server: &MockServer, zone_id: &str, record_name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("{}/{}", ZONES_PATH, zone_id)))
+        .and(query_param("rrset_name", format!("{}.", record_name)))
+        .respond_with(zone(
+            zone_id,
+            &[(
+                &format!("{}.", record_name),
+                "A",
+                60,
+                &[("203.0.113.5", false)],
+            )],
+        ))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn delegated_subzone_wins_over_parent() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "host.lab.example.com", 1).await;
+    mock_zone(&server, "lab.example.com", "lab.example.com.", 1).await;
+    mock_zone(&server, "example.com", "example.com.", 0).await;
+    mock_rrset(&server, "lab.example.com.", "host.lab.example.com").await;
+
+    let record = provider(&server)
+        .get_record_for("Host.Lab.Example.com", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        record.ip,
+        "203.0.113.5".parse::<std::net::IpAddr>().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn escaped_zone_ids_are_sent_unchanged() {
+    // PowerDNS escapes `/` in names of classless reverse zones as `=2F`
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "host.0/26.example.com", 1).await;
+    mock_zone(&server, "0/26.example.com", "0=2F26.example.com.", 1).await;
+    mock_rrset(&server, "0=2F26.example.com.", "host.0/26.example.com").await;
+
+    assert!(
+        provider(&server)
+            .get_record_for("host.0/26.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn configured_zone_skips_search() {
+    let server = MockServer::start().await;
+    mock_zone(&server, "example.com", "example.com.", 0).await;
+    mock_rrset(&server, "example.com.", "home.example.com").await;
+
+    let provider = builder(&server).zone("example.com").build().unwrap();
+
+    assert!(
+        provider
+            .get_record_for("home.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn name_outside_configured_zone_is_rejected() {
+    let server = MockServer::start().await;
+
+    let provider = builder(&server).zone("example.com").build().unwrap();
+    let result = provider
+        .update_record("home.notexample.com", "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(matches!(result, Err(Error::Config(_))), "{:?}", result);
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn unknown_zone_is_not_found() {
+    let server = MockServer::start().await;
+    mock_not_a_zone(&server, "home.example.org", 1).await;
+    mock_not_a_zone(&server, "example.org", 1).await;
+
+    let result = provider(&server)
+        .get_record_for("home.example.org", IpVersion::V4)
+        .await;
+
+    match result {
+        Err(Error::NotFound(message)) => {
+            assert!(message.contains("home.example.org"), "{}", message)
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
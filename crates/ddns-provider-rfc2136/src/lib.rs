@@ -0,0 +1,695 @@
+// # RFC 2136 DNS Provider
+//
+// This crate provides a DNS provider that updates records on an
+// authoritative server (BIND, Knot, PowerDNS, ...) with DNS UPDATE
+// messages (RFC 2136), signed with TSIG (RFC 8945).
+//
+// ## Implementation Status
+//
+// - ✅ One query to read the current RRset, one UPDATE if it differs
+// - ✅ Each update deletes the RRset and adds the new record in a single
+//   message, which the server applies atomically
+// - ✅ Missing records are created (the delete is a no-op)
+// - ✅ TSIG with HMAC-SHA256 or HMAC-SHA512; responses are verified, so a
+//   forged "NOERROR" is not mistaken for a successful update
+// - ✅ UDP with TCP fallback for truncated responses
+// - ✅ Zone from configuration, or discovered with a SOA query
+// - ✅ Dry-run mode (queries are sent, updates are not)
+// - ✅ Both A and AAAA record support
+// - ❌ NO retransmissions or retry logic (owned by DdnsEngine)
+// - ❌ NO caching of DNS state or zones (state owned by StateStore)
+// - ❌ NO background tasks (violates shutdown determinism)
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated, stateless and single-shot. It
+// only talks to the configured server.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The TSIG secret NEVER appears in logs
+// - The TSIG secret MUST be provided via environment variables only
+// - Without a TSIG key, messages are unsigned; only use this when the
+//   server restricts updates by source address
+//
+// ## Protocol Reference
+//
+// - DNS UPDATE: RFC 2136 (zone section, update section, response codes)
+// - TSIG: RFC 8945
+// - Message format and TCP framing: RFC 1035, section 4
+
+mod message;
+mod transport;
+mod tsig;
+
+use async_trait::async_trait;
+use ddns_core::config::{ProviderConfig, TsigAlgorithm};
+use ddns_core::dns::{self, normalize_name, relative_name};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use message::{RCODE_NOERROR, RCODE_NXDOMAIN, Record, Response, TYPE_A, TYPE_AAAA, TYPE_SOA};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tsig::TsigKey;
+
+/// Default DNS port
+pub const DEFAULT_PORT: u16 = 53;
+
+/// Default TTL of the records written (5 minutes)
+const DEFAULT_TTL: u32 = 300;
+
+/// Default timeout for one exchange with the server (10 seconds)
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// RFC 2136 dynamic update provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated, stateless, and single-shot. All coordination
+/// (retries, backoff, scheduling) is owned by `DdnsEngine`.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the TSIG secret.
+#[derive(Debug)]
+pub struct Rfc2136Provider {
+    /// Server as configured (`host[:port]`), resolved for each request
+    server: String,
+
+    /// Zone of all records, normalized (None = discovered per record)
+    zone: Option<String>,
+
+    /// TSIG key (None = unsigned messages)
+    key: Option<TsigKey>,
+
+    /// TTL of the records written
+    ttl: u32,
+
+    /// Timeout for one exchange with the server
+    timeout: Duration,
+
+    /// Dry-run mode: if true, send queries but skip updates
+    dry_run: bool,
+}
+
+impl Rfc2136Provider {
+    /// Start building a provider for `server` (`host[:port]`)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_core::config::TsigAlgorithm;
+    /// use ddns_provider_rfc2136::Rfc2136Provider;
+    ///
+    /// let provider = Rfc2136Provider::builder("ns1.example.com")
+    ///     .zone("example.com")
+    ///     .tsig("ddns-key", TsigAlgorithm::HmacSha256, "c2VjcmV0")
+    ///     .ttl(60)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(server: impl Into<String>) -> Rfc2136ProviderBuilder {
+        Rfc2136ProviderBuilder::new(server)
+    }
+
+    /// Resolve the configured server
+    async fn server_address(&self) -> Result<SocketAddr> {
+        if let Ok(address) = self.server.parse::<SocketAddr>() {
+            return Ok(address);
+        }
+        if let Ok(ip) = self.server.parse::<IpAddr>() {
+            return Ok(SocketAddr::new(ip, DEFAULT_PORT));
+        }
+
+        let host = if self.server.contains(':') {
+            self.server.clone()
+        } else {
+            format!("{}:{}", self.server, DEFAULT_PORT)
+        };
+        tokio::net::lookup_host(&host)
+            .await
+            .map_err(|e| {
+                Error::provider(
+                    "rfc2136",
+                    format!("Failed to resolve {}: {}", self.server, e),
+                )
+            })?
+            .next()
+            .ok_or_else(|| Error::provider("rfc2136", format!("{} has no addresses", self.server)))
+    }
+
+    /// Sign `request` (if a key is configured), send it and verify the
+    /// response
+    async fn exchange(&self, mut request: Vec<u8>) -> Result<Response> {
+        let server = self.server_address().await?;
+
+        let request_mac = match &self.key {
+            Some(key) => Some(key.sign(&mut request, tsig::now())?),
+            None => None,
+        };
+
+        let raw = transport::exchange(server, &request, self.timeout).await?;
+        let response = Response::parse(&raw)?;
+        if Some(response.id) != message::id_of(&request) {
+            return Err(Error::provider(
+                "rfc2136",
+                format!("Response from {} does not match the request ID", server),
+            ));
+        }
+
+        if let (Some(key), Some(request_mac)) = (&self.key, request_mac) {
+            key.verify(&raw, &response, &request_mac, tsig::now())?;
+        }
+
+        Ok(response)
+    }
+
+    /// Query the `record_type` RRset of `record_name`
+    ///
+    /// Returns an empty RRset if the name or RRset does not exist.
+    async fn lookup(&self, record_name: &str, record_type: u16) -> Result<Vec<Record>> {
+        let request = message::query(message::message_id()?, record_name, record_type)?;
+        let response = self.exchange(request).await?;
+
+        match response.rcode {
+            RCODE_NOERROR | RCODE_NXDOMAIN => {}
+            rcode => return Err(rcode_error(rcode, "Query", record_name)),
+        }
+
+        let owner = normalize_name(record_name);
+        Ok(response
+            .answers
+            .into_iter()
+            .filter(|record| record.name == owner && record.record_type == record_type)
+            .collect())
+    }
+
+    /// Get the zone of a record name
+    ///
+    /// Resolution order:
+    /// 1. The configured zone, which must contain the name
+    /// 2. The owner of the SOA record returned for a SOA query for the name
+    ///    (in the answer for a zone apex, in the authority section
+    ///    otherwise), trying parent names if the server returns none
+    async fn zone_for(&self, record_name: &str) -> Result<String> {
+        let name = normalize_name(record_name);
+
+        if let Some(zone) = &self.zone {
+            if relative_name(&name, zone).is_none() {
+                return Err(Error::config(format!(
+                    "{} is not in zone {}",
+                    record_name, zone
+                )));
+            }
+            return Ok(zone.clone());
+        }
+
+        let mut candidate = name.as_str();
+        loop {
+            let request = message::query(message::message_id()?, candidate, TYPE_SOA)?;
+            let response = self.exchange(request).await?;
+
+            match response.rcode {
+                RCODE_NOERROR | RCODE_NXDOMAIN => {}
+                rcode => return Err(rcode_error(rcode, "SOA query", candidate)),
+            }
+
+            let soa = response
+                .answers
+                .iter()
+                .chain(&response.authority)
+                .find(|record| {
+                    record.record_type == TYPE_SOA && relative_name(&name, &record.name).is_some()
+                });
+            if let Some(soa) = soa {
+                tracing::debug!("Found zone for {}: {}", name, soa.name);
+                return Ok(soa.name.clone());
+            }
+
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => break,
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No zone found for {} on {}",
+            record_name, self.server
+        )))
+    }
+}
+
+/// Wire type code of the address record type for a family
+fn type_code(version: IpVersion) -> u16 {
+    match version {
+        IpVersion::V4 => TYPE_A,
+        IpVersion::V6 => TYPE_AAAA,
+    }
+}
+
+/// Error for a response code other than NOERROR
+fn rcode_error(rcode: u8, action: &str, name: &str) -> Error {
+    let rcode_name = message::rcode_name(rcode.into());
+    match rcode {
+        // REFUSED: the server's policy does not allow this (key or
+        // address not permitted, or the server does not serve the zone)
+        5 => Error::auth(format!(
+            "{} for {} refused by the server ({}); check its update policy for this key",
+            action, name, rcode_name
+        )),
+        // NOTAUTH: the server is not authoritative for the zone
+        9 => Error::auth(format!(
+            "{} for {} rejected: server is not authoritative for the zone ({})",
+            action, name, rcode_name
+        )),
+        // NOTZONE: the name is outside the zone of the update
+        10 => Error::config(format!(
+            "{} for {} rejected: name is not in the zone ({})",
+            action, name, rcode_name
+        )),
+        _ => Error::provider(
+            "rfc2136",
+            format!("{} for {} failed: {}", action, name, rcode_name),
+        ),
+    }
+}
+
+/// Builder for [`Rfc2136Provider`]
+pub struct Rfc2136ProviderBuilder {
+    server: String,
+    zone: Option<String>,
+    /// Key name, algorithm and base64 secret
+    tsig: Option<(String, TsigAlgorithm, String)>,
+    ttl: u32,
+    timeout: Duration,
+    dry_run: bool,
+}
+
+impl std::fmt::Debug for Rfc2136ProviderBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tsig = self
+            .tsig
+            .as_ref()
+            .map(|(name, algorithm, _)| (name, algorithm, "<REDACTED>"));
+        f.debug_struct("Rfc2136ProviderBuilder")
+            .field("server", &self.server)
+            .field("zone", &self.zone)
+            .field("tsig", &tsig)
+            .field("ttl", &self.ttl)
+            .field("timeout", &self.timeout)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl Rfc2136ProviderBuilder {
+    /// Create a builder for `server` (`host[:port]`)
+    pub fn new(server: impl Into<String>) -> Self {
+        Self {
+            server: server.into(),
+            zone: None,
+            tsig: None,
+            ttl: DEFAULT_TTL,
+            timeout: DEFAULT_TIMEOUT,
+            dry_run: false,
+        }
+    }
+
+    /// Zone of all records, instead of discovering it with a SOA query
+    pub fn zone(mut self, zone: impl Into<String>) -> Self {
+        self.zone = Some(zone.into());
+        self
+    }
+
+    /// Sign messages with a TSIG key (secret in base64)
+    pub fn tsig(
+        mut self,
+        key_name: impl Into<String>,
+        algorithm: TsigAlgorithm,
+        secret: impl Into<String>,
+    ) -> Self {
+        self.tsig = Some((key_name.into(), algorithm, secret.into()));
+        self
+    }
+
+    /// TTL of the records written (default: 300 seconds)
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Timeout for one exchange with the server (default: 10 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send queries but skip updates
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the server is empty or the zone is not a valid name
+    /// - `Error::Config` if the TSIG key name or secret is invalid
+    pub fn build(self) -> Result<Rfc2136Provider> {
+        let server = self.server.trim().to_string();
+        if server.is_empty() {
+            return Err(Error::config("RFC 2136 server cannot be empty"));
+        }
+
+        let zone = match self.zone {
+            Some(zone) => {
+                message::write_name(&mut Vec::new(), &zone)?;
+                Some(normalize_name(&zone))
+            }
+            None => None,
+        };
+
+        let key = match self.tsig {
+            Some((name, algorithm, secret)) => Some(TsigKey::new(&name, algorithm, &secret)?),
+            None => None,
+        };
+
+        Ok(Rfc2136Provider {
+            server,
+            zone,
+            key,
+            ttl: self.ttl,
+            timeout: self.timeout,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for Rfc2136Provider {
+    /// Replace the A or AAAA RRset of a name with the new address
+    ///
+    /// Queries the RRset first and only sends an UPDATE if it does not
+    /// already consist of exactly the new address.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
+        };
+        let (record_type, record_type_name) = (type_code(version), dns::record_type(version));
+
+        let current: Vec<IpAddr> = self
+            .lookup(record_name, record_type)
+            .await?
+            .iter()
+            .filter_map(Record::address)
+            .collect();
+
+        if current == [new_ip] {
+            tracing::info!(
+                "DNS record already has correct IP: {} -> {}",
+                record_name,
+                new_ip
+            );
+            return Ok(UpdateResult::Unchanged { current_ip: new_ip });
+        }
+
+        let zone = self.zone_for(record_name).await?;
+        let previous_ip = current.first().copied();
+        let result = match previous_ip {
+            Some(previous_ip) => UpdateResult::Updated {
+                previous_ip: Some(previous_ip),
+                new_ip,
+            },
+            None => UpdateResult::Created { new_ip },
+        };
+
+        tracing::info!(
+            "{} {} record: {} -> {} (was: {:?}, zone: {}) [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            record_type_name,
+            record_name,
+            new_ip,
+            current,
+            zone,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if self.dry_run {
+            return Ok(result);
+        }
+
+        let request =
+            message::replace_rrset(message::message_id()?, &zone, record_name, new_ip, self.ttl)?;
+        let response = self.exchange(request).await?;
+        if response.rcode != RCODE_NOERROR {
+            return Err(rcode_error(response.rcode, "Update", record_name));
+        }
+
+        tracing::info!(
+            "DNS record updated successfully: {} -> {}",
+            record_name,
+            new_ip
+        );
+        Ok(result)
+    }
+
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// If the RRset holds several addresses, the first one is returned and
+    /// all of them are listed in `extra.addresses`.
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let (record_type, record_type_name) = (type_code(version), dns::record_type(version));
+        let records = self.lookup(record_name, record_type).await?;
+
+        let addresses: Vec<IpAddr> = records.iter().filter_map(Record::address).collect();
+        let (Some(record), Some(ip)) = (records.first(), addresses.first()) else {
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type_name, record_name
+            )));
+        };
+
+        Ok(RecordMetadata {
+            id: format!("{}/{}", record.name, record_type_name),
+            name: record_name.to_string(),
+            ip: *ip,
+            ttl: Some(record.ttl),
+            extra: serde_json::json!({
+                "server": self.server,
+                "addresses": addresses,
+            }),
+        })
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        message::write_name(&mut Vec::new(), record_name).is_ok()
+            && record_name.contains('.')
+            && self
+                .zone
+                .as_ref()
+                .is_none_or(|zone| relative_name(&normalize_name(record_name), zone).is_some())
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "rfc2136"
+    }
+}
+
+/// Factory for creating RFC 2136 providers
+pub struct Rfc2136Factory;
+
+impl DnsProviderFactory for Rfc2136Factory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::Rfc2136 {
+                server,
+                zone,
+                tsig_key_name,
+                tsig_secret,
+                tsig_algorithm,
+                ttl,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "RFC 2136 provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = Rfc2136Provider::builder(server.clone())
+                    .ttl(*ttl)
+                    .dry_run(dry_run);
+
+                if let Some(zone) = zone {
+                    builder = builder.zone(zone.clone());
+                }
+                match (tsig_key_name, tsig_secret) {
+                    (Some(key_name), Some(secret)) => {
+                        builder = builder.tsig(key_name.clone(), *tsig_algorithm, secret.clone());
+                    }
+                    (None, None) => {
+                        tracing::warn!(
+                            "RFC 2136 provider has no TSIG key - updates are sent unsigned"
+                        );
+                    }
+                    _ => {
+                        return Err(Error::config(
+                            "RFC 2136 TSIG key name and secret must be set together",
+                        ));
+                    }
+                }
+
+                Ok(Box::new(builder.build()?))
+            }
+            _ => Err(Error::config("Invalid config for RFC 2136 provider")),
+        }
+    }
+}
+
+/// Register the RFC 2136 provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_rfc2136::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("rfc2136", Box::new(Rfc2136Factory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(tsig_key_name: Option<&str>, tsig_secret: Option<&str>) -> ProviderConfig {
+        ProviderConfig::Rfc2136 {
+            server: "127.0.0.1:5353".to_string(),
+            zone: Some("example.com".to_string()),
+            tsig_key_name: tsig_key_name.map(str::to_string),
+            tsig_secret: tsig_secret.map(str::to_string),
+            tsig_algorithm: TsigAlgorithm::HmacSha512,
+            ttl: 60,
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(
+            Rfc2136Factory
+                .create(&config(Some("ddns"), Some("c2VjcmV0")))
+                .is_ok()
+        );
+        assert!(Rfc2136Factory.create(&config(None, None)).is_ok());
+
+        assert!(Rfc2136Factory.create(&config(Some("ddns"), None)).is_err());
+        assert!(
+            Rfc2136Factory
+                .create(&config(Some("ddns"), Some("not base64!")))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(Rfc2136Provider::builder(" ").build().is_err());
+        assert!(
+            Rfc2136Provider::builder("ns1.example.com")
+                .zone("example..com")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_secret_not_exposed_in_debug() {
+        let provider = Rfc2136Provider::builder("ns1.example.com")
+            .tsig("ddns", TsigAlgorithm::HmacSha256, "c2VjcmV0LXZhbHVl")
+            .build()
+            .unwrap();
+
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("c2VjcmV0LXZhbHVl"));
+
+        let builder = Rfc2136Provider::builder("ns1.example.com").tsig(
+            "ddns",
+            TsigAlgorithm::HmacSha256,
+            "c2VjcmV0LXZhbHVl",
+        );
+        assert!(!format!("{:?}", builder).contains("c2VjcmV0LXZhbHVl"));
+    }
+
+    #[test]
+    fn test_supports_record() {
+        let provider = Rfc2136Provider::builder("ns1.example.com")
+            .zone("Example.com.")
+            .build()
+            .unwrap();
+
+        assert!(provider.supports_record("example.com"));
+        assert!(provider.supports_record("home.example.com."));
+        assert!(!provider.supports_record("home.example.net"));
+        assert!(!provider.supports_record("badexample.com"));
+        assert!(!provider.supports_record(""));
+        assert_eq!(provider.provider_name(), "rfc2136");
+    }
+
+    #[tokio::test]
+    async fn test_server_address() {
+        let provider = |server: &str| Rfc2136Provider::builder(server).build().unwrap();
+
+        assert_eq!(
+            provider("192.0.2.53").server_address().await.unwrap(),
+            "192.0.2.53:53".parse().unwrap()
+        );
+        assert_eq!(
+            provider("2001:db8::53").server_address().await.unwrap(),
+            "[2001:db8::53]:53".parse().unwrap()
+        );
+        assert_eq!(
+            provider("[2001:db8::53]:5353")
+                .server_address()
+                .await
+                .unwrap(),
+            "[2001:db8::53]:5353".parse().unwrap()
+        );
+        assert_eq!(
+            provider("localhost:5353")
+                .server_address()
+                .await
+                .unwrap()
+                .port(),
+            5353
+        );
+    }
+}
@@ -0,0 +1,441 @@
+// # DNS Messages
+//
+// Encoding of queries and UPDATE messages, and decoding of responses
+// (RFC 1035, section 4, and RFC 2136, section 2).
+//
+// ## Format
+//
+// ```text
+//                                 1  1  1  1  1  1
+//   0  1  2  3  4  5  6  7  8  9  0  1  2  3  4  5
+// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+// |                      ID                       |
+// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+// |QR|   Opcode  |AA|TC|RD|RA|   Z    |   RCODE   |
+// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+// |        QDCOUNT (UPDATE: ZOCOUNT)              |
+// |        ANCOUNT (UPDATE: PRCOUNT)              |
+// |        NSCOUNT (UPDATE: UPCOUNT)              |
+// |        ARCOUNT                                |
+// +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
+// ```
+//
+// Names are always written uncompressed. Compressed names in responses are
+// followed, but only backwards, so a malicious response cannot loop.
+
+use ddns_core::{Error, Result};
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Size of the message header
+pub(crate) const HEADER_LEN: usize = 12;
+
+pub(crate) const TYPE_A: u16 = 1;
+pub(crate) const TYPE_SOA: u16 = 6;
+pub(crate) const TYPE_AAAA: u16 = 28;
+pub(crate) const TYPE_TSIG: u16 = 250;
+
+pub(crate) const CLASS_IN: u16 = 1;
+pub(crate) const CLASS_ANY: u16 = 255;
+
+const OPCODE_QUERY: u16 = 0;
+const OPCODE_UPDATE: u16 = 5;
+
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_TRUNCATED: u16 = 0x0200;
+
+pub(crate) const RCODE_NOERROR: u8 = 0;
+pub(crate) const RCODE_NXDOMAIN: u8 = 3;
+
+/// Maximum length of a name in wire format
+const MAX_NAME_LEN: usize = 255;
+
+/// Generate a random message ID
+///
+/// Drawn from the operating system's CSPRNG, since a guessable ID lets an
+/// off-path attacker spoof responses to queries.
+pub(crate) fn message_id() -> Result<u16> {
+    let mut id = [0u8; 2];
+    getrandom::fill(&mut id).map_err(|e| {
+        Error::provider("rfc2136", format!("Failed to generate a message ID: {}", e))
+    })?;
+    Ok(u16::from_be_bytes(id))
+}
+
+/// Encode a query for `name` and `record_type`
+pub(crate) fn query(id: u16, name: &str, record_type: u16) -> Result<Vec<u8>> {
+    let mut message = header(id, OPCODE_QUERY, [1, 0, 0, 0]);
+    write_name(&mut message, name)?;
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(message)
+}
+
+/// Encode an UPDATE replacing the `record_type` RRset of `name` in `zone`
+/// with a single address
+///
+/// The update section deletes the RRset, then adds the new record
+/// (RFC 2136, section 2.5). Both happen atomically on the server, so the
+/// name is never without an address.
+pub(crate) fn replace_rrset(
+    id: u16,
+    zone: &str,
+    name: &str,
+    ip: IpAddr,
+    ttl: u32,
+) -> Result<Vec<u8>> {
+    let (record_type, rdata) = match ip {
+        IpAddr::V4(ip) => (TYPE_A, ip.octets().to_vec()),
+        IpAddr::V6(ip) => (TYPE_AAAA, ip.octets().to_vec()),
+    };
+
+    let mut message = header(id, OPCODE_UPDATE, [1, 0, 2, 0]);
+
+    // Zone section
+    write_name(&mut message, zone)?;
+    message.extend_from_slice(&TYPE_SOA.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    // Update section: delete the RRset (class ANY, TTL 0, no RDATA)
+    write_name(&mut message, name)?;
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&CLASS_ANY.to_be_bytes());
+    message.extend_from_slice(&0u32.to_be_bytes());
+    message.extend_from_slice(&0u16.to_be_bytes());
+
+    // Update section: add the record
+    write_name(&mut message, name)?;
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+    message.extend_from_slice(&ttl.to_be_bytes());
+    message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    message.extend_from_slice(&rdata);
+
+    Ok(message)
+}
+
+/// Encode a header with the given section counts
+fn header(id: u16, opcode: u16, counts: [u16; 4]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(512);
+    message.extend_from_slice(&id.to_be_bytes());
+    message.extend_from_slice(&(opcode << 11).to_be_bytes());
+    for count in counts {
+        message.extend_from_slice(&count.to_be_bytes());
+    }
+    message
+}
+
+/// Append `name` in uncompressed wire format
+///
+/// A trailing dot is optional; the root is written for an empty name.
+pub(crate) fn write_name(message: &mut Vec<u8>, name: &str) -> Result<()> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    let start = message.len();
+
+    if !name.is_empty() {
+        for label in name.split('.') {
+            if label.is_empty() || label.len() > 63 {
+                return Err(Error::config(format!("Invalid DNS name: '{}'", name)));
+            }
+            message.push(label.len() as u8);
+            message.extend_from_slice(label.as_bytes());
+        }
+    }
+    message.push(0);
+
+    if message.len() - start > MAX_NAME_LEN {
+        return Err(Error::config(format!("DNS name too long: '{}'", name)));
+    }
+    Ok(())
+}
+
+/// Whether the response has the TC bit set
+pub(crate) fn is_truncated(message: &[u8]) -> bool {
+    message.len() >= HEADER_LEN
+        && u16::from_be_bytes([message[2], message[3]]) & FLAG_TRUNCATED != 0
+}
+
+/// Message ID of an encoded message
+pub(crate) fn id_of(message: &[u8]) -> Option<u16> {
+    Some(u16::from_be_bytes([*message.first()?, *message.get(1)?]))
+}
+
+/// A resource record of a decoded response
+#[derive(Debug, Clone)]
+pub(crate) struct Record {
+    /// Owner name, normalized
+    pub name: String,
+    pub record_type: u16,
+    pub ttl: u32,
+    pub rdata: Vec<u8>,
+    /// Offset of the record in the message
+    pub offset: usize,
+}
+
+impl Record {
+    /// Address of an A or AAAA record
+    pub fn address(&self) -> Option<IpAddr> {
+        match (self.record_type, self.rdata.len()) {
+            (TYPE_A, 4) => {
+                let octets: [u8; 4] = self.rdata[..].try_into().ok()?;
+                Some(IpAddr::V4(Ipv4Addr::from(octets)))
+            }
+            (TYPE_AAAA, 16) => {
+                let octets: [u8; 16] = self.rdata[..].try_into().ok()?;
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A decoded response
+#[derive(Debug, Clone)]
+pub(crate) struct Response {
+    pub id: u16,
+    pub rcode: u8,
+    pub answers: Vec<Record>,
+    pub authority: Vec<Record>,
+    pub additional: Vec<Record>,
+}
+
+impl Response {
+    /// Decode a response
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Provider` for messages that are not responses or are
+    /// malformed.
+    pub fn parse(message: &[u8]) -> Result<Self> {
+        if message.len() < HEADER_LEN {
+            return Err(malformed("message shorter than its header"));
+        }
+
+        let word = |index: usize| u16::from_be_bytes([message[index], message[index + 1]]);
+        let flags = word(2);
+        if flags & FLAG_RESPONSE == 0 {
+            return Err(malformed("message is not a response"));
+        }
+
+        let mut position = HEADER_LEN;
+        for _ in 0..word(4) {
+            let (_, end) = read_name(message, position)?;
+            position = end + 4;
+        }
+
+        let mut sections = [Vec::new(), Vec::new(), Vec::new()];
+        for (section, count) in sections.iter_mut().zip([word(6), word(8), word(10)]) {
+            for _ in 0..count {
+                let (record, end) = read_record(message, position)?;
+                section.push(record);
+                position = end;
+            }
+        }
+        let [answers, authority, additional] = sections;
+
+        Ok(Self {
+            id: word(0),
+            rcode: (flags & 0x000f) as u8,
+            answers,
+            authority,
+            additional,
+        })
+    }
+}
+
+/// Decode the record at `position`, returning it and the offset after it
+fn read_record(message: &[u8], position: usize) -> Result<(Record, usize)> {
+    let (name, mut cursor) = read_name(message, position)?;
+    let fixed = message
+        .get(cursor..cursor + 10)
+        .ok_or_else(|| malformed("record truncated"))?;
+    let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+    let ttl = u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+    let rdlength = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+    cursor += 10;
+
+    let rdata = message
+        .get(cursor..cursor + rdlength)
+        .ok_or_else(|| malformed("record data truncated"))?;
+
+    Ok((
+        Record {
+            name,
+            record_type,
+            ttl,
+            rdata: rdata.to_vec(),
+            offset: position,
+        },
+        cursor + rdlength,
+    ))
+}
+
+/// Decode the name at `position`, returning it normalized and the offset
+/// after it
+pub(crate) fn read_name(message: &[u8], position: usize) -> Result<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut cursor = position;
+    // Offset after the name, known once the first pointer was followed
+    let mut end = None;
+    let mut length = 0;
+
+    loop {
+        let byte = *message
+            .get(cursor)
+            .ok_or_else(|| malformed("name truncated"))?;
+
+        match byte & 0xc0 {
+            0x00 if byte == 0 => {
+                let name = labels.join(".");
+                return Ok((name, end.unwrap_or(cursor + 1)));
+            }
+            0x00 => {
+                let label = message
+                    .get(cursor + 1..cursor + 1 + byte as usize)
+                    .ok_or_else(|| malformed("label truncated"))?;
+                length += label.len() + 1;
+                if length > MAX_NAME_LEN {
+                    return Err(malformed("name too long"));
+                }
+                labels.push(String::from_utf8_lossy(label).to_ascii_lowercase());
+                cursor += 1 + label.len();
+            }
+            0xc0 => {
+                let low = *message
+                    .get(cursor + 1)
+                    .ok_or_else(|| malformed("name truncated"))?;
+                let target = (((byte & 0x3f) as usize) << 8) | low as usize;
+                // Only follow pointers backwards, which rules out loops
+                if target >= cursor {
+                    return Err(malformed("forward compression pointer"));
+                }
+                end.get_or_insert(cursor + 2);
+                cursor = target;
+            }
+            _ => return Err(malformed("unsupported label type")),
+        }
+    }
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::provider("rfc2136", format!("Malformed DNS response: {}", reason))
+}
+
+/// Human-readable name of a response code (RFC 1035, RFC 2136, RFC 8945)
+pub(crate) fn rcode_name(rcode: u16) -> &'static str {
+    match rcode {
+        0 => "NOERROR",
+        1 => "FORMERR",
+        2 => "SERVFAIL",
+        3 => "NXDOMAIN",
+        4 => "NOTIMP",
+        5 => "REFUSED",
+        6 => "YXDOMAIN",
+        7 => "YXRRSET",
+        8 => "NXRRSET",
+        9 => "NOTAUTH",
+        10 => "NOTZONE",
+        16 => "BADSIG",
+        17 => "BADKEY",
+        18 => "BADTIME",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Turn an encoded request into a response with the given sections
+    fn respond(request: &[u8], rcode: u8, records: &[u8], answers: u16) -> Vec<u8> {
+        let mut response = request.to_vec();
+        response[2] |= 0x80;
+        response[3] = rcode;
+        response[6..8].copy_from_slice(&answers.to_be_bytes());
+        response.extend_from_slice(records);
+        response
+    }
+
+    #[test]
+    fn test_query_encoding() {
+        let message = query(0x1234, "home.example.com.", TYPE_AAAA).unwrap();
+        assert_eq!(&message[..4], &[0x12, 0x34, 0x00, 0x00]);
+        assert_eq!(&message[4..12], &[0, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            &message[12..],
+            b"\x04home\x07example\x03com\x00\x00\x1c\x00\x01"
+        );
+    }
+
+    #[test]
+    fn test_update_encoding() {
+        let message = replace_rrset(
+            7,
+            "example.com",
+            "home.example.com",
+            "203.0.113.5".parse().unwrap(),
+            300,
+        )
+        .unwrap();
+
+        // Opcode UPDATE, one zone, two updates
+        assert_eq!(message[2], 5 << 3);
+        assert_eq!(&message[4..12], &[0, 1, 0, 0, 0, 2, 0, 0]);
+
+        let zone = b"\x07example\x03com\x00\x00\x06\x00\x01";
+        let name = b"\x04home\x07example\x03com\x00";
+        let mut expected = zone.to_vec();
+        expected.extend_from_slice(name);
+        expected.extend_from_slice(&[0, 1, 0, 255, 0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(name);
+        expected.extend_from_slice(&[0, 1, 0, 1, 0, 0, 1, 44, 0, 4, 203, 0, 113, 5]);
+        assert_eq!(&message[12..], &expected[..]);
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert!(query(1, "a..example.com", TYPE_A).is_err());
+        assert!(query(1, &format!("{}.com", "a".repeat(64)), TYPE_A).is_err());
+        assert!(query(1, &vec!["abcdefgh"; 32].join("."), TYPE_A).is_err());
+    }
+
+    #[test]
+    fn test_parse_compressed_answer() {
+        let request = query(9, "Home.Example.com", TYPE_A).unwrap();
+        // Owner is a pointer to the question name
+        let record = [0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 203, 0, 113, 5];
+        let response = Response::parse(&respond(&request, 0, &record, 1)).unwrap();
+
+        assert_eq!(response.id, 9);
+        assert_eq!(response.rcode, RCODE_NOERROR);
+        let answer = &response.answers[0];
+        assert_eq!(answer.name, "home.example.com");
+        assert_eq!(answer.ttl, 60);
+        assert_eq!(answer.offset, request.len());
+        assert_eq!(answer.address(), Some("203.0.113.5".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        let request = query(9, "example.com", TYPE_A).unwrap();
+        assert!(Response::parse(&request).is_err());
+        assert!(Response::parse(&respond(&request, 0, &[], 0)[..11]).is_err());
+
+        // Answer count without an answer
+        assert!(Response::parse(&respond(&request, 0, &[], 1)).is_err());
+
+        // Pointer to itself
+        let record = [0xc0, request.len() as u8, 0, 1, 0, 1, 0, 0, 0, 60, 0, 0];
+        assert!(Response::parse(&respond(&request, 0, &record, 1)).is_err());
+    }
+
+    #[test]
+    fn test_truncated_flag() {
+        let request = query(9, "example.com", TYPE_A).unwrap();
+        let mut response = respond(&request, 0, &[], 0);
+        assert!(!is_truncated(&response));
+        response[2] |= 0x02;
+        assert!(is_truncated(&response));
+        assert_eq!(id_of(&response), Some(9));
+    }
+}
@@ -0,0 +1,88 @@
+// # Transport
+//
+// One request, one response: over UDP first, and over TCP if the UDP
+// response is truncated or the request does not fit a UDP datagram
+// (RFC 1035, section 4.2). There are no retransmissions; a lost datagram
+// is an error like any other and retried by the engine.
+
+use crate::message;
+
+use ddns_core::{Error, Result};
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Largest message sent over UDP without EDNS
+const MAX_UDP_REQUEST: usize = 512;
+
+/// Send `request` to `server` and return the response
+///
+/// # Errors
+///
+/// Returns `Error::Provider` if no response arrives within `timeout` or the
+/// server cannot be reached.
+pub(crate) async fn exchange(
+    server: SocketAddr,
+    request: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let exchange = async {
+        if request.len() <= MAX_UDP_REQUEST {
+            let response = exchange_udp(server, request).await?;
+            if !message::is_truncated(&response) {
+                return Ok(response);
+            }
+            tracing::debug!("Response from {} truncated, retrying over TCP", server);
+        }
+        exchange_tcp(server, request).await
+    };
+
+    tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| {
+            Error::provider(
+                "rfc2136",
+                format!("No response from {} within {:?}", server, timeout),
+            )
+        })?
+        .map_err(|e: std::io::Error| {
+            Error::provider("rfc2136", format!("Exchange with {} failed: {}", server, e))
+        })
+}
+
+async fn exchange_udp(server: SocketAddr, request: &[u8]) -> std::io::Result<Vec<u8>> {
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(request).await?;
+
+    let mut buffer = vec![0u8; 65535];
+    loop {
+        let len = socket.recv(&mut buffer).await?;
+        // Ignore stray datagrams (e.g. late answers to earlier requests)
+        if len >= message::HEADER_LEN && message::id_of(&buffer) == message::id_of(request) {
+            buffer.truncate(len);
+            return Ok(buffer);
+        }
+    }
+}
+
+async fn exchange_tcp(server: SocketAddr, request: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(server).await?;
+
+    // Messages are prefixed with their length
+    let mut framed = (request.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(request);
+    stream.write_all(&framed).await?;
+
+    let len = stream.read_u16().await? as usize;
+    let mut response = vec![0u8; len];
+    stream.read_exact(&mut response).await?;
+    Ok(response)
+}
@@ -0,0 +1,431 @@
+// # TSIG
+//
+// Signing of requests and verification of responses with a shared secret
+// (RFC 8945).
+//
+// The MAC of a request covers the message without the TSIG record, then
+// the "TSIG variables": key name, class, TTL, algorithm, time signed,
+// fudge, error and other data. The MAC of a response additionally starts
+// with the MAC of the request, which binds the response to the request.
+//
+// Only HMAC-SHA256 and HMAC-SHA512 are supported; HMAC-MD5 and HMAC-SHA1
+// are deprecated. Truncated MACs are not accepted.
+
+use crate::message::{self, CLASS_ANY, Response, TYPE_TSIG};
+
+use ddns_core::config::TsigAlgorithm;
+use ddns_core::dns::normalize_name;
+use ddns_core::{Error, Result};
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+/// Permitted clock skew between us and the server, in seconds
+const FUDGE: u16 = 300;
+
+/// A TSIG key
+#[derive(Clone)]
+pub(crate) struct TsigKey {
+    /// Key name, normalized
+    name: String,
+    algorithm: TsigAlgorithm,
+    /// ⚠️ NEVER log this value
+    secret: Vec<u8>,
+}
+
+// Custom Debug implementation that hides the secret
+impl std::fmt::Debug for TsigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TsigKey")
+            .field("name", &self.name)
+            .field("algorithm", &self.algorithm)
+            .field("secret", &"<REDACTED>")
+            .finish()
+    }
+}
+
+impl TsigKey {
+    /// Create a key from its name and base64-encoded secret
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the name is invalid or the secret is not
+    /// valid, non-empty base64.
+    pub fn new(name: &str, algorithm: TsigAlgorithm, secret: &str) -> Result<Self> {
+        // Checks the name
+        message::write_name(&mut Vec::new(), name)?;
+
+        let secret = base64::engine::general_purpose::STANDARD
+            .decode(secret.trim())
+            .map_err(|_| Error::config("TSIG secret is not valid base64"))?;
+        if secret.is_empty() {
+            return Err(Error::config("TSIG secret cannot be empty"));
+        }
+
+        Ok(Self {
+            name: normalize_name(name),
+            algorithm,
+            secret,
+        })
+    }
+
+    /// Sign `request`, appending a TSIG record
+    ///
+    /// Returns the MAC, which is needed to verify the response.
+    pub fn sign(&self, request: &mut Vec<u8>, time_signed: u64) -> Result<Vec<u8>> {
+        let mut data = request.clone();
+        self.write_variables(&mut data, time_signed, FUDGE, 0, &[])?;
+        let mac = self.mac(&[&data]);
+
+        let mut rdata = Vec::new();
+        message::write_name(&mut rdata, algorithm_name(self.algorithm))?;
+        rdata.extend_from_slice(&time_signed.to_be_bytes()[2..]);
+        rdata.extend_from_slice(&FUDGE.to_be_bytes());
+        rdata.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+        rdata.extend_from_slice(&mac);
+        // Original ID
+        rdata.extend_from_slice(&request[0..2]);
+        // Error, other len
+        rdata.extend_from_slice(&[0, 0, 0, 0]);
+
+        message::write_name(request, &self.name)?;
+        request.extend_from_slice(&TYPE_TSIG.to_be_bytes());
+        request.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        request.extend_from_slice(&0u32.to_be_bytes());
+        request.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        request.extend_from_slice(&rdata);
+
+        let additional = u16::from_be_bytes([request[10], request[11]]) + 1;
+        request[10..12].copy_from_slice(&additional.to_be_bytes());
+
+        Ok(mac)
+    }
+
+    /// Verify the TSIG record of a response to a request signed with
+    /// `request_mac`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Authentication` if the response is unsigned, signed
+    /// with another key, carries a wrong MAC, was signed too long ago, or
+    /// reports a TSIG error for the request.
+    pub fn verify(
+        &self,
+        raw: &[u8],
+        response: &Response,
+        request_mac: &[u8],
+        now: u64,
+    ) -> Result<()> {
+        let record = response
+            .additional
+            .last()
+            .filter(|record| record.record_type == TYPE_TSIG)
+            .ok_or_else(|| {
+                Error::auth(format!(
+                    "Server response is not signed ({})",
+                    message::rcode_name(response.rcode.into())
+                ))
+            })?;
+
+        if record.name != self.name {
+            return Err(Error::auth(format!(
+                "Server response is signed with another key: {}",
+                record.name
+            )));
+        }
+
+        let tsig = Tsig::parse(&record.rdata)?;
+        if tsig.error != 0 {
+            return Err(Error::auth(format!(
+                "Server rejected the TSIG signature: {}",
+                message::rcode_name(tsig.error)
+            )));
+        }
+        if tsig.algorithm != algorithm_name(self.algorithm) {
+            return Err(Error::auth(format!(
+                "Server response is signed with another algorithm: {}",
+                tsig.algorithm
+            )));
+        }
+
+        // The message as it was before the server added the TSIG record
+        let mut unsigned = raw[..record.offset].to_vec();
+        unsigned[0..2].copy_from_slice(&tsig.original_id.to_be_bytes());
+        let additional = u16::from_be_bytes([unsigned[10], unsigned[11]]).saturating_sub(1);
+        unsigned[10..12].copy_from_slice(&additional.to_be_bytes());
+
+        let mut variables = Vec::new();
+        self.write_variables(
+            &mut variables,
+            tsig.time_signed,
+            tsig.fudge,
+            tsig.error,
+            tsig.other,
+        )?;
+
+        let request_mac_len = (request_mac.len() as u16).to_be_bytes();
+        let mut mac = self.hmac();
+        for part in [&request_mac_len[..], request_mac, &unsigned, &variables] {
+            mac.update(part);
+        }
+        if mac.verify_slice(tsig.mac).is_err() {
+            return Err(Error::auth("Server response has an invalid TSIG signature"));
+        }
+
+        if now.abs_diff(tsig.time_signed) > tsig.fudge.into() {
+            return Err(Error::auth(format!(
+                "Server response was signed {}s away from the local time; check the clocks",
+                now.abs_diff(tsig.time_signed)
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Append the TSIG variables
+    fn write_variables(
+        &self,
+        data: &mut Vec<u8>,
+        time_signed: u64,
+        fudge: u16,
+        error: u16,
+        other: &[u8],
+    ) -> Result<()> {
+        message::write_name(data, &self.name)?;
+        data.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        message::write_name(data, algorithm_name(self.algorithm))?;
+        data.extend_from_slice(&time_signed.to_be_bytes()[2..]);
+        data.extend_from_slice(&fudge.to_be_bytes());
+        data.extend_from_slice(&error.to_be_bytes());
+        data.extend_from_slice(&(other.len() as u16).to_be_bytes());
+        data.extend_from_slice(other);
+        Ok(())
+    }
+
+    fn hmac(&self) -> Hmac256Or512 {
+        // HMAC accepts keys of any length
+        match self.algorithm {
+            TsigAlgorithm::HmacSha256 => Hmac256Or512::Sha256(
+                Hmac::new_from_slice(&self.secret).expect("HMAC accepts any key length"),
+            ),
+            TsigAlgorithm::HmacSha512 => Hmac256Or512::Sha512(
+                Hmac::new_from_slice(&self.secret).expect("HMAC accepts any key length"),
+            ),
+        }
+    }
+
+    fn mac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac = self.hmac();
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize()
+    }
+}
+
+/// HMAC state for either algorithm
+enum Hmac256Or512 {
+    Sha256(Hmac<Sha256>),
+    Sha512(Hmac<Sha512>),
+}
+
+impl Hmac256Or512 {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(mac) => mac.update(data),
+            Self::Sha512(mac) => mac.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(mac) => mac.finalize().into_bytes().to_vec(),
+            Self::Sha512(mac) => mac.finalize().into_bytes().to_vec(),
+        }
+    }
+
+    /// Compare with `expected` in constant time
+    fn verify_slice(self, expected: &[u8]) -> std::result::Result<(), hmac::digest::MacError> {
+        match self {
+            Self::Sha256(mac) => mac.verify_slice(expected),
+            Self::Sha512(mac) => mac.verify_slice(expected),
+        }
+    }
+}
+
+/// Name of an algorithm in TSIG records
+fn algorithm_name(algorithm: TsigAlgorithm) -> &'static str {
+    match algorithm {
+        TsigAlgorithm::HmacSha256 => "hmac-sha256",
+        TsigAlgorithm::HmacSha512 => "hmac-sha512",
+    }
+}
+
+/// Decoded TSIG RDATA
+struct Tsig<'a> {
+    /// Algorithm name, normalized
+    algorithm: String,
+    time_signed: u64,
+    fudge: u16,
+    mac: &'a [u8],
+    original_id: u16,
+    error: u16,
+    other: &'a [u8],
+}
+
+impl<'a> Tsig<'a> {
+    fn parse(rdata: &'a [u8]) -> Result<Self> {
+        let malformed = || Error::auth("Server response has a malformed TSIG record");
+
+        // The algorithm name is never compressed
+        let (algorithm, mut cursor) = message::read_name(rdata, 0).map_err(|_| malformed())?;
+        let mut take = |len: usize| {
+            let field = rdata.get(cursor..cursor + len).ok_or_else(malformed);
+            cursor += len;
+            field
+        };
+        let word = |field: &[u8]| u16::from_be_bytes([field[0], field[1]]);
+
+        let time = take(6)?;
+        let time_signed = time
+            .iter()
+            .fold(0u64, |time, byte| (time << 8) | *byte as u64);
+        let fudge = word(take(2)?);
+        let mac_len = word(take(2)?) as usize;
+        let mac = take(mac_len)?;
+        let original_id = word(take(2)?);
+        let error = word(take(2)?);
+        let other_len = word(take(2)?) as usize;
+        let other = take(other_len)?;
+
+        Ok(Self {
+            algorithm,
+            time_signed,
+            fudge,
+            mac,
+            original_id,
+            error,
+            other,
+        })
+    }
+}
+
+/// Seconds since the Unix epoch
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "c2VjcmV0LWtleS1mb3ItdGVzdHMtb25seQ==";
+
+    #[test]
+    fn test_invalid_keys() {
+        let algorithm = TsigAlgorithm::HmacSha256;
+        assert!(TsigKey::new("ddns.", algorithm, SECRET).is_ok());
+        assert!(TsigKey::new("ddns", algorithm, "not base64!").is_err());
+        assert!(TsigKey::new("ddns", algorithm, "").is_err());
+        assert!(TsigKey::new("ddns..key", algorithm, SECRET).is_err());
+    }
+
+    #[test]
+    fn test_secret_not_exposed_in_debug() {
+        let key = TsigKey::new("ddns", TsigAlgorithm::HmacSha256, SECRET).unwrap();
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains(SECRET));
+    }
+
+    #[test]
+    fn test_sign_appends_tsig_record() {
+        for (algorithm, mac_len) in [
+            (TsigAlgorithm::HmacSha256, 32),
+            (TsigAlgorithm::HmacSha512, 64),
+        ] {
+            let key = TsigKey::new("DDNS.Key.", algorithm, SECRET).unwrap();
+            let mut request = message::query(0x4242, "example.com", message::TYPE_SOA).unwrap();
+            let unsigned_len = request.len();
+
+            let mac = key.sign(&mut request, 1_700_000_000).unwrap();
+            assert_eq!(mac.len(), mac_len);
+            assert_eq!(&request[10..12], &[0, 1]);
+
+            // Key name is written normalized
+            assert_eq!(
+                &request[unsigned_len..unsigned_len + 12],
+                b"\x04ddns\x03key\x00\x00\xfa"
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_response() {
+        let key = TsigKey::new("ddns", TsigAlgorithm::HmacSha256, SECRET).unwrap();
+        let mut request = message::query(7, "example.com", message::TYPE_SOA).unwrap();
+        let request_mac = key.sign(&mut request, 1_700_000_000).unwrap();
+
+        // Response: the unsigned request with QR set, signed by the server
+        let mut unsigned = request[..29].to_vec();
+        unsigned[10..12].copy_from_slice(&[0, 0]);
+        unsigned[2] |= 0x80;
+
+        let mut variables = Vec::new();
+        key.write_variables(&mut variables, 1_700_000_010, FUDGE, 0, &[])
+            .unwrap();
+        let response_mac = key.mac(&[&[0, 32], &request_mac, &unsigned, &variables]);
+
+        let mut raw = unsigned.clone();
+        message::write_name(&mut raw, "ddns").unwrap();
+        raw.extend_from_slice(&[0, 250, 0, 255, 0, 0, 0, 0]);
+        let mut rdata = Vec::new();
+        message::write_name(&mut rdata, "hmac-sha256").unwrap();
+        rdata.extend_from_slice(&1_700_000_010u64.to_be_bytes()[2..]);
+        rdata.extend_from_slice(&FUDGE.to_be_bytes());
+        rdata.extend_from_slice(&[0, 32]);
+        rdata.extend_from_slice(&response_mac);
+        rdata.extend_from_slice(&[0, 7, 0, 0, 0, 0]);
+        raw.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        raw.extend_from_slice(&rdata);
+        raw[10..12].copy_from_slice(&[0, 1]);
+
+        let response = Response::parse(&raw).unwrap();
+        assert!(
+            key.verify(&raw, &response, &request_mac, 1_700_000_000)
+                .is_ok()
+        );
+
+        // Outside the fudge window
+        assert!(
+            key.verify(&raw, &response, &request_mac, 1_700_001_000)
+                .is_err()
+        );
+
+        // Bound to the request
+        let other_mac = vec![0u8; 32];
+        assert!(
+            key.verify(&raw, &response, &other_mac, 1_700_000_000)
+                .is_err()
+        );
+
+        // Another key
+        let other = TsigKey::new("ddns", TsigAlgorithm::HmacSha256, "b3RoZXI=").unwrap();
+        assert!(
+            other
+                .verify(&raw, &response, &request_mac, 1_700_000_000)
+                .is_err()
+        );
+
+        // Unsigned
+        let response = Response::parse(&unsigned).unwrap();
+        assert!(
+            key.verify(&unsigned, &response, &request_mac, 1_700_000_000)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,819 @@
+//! RFC 2136 provider against an in-process authoritative server
+//!
+//! The responder serves `example.com` on 127.0.0.1 over UDP and TCP,
+//! answers SOA/A/AAAA queries, applies UPDATE messages, and checks and
+//! adds TSIG signatures with its own implementation of RFC 8945.
+//!
+//! If this test fails, updates do not reach BIND/Knot-style servers, are
+//! applied although they should not be, or a rejected update is reported
+//! as a success.
+
+use ddns_core::Error;
+use ddns_core::config::TsigAlgorithm;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_rfc2136::{Rfc2136Provider, Rfc2136ProviderBuilder};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, UdpSocket};
+
+const ZONE: &str = "example.com";
+const KEY_NAME: &str = "ddns-key";
+const SECRET: &str = "c2hhcmVkLXNlY3JldC1mb3ItdGhlLXRlc3QtcmVzcG9uZGVy";
+
+const TYPE_A: u16 = 1;
+const TYPE_SOA: u16 = 6;
+const TYPE_AAAA: u16 = 28;
+const TYPE_TSIG: u16 = 250;
+const CLASS_IN: u16 = 1;
+const CLASS_ANY: u16 = 255;
+
+const NOERROR: u8 = 0;
+const NXDOMAIN: u8 = 3;
+const REFUSED: u8 = 5;
+const NOTAUTH: u8 = 9;
+const NOTZONE: u8 = 10;
+const BADSIG: u16 = 16;
+
+/// How the responder behaves
+#[derive(Clone, Copy)]
+struct Options {
+    /// TSIG key requests must be signed with (None = no TSIG)
+    key: Option<TsigAlgorithm>,
+    /// Sign responses to signed requests
+    sign_responses: bool,
+    /// Answer queries over UDP with truncated responses
+    truncate_udp: bool,
+    /// Refuse all updates
+    refuse_updates: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            key: Some(TsigAlgorithm::HmacSha256),
+            sign_responses: true,
+            truncate_udp: false,
+            refuse_updates: false,
+        }
+    }
+}
+
+/// RRsets by owner name and type, as (TTL, RDATA) pairs
+type RRsets = HashMap<(String, u16), Vec<(u32, Vec<u8>)>>;
+
+struct Responder {
+    address: SocketAddr,
+    options: Options,
+    rrsets: Mutex<RRsets>,
+    updates: AtomicUsize,
+    tcp_requests: AtomicUsize,
+}
+
+impl Responder {
+    async fn start(options: Options) -> Arc<Self> {
+        // The TCP side of the UDP port may be taken; try another port then
+        let (udp, tcp, address) = loop {
+            let udp = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let address = udp.local_addr().unwrap();
+            if let Ok(tcp) = TcpListener::bind(address).await {
+                break (udp, tcp, address);
+            }
+        };
+
+        let responder = Arc::new(Self {
+            address,
+            options,
+            rrsets: Mutex::new(HashMap::new()),
+            updates: AtomicUsize::new(0),
+            tcp_requests: AtomicUsize::new(0),
+        });
+
+        let udp_responder = responder.clone();
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 65535];
+            while let Ok((len, peer)) = udp.recv_from(&mut buffer).await {
+                let response = udp_responder.handle(&buffer[..len], false);
+                udp.send_to(&response, peer).await.unwrap();
+            }
+        });
+
+        let tcp_responder = responder.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = tcp.accept().await {
+                tcp_responder.tcp_requests.fetch_add(1, Ordering::SeqCst);
+                let len = stream.read_u16().await.unwrap() as usize;
+                let mut request = vec![0u8; len];
+                stream.read_exact(&mut request).await.unwrap();
+
+                let response = tcp_responder.handle(&request, true);
+                let mut framed = (response.len() as u16).to_be_bytes().to_vec();
+                framed.extend_from_slice(&response);
+                stream.write_all(&framed).await.unwrap();
+            }
+        });
+
+        responder
+    }
+
+    fn provider(&self) -> Rfc2136ProviderBuilder {
+        let builder = Rfc2136Provider::builder(self.address.to_string());
+        match self.options.key {
+            Some(algorithm) => builder.tsig(KEY_NAME, algorithm, SECRET),
+            None => builder,
+        }
+    }
+
+    fn insert(&self, name: &str, ip: IpAddr) {
+        let (record_type, rdata) = rdata(ip);
+        self.rrsets
+            .lock()
+            .unwrap()
+            .entry((name.to_string(), record_type))
+            .or_default()
+            .push((300, rdata));
+    }
+
+    fn addresses(&self, name: &str, record_type: u16) -> Vec<(u32, IpAddr)> {
+        self.rrsets
+            .lock()
+            .unwrap()
+            .get(&(name.to_string(), record_type))
+            .map(|rrset| {
+                rrset
+                    .iter()
+                    .map(|(ttl, rdata)| (*ttl, address(rdata)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn handle(&self, request: &[u8], over_tcp: bool) -> Vec<u8> {
+        let mut reader = Reader::new(request);
+        let id = reader.u16();
+        let opcode = (reader.u16() >> 11) & 0x0f;
+        let counts = [reader.u16(), reader.u16(), reader.u16(), reader.u16()];
+
+        // Question (query) or zone (update) section
+        let question_start = reader.position;
+        let question = reader.name();
+        let question_type = reader.u16();
+        let _class = reader.u16();
+        let question_end = reader.position;
+
+        // Update section
+        let mut updates = Vec::new();
+        for _ in 0..counts[2] {
+            let record = reader.record();
+            updates.push(record);
+        }
+
+        // TSIG
+        let tsig = (counts[3] == 1).then(|| {
+            let offset = reader.position;
+            (offset, reader.record())
+        });
+        let request_mac = match (self.options.key, tsig) {
+            (None, _) => None,
+            (Some(_), None) => return self.response(id, opcode, NOTAUTH, &[], &[], &[], None),
+            (Some(algorithm), Some((offset, record))) => {
+                match verify_request(algorithm, request, offset, &record) {
+                    Some(mac) => Some(mac),
+                    None => return unsigned_error(id, opcode, algorithm, BADSIG),
+                }
+            }
+        };
+
+        let question_bytes = &request[question_start..question_end];
+        let sign = request_mac
+            .as_deref()
+            .filter(|_| self.options.sign_responses);
+
+        if opcode == 5 {
+            let rcode = self.update(&question, &updates);
+            return self.response(id, opcode, rcode, question_bytes, &[], &[], sign);
+        }
+
+        if !over_tcp && self.options.truncate_udp {
+            let mut response = self.response(id, opcode, NOERROR, question_bytes, &[], &[], None);
+            response[2] |= 0x02;
+            return response;
+        }
+
+        let (rcode, answers, authority) = self.query(&question, question_type);
+        self.response(
+            id,
+            opcode,
+            rcode,
+            question_bytes,
+            &answers,
+            &authority,
+            sign,
+        )
+    }
+
+    /// Response code, answer records and authority records
+    fn query(&self, name: &str, record_type: u16) -> (u8, Vec<u8>, Vec<u8>) {
+        if name != ZONE && !name.ends_with(".example.com") {
+            return (REFUSED, Vec::new(), Vec::new());
+        }
+
+        let rcode = if name == ZONE || self.name_exists(name) {
+            NOERROR
+        } else {
+            NXDOMAIN
+        };
+
+        // The SOA record is the answer for the apex; below it, it is
+        // returned in the authority section of NODATA/NXDOMAIN responses
+        if name == ZONE && record_type == TYPE_SOA {
+            return (rcode, soa(&[0xc0, 12]), Vec::new());
+        }
+
+        let mut answers = Vec::new();
+        for (ttl, rdata) in self
+            .rrsets
+            .lock()
+            .unwrap()
+            .get(&(name.to_string(), record_type))
+            .into_iter()
+            .flatten()
+        {
+            answers.extend_from_slice(&[0xc0, 12]);
+            write_record(&mut answers, record_type, CLASS_IN, *ttl, rdata);
+        }
+
+        if answers.is_empty() {
+            let mut owner = Vec::new();
+            write_name(&mut owner, ZONE);
+            return (rcode, Vec::new(), soa(&owner));
+        }
+        (rcode, answers, Vec::new())
+    }
+
+    fn name_exists(&self, name: &str) -> bool {
+        self.rrsets
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|((owner, _), rrset)| owner == name && !rrset.is_empty())
+    }
+
+    fn update(&self, zone: &str, updates: &[ParsedRecord]) -> u8 {
+        if self.options.refuse_updates {
+            return REFUSED;
+        }
+        if zone != ZONE {
+            return NOTAUTH;
+        }
+        if updates
+            .iter()
+            .any(|record| record.name != ZONE && !record.name.ends_with(".example.com"))
+        {
+            return NOTZONE;
+        }
+
+        let mut rrsets = self.rrsets.lock().unwrap();
+        for record in updates {
+            let key = (record.name.clone(), record.record_type);
+            match record.class {
+                CLASS_ANY => {
+                    rrsets.remove(&key);
+                }
+                CLASS_IN => rrsets
+                    .entry(key)
+                    .or_default()
+                    .push((record.ttl, record.rdata.clone())),
+                _ => panic!("unexpected update class {}", record.class),
+            }
+        }
+        self.updates.fetch_add(1, Ordering::SeqCst);
+        NOERROR
+    }
+
+    /// Encode an authoritative response
+    #[allow(clippy::too_many_arguments)]
+    fn response(
+        &self,
+        id: u16,
+        opcode: u16,
+        rcode: u8,
+        question: &[u8],
+        answers: &[u8],
+        authority: &[u8],
+        request_mac: Option<&[u8]>,
+    ) -> Vec<u8> {
+        let mut response = id.to_be_bytes().to_vec();
+        response.extend_from_slice(&(0x8400 | (opcode << 11) | rcode as u16).to_be_bytes());
+        for count in [
+            (!question.is_empty()) as u16,
+            count_records(answers),
+            count_records(authority),
+            0,
+        ] {
+            response.extend_from_slice(&count.to_be_bytes());
+        }
+        response.extend_from_slice(question);
+        response.extend_from_slice(answers);
+        response.extend_from_slice(authority);
+
+        if let (Some(request_mac), Some(algorithm)) = (request_mac, self.options.key) {
+            sign_response(algorithm, &mut response, request_mac);
+        }
+        response
+    }
+}
+
+/// A record of a request
+#[derive(Clone)]
+struct ParsedRecord {
+    name: String,
+    record_type: u16,
+    class: u16,
+    ttl: u32,
+    rdata: Vec<u8>,
+}
+
+/// Reader for requests, whose names are never compressed
+struct Reader<'a> {
+    message: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(message: &'a [u8]) -> Self {
+        Self {
+            message,
+            position: 0,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let bytes = &self.message[self.position..self.position + len];
+        self.position += len;
+        bytes
+    }
+
+    fn u16(&mut self) -> u16 {
+        let bytes = self.take(2);
+        u16::from_be_bytes([bytes[0], bytes[1]])
+    }
+
+    fn u32(&mut self) -> u32 {
+        let bytes = self.take(4);
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn name(&mut self) -> String {
+        let mut labels = Vec::new();
+        loop {
+            let len = self.take(1)[0] as usize;
+            assert!(len < 64, "requests must not use compression");
+            if len == 0 {
+                return labels.join(".");
+            }
+            labels.push(
+                String::from_utf8(self.take(len).to_vec())
+                    .unwrap()
+                    .to_lowercase(),
+            );
+        }
+    }
+
+    fn record(&mut self) -> ParsedRecord {
+        let name = self.name();
+        let record_type = self.u16();
+        let class = self.u16();
+        let ttl = self.u32();
+        let len = self.u16() as usize;
+        ParsedRecord {
+            name,
+            record_type,
+            class,
+            ttl,
+            rdata: self.take(len).to_vec(),
+        }
+    }
+}
+
+fn write_name(message: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+}
+
+fn write_record(message: &mut Vec<u8>, record_type: u16, class: u16, ttl: u32, rdata: &[u8]) {
+    message.extend_from_slice(&record_type.to_be_bytes());
+    message.extend_from_slice(&class.to_be_bytes());
+    message.extend_from_slice(&ttl.to_be_bytes());
+    message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+    message.extend_from_slice(rdata);
+}
+
+/// SOA record of the zone, with an encoded owner name
+fn soa(owner: &[u8]) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    write_name(&mut rdata, "ns1.example.com");
+    write_name(&mut rdata, "hostmaster.example.com");
+    for value in [1u32, 3600, 600, 86400, 300] {
+        rdata.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut record = owner.to_vec();
+    write_record(&mut record, TYPE_SOA, CLASS_IN, 300, &rdata);
+    record
+}
+
+/// Number of encoded records (owners are compression pointers or
+/// uncompressed names)
+fn count_records(mut records: &[u8]) -> u16 {
+    let mut count = 0;
+    while !records.is_empty() {
+        let owner_len = if records[0] == 0xc0 {
+            2
+        } else {
+            records.iter().position(|byte| *byte == 0).unwrap() + 1
+        };
+        let rdlength = u16::from_be_bytes([records[owner_len + 8], records[owner_len + 9]]);
+        records = &records[owner_len + 10 + rdlength as usize..];
+        count += 1;
+    }
+    count
+}
+
+fn rdata(ip: IpAddr) -> (u16, Vec<u8>) {
+    match ip {
+        IpAddr::V4(ip) => (TYPE_A, ip.octets().to_vec()),
+        IpAddr::V6(ip) => (TYPE_AAAA, ip.octets().to_vec()),
+    }
+}
+
+fn address(rdata: &[u8]) -> IpAddr {
+    match rdata.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(rdata).unwrap()),
+        _ => IpAddr::from(<[u8; 16]>::try_from(rdata).unwrap()),
+    }
+}
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn algorithm_name(algorithm: TsigAlgorithm) -> &'static str {
+    match algorithm {
+        TsigAlgorithm::HmacSha256 => "hmac-sha256",
+        TsigAlgorithm::HmacSha512 => "hmac-sha512",
+    }
+}
+
+fn mac(algorithm: TsigAlgorithm, data: &[u8]) -> Vec<u8> {
+    use base64::Engine;
+    let secret = base64::engine::general_purpose::STANDARD
+        .decode(SECRET)
+        .unwrap();
+    match algorithm {
+        TsigAlgorithm::HmacSha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(&secret).unwrap();
+            mac.update(data);
+            mac.finalize().into_bytes().to_vec()
+        }
+        TsigAlgorithm::HmacSha512 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(&secret).unwrap();
+            mac.update(data);
+            mac.finalize().into_bytes().to_vec()
+        }
+    }
+}
+
+/// TSIG variables (RFC 8945, section 4.3.3)
+fn variables(algorithm: TsigAlgorithm, time_signed: u64, error: u16) -> Vec<u8> {
+    let mut data = Vec::new();
+    write_name(&mut data, KEY_NAME);
+    data.extend_from_slice(&CLASS_ANY.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes());
+    write_name(&mut data, algorithm_name(algorithm));
+    data.extend_from_slice(&time_signed.to_be_bytes()[2..]);
+    data.extend_from_slice(&300u16.to_be_bytes());
+    data.extend_from_slice(&error.to_be_bytes());
+    data.extend_from_slice(&0u16.to_be_bytes());
+    data
+}
+
+/// Check the TSIG record at `offset`, returning the request MAC if valid
+fn verify_request(
+    algorithm: TsigAlgorithm,
+    request: &[u8],
+    offset: usize,
+    record: &ParsedRecord,
+) -> Option<Vec<u8>> {
+    if record.record_type != TYPE_TSIG || record.class != CLASS_ANY || record.name != KEY_NAME {
+        return None;
+    }
+
+    let mut rdata = Reader::new(&record.rdata);
+    if rdata.name() != algorithm_name(algorithm) {
+        return None;
+    }
+    let time = rdata.take(6);
+    let time_signed = time
+        .iter()
+        .fold(0u64, |time, byte| (time << 8) | *byte as u64);
+    let _fudge = rdata.u16();
+    let mac_len = rdata.u16() as usize;
+    let request_mac = rdata.take(mac_len).to_vec();
+    assert_eq!(rdata.take(2), &request[0..2], "original ID");
+
+    let mut unsigned = request[..offset].to_vec();
+    unsigned[10..12].copy_from_slice(&0u16.to_be_bytes());
+    unsigned.extend_from_slice(&variables(algorithm, time_signed, 0));
+
+    (mac(algorithm, &unsigned) == request_mac && now().abs_diff(time_signed) <= 300)
+        .then_some(request_mac)
+}
+
+fn tsig_record(
+    algorithm: TsigAlgorithm,
+    time_signed: u64,
+    mac: &[u8],
+    id: &[u8],
+    error: u16,
+) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    write_name(&mut rdata, algorithm_name(algorithm));
+    rdata.extend_from_slice(&time_signed.to_be_bytes()[2..]);
+    rdata.extend_from_slice(&300u16.to_be_bytes());
+    rdata.extend_from_slice(&(mac.len() as u16).to_be_bytes());
+    rdata.extend_from_slice(mac);
+    rdata.extend_from_slice(id);
+    rdata.extend_from_slice(&error.to_be_bytes());
+    rdata.extend_from_slice(&0u16.to_be_bytes());
+
+    let mut record = Vec::new();
+    write_name(&mut record, KEY_NAME);
+    write_record(&mut record, TYPE_TSIG, CLASS_ANY, 0, &rdata);
+    record
+}
+
+fn sign_response(algorithm: TsigAlgorithm, response: &mut Vec<u8>, request_mac: &[u8]) {
+    let time_signed = now();
+    let mut data = (request_mac.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(request_mac);
+    data.extend_from_slice(response);
+    data.extend_from_slice(&variables(algorithm, time_signed, 0));
+    let response_mac = mac(algorithm, &data);
+
+    let id = response[0..2].to_vec();
+    response.extend_from_slice(&tsig_record(algorithm, time_signed, &response_mac, &id, 0));
+    response[10..12].copy_from_slice(&1u16.to_be_bytes());
+}
+
+/// NOTAUTH response with an unsigned TSIG record carrying `error`
+fn unsigned_error(id: u16, opcode: u16, algorithm: TsigAlgorithm, error: u16) -> Vec<u8> {
+    let mut response = id.to_be_bytes().to_vec();
+    response.extend_from_slice(&(0x8000 | (opcode << 11) | NOTAUTH as u16).to_be_bytes());
+    response.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+    response.extend_from_slice(&tsig_record(
+        algorithm,
+        now(),
+        &[],
+        &id.to_be_bytes(),
+        error,
+    ));
+    response
+}
+
+#[tokio::test]
+async fn updates_create_and_replace_records() {
+    let responder = Responder::start(Options::default()).await;
+    let provider = responder.provider().zone(ZONE).ttl(60).build().unwrap();
+
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("203.0.113.5")
+        }
+    );
+    assert_eq!(
+        responder.addresses("home.example.com", TYPE_A),
+        vec![(60, ip("203.0.113.5"))]
+    );
+
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.6"))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some(ip("203.0.113.5")),
+            new_ip: ip("203.0.113.6")
+        }
+    );
+    // The RRset was replaced, not extended
+    assert_eq!(
+        responder.addresses("home.example.com", TYPE_A),
+        vec![(60, ip("203.0.113.6"))]
+    );
+
+    // IPv6 is a separate RRset
+    provider
+        .update_record("home.example.com", ip("2001:db8::6"))
+        .await
+        .unwrap();
+    assert_eq!(responder.addresses("home.example.com", TYPE_A).len(), 1);
+    assert_eq!(responder.updates.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn unchanged_records_are_not_updated() {
+    let responder = Responder::start(Options::default()).await;
+    responder.insert("home.example.com", ip("203.0.113.5"));
+    let provider = responder.provider().zone(ZONE).build().unwrap();
+
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        UpdateResult::Unchanged {
+            current_ip: ip("203.0.113.5")
+        }
+    );
+    assert_eq!(responder.updates.load(Ordering::SeqCst), 0);
+
+    // An RRset with a second address is converged to the single address
+    responder.insert("home.example.com", ip("203.0.113.9"));
+    provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    assert_eq!(
+        responder.addresses("home.example.com", TYPE_A),
+        vec![(300, ip("203.0.113.5"))]
+    );
+}
+
+#[tokio::test]
+async fn get_record_reads_the_rrset() {
+    let responder = Responder::start(Options {
+        key: Some(TsigAlgorithm::HmacSha512),
+        ..Options::default()
+    })
+    .await;
+    responder.insert("home.example.com", ip("2001:db8::5"));
+    let provider = responder.provider().build().unwrap();
+
+    // No A record, so the AAAA record is returned
+    let record = provider.get_record("Home.Example.com.").await.unwrap();
+    assert_eq!(record.ip, ip("2001:db8::5"));
+    assert_eq!(record.ttl, Some(300));
+
+    let err = provider
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound(_)));
+    let err = provider
+        .get_record("missing.example.com")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound(_)));
+}
+
+#[tokio::test]
+async fn zone_is_discovered_with_a_soa_query() {
+    let responder = Responder::start(Options::default()).await;
+    let provider = responder.provider().build().unwrap();
+
+    provider
+        .update_record("a.b.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    provider
+        .update_record("example.com", ip("203.0.113.7"))
+        .await
+        .unwrap();
+
+    assert_eq!(responder.addresses("a.b.example.com", TYPE_A).len(), 1);
+    assert_eq!(responder.addresses("example.com", TYPE_A).len(), 1);
+
+    // A name the server is not authoritative for
+    let err = provider
+        .update_record("home.example.net", ip("203.0.113.5"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Authentication(_)), "{}", err);
+}
+
+#[tokio::test]
+async fn truncated_responses_are_retried_over_tcp() {
+    let responder = Responder::start(Options {
+        truncate_udp: true,
+        ..Options::default()
+    })
+    .await;
+    responder.insert("home.example.com", ip("203.0.113.5"));
+    let provider = responder.provider().build().unwrap();
+
+    let record = provider.get_record("home.example.com").await.unwrap();
+    assert_eq!(record.ip, ip("203.0.113.5"));
+    assert_eq!(responder.tcp_requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn wrong_key_is_rejected() {
+    let responder = Responder::start(Options::default()).await;
+    let provider = Rfc2136Provider::builder(responder.address.to_string())
+        .zone(ZONE)
+        .tsig(KEY_NAME, TsigAlgorithm::HmacSha256, "d3Jvbmctc2VjcmV0")
+        .build()
+        .unwrap();
+
+    let err = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Authentication(_)), "{}", err);
+    assert!(err.to_string().contains("BADSIG"), "{}", err);
+    assert_eq!(responder.updates.load(Ordering::SeqCst), 0);
+
+    // Unsigned messages are rejected as well
+    let provider = Rfc2136Provider::builder(responder.address.to_string())
+        .zone(ZONE)
+        .build()
+        .unwrap();
+    assert!(
+        provider
+            .update_record("home.example.com", ip("203.0.113.5"))
+            .await
+            .is_err()
+    );
+    assert_eq!(responder.updates.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn unsigned_responses_are_rejected() {
+    let responder = Responder::start(Options {
+        sign_responses: false,
+        ..Options::default()
+    })
+    .await;
+    let provider = responder.provider().zone(ZONE).build().unwrap();
+
+    let err = provider.get_record("home.example.com").await.unwrap_err();
+    assert!(matches!(err, Error::Authentication(_)), "{}", err);
+}
+
+#[tokio::test]
+async fn refused_updates_are_errors() {
+    let responder = Responder::start(Options {
+        key: None,
+        refuse_updates: true,
+        ..Options::default()
+    })
+    .await;
+    let provider = responder.provider().zone(ZONE).build().unwrap();
+
+    let err = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Authentication(_)), "{}", err);
+    assert!(err.to_string().contains("REFUSED"), "{}", err);
+}
+
+#[tokio::test]
+async fn dry_run_sends_no_updates() {
+    let responder = Responder::start(Options::default()).await;
+    let provider = responder.provider().dry_run(true).build().unwrap();
+
+    let result = provider
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("203.0.113.5")
+        }
+    );
+    assert_eq!(responder.updates.load(Ordering::SeqCst), 0);
+}
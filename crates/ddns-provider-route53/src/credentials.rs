@@ -0,0 +1,241 @@
+// # Credentials
+//
+// AWS credentials come from the provider configuration or, if none are
+// configured, from the first source of the standard chain that has them:
+//
+// 1. `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`
+// 2. The shared credentials file (`AWS_SHARED_CREDENTIALS_FILE`, or
+//    `~/.aws/credentials`), profile from the configuration, `AWS_PROFILE`
+//    or "default"
+//
+// Instance metadata, SSO and `credential_process` are not supported.
+//
+// The chain is resolved for each request, so rotated credentials (e.g. a
+// credentials file rewritten by another tool) are picked up without a
+// restart.
+
+use ddns_core::{Error, Result};
+
+use std::path::PathBuf;
+
+/// Profile used if none is configured or set in `AWS_PROFILE`
+const DEFAULT_PROFILE: &str = "default";
+
+/// AWS access key and secret, with a session token for temporary
+/// credentials
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the secret
+/// access key or session token.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct Credentials {
+    pub(crate) access_key_id: String,
+    pub(crate) secret_access_key: String,
+    pub(crate) session_token: Option<String>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<REDACTED>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<REDACTED>"),
+            )
+            .finish()
+    }
+}
+
+/// Where the provider gets its credentials from
+#[derive(Debug, Clone)]
+pub(crate) enum CredentialSource {
+    /// Credentials from the provider configuration
+    Static(Credentials),
+    /// The standard chain (environment, then shared credentials file)
+    Chain {
+        /// Profile in the shared credentials file
+        profile: Option<String>,
+    },
+}
+
+impl CredentialSource {
+    /// Get the current credentials
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Auth` if no source in the chain has credentials
+    /// - `Error::Config` if the shared credentials file cannot be read
+    pub(crate) fn resolve(&self) -> Result<Credentials> {
+        let profile = match self {
+            CredentialSource::Static(credentials) => return Ok(credentials.clone()),
+            CredentialSource::Chain { profile } => profile,
+        };
+
+        if let Some(credentials) = from_env() {
+            return Ok(credentials);
+        }
+
+        let profile = profile
+            .clone()
+            .or_else(|| non_empty_var("AWS_PROFILE"))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        if let Some(path) = shared_credentials_file() {
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => {
+                    if let Some(credentials) = parse_credentials_file(&contents, &profile) {
+                        return Ok(credentials);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(Error::config(format!(
+                        "Failed to read AWS credentials file {}: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+            }
+        }
+
+        Err(Error::auth(format!(
+            "No AWS credentials found: set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY, \
+             or add profile \"{}\" to the shared credentials file",
+            profile
+        )))
+    }
+}
+
+/// Credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+/// `AWS_SESSION_TOKEN`
+fn from_env() -> Option<Credentials> {
+    Some(Credentials {
+        access_key_id: non_empty_var("AWS_ACCESS_KEY_ID")?,
+        secret_access_key: non_empty_var("AWS_SECRET_ACCESS_KEY")?,
+        session_token: non_empty_var("AWS_SESSION_TOKEN"),
+    })
+}
+
+/// Path of the shared credentials file
+fn shared_credentials_file() -> Option<PathBuf> {
+    if let Some(path) = non_empty_var("AWS_SHARED_CREDENTIALS_FILE") {
+        return Some(PathBuf::from(path));
+    }
+    non_empty_var("HOME").map(|home| PathBuf::from(home).join(".aws").join("credentials"))
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Credentials of `profile` in the contents of a shared credentials file
+///
+/// The file is INI-style: `[profile]` sections with `key = value` lines.
+/// Lines starting with `#` or `;` are comments.
+pub(crate) fn parse_credentials_file(contents: &str, profile: &str) -> Option<Credentials> {
+    let mut in_profile = false;
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_profile = section.trim() == profile;
+            continue;
+        }
+
+        if !in_profile {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "aws_access_key_id" => access_key_id = Some(value),
+            "aws_secret_access_key" => secret_access_key = Some(value),
+            "aws_session_token" => session_token = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(Credentials {
+        access_key_id: access_key_id.filter(|id| !id.is_empty())?,
+        secret_access_key: secret_access_key.filter(|secret| !secret.is_empty())?,
+        session_token: session_token.filter(|token| !token.is_empty()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+# Managed by hand
+[default]
+aws_access_key_id = AKIDDEFAULT
+aws_secret_access_key = default-secret
+
+[ddns]
+aws_access_key_id=AKIDDDNS
+aws_secret_access_key=ddns-secret
+aws_session_token = ddns-token
+region = eu-west-1
+
+[incomplete]
+aws_access_key_id = AKIDINCOMPLETE
+";
+
+    #[test]
+    fn test_parse_credentials_file() {
+        assert_eq!(
+            parse_credentials_file(FILE, "default"),
+            Some(Credentials {
+                access_key_id: "AKIDDEFAULT".to_string(),
+                secret_access_key: "default-secret".to_string(),
+                session_token: None,
+            })
+        );
+        assert_eq!(
+            parse_credentials_file(FILE, "ddns"),
+            Some(Credentials {
+                access_key_id: "AKIDDDNS".to_string(),
+                secret_access_key: "ddns-secret".to_string(),
+                session_token: Some("ddns-token".to_string()),
+            })
+        );
+        assert_eq!(parse_credentials_file(FILE, "incomplete"), None);
+        assert_eq!(parse_credentials_file(FILE, "missing"), None);
+    }
+
+    #[test]
+    fn test_static_source() {
+        let credentials = Credentials {
+            access_key_id: "AKID".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        };
+        let source = CredentialSource::Static(credentials.clone());
+        assert_eq!(source.resolve().unwrap(), credentials);
+    }
+
+    #[test]
+    fn test_secrets_not_exposed_in_debug() {
+        let credentials = Credentials {
+            access_key_id: "AKID".to_string(),
+            secret_access_key: "secret-value".to_string(),
+            session_token: Some("token-value".to_string()),
+        };
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("AKID"));
+        assert!(!debug.contains("secret-value"));
+        assert!(!debug.contains("token-value"));
+    }
+}
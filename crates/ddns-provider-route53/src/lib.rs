@@ -0,0 +1,1039 @@
+// # Route 53 DNS Provider
+//
+// This crate provides a DNS provider that updates records in AWS Route 53
+// hosted zones through the Route 53 REST API.
+//
+// ## Implementation Status
+//
+// - ✅ Hosted zone from configuration, or looked up by name (longest
+//   matching suffix, public zones preferred over private ones)
+// - ✅ One request to read the current record set, one UPSERT if it differs
+// - ✅ Missing records are created by the same UPSERT
+// - ✅ Optional wait until the change is INSYNC (`GetChange` polling,
+//   bounded by a timeout; a change still pending at the timeout is logged,
+//   since Route 53 has accepted it and will apply it)
+// - ✅ Signature Version 4 signing with static credentials, or credentials
+//   from the AWS environment variables or shared credentials file
+// - ✅ Throttling errors map to `Error::RateLimited`
+// - ✅ Configurable endpoint and injectable HTTP client (for offline tests)
+// - ✅ Dry-run mode (reads are sent, changes are not)
+// - ✅ Both A and AAAA record support
+// - ❌ NO retry logic (owned by DdnsEngine)
+// - ❌ NO caching of DNS state or zones (state owned by StateStore)
+// - ❌ NO background tasks (violates shutdown determinism); waiting for
+//   INSYNC happens inside the `update_record` call
+// - ❌ NO alias records: an alias A/AAAA record is reported as an error
+//   rather than replaced
+//
+// ## Architectural Constraints (Per AI_CONTRACT.md)
+//
+// ### Trust Level: Untrusted (DNS Provider)
+//
+// Like every provider, this one is isolated, stateless and single-shot. It
+// only talks to the configured Route 53 endpoint.
+//
+// See `docs/architecture/TRUST_LEVELS.md` for complete trust level definitions.
+//
+// ## Security Requirements
+//
+// - The secret access key and session token NEVER appear in logs
+// - Credentials MUST be provided via environment variables (or the shared
+//   credentials file of the AWS tooling)
+// - The IAM policy only needs `route53:ListHostedZonesByName`,
+//   `route53:ListResourceRecordSets`, `route53:ChangeResourceRecordSets`
+//   and `route53:GetChange`
+//
+// ## API Reference
+//
+// - Route 53 API 2013-04-01: https://docs.aws.amazon.com/Route53/latest/APIReference/
+// - ListHostedZonesByName: GET `/2013-04-01/hostedzonesbyname?dnsname=...`
+//   (one request per suffix)
+// - ListResourceRecordSets: GET `/2013-04-01/hostedzone/:id/rrset?name=...&type=...`
+// - ChangeResourceRecordSets: POST `/2013-04-01/hostedzone/:id/rrset/`
+// - GetChange: GET `/2013-04-01/change/:id`
+// - Signature Version 4: https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv.html
+
+mod credentials;
+mod sigv4;
+mod xml;
+
+use async_trait::async_trait;
+use credentials::{CredentialSource, Credentials};
+use ddns_core::config::ProviderConfig;
+use ddns_core::dns::{normalize_name, record_type, zone_candidates};
+use ddns_core::traits::{DnsProvider, DnsProviderFactory, IpVersion, RecordMetadata, UpdateResult};
+use ddns_core::{Error, Result};
+use reqwest::{Method, StatusCode, Url};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Route 53 API endpoint
+pub const ROUTE53_ENDPOINT: &str = "https://route53.amazonaws.com";
+
+/// Path prefix of the API version used
+const API_VERSION: &str = "/2013-04-01";
+
+/// Default TTL of created records (5 minutes)
+const DEFAULT_TTL: u32 = 300;
+
+/// Default HTTP timeout for API requests (30 seconds)
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time to wait for a change to become INSYNC (2 minutes)
+const DEFAULT_SYNC_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Default interval between `GetChange` requests (5 seconds)
+const DEFAULT_SYNC_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Error codes Route 53 returns when requests are throttled
+const THROTTLING_CODES: &[&str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "PriorRequestNotComplete",
+    "RequestLimitExceeded",
+];
+
+/// AWS Route 53 DNS provider
+///
+/// # Trust Level: Untrusted
+///
+/// This provider is isolated, stateless, and single-shot. All coordination
+/// (retries, backoff, scheduling) is owned by `DdnsEngine`.
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the secret
+/// access key or session token.
+#[derive(Debug)]
+pub struct Route53Provider {
+    /// Where credentials come from, resolved for each request
+    credentials: CredentialSource,
+
+    /// Hosted zone of all records (None = looked up per record)
+    hosted_zone_id: Option<String>,
+
+    /// TTL of created records
+    ttl: u32,
+
+    /// Wait until changes are INSYNC before returning
+    wait_for_sync: bool,
+
+    /// Longest time to wait for a change to become INSYNC
+    sync_timeout: Duration,
+
+    /// Interval between `GetChange` requests
+    sync_poll_interval: Duration,
+
+    /// API endpoint without a trailing slash
+    endpoint: String,
+
+    /// HTTP client
+    client: reqwest::Client,
+
+    /// Dry-run mode: if true, send reads but skip changes
+    dry_run: bool,
+}
+
+/// Current state of an A or AAAA record set
+#[derive(Debug)]
+struct RecordSet {
+    /// Name as returned by Route 53 (with trailing dot)
+    name: String,
+    /// TTL (None for alias records)
+    ttl: Option<u32>,
+    /// Record values
+    values: Vec<String>,
+    /// Whether this is an alias record (no values of its own)
+    alias: bool,
+}
+
+impl RecordSet {
+    fn addresses(&self) -> Vec<IpAddr> {
+        self.values
+            .iter()
+            .filter_map(|value| value.parse().ok())
+            .collect()
+    }
+}
+
+impl Route53Provider {
+    /// Start building a provider
+    ///
+    /// Without [`credentials`](Route53ProviderBuilder::credentials), the
+    /// provider uses the AWS environment variables or shared credentials
+    /// file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ddns_provider_route53::Route53Provider;
+    ///
+    /// let provider = Route53Provider::builder()
+    ///     .credentials("AKIDEXAMPLE", "secret", None)
+    ///     .hosted_zone_id("Z0123456789ABCDEFGHIJ")
+    ///     .wait_for_sync(true)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> Route53ProviderBuilder {
+        Route53ProviderBuilder::new()
+    }
+
+    /// URL of an API path with query parameters
+    fn url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}{}{}", self.endpoint, API_VERSION, path))
+            .map_err(|e| Error::config(format!("Invalid Route 53 endpoint: {}", e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    /// Send a signed request and return the response body
+    ///
+    /// `action` names the API call in error messages.
+    async fn send(
+        &self,
+        action: &str,
+        method: Method,
+        url: Url,
+        body: Option<String>,
+    ) -> Result<String> {
+        let credentials = self.credentials.resolve()?;
+
+        let mut request = self.client.request(method, url);
+        if let Some(body) = body {
+            request = request.header("content-type", "application/xml").body(body);
+        }
+        let mut request = request.build().map_err(|e| {
+            Error::provider(
+                "route53",
+                format!("Failed to build {} request: {}", action, e),
+            )
+        })?;
+        sigv4::sign(
+            &mut request,
+            &credentials,
+            sigv4::REGION,
+            sigv4::SERVICE,
+            chrono::Utc::now(),
+        )?;
+
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|e| Error::provider("route53", format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            Error::provider(
+                "route53",
+                format!("Failed to read {} response: {}", action, e),
+            )
+        })?;
+
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(api_error(action, status, &text))
+        }
+    }
+
+    /// Get the hosted zone ID of a record name
+    ///
+    /// Resolution order:
+    /// 1. The configured hosted zone ID
+    /// 2. The hosted zone named like the longest suffix of the record name
+    ///    (`ListHostedZonesByName`, one request per suffix)
+    async fn hosted_zone_for(&self, record_name: &str) -> Result<String> {
+        if let Some(id) = &self.hosted_zone_id {
+            return Ok(id.clone());
+        }
+
+        let name = normalize(record_name);
+        for candidate in zone_candidates(&name) {
+            if let Some(id) = self.find_hosted_zone(&candidate).await? {
+                tracing::debug!("Found hosted zone for {}: {} ({})", name, candidate, id);
+                return Ok(id);
+            }
+        }
+
+        Err(Error::not_found(format!(
+            "No hosted zone found for {}",
+            record_name
+        )))
+    }
+
+    /// ID of the hosted zone named `zone`, preferring a public zone if
+    /// there are public and private zones of that name
+    async fn find_hosted_zone(&self, zone: &str) -> Result<Option<String>> {
+        let url = self.url(
+            "/hostedzonesbyname",
+            &[("dnsname", zone), ("maxitems", "10")],
+        )?;
+        let body = self
+            .send("ListHostedZonesByName", Method::GET, url, None)
+            .await?;
+
+        let mut zones: Vec<(bool, String)> = xml::elements(&body, "HostedZone")
+            .filter(|hosted_zone| {
+                xml::element_text(hosted_zone, "Name")
+                    .is_some_and(|zone_name| normalize(&zone_name) == zone)
+            })
+            .filter_map(|hosted_zone| {
+                let id = xml::element_text(hosted_zone, "Id")?;
+                let private = xml::element_text(hosted_zone, "PrivateZone")
+                    .is_some_and(|private| private == "true");
+                Some((private, id.trim_start_matches("/hostedzone/").to_string()))
+            })
+            .collect();
+        zones.sort_by_key(|(private, _)| *private);
+
+        Ok(zones.into_iter().next().map(|(_, id)| id))
+    }
+
+    /// Get the `record_type` record set of `record_name`
+    ///
+    /// Returns `None` if the record set does not exist.
+    async fn record_set(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+    ) -> Result<Option<RecordSet>> {
+        let name = normalize(record_name);
+        let url = self.url(
+            &format!("/hostedzone/{}/rrset", zone_id),
+            &[("name", &name), ("type", record_type), ("maxitems", "1")],
+        )?;
+        let body = self
+            .send("ListResourceRecordSets", Method::GET, url, None)
+            .await?;
+
+        // The list starts at the requested name and type but continues
+        // with the next record sets if it does not exist
+        let Some(record_set) = xml::elements(&body, "ResourceRecordSet").next() else {
+            return Ok(None);
+        };
+        let set_name = xml::element_text(record_set, "Name").unwrap_or_default();
+        let set_type = xml::element_text(record_set, "Type").unwrap_or_default();
+        if normalize(&set_name) != name || set_type != record_type {
+            return Ok(None);
+        }
+
+        Ok(Some(RecordSet {
+            name: set_name,
+            ttl: xml::element_text(record_set, "TTL").and_then(|ttl| ttl.parse().ok()),
+            values: xml::elements(record_set, "ResourceRecord")
+                .filter_map(|record| xml::element_text(record, "Value"))
+                .collect(),
+            alias: xml::element_text(record_set, "AliasTarget").is_some(),
+        }))
+    }
+
+    /// Replace a record set with a single address, creating it if needed
+    ///
+    /// Returns the change ID and status.
+    async fn upsert(
+        &self,
+        zone_id: &str,
+        record_name: &str,
+        record_type: &str,
+        ttl: u32,
+        ip: IpAddr,
+    ) -> Result<(String, String)> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ChangeResourceRecordSetsRequest xmlns="https://route53.amazonaws.com/doc/2013-04-01/">
+  <ChangeBatch>
+    <Comment>ddns</Comment>
+    <Changes>
+      <Change>
+        <Action>UPSERT</Action>
+        <ResourceRecordSet>
+          <Name>{}</Name>
+          <Type>{}</Type>
+          <TTL>{}</TTL>
+          <ResourceRecords>
+            <ResourceRecord>
+              <Value>{}</Value>
+            </ResourceRecord>
+          </ResourceRecords>
+        </ResourceRecordSet>
+      </Change>
+    </Changes>
+  </ChangeBatch>
+</ChangeResourceRecordSetsRequest>
+"#,
+            xml::escape(&normalize(record_name)),
+            record_type,
+            ttl,
+            ip
+        );
+
+        let url = self.url(&format!("/hostedzone/{}/rrset/", zone_id), &[])?;
+        let response = self
+            .send("ChangeResourceRecordSets", Method::POST, url, Some(body))
+            .await?;
+        change_info(&response)
+    }
+
+    /// Poll `GetChange` until the change is INSYNC
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Provider` if the change is still pending after the
+    /// sync timeout, or the error of a failed `GetChange`. The change itself
+    /// has been accepted and will still be applied.
+    async fn wait_until_in_sync(&self, change_id: &str, mut status: String) -> Result<()> {
+        let deadline = Instant::now() + self.sync_timeout;
+
+        while status != "INSYNC" {
+            if Instant::now() + self.sync_poll_interval > deadline {
+                return Err(Error::provider(
+                    "route53",
+                    format!(
+                        "Change {} not in sync after {:?} (status: {})",
+                        change_id, self.sync_timeout, status
+                    ),
+                ));
+            }
+            tokio::time::sleep(self.sync_poll_interval).await;
+
+            let url = self.url(&format!("/change/{}", change_id), &[])?;
+            let response = self.send("GetChange", Method::GET, url, None).await?;
+            (_, status) = change_info(&response)?;
+            tracing::debug!("Change {} status: {}", change_id, status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Change ID (without the `/change/` prefix) and status of a `ChangeInfo`
+fn change_info(response: &str) -> Result<(String, String)> {
+    let id = xml::element_text(response, "Id");
+    let status = xml::element_text(response, "Status");
+    match (id, status) {
+        (Some(id), Some(status)) => Ok((id.trim_start_matches("/change/").to_string(), status)),
+        _ => Err(Error::provider(
+            "route53",
+            "Response has no change ID or status",
+        )),
+    }
+}
+
+/// Map an error response onto an error kind
+fn api_error(action: &str, status: StatusCode, body: &str) -> Error {
+    let code = xml::element_text(body, "Code").unwrap_or_default();
+    let message = xml::element_text(body, "Message").unwrap_or_else(|| body.trim().to_string());
+
+    if status == StatusCode::TOO_MANY_REQUESTS || THROTTLING_CODES.contains(&code.as_str()) {
+        return Error::rate_limited(format!(
+            "Route 53 {} throttled: {} {}",
+            action, code, message
+        ));
+    }
+
+    match status.as_u16() {
+        401 | 403 => Error::auth(format!(
+            "Route 53 {} rejected: {} {}; check the credentials and their IAM permissions",
+            action, code, message
+        )),
+        404 => Error::not_found(format!("Route 53 {}: {} {}", action, code, message)),
+        500..=599 => Error::provider(
+            "route53",
+            format!(
+                "Route 53 server error (transient) in {}: {} - {} {}",
+                action, status, code, message
+            ),
+        ),
+        _ => Error::provider(
+            "route53",
+            format!("{} failed: {} - {} {}", action, status, code, message),
+        ),
+    }
+}
+
+/// Lowercase a name and remove its trailing dot
+///
+/// Route 53 returns `*` in names as the escape `\052`.
+fn normalize(name: &str) -> String {
+    normalize_name(name).replace("\\052", "*")
+}
+
+/// Builder for [`Route53Provider`]
+///
+/// # Security
+///
+/// The Debug implementation intentionally does NOT expose the secret
+/// access key or session token.
+#[derive(Debug)]
+pub struct Route53ProviderBuilder {
+    credentials: Option<Credentials>,
+    profile: Option<String>,
+    hosted_zone_id: Option<String>,
+    ttl: u32,
+    wait_for_sync: bool,
+    sync_timeout: Duration,
+    sync_poll_interval: Duration,
+    endpoint: Option<String>,
+    timeout: Option<Duration>,
+    client: Option<reqwest::Client>,
+    dry_run: bool,
+}
+
+impl Default for Route53ProviderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Route53ProviderBuilder {
+    /// Create a builder that uses the standard credential chain
+    pub fn new() -> Self {
+        Self {
+            credentials: None,
+            profile: None,
+            hosted_zone_id: None,
+            ttl: DEFAULT_TTL,
+            wait_for_sync: false,
+            sync_timeout: DEFAULT_SYNC_TIMEOUT,
+            sync_poll_interval: DEFAULT_SYNC_POLL_INTERVAL,
+            endpoint: None,
+            timeout: None,
+            client: None,
+            dry_run: false,
+        }
+    }
+
+    /// Use these credentials instead of the standard credential chain
+    pub fn credentials(
+        mut self,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+        session_token: Option<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token,
+        });
+        self
+    }
+
+    /// Profile of the shared credentials file (default: `AWS_PROFILE` or
+    /// "default")
+    ///
+    /// Ignored when credentials are set with
+    /// [`credentials`](Self::credentials).
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Use this hosted zone for all records instead of looking it up
+    ///
+    /// Both `Z0123...` and `/hostedzone/Z0123...` are accepted.
+    pub fn hosted_zone_id(mut self, hosted_zone_id: impl Into<String>) -> Self {
+        self.hosted_zone_id = Some(hosted_zone_id.into());
+        self
+    }
+
+    /// TTL of created records (default: 300 seconds)
+    ///
+    /// Existing records keep their TTL.
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Wait until a change is INSYNC before `update_record` returns
+    pub fn wait_for_sync(mut self, wait_for_sync: bool) -> Self {
+        self.wait_for_sync = wait_for_sync;
+        self
+    }
+
+    /// Longest time to wait for a change to become INSYNC (default: 2 minutes)
+    pub fn sync_timeout(mut self, timeout: Duration) -> Self {
+        self.sync_timeout = timeout;
+        self
+    }
+
+    /// Interval between `GetChange` requests while waiting (default: 5 seconds)
+    pub fn sync_poll_interval(mut self, interval: Duration) -> Self {
+        self.sync_poll_interval = interval;
+        self
+    }
+
+    /// Send API requests to this endpoint instead of the public Route 53 API
+    ///
+    /// A trailing slash is ignored.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// HTTP timeout for API requests (default: 30 seconds)
+    ///
+    /// Ignored when a client is supplied with [`client`](Self::client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Use a caller-supplied HTTP client
+    ///
+    /// The client is used as-is; its timeout, proxy and TLS settings apply.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send reads but skip changes
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the provider
+    ///
+    /// # Errors
+    ///
+    /// - `Error::Config` if the access key ID, secret or hosted zone ID is empty
+    /// - `Error::Config` if the endpoint is not an http(s) URL
+    /// - `Error::Provider` if the HTTP client cannot be built
+    pub fn build(self) -> Result<Route53Provider> {
+        let credentials = match self.credentials {
+            Some(credentials)
+                if credentials.access_key_id.is_empty()
+                    || credentials.secret_access_key.is_empty() =>
+            {
+                return Err(Error::config(
+                    "Route 53 access key ID and secret access key cannot be empty",
+                ));
+            }
+            Some(credentials) => CredentialSource::Static(credentials),
+            None => CredentialSource::Chain {
+                profile: self.profile,
+            },
+        };
+
+        let hosted_zone_id = match self.hosted_zone_id {
+            Some(id) => {
+                let id = id.trim().trim_start_matches("/hostedzone/").to_string();
+                if id.is_empty() {
+                    return Err(Error::config("Route 53 hosted zone ID cannot be empty"));
+                }
+                Some(id)
+            }
+            None => None,
+        };
+
+        let endpoint = match self.endpoint {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(url) => {
+                return Err(Error::config(format!(
+                    "Route 53 endpoint must start with http:// or https://, got {}",
+                    url
+                )));
+            }
+            None => ROUTE53_ENDPOINT.to_string(),
+        };
+
+        let client = match self.client {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout.unwrap_or(DEFAULT_HTTP_TIMEOUT))
+                .build()
+                .map_err(|e| {
+                    Error::provider("route53", format!("Failed to build HTTP client: {}", e))
+                })?,
+        };
+
+        Ok(Route53Provider {
+            credentials,
+            hosted_zone_id,
+            ttl: self.ttl,
+            wait_for_sync: self.wait_for_sync,
+            sync_timeout: self.sync_timeout,
+            sync_poll_interval: self.sync_poll_interval,
+            endpoint,
+            client,
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsProvider for Route53Provider {
+    /// Set the A or AAAA record set of a name to the new address
+    ///
+    /// Reads the record set first and only sends an UPSERT if it does not
+    /// already consist of exactly the new address. With `wait_for_sync`,
+    /// returns once Route 53 reports the change as INSYNC, or with a warning
+    /// once the sync timeout has passed: the UPSERT was accepted, so
+    /// reporting a failure would only make the engine send it again.
+    async fn update_record(&self, record_name: &str, new_ip: IpAddr) -> Result<UpdateResult> {
+        let version = match new_ip {
+            IpAddr::V4(_) => IpVersion::V4,
+            IpAddr::V6(_) => IpVersion::V6,
+        };
+        let record_type = record_type(version);
+
+        let zone_id = self.hosted_zone_for(record_name).await?;
+        let current = self.record_set(&zone_id, record_name, record_type).await?;
+
+        if let Some(current) = &current
+            && current.alias
+        {
+            return Err(Error::provider(
+                "route53",
+                format!(
+                    "{} {} is an alias record; not replacing it",
+                    record_name, record_type
+                ),
+            ));
+        }
+
+        let addresses = current
+            .as_ref()
+            .map(RecordSet::addresses)
+            .unwrap_or_default();
+        if addresses == [new_ip] {
+            tracing::info!(
+                "DNS record already has correct IP: {} -> {}",
+                record_name,
+                new_ip
+            );
+            return Ok(UpdateResult::Unchanged { current_ip: new_ip });
+        }
+
+        let result = match addresses.first() {
+            Some(previous_ip) => UpdateResult::Updated {
+                previous_ip: Some(*previous_ip),
+                new_ip,
+            },
+            None => UpdateResult::Created { new_ip },
+        };
+
+        tracing::info!(
+            "{} {} record: {} -> {} (was: {:?}, hosted zone: {}) [mode: {}]",
+            if self.dry_run {
+                "Would update"
+            } else {
+                "Updating"
+            },
+            record_type,
+            record_name,
+            new_ip,
+            addresses,
+            zone_id,
+            if self.dry_run { "DRY-RUN" } else { "LIVE" }
+        );
+
+        if self.dry_run {
+            return Ok(result);
+        }
+
+        let ttl = current
+            .as_ref()
+            .and_then(|current| current.ttl)
+            .unwrap_or(self.ttl);
+        let (change_id, status) = self
+            .upsert(&zone_id, record_name, record_type, ttl, new_ip)
+            .await?;
+
+        if self.wait_for_sync {
+            tracing::debug!("Waiting for change {} to be in sync", change_id);
+            if let Err(e) = self.wait_until_in_sync(&change_id, status).await {
+                tracing::warn!(
+                    "Change {} for {} was accepted but is not confirmed: {}",
+                    change_id,
+                    record_name,
+                    e
+                );
+            }
+        }
+
+        tracing::info!(
+            "DNS record updated successfully: {} -> {} (change: {})",
+            record_name,
+            new_ip,
+            change_id
+        );
+        Ok(result)
+    }
+
+    /// Get the A record of a name, or its AAAA record if it has no A record
+    async fn get_record(&self, record_name: &str) -> Result<RecordMetadata> {
+        match self.get_record_for(record_name, IpVersion::V4).await {
+            Err(Error::NotFound(_)) => self.get_record_for(record_name, IpVersion::V6).await,
+            result => result,
+        }
+    }
+
+    /// Get the A (IPv4) or AAAA (IPv6) record of a name
+    ///
+    /// If the record set holds several addresses, the first one is returned
+    /// and all of them are listed in `extra.values`.
+    async fn get_record_for(
+        &self,
+        record_name: &str,
+        version: IpVersion,
+    ) -> Result<RecordMetadata> {
+        let record_type = record_type(version);
+        let zone_id = self.hosted_zone_for(record_name).await?;
+        let record_set = self.record_set(&zone_id, record_name, record_type).await?;
+
+        let Some((record_set, ip)) = record_set.and_then(|record_set| {
+            let ip = *record_set.addresses().first()?;
+            Some((record_set, ip))
+        }) else {
+            return Err(Error::not_found(format!(
+                "No {} record found for {}",
+                record_type, record_name
+            )));
+        };
+
+        Ok(RecordMetadata {
+            id: format!("{}/{}/{}", zone_id, record_set.name, record_type),
+            name: record_name.to_string(),
+            ip,
+            ttl: record_set.ttl,
+            extra: serde_json::json!({
+                "hosted_zone_id": zone_id,
+                "values": record_set.values,
+            }),
+        })
+    }
+
+    fn supports_record(&self, record_name: &str) -> bool {
+        record_name.contains('.') && record_name.len() <= 253
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "route53"
+    }
+}
+
+/// Factory for creating Route 53 providers
+pub struct Route53Factory;
+
+impl DnsProviderFactory for Route53Factory {
+    fn create(&self, config: &ProviderConfig) -> Result<Box<dyn DnsProvider>> {
+        match config {
+            ProviderConfig::Route53 {
+                access_key_id,
+                secret_access_key,
+                session_token,
+                profile,
+                hosted_zone_id,
+                ttl,
+                wait_for_sync,
+                endpoint,
+            } => {
+                // Check for dry-run mode environment variable
+                let dry_run = std::env::var("DDNS_MODE")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    == "dry-run";
+
+                if dry_run {
+                    tracing::warn!(
+                        "Route 53 provider running in DRY-RUN mode - no changes will be made"
+                    );
+                }
+
+                let mut builder = Route53Provider::builder()
+                    .ttl(*ttl)
+                    .wait_for_sync(*wait_for_sync)
+                    .dry_run(dry_run);
+
+                match (access_key_id, secret_access_key) {
+                    (Some(access_key_id), Some(secret_access_key)) => {
+                        builder = builder.credentials(
+                            access_key_id.clone(),
+                            secret_access_key.clone(),
+                            session_token.clone(),
+                        );
+                    }
+                    (None, None) => {}
+                    _ => {
+                        return Err(Error::config(
+                            "Route 53 access key ID and secret access key must be set together",
+                        ));
+                    }
+                }
+                if let Some(profile) = profile {
+                    builder = builder.profile(profile.clone());
+                }
+                if let Some(hosted_zone_id) = hosted_zone_id {
+                    builder = builder.hosted_zone_id(hosted_zone_id.clone());
+                }
+                if let Some(endpoint) = endpoint {
+                    builder = builder.endpoint(endpoint.clone());
+                }
+
+                let provider = builder.build()?;
+
+                // Fail at startup rather than on the first update if the
+                // credential chain has nothing to offer
+                provider.credentials.resolve()?;
+
+                Ok(Box::new(provider))
+            }
+            _ => Err(Error::config("Invalid config for Route 53 provider")),
+        }
+    }
+}
+
+/// Register the Route 53 provider with a registry
+///
+/// # Example
+///
+/// ```rust
+/// use ddns_core::ProviderRegistry;
+///
+/// let registry = ProviderRegistry::new();
+/// ddns_provider_route53::register(&registry);
+/// ```
+pub fn register(registry: &ddns_core::ProviderRegistry) {
+    registry.register_provider("route53", Box::new(Route53Factory));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(access_key_id: Option<&str>, secret_access_key: Option<&str>) -> ProviderConfig {
+        ProviderConfig::Route53 {
+            access_key_id: access_key_id.map(str::to_string),
+            secret_access_key: secret_access_key.map(str::to_string),
+            session_token: None,
+            profile: None,
+            hosted_zone_id: Some("Z1".to_string()),
+            ttl: 60,
+            wait_for_sync: true,
+            endpoint: Some("http://127.0.0.1:8080".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_factory_creation() {
+        assert!(
+            Route53Factory
+                .create(&config(Some("AKID"), Some("secret")))
+                .is_ok()
+        );
+        assert!(Route53Factory.create(&config(Some("AKID"), None)).is_err());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        assert!(
+            Route53Provider::builder()
+                .credentials("", "secret", None)
+                .build()
+                .is_err()
+        );
+        assert!(
+            Route53Provider::builder()
+                .hosted_zone_id("/hostedzone/")
+                .build()
+                .is_err()
+        );
+        assert!(
+            Route53Provider::builder()
+                .endpoint("route53.amazonaws.com")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_builder_normalizes_input() {
+        let provider = Route53Provider::builder()
+            .hosted_zone_id("/hostedzone/Z1")
+            .endpoint("http://127.0.0.1:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(provider.hosted_zone_id.as_deref(), Some("Z1"));
+        assert_eq!(
+            provider.url("/change/C1", &[]).unwrap().as_str(),
+            "http://127.0.0.1:8080/2013-04-01/change/C1"
+        );
+    }
+
+    #[test]
+    fn test_secrets_not_exposed_in_debug() {
+        let builder =
+            Route53Provider::builder().credentials("AKID", "secret-value", Some("token".into()));
+        assert!(!format!("{:?}", builder).contains("secret-value"));
+
+        let provider = builder.build().unwrap();
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("secret-value"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Home.Example.com."), "home.example.com");
+        assert_eq!(normalize("\\052.example.com."), "*.example.com");
+    }
+
+    #[test]
+    fn test_api_error_mapping() {
+        let body = |code: &str| {
+            format!(
+                "<ErrorResponse><Error><Type>Sender</Type><Code>{}</Code><Message>msg</Message></Error></ErrorResponse>",
+                code
+            )
+        };
+
+        assert!(matches!(
+            api_error("GetChange", StatusCode::BAD_REQUEST, &body("Throttling")),
+            Error::RateLimited(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "ChangeResourceRecordSets",
+                StatusCode::BAD_REQUEST,
+                &body("PriorRequestNotComplete")
+            ),
+            Error::RateLimited(_)
+        ));
+        assert!(matches!(
+            api_error("GetChange", StatusCode::TOO_MANY_REQUESTS, ""),
+            Error::RateLimited(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "GetChange",
+                StatusCode::FORBIDDEN,
+                &body("SignatureDoesNotMatch")
+            ),
+            Error::Authentication(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "ListResourceRecordSets",
+                StatusCode::NOT_FOUND,
+                &body("NoSuchHostedZone")
+            ),
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            api_error(
+                "ChangeResourceRecordSets",
+                StatusCode::BAD_REQUEST,
+                &body("InvalidChangeBatch")
+            ),
+            Error::Provider { .. }
+        ));
+    }
+}
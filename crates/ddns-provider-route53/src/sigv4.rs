@@ -0,0 +1,262 @@
+// # Signature Version 4
+//
+// Every Route 53 request is signed with AWS Signature Version 4: a
+// canonical form of the request is hashed, and the hash is signed with a
+// key derived from the secret access key, the date, the region and the
+// service. The signature goes into the `Authorization` header together
+// with the access key ID and the names of the signed headers.
+//
+// Route 53 is a global service; its requests are signed for `us-east-1`.
+
+use crate::credentials::Credentials;
+
+use chrono::{DateTime, Utc};
+use ddns_core::{Error, Result};
+use hmac::{Hmac, Mac};
+use reqwest::Request;
+use reqwest::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+
+/// Region Route 53 requests are signed for
+pub(crate) const REGION: &str = "us-east-1";
+
+/// Service name in the credential scope
+pub(crate) const SERVICE: &str = "route53";
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Sign `request` for `region` and `service` at `time`
+///
+/// Adds the `x-amz-date`, `x-amz-security-token` (for temporary
+/// credentials) and `authorization` headers. All headers already on the
+/// request are signed, so headers must not be changed afterwards.
+pub(crate) fn sign(
+    request: &mut Request,
+    credentials: &Credentials,
+    region: &str,
+    service: &str,
+    time: DateTime<Utc>,
+) -> Result<()> {
+    let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = time.format("%Y%m%d").to_string();
+
+    insert_header(request, "x-amz-date", &amz_date)?;
+    if let Some(token) = &credentials.session_token {
+        insert_header(request, "x-amz-security-token", token)?;
+    }
+
+    let (canonical_request, signed_headers) = canonical_request(request)?;
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        amz_date,
+        scope,
+        hex_sha256(canonical_request.as_bytes())
+    );
+
+    let key = signing_key(&credentials.secret_access_key, &date, region, service);
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM, credentials.access_key_id, scope, signed_headers, signature
+    );
+    insert_header(request, "authorization", &authorization)
+}
+
+/// Canonical request and the list of signed headers
+fn canonical_request(request: &Request) -> Result<(String, String)> {
+    let url = request.url();
+
+    let path = match url.path() {
+        "" => "/",
+        path => path,
+    };
+
+    let mut query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (uri_encode(&key), uri_encode(&value)))
+        .collect();
+    query.sort();
+    let query = query
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    // The Host header is added by the HTTP client when the request is sent
+    // but must be signed, so it is derived from the URL here
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(Error::config(format!("URL has no host: {}", url))),
+    };
+
+    let mut headers = vec![("host".to_string(), host)];
+    for (name, value) in request.headers() {
+        let value = value
+            .to_str()
+            .map_err(|_| Error::config(format!("Header {} is not valid for signing", name)))?;
+        headers.push((
+            name.as_str().to_string(),
+            value.split_whitespace().collect::<Vec<_>>().join(" "),
+        ));
+    }
+    headers.sort();
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let payload = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default();
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method().as_str(),
+        path,
+        query,
+        canonical_headers,
+        signed_headers,
+        hex_sha256(payload)
+    );
+    Ok((canonical_request, signed_headers))
+}
+
+/// Derive the signing key for a date, region and service
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(
+        format!("AWS4{}", secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// Percent-encode everything except unreserved characters (RFC 3986)
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn insert_header(request: &mut Request, name: &'static str, value: &str) -> Result<()> {
+    let value = HeaderValue::from_str(value)
+        .map_err(|_| Error::config(format!("Invalid value for header {}", name)))?;
+    request
+        .headers_mut()
+        .insert(HeaderName::from_static(name), value);
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::Method;
+
+    fn credentials(session_token: Option<&str>) -> Credentials {
+        Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: session_token.map(str::to_string),
+        }
+    }
+
+    fn time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap()
+    }
+
+    /// The IAM `ListUsers` example from the AWS Signature Version 4
+    /// documentation
+    #[test]
+    fn test_sign_documented_example() {
+        let mut request = Request::new(
+            Method::GET,
+            "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08"
+                .parse()
+                .unwrap(),
+        );
+        request.headers_mut().insert(
+            "content-type",
+            HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+        );
+
+        sign(&mut request, &credentials(None), "us-east-1", "iam", time()).unwrap();
+
+        assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            request.headers()["authorization"],
+            "AWS4-HMAC-SHA256 \
+             Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    fn test_sign_with_session_token() {
+        let mut request = Request::new(
+            Method::POST,
+            "http://127.0.0.1:8080/2013-04-01/hostedzone/Z1/rrset/"
+                .parse()
+                .unwrap(),
+        );
+        *request.body_mut() = Some("<xml/>".into());
+
+        sign(
+            &mut request,
+            &credentials(Some("token")),
+            REGION,
+            SERVICE,
+            time(),
+        )
+        .unwrap();
+
+        assert_eq!(request.headers()["x-amz-security-token"], "token");
+        let authorization = request.headers()["authorization"].to_str().unwrap();
+        assert!(authorization.contains("/20150830/us-east-1/route53/aws4_request"));
+        assert!(authorization.contains("SignedHeaders=host;x-amz-date;x-amz-security-token,"));
+
+        let (canonical_request, _) = canonical_request(&request).unwrap();
+        assert!(canonical_request.contains("\nhost:127.0.0.1:8080\n"));
+        assert!(canonical_request.ends_with(&hex_sha256(b"<xml/>")));
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("home.example.com."), "home.example.com.");
+        assert_eq!(uri_encode("a b/c*~"), "a%20b%2Fc%2A~");
+    }
+}
@@ -0,0 +1,93 @@
+// # XML
+//
+// The Route 53 API speaks XML. The few elements the provider reads are
+// found by scanning for their tags rather than with a full parser; request
+// bodies are written with `format!` and escaped values.
+
+/// Contents of every `<name>` element
+pub(crate) fn elements<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        let (start, end) = find_element(rest, name)?;
+        let content = &rest[start..end];
+        rest = &rest[end..];
+        Some(content)
+    })
+}
+
+/// Trimmed, unescaped text of the first `<name>` element
+pub(crate) fn element_text(xml: &str, name: &str) -> Option<String> {
+    find_element(xml, name).map(|(start, end)| unescape(xml[start..end].trim()))
+}
+
+/// Byte range of the content of the first `<name>` element
+fn find_element(xml: &str, name: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    while let Some(position) = xml[offset..].find('<') {
+        let tag_start = offset + position + 1;
+        let tag_end = tag_start + xml[tag_start..].find('>')?;
+        let tag = &xml[tag_start..tag_end];
+        offset = tag_end;
+
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        if tag_name != name || tag.ends_with('/') {
+            continue;
+        }
+
+        // Elements of the same name are never nested in Route 53 responses
+        let content_start = tag_end + 1;
+        let closing = format!("</{}>", name);
+        let content_end = content_start + xml[content_start..].find(&closing)?;
+        return Some((content_start, content_end));
+    }
+    None
+}
+
+/// Escape text for use in element content
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Replace the predefined entities in element content
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements() {
+        let xml = r#"<?xml version="1.0"?>
+            <ResourceRecords>
+              <ResourceRecord><Value>192.0.2.1</Value></ResourceRecord>
+              <ResourceRecord><Value> 192.0.2.2 </Value></ResourceRecord>
+            </ResourceRecords>"#;
+
+        let values: Vec<_> = elements(xml, "ResourceRecord")
+            .filter_map(|record| element_text(record, "Value"))
+            .collect();
+        assert_eq!(values, ["192.0.2.1", "192.0.2.2"]);
+        assert_eq!(element_text(xml, "Missing"), None);
+    }
+
+    #[test]
+    fn test_element_with_attributes() {
+        let xml = r#"<ErrorResponse xmlns="https://route53.amazonaws.com/doc/2013-04-01/"><Error><Code>Throttling</Code></Error></ErrorResponse>"#;
+        assert_eq!(element_text(xml, "Code").as_deref(), Some("Throttling"));
+        assert!(element_text(xml, "ErrorResponse").is_some());
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b>&c"), "a&lt;b&gt;&amp;c");
+        assert_eq!(unescape("a&lt;b&gt;&amp;lt;"), "a<b>&lt;");
+    }
+}
@@ -0,0 +1,304 @@
+//! Route 53 API error mapping against a local stub server
+//!
+//! An update searches the hosted zone, reads the record set and sends an
+//! UPSERT. Each call is failed in turn while the calls before it succeed.
+//! Route 53 signals throttling with a 400 and an error code rather than a
+//! 429, so the codes are checked before the status. A rejected change batch
+//! comes back as an `InvalidChangeBatch` document without an error code,
+//! whose messages are passed on.
+
+mod common;
+
+use common::{api_error, change_info, hosted_zones, provider, record_sets};
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_test_support::{ApiCall, Expect, assert_maps_to, fail_at};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The zone apex, so that hosted zone search takes a single request
+const RECORD_NAME: &str = "example.com";
+
+/// The API calls made by `update_record`, in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Call {
+    /// GET /2013-04-01/hostedzonesbyname?dnsname=...
+    ListHostedZones,
+    /// GET /2013-04-01/hostedzone/:id/rrset?name=...&type=...
+    ListRecordSets,
+    /// POST /2013-04-01/hostedzone/:id/rrset/
+    ChangeRecordSets,
+}
+
+const CALLS: [Call; 3] = [
+    Call::ListHostedZones,
+    Call::ListRecordSets,
+    Call::ChangeRecordSets,
+];
+
+impl ApiCall for Call {
+    fn method(self) -> &'static str {
+        match self {
+            Call::ChangeRecordSets => "POST",
+            _ => "GET",
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Call::ListHostedZones => "/2013-04-01/hostedzonesbyname",
+            Call::ListRecordSets => "/2013-04-01/hostedzone/Z1/rrset",
+            Call::ChangeRecordSets => "/2013-04-01/hostedzone/Z1/rrset/",
+        }
+    }
+
+    fn success(self) -> ResponseTemplate {
+        match self {
+            Call::ListHostedZones => hosted_zones(&[("Z1", "example.com.", false)]),
+            Call::ListRecordSets => record_sets(&[("example.com.", "A", 300, &["198.51.100.1"])]),
+            Call::ChangeRecordSets => change_info("ChangeResourceRecordSetsResponse", "PENDING"),
+        }
+    }
+}
+
+/// Run `update_record` with `failing` answered by `response`
+async fn update_failing_at(
+    failing: Call,
+    response: ResponseTemplate,
+) -> ddns_core::Result<UpdateResult> {
+    let server = fail_at(&CALLS, failing, response).await;
+    provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+}
+
+#[tokio::test]
+async fn update_upserts_after_reading_the_record_set() {
+    let server = MockServer::start().await;
+    for call in CALLS {
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(call.success())
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let result = provider(&server)
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some("198.51.100.1".parse().unwrap()),
+            new_ip: "203.0.113.5".parse().unwrap(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn status_codes_map_to_error_kinds() {
+    for call in CALLS {
+        let cases = [
+            (
+                api_error(
+                    403,
+                    "SignatureDoesNotMatch",
+                    "The request signature we calculated does not match the signature you provided.",
+                ),
+                Expect::Authentication,
+            ),
+            (
+                api_error(
+                    403,
+                    "AccessDenied",
+                    "User: arn:aws:iam::123456789012:user/ddns is not authorized to perform: route53:ChangeResourceRecordSets",
+                ),
+                Expect::Authentication,
+            ),
+            (
+                api_error(404, "NoSuchHostedZone", "No hosted zone found with ID: Z1"),
+                Expect::NotFound,
+            ),
+            (ResponseTemplate::new(429), Expect::RateLimited),
+            (
+                api_error(500, "InternalFailure", "An internal error occurred."),
+                Expect::Provider("server error (transient)"),
+            ),
+            (
+                api_error(503, "ServiceUnavailable", "Service unavailable"),
+                Expect::Provider("server error (transient)"),
+            ),
+        ];
+        for (response, expect) in cases {
+            assert_maps_to(
+                update_failing_at(call, response).await,
+                "route53",
+                expect,
+                &format!("{:?} -> {:?}", call, expect),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn throttling_codes_on_a_400_are_rate_limited() {
+    for call in CALLS {
+        for (code, message) in [
+            ("Throttling", "Rate exceeded"),
+            ("ThrottlingException", "Rate exceeded"),
+            (
+                "PriorRequestNotComplete",
+                "The request was rejected because Route 53 was still processing a prior request.",
+            ),
+            ("RequestLimitExceeded", "Request limit exceeded."),
+        ] {
+            assert_maps_to(
+                update_failing_at(call, api_error(400, code, message)).await,
+                "route53",
+                Expect::RateLimited,
+                &format!("{:?} -> 400 {}", call, code),
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn rejected_change_batch_passes_on_its_messages() {
+    // Route 53 answers a change it cannot apply with its own document,
+    // which has no `Code`
+    let response = ResponseTemplate::new(400)
+        .insert_header("content-type", "text/xml")
+        .set_body_string(
+            "<?xml version=\"1.0\"?>\n\
+             <InvalidChangeBatch xmlns=\"https://route53.amazonaws.com/doc/2013-04-01/\">\
+             <Messages><Message>RRSet of type A with DNS name example.com. is not \
+             permitted because a conflicting RRSet of type CNAME with the same DNS name \
+             already exists in zone example.com.</Message></Messages>\
+             <RequestId>b25f48e8-84fd-11e6-80d9-574e0c4664cb</RequestId>\
+             </InvalidChangeBatch>",
+        );
+
+    assert_maps_to(
+        update_failing_at(Call::ChangeRecordSets, response).await,
+        "route53",
+        Expect::Provider("conflicting RRSet of type CNAME"),
+        "ChangeRecordSets -> InvalidChangeBatch",
+    );
+
+    // The same rejection also arrives as an `ErrorResponse` with a code
+    assert_maps_to(
+        update_failing_at(
+            Call::ChangeRecordSets,
+            api_error(
+                400,
+                "InvalidChangeBatch",
+                "[Tried to create resource record set [name='example.com.', type='A'] but it already exists]",
+            ),
+        )
+        .await,
+        "route53",
+        Expect::Provider("InvalidChangeBatch"),
+        "ChangeRecordSets -> 400 InvalidChangeBatch",
+    );
+}
+
+#[tokio::test]
+async fn invalid_input_includes_the_error_code() {
+    for call in CALLS {
+        assert_maps_to(
+            update_failing_at(
+                call,
+                api_error(400, "InvalidInput", "Invalid XML ; cvc-complex-type.2.4.a"),
+            )
+            .await,
+            "route53",
+            Expect::Provider("InvalidInput"),
+            &format!("{:?} -> 400 InvalidInput", call),
+        );
+    }
+}
+
+#[tokio::test]
+async fn change_response_without_change_info_fails_the_update() {
+    // The search and read responses are only scanned for elements; the
+    // change response must carry the change to report
+    assert_maps_to(
+        update_failing_at(
+            Call::ChangeRecordSets,
+            ResponseTemplate::new(200).set_body_string("<ChangeResourceRecordSetsResponse/>"),
+        )
+        .await,
+        "route53",
+        Expect::Provider("no change ID or status"),
+        "ChangeRecordSets -> empty response",
+    );
+}
+
+#[tokio::test]
+async fn get_record_maps_errors() {
+    for (response, expect) in [
+        (
+            api_error(403, "AccessDenied", "not authorized"),
+            Expect::Authentication,
+        ),
+        (
+            api_error(404, "NoSuchHostedZone", "No hosted zone found with ID: Z1"),
+            Expect::NotFound,
+        ),
+        (
+            api_error(400, "Throttling", "Rate exceeded"),
+            Expect::RateLimited,
+        ),
+        (
+            api_error(500, "InternalFailure", "An internal error occurred."),
+            Expect::Provider("server error (transient)"),
+        ),
+    ] {
+        let server = fail_at(&CALLS, Call::ListRecordSets, response).await;
+
+        let result = provider(&server)
+            .get_record_for(RECORD_NAME, IpVersion::V4)
+            .await;
+        assert_maps_to(
+            result,
+            "route53",
+            expect,
+            &format!("get_record -> {:?}", expect),
+        );
+    }
+}
+
+#[tokio::test]
+async fn slow_hosted_zone_search_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(Call::ListHostedZones.path()))
+        .respond_with(
+            Call::ListHostedZones
+                .success()
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = common::builder(&server)
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let result = provider
+        .update_record(RECORD_NAME, "203.0.113.5".parse().unwrap())
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_maps_to(
+        result,
+        "route53",
+        Expect::Provider("HTTP request failed"),
+        "ListHostedZones -> timeout",
+    );
+}
@@ -0,0 +1,88 @@
+//! Stub server fixtures shared by the Route 53 API tests
+
+#![allow(dead_code)]
+
+use ddns_provider_route53::{Route53Provider, Route53ProviderBuilder};
+use wiremock::{MockServer, ResponseTemplate};
+
+/// A builder with test credentials talking to the stub server
+pub fn builder(server: &MockServer) -> Route53ProviderBuilder {
+    Route53Provider::builder()
+        .credentials("AKIDEXAMPLE", "secret", None)
+        .endpoint(server.uri())
+}
+
+/// A provider with default settings talking to the stub server
+pub fn provider(server: &MockServer) -> Route53Provider {
+    builder(server).build().expect("provider builds")
+}
+
+/// A `ListHostedZonesByName` response with zones as (id, name, private)
+pub fn hosted_zones(zones: &[(&str, &str, bool)]) -> ResponseTemplate {
+    let zones: String = zones
+        .iter()
+        .map(|(id, name, private)| {
+            format!(
+                "<HostedZone><Id>/hostedzone/{}</Id><Name>{}</Name>\
+                 <Config><PrivateZone>{}</PrivateZone></Config></HostedZone>",
+                id, name, private
+            )
+        })
+        .collect();
+    xml(format!(
+        "<ListHostedZonesByNameResponse><HostedZones>{}</HostedZones>\
+         <IsTruncated>false</IsTruncated></ListHostedZonesByNameResponse>",
+        zones
+    ))
+}
+
+/// A `ListResourceRecordSets` response with sets as (name, type, ttl, values)
+pub fn record_sets(record_sets: &[(&str, &str, u32, &[&str])]) -> ResponseTemplate {
+    let record_sets: String = record_sets
+        .iter()
+        .map(|(name, record_type, ttl, values)| {
+            let values: String = values
+                .iter()
+                .map(|value| format!("<ResourceRecord><Value>{}</Value></ResourceRecord>", value))
+                .collect();
+            format!(
+                "<ResourceRecordSet><Name>{}</Name><Type>{}</Type><TTL>{}</TTL>\
+                 <ResourceRecords>{}</ResourceRecords></ResourceRecordSet>",
+                name, record_type, ttl, values
+            )
+        })
+        .collect();
+    xml(format!(
+        "<ListResourceRecordSetsResponse><ResourceRecordSets>{}</ResourceRecordSets>\
+         <IsTruncated>false</IsTruncated></ListResourceRecordSetsResponse>",
+        record_sets
+    ))
+}
+
+/// A `ChangeInfo` of change C1 with `status`, wrapped in `response`
+pub fn change_info(response: &str, status: &str) -> ResponseTemplate {
+    xml(format!(
+        "<{0}><ChangeInfo><Id>/change/C1</Id><Status>{1}</Status>\
+         <SubmittedAt>2026-01-01T00:00:00Z</SubmittedAt></ChangeInfo></{0}>",
+        response, status
+    ))
+}
+
+/// An `ErrorResponse` as sent by the Route 53 API for most failures
+pub fn api_error(status: u16, code: &str, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("content-type", "text/xml")
+        .set_body_string(format!(
+            "<?xml version=\"1.0\"?>\n\
+             <ErrorResponse xmlns=\"https://route53.amazonaws.com/doc/2013-04-01/\">\
+             <Error><Type>Sender</Type><Code>{}</Code><Message>{}</Message></Error>\
+             <RequestId>9e5b0c3d-2f0a-4c51-8c3e-2a1f0d6f7b11</RequestId></ErrorResponse>",
+            code, message
+        ))
+}
+
+fn xml(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/xml")
+        .set_body_string(body)
+}
@@ -0,0 +1,322 @@
+//! Route 53 record updates against a local stub server
+//!
+//! An update reads the record set of the name and type and, if its
+//! addresses differ, sends one signed UPSERT that keeps the set's TTL.
+//! Alias record sets are left alone. With `wait_for_sync`, `GetChange` is
+//! polled until the change is INSYNC; a change that is not confirmed in
+//! time still counts as done, since Route 53 has accepted it.
+
+mod common;
+
+use common::{change_info, record_sets};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion, UpdateResult};
+use ddns_provider_route53::Route53ProviderBuilder;
+use std::net::IpAddr;
+use std::time::Duration;
+use wiremock::matchers::{body_string_contains, header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RRSET_PATH: &str = "/2013-04-01/hostedzone/Z1/rrset";
+const CHANGE_PATH: &str = "/2013-04-01/hostedzone/Z1/rrset/";
+const GET_CHANGE_PATH: &str = "/2013-04-01/change/C1";
+
+/// Answer record set reads of `record_type` with sets as
+/// (name, type, ttl, values)
+async fn mock_record_sets(
+    server: &MockServer,
+    record_type: &str,
+    sets: &[(&str, &str, u32, &[&str])],
+) {
+    Mock::given(method("GET"))
+        .and(path(RRSET_PATH))
+        .and(query_param("type", record_type))
+        .respond_with(record_sets(sets))
+        .mount(server)
+        .await;
+}
+
+/// Expect `calls` UPSERTs whose body contains every fragment
+async fn expect_upsert(server: &MockServer, fragments: &[&str], calls: u64) {
+    let mut mock = Mock::given(method("POST"))
+        .and(path(CHANGE_PATH))
+        .and(header_exists("authorization"))
+        .and(header_exists("x-amz-date"))
+        .and(body_string_contains("<Action>UPSERT</Action>"));
+    for fragment in fragments {
+        mock = mock.and(body_string_contains(*fragment));
+    }
+    mock.respond_with(change_info("ChangeResourceRecordSetsResponse", "PENDING"))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+fn builder(server: &MockServer) -> Route53ProviderBuilder {
+    common::builder(server).hosted_zone_id("Z1")
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn changed_address_is_upserted_with_its_ttl() {
+    let server = MockServer::start().await;
+    mock_record_sets(
+        &server,
+        "AAAA",
+        &[("home.example.com.", "AAAA", 3600, &["2001:db8::1"])],
+    )
+    .await;
+    expect_upsert(
+        &server,
+        &[
+            "<Name>home.example.com</Name>",
+            "<Type>AAAA</Type>",
+            "<TTL>3600</TTL>",
+            "<Value>2001:db8::2</Value>",
+        ],
+        1,
+    )
+    .await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("Home.Example.com.", ip("2001:db8::2"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Updated {
+            previous_ip: Some(ip("2001:db8::1")),
+            new_ip: ip("2001:db8::2"),
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_record_is_created_with_the_configured_ttl() {
+    let server = MockServer::start().await;
+    // The listing continues with the next record set if the requested one
+    // is missing
+    mock_record_sets(
+        &server,
+        "A",
+        &[("www.example.com.", "A", 60, &["192.0.2.9"])],
+    )
+    .await;
+    expect_upsert(
+        &server,
+        &["<Name>home.example.com</Name>", "<TTL>120</TTL>"],
+        1,
+    )
+    .await;
+
+    let result = builder(&server)
+        .ttl(120)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Created {
+            new_ip: ip("203.0.113.5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn escaped_wildcard_names_match() {
+    // Route 53 returns `*` in names as `\052`
+    let server = MockServer::start().await;
+    mock_record_sets(
+        &server,
+        "A",
+        &[("\\052.example.com.", "A", 300, &["203.0.113.5"])],
+    )
+    .await;
+    expect_upsert(&server, &[], 0).await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("*.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Unchanged { .. }));
+}
+
+#[tokio::test]
+async fn unchanged_address_sends_no_change() {
+    let server = MockServer::start().await;
+    mock_record_sets(
+        &server,
+        "A",
+        &[("home.example.com.", "A", 300, &["203.0.113.5"])],
+    )
+    .await;
+    expect_upsert(&server, &[], 0).await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result,
+        UpdateResult::Unchanged {
+            current_ip: ip("203.0.113.5")
+        }
+    );
+}
+
+#[tokio::test]
+async fn alias_record_is_not_replaced() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(RRSET_PATH))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "<ListResourceRecordSetsResponse><ResourceRecordSets><ResourceRecordSet>\
+             <Name>home.example.com.</Name><Type>A</Type><AliasTarget>\
+             <HostedZoneId>Z2FDTNDATAQYW2</HostedZoneId>\
+             <DNSName>d111111abcdef8.cloudfront.net.</DNSName>\
+             <EvaluateTargetHealth>false</EvaluateTargetHealth></AliasTarget>\
+             </ResourceRecordSet></ResourceRecordSets></ListResourceRecordSetsResponse>",
+        ))
+        .mount(&server)
+        .await;
+    expect_upsert(&server, &[], 0).await;
+
+    let result = builder(&server)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await;
+
+    match result {
+        Err(Error::Provider { message, .. }) => {
+            assert!(message.contains("alias record"), "{}", message)
+        }
+        other => panic!("expected a provider error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn dry_run_sends_no_change() {
+    let server = MockServer::start().await;
+    mock_record_sets(&server, "A", &[]).await;
+    expect_upsert(&server, &[], 0).await;
+
+    let result = builder(&server)
+        .dry_run(true)
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+
+    assert!(matches!(result, UpdateResult::Created { .. }));
+}
+
+#[tokio::test]
+async fn waits_until_in_sync() {
+    let server = MockServer::start().await;
+    mock_record_sets(&server, "A", &[]).await;
+    expect_upsert(&server, &[], 1).await;
+    // Pending once, then in sync
+    Mock::given(method("GET"))
+        .and(path(GET_CHANGE_PATH))
+        .respond_with(change_info("GetChangeResponse", "PENDING"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(GET_CHANGE_PATH))
+        .respond_with(change_info("GetChangeResponse", "INSYNC"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    builder(&server)
+        .wait_for_sync(true)
+        .sync_poll_interval(Duration::from_millis(10))
+        .build()
+        .unwrap()
+        .update_record("home.example.com", ip("203.0.113.5"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unconfirmed_change_still_counts_as_done() {
+    for get_change in [
+        // Still pending at the sync timeout
+        change_info("GetChangeResponse", "PENDING"),
+        // GetChange itself failing
+        ResponseTemplate::new(503),
+    ] {
+        let server = MockServer::start().await;
+        mock_record_sets(&server, "A", &[]).await;
+        expect_upsert(&server, &[], 1).await;
+        Mock::given(method("GET"))
+            .and(path(GET_CHANGE_PATH))
+            .respond_with(get_change)
+            .mount(&server)
+            .await;
+
+        let result = builder(&server)
+            .wait_for_sync(true)
+            .sync_poll_interval(Duration::from_millis(10))
+            .sync_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap()
+            .update_record("home.example.com", ip("203.0.113.5"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            UpdateResult::Created {
+                new_ip: ip("203.0.113.5")
+            }
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_record_falls_back_to_aaaa() {
+    let server = MockServer::start().await;
+    mock_record_sets(&server, "A", &[]).await;
+    mock_record_sets(
+        &server,
+        "AAAA",
+        &[(
+            "home.example.com.",
+            "AAAA",
+            60,
+            &["2001:db8::1", "2001:db8::2"],
+        )],
+    )
+    .await;
+
+    let provider = builder(&server).build().unwrap();
+    let record = provider.get_record("home.example.com").await.unwrap();
+    assert_eq!(record.ip, ip("2001:db8::1"));
+    assert_eq!(record.ttl, Some(60));
+    assert_eq!(record.extra["values"][1], "2001:db8::2");
+
+    let result = provider
+        .get_record_for("home.example.com", IpVersion::V4)
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
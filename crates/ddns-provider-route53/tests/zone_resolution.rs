@@ -0,0 +1,139 @@
+//! Route 53 hosted zone resolution against a local stub server
+//!
+//! Without a configured hosted zone ID, `ListHostedZonesByName` is queried
+//! for each suffix of the record name, longest first. The listing starts at
+//! the requested name and continues with the next zones, so only exact
+//! names count. A public zone is preferred over a private zone of the same
+//! name.
+
+mod common;
+
+use common::{hosted_zones, provider, record_sets};
+use ddns_core::Error;
+use ddns_core::traits::{DnsProvider, IpVersion};
+use wiremock::matchers::{header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer};
+
+const ZONES_PATH: &str = "/2013-04-01/hostedzonesbyname";
+
+/// Answer searches starting at `dns_name` with hosted zones as
+/// (id, name, private), expecting `calls` of them
+async fn mock_hosted_zones(
+    server: &MockServer,
+    dns_name: &str,
+    zones: &[(&str, &str, bool)],
+    calls: u64,
+) {
+    Mock::given(method("GET"))
+        .and(path(ZONES_PATH))
+        .and(query_param("dnsname", dns_name))
+        .and(header_exists("authorization"))
+        .and(header_exists("x-amz-date"))
+        .respond_with(hosted_zones(zones))
+        .expect(calls)
+        .mount(server)
+        .await;
+}
+
+/// Answer A record set reads of `record_name` in hosted zone `zone_id`
+async fn mock_record_set(server: &MockServer, zone_id: &str, record_name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/2013-04-01/hostedzone/{}/rrset", zone_id)))
+        .respond_with(record_sets(&[(
+            &format!("{}.", record_name),
+            "A",
+            300,
+            &["203.0.113.5"],
+        )]))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn delegated_subzone_wins_over_parent() {
+    let server = MockServer::start().await;
+    // The listing continues past the missing name with the next zone
+    mock_hosted_zones(
+        &server,
+        "host.lab.example.com",
+        &[("Z9", "other.example.com.", false)],
+        1,
+    )
+    .await;
+    mock_hosted_zones(
+        &server,
+        "lab.example.com",
+        &[("Z2", "lab.example.com.", false)],
+        1,
+    )
+    .await;
+    mock_hosted_zones(&server, "example.com", &[("Z1", "example.com.", false)], 0).await;
+    mock_record_set(&server, "Z2", "host.lab.example.com").await;
+
+    let record = provider(&server)
+        .get_record_for("Host.Lab.Example.com.", IpVersion::V4)
+        .await
+        .unwrap();
+
+    assert_eq!(record.id, "Z2/host.lab.example.com./A");
+}
+
+#[tokio::test]
+async fn public_zone_wins_over_private_zone() {
+    let server = MockServer::start().await;
+    mock_hosted_zones(
+        &server,
+        "example.com",
+        &[
+            ("ZPRIVATE", "example.com.", true),
+            ("ZPUBLIC", "example.com.", false),
+        ],
+        1,
+    )
+    .await;
+    mock_record_set(&server, "ZPUBLIC", "example.com").await;
+
+    assert!(
+        provider(&server)
+            .get_record_for("example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn configured_hosted_zone_skips_search() {
+    let server = MockServer::start().await;
+    mock_hosted_zones(&server, "example.com", &[("Z2", "example.com.", false)], 0).await;
+    mock_record_set(&server, "Z1", "home.example.com").await;
+
+    let provider = common::builder(&server)
+        .hosted_zone_id("Z1")
+        .build()
+        .unwrap();
+
+    assert!(
+        provider
+            .get_record_for("home.example.com", IpVersion::V4)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn unknown_zone_is_not_found() {
+    let server = MockServer::start().await;
+    mock_hosted_zones(&server, "home.example.org", &[], 1).await;
+    mock_hosted_zones(&server, "example.org", &[("Z3", "example.pub.", false)], 1).await;
+
+    let result = provider(&server)
+        .get_record_for("home.example.org", IpVersion::V4)
+        .await;
+
+    match result {
+        Err(Error::NotFound(message)) => {
+            assert!(message.contains("home.example.org"), "{}", message)
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+}
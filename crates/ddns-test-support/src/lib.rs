@@ -0,0 +1,98 @@
+// # Provider Test Support
+//
+// Stub server helpers shared by the integration tests of the HTTP DNS
+// provider crates, which use it as a dev-dependency only.
+//
+// An update is a fixed sequence of API calls. To check how a failure maps
+// onto an error kind, a provider's tests describe that sequence with
+// [`ApiCall`], fail one call with [`fail_at`] while the calls before it
+// succeed, and check the result with [`assert_maps_to`]. The provider's
+// own failure modes (error bodies, throttling codes, pagination) stay in
+// the provider's tests.
+
+use ddns_core::{Error, Result};
+use std::fmt::Debug;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// One API call of a provider's update sequence
+pub trait ApiCall: Copy + PartialEq + Debug {
+    /// HTTP method of the call
+    fn method(self) -> &'static str;
+
+    /// Path of the call on the stub server
+    fn path(self) -> &'static str;
+
+    /// A successful response to the call
+    fn success(self) -> ResponseTemplate;
+}
+
+/// Start a stub server that answers `failing` with `response`
+///
+/// The calls before `failing` in `calls` are answered successfully, so the
+/// failure is attributed to the call under test. Calls after it are not
+/// answered at all.
+pub async fn fail_at<C: ApiCall>(
+    calls: &[C],
+    failing: C,
+    response: ResponseTemplate,
+) -> MockServer {
+    let server = MockServer::start().await;
+
+    for &call in calls {
+        let template = if call == failing {
+            response.clone()
+        } else {
+            call.success()
+        };
+        Mock::given(method(call.method()))
+            .and(path(call.path()))
+            .respond_with(template)
+            .mount(&server)
+            .await;
+        if call == failing {
+            break;
+        }
+    }
+
+    server
+}
+
+/// The error kind a failed call must map to
+#[derive(Debug, Clone, Copy)]
+pub enum Expect {
+    Authentication,
+    NotFound,
+    RateLimited,
+    /// A provider error whose message contains the given fragment
+    Provider(&'static str),
+}
+
+/// Assert that `result` failed with the error kind `expect`
+///
+/// Provider errors must be reported by `provider`. `context` names the
+/// failure in assertion messages.
+pub fn assert_maps_to<T: Debug>(result: Result<T>, provider: &str, expect: Expect, context: &str) {
+    match (result, expect) {
+        (Err(Error::Authentication(_)), Expect::Authentication)
+        | (Err(Error::NotFound(_)), Expect::NotFound)
+        | (Err(Error::RateLimited(_)), Expect::RateLimited) => {}
+        (
+            Err(Error::Provider {
+                provider: reported,
+                message,
+            }),
+            Expect::Provider(fragment),
+        ) => {
+            assert_eq!(reported, provider, "{}", context);
+            assert!(
+                message.contains(fragment),
+                "{}: expected '{}' in '{}'",
+                context,
+                fragment,
+                message
+            );
+        }
+        (result, expect) => panic!("{}: expected {:?}, got {:?}", context, expect, result),
+    }
+}
@@ -20,18 +20,118 @@
 // All configuration is done via environment variables:
 //
 // ### IP Source
-// - `DDNS_IP_SOURCE_TYPE`: Type of IP source (netlink, http)
+// - `DDNS_IP_SOURCE_TYPE`: Type of IP source (netlink, http, upnp, stun, exec, file,
+//   static, fallback)
 // - `DDNS_IP_SOURCE_INTERFACE`: Network interface (for netlink)
-// - `DDNS_IP_SOURCE_URL`: URL to fetch IP from (for http)
-// - `DDNS_IP_SOURCE_INTERVAL`: Poll interval in seconds (for http)
+// - `DDNS_IP_SOURCE_GLOBAL_ONLY`: Only publish global-scope, globally routable addresses (for netlink, default: true)
+// - `DDNS_IP_SOURCE_ALLOW_TEMPORARY`: Allow IPv6 temporary (privacy) addresses (for netlink, default: false)
+// - `DDNS_IP_SOURCE_ALLOW_DEPRECATED`: Allow deprecated IPv6 addresses (for netlink, default: false)
+// - `DDNS_IP_SOURCE_ALLOW_CIDRS`: Comma-separated CIDRs addresses must fall into (for netlink)
+// - `DDNS_IP_SOURCE_DENY_CIDRS`: Comma-separated CIDRs to exclude (for netlink)
+// - `DDNS_IP_SOURCE_PREFER_EUI64`: Prefer EUI-64 derived IPv6 addresses (for netlink, default: false)
+// - `DDNS_IP_SOURCE_INTERFACE_ID`: Preferred IPv6 interface identifier, e.g. `::1234` (for netlink)
+// - `DDNS_IP_SOURCE_URL`: Comma-separated URLs to fetch IP from (for http)
+// - `DDNS_IP_SOURCE_URL_V4`, `DDNS_IP_SOURCE_URL_V6`: Comma-separated URLs to
+//   fetch each family's IP from, always over that family (for http, instead
+//   of `DDNS_IP_SOURCE_URL`)
+// - `DDNS_IP_SOURCE_STRATEGY`: How the URLs are queried: failover, round_robin,
+//   consensus (for http, default: failover)
+// - `DDNS_IP_SOURCE_QUORUM`: URLs that must agree on the address (for http
+//   with consensus, default: majority)
+// - `DDNS_IP_SOURCE_EXTRACT`: Where the address is in the response: body,
+//   json:<pointer>, regex:<pattern>, key:<key>, header:<name> (for http,
+//   default: body; exec supports body and json:<pointer>)
+// - `DDNS_IP_SOURCE_HEADERS`: Extra request headers as `Name=value` pairs,
+//   comma-separated (for http)
+// - `DDNS_IP_SOURCE_UPNP_LOCATION`: Gateway device description URL, skipping
+//   SSDP discovery (for upnp)
+// - `DDNS_IP_SOURCE_NATPMP_GATEWAY`: Gateway address to ask via NAT-PMP if
+//   UPnP fails (for upnp)
+// - `DDNS_IP_SOURCE_STUN_SERVERS`: Comma-separated STUN servers as
+//   `host[:port]`, tried in order (for stun)
+// - `DDNS_IP_SOURCE_VERSION`: IP version to monitor: v4, v6, both (for stun,
+//   default: both)
+// - `DDNS_IP_SOURCE_EXEC_COMMAND`: Command printing the address, run with
+//   `/bin/sh -c` (for exec)
+// - `DDNS_IP_SOURCE_EXEC_TIMEOUT`: Seconds the command may run (for exec,
+//   default: 10)
+// - `DDNS_IP_SOURCE_EXEC_SIGNAL`: Signal that runs the command immediately:
+//   HUP, USR1, USR2 (for exec; not HUP while a static IP source has a
+//   control file)
+// - `DDNS_IP_SOURCE_FILE_PATH`: File holding the address, watched with
+//   inotify (for file)
+// - `DDNS_IP_SOURCE_FILE_DEBOUNCE_MS`: Quiet time after the last change before
+//   the file is read (for file, default: 500)
+// - `DDNS_IP_SOURCE_STATIC_V4`, `DDNS_IP_SOURCE_STATIC_V6`: Pinned addresses
+//   (for static, at least one is required)
+// - `DDNS_IP_SOURCE_STATIC_CONTROL`: Control file whose addresses are pinned
+//   on SIGHUP (for static, optional; see `static_control.rs`)
+// - `DDNS_IP_SOURCE_INTERVAL`: Poll interval in seconds (for http, upnp, stun,
+//   exec)
+// - `DDNS_IP_SOURCE_FALLBACK`: Comma-separated named IP sources, in priority
+//   order (for fallback)
+//
+// ### Named IP Sources
+// - `DDNS_IP_SOURCES`: Comma-separated names of additional IP sources (optional)
+// - `DDNS_IP_SOURCE_<NAME>_*`: Settings of each named IP source, with the same
+//   suffixes as the default IP source (e.g. `DDNS_IP_SOURCE_LAN_INTERFACE`)
+// - `DDNS_RECORD_IP_SOURCES`: Records published from a named IP source as
+//   `record=name` pairs, comma-separated (optional)
 //
 // ### DNS Provider
-// - `DDNS_PROVIDER_TYPE`: Provider type (cloudflare)
-// - `DDNS_PROVIDER_API_TOKEN`: API token
-// - `DDNS_PROVIDER_ZONE_ID`: Zone ID (optional)
+// - `DDNS_PROVIDER_TYPE`: Provider type (cloudflare, rfc2136, route53, dyndns2,
+//   powerdns, hetzner, digitalocean)
+// - `DDNS_PROVIDER_API_TOKEN`: API token (for cloudflare, hetzner, digitalocean)
+// - `DDNS_PROVIDER_ZONE_ID`: Zone ID (optional; for route53, the hosted zone ID)
+// - `DDNS_PROVIDER_ZONE_OVERRIDES`: Per-record zone IDs as `record=zone_id` pairs,
+//   comma-separated (optional, take precedence over `DDNS_PROVIDER_ZONE_ID`)
+// - `DDNS_PROVIDER_CREATE_IF_MISSING`: Create missing A/AAAA records (default: false)
+// - `DDNS_PROVIDER_TTL`: TTL for created records in seconds (default: automatic;
+//   for rfc2136, TTL of all records written, default: 300; for route53 and
+//   powerdns, default: 300; for digitalocean, default: 1800)
+// - `DDNS_PROVIDER_PROXIED`: Proxy created records through Cloudflare (default: false)
+// - `DDNS_PROVIDER_COMMENT`: Comment attached to created records (optional)
+// - `DDNS_PROVIDER_API_BASE_URL`: API base URL or Route 53 endpoint (default:
+//   public API); for powerdns, the API URL (required)
+// - `DDNS_PROVIDER_SERVER`: Primary name server as `host[:port]` (for rfc2136)
+// - `DDNS_PROVIDER_ZONE`: Zone of the records (for rfc2136, default: found with
+//   a SOA query; for powerdns and digitalocean (the domain), default: looked
+//   up by the record name)
+// - `DDNS_PROVIDER_TSIG_KEY_NAME`, `DDNS_PROVIDER_TSIG_SECRET`: TSIG key name and
+//   base64 secret (for rfc2136, optional; without them updates are unsigned)
+// - `DDNS_PROVIDER_TSIG_ALGORITHM`: hmac-sha256, hmac-sha512 (for rfc2136,
+//   default: hmac-sha256)
+// - `DDNS_PROVIDER_ACCESS_KEY_ID`, `DDNS_PROVIDER_SECRET_ACCESS_KEY`: AWS
+//   credentials (for route53, optional; default: `AWS_ACCESS_KEY_ID` and
+//   `AWS_SECRET_ACCESS_KEY`, then the shared credentials file)
+// - `DDNS_PROVIDER_SESSION_TOKEN`: AWS session token (for route53, optional)
+// - `DDNS_PROVIDER_PROFILE`: Profile of the shared credentials file (for
+//   route53, default: `AWS_PROFILE` or "default")
+// - `DDNS_PROVIDER_WAIT_FOR_SYNC`: Wait until changes are INSYNC (for route53,
+//   default: false)
+// - `DDNS_PROVIDER_UPDATE_URL`: Update URL, e.g.
+//   `https://members.dyndns.org/nic/update` (for dyndns2)
+// - `DDNS_PROVIDER_USERNAME`, `DDNS_PROVIDER_PASSWORD`: Account credentials
+//   (for dyndns2)
+// - `DDNS_PROVIDER_EXTRA_HOSTNAMES`: Hostnames updated in the same request as
+//   a record, as `record=host+host` pairs, comma-separated (for dyndns2,
+//   optional)
+// - `DDNS_PROVIDER_API_KEY`: API key (for powerdns)
+// - `DDNS_PROVIDER_SERVER_ID`: Server ID in API paths (for powerdns, default:
+//   localhost)
+// - `DDNS_PROVIDER_RECTIFY`: Rectify the zone after a change (for powerdns,
+//   default: false)
+// - `DDNS_PROVIDER_NOTIFY`: NOTIFY the zone's secondaries after a change (for
+//   powerdns, default: false)
+//
+// ### Named DNS Providers
+// - `DDNS_PROVIDERS`: Comma-separated names of additional providers (optional)
+// - `DDNS_PROVIDER_<NAME>_*`: Settings of each named provider, with the same
+//   suffixes as the default provider (e.g. `DDNS_PROVIDER_WORK_API_TOKEN`)
 //
 // ### Records
-// - `DDNS_RECORDS`: Comma-separated list of DNS records to manage
+// - `DDNS_RECORDS`: Comma-separated list of DNS records to manage; append
+//   `@name` to manage a record through a named provider (`vpn.example.net@work`)
 //
 // ### State Store
 // - `DDNS_STATE_STORE_TYPE`: Type of state store (file, memory)
@@ -40,6 +140,8 @@
 // ### Engine
 // - `DDNS_MAX_RETRIES`: Maximum retry attempts
 // - `DDNS_RETRY_DELAY_SECS`: Delay between retries
+// - `DDNS_STARTUP_DELAY_SECS`: Delay before the initial IP query and record reconciliation
+// - `DDNS_RECONCILE_INTERVAL_SECS`: Interval between drift checks against the provider (0 = disabled)
 //
 // ## Example
 //
@@ -55,7 +157,10 @@
 // ddnsd
 // ```
 
+mod static_control;
+
 use anyhow::Result;
+use std::collections::HashMap;
 use std::env;
 use std::process::ExitCode;
 use std::time::Duration;
@@ -90,13 +195,11 @@ impl From<DdnsExitCode> for ExitCode {
 /// Application configuration
 #[allow(dead_code)]
 struct Config {
-    ip_source_type: String,
-    ip_source_interface: Option<String>,
-    ip_source_url: Option<String>,
-    ip_source_interval: Option<u64>,
-    provider_type: String,
-    provider_api_token: String,
-    provider_zone_id: Option<String>,
+    ip_source: IpSourceEnv,
+    ip_sources: Vec<(String, IpSourceEnv)>,
+    record_ip_sources: HashMap<String, String>,
+    provider: ProviderEnv,
+    providers: Vec<(String, ProviderEnv)>,
     records: Vec<String>,
     state_store_type: String,
     state_store_path: Option<String>,
@@ -104,6 +207,7 @@ struct Config {
     retry_delay_secs: Option<u64>,
     startup_delay_secs: Option<u64>,
     min_update_interval_secs: Option<u64>,
+    reconcile_interval_secs: Option<u64>,
     log_level: String,
 }
 
@@ -111,17 +215,23 @@ impl Config {
     /// Load configuration from environment variables
     fn from_env() -> Result<Self> {
         Ok(Self {
-            ip_source_type: env::var("DDNS_IP_SOURCE_TYPE")
-                .unwrap_or_else(|_| "netlink".to_string()),
-            ip_source_interface: env::var("DDNS_IP_SOURCE_INTERFACE").ok(),
-            ip_source_url: env::var("DDNS_IP_SOURCE_URL").ok(),
-            ip_source_interval: env::var("DDNS_IP_SOURCE_INTERVAL")
-                .ok()
-                .map(|s| s.parse().unwrap_or(60)),
-            provider_type: env::var("DDNS_PROVIDER_TYPE")
-                .unwrap_or_else(|_| "cloudflare".to_string()),
-            provider_api_token: env::var("DDNS_PROVIDER_API_TOKEN")?,
-            provider_zone_id: env::var("DDNS_PROVIDER_ZONE_ID").ok(),
+            ip_source: IpSourceEnv::from_env("DDNS_IP_SOURCE_")?,
+            ip_sources: env_list("DDNS_IP_SOURCES")
+                .into_iter()
+                .map(|name| {
+                    let prefix = format!("DDNS_IP_SOURCE_{}_", env_name(&name));
+                    Ok((name, IpSourceEnv::from_env(&prefix)?))
+                })
+                .collect::<Result<_>>()?,
+            record_ip_sources: env_pairs("DDNS_RECORD_IP_SOURCES", "record=name")?,
+            provider: ProviderEnv::from_env("DDNS_PROVIDER_")?,
+            providers: env_list("DDNS_PROVIDERS")
+                .into_iter()
+                .map(|name| {
+                    let prefix = format!("DDNS_PROVIDER_{}_", env_name(&name));
+                    Ok((name, ProviderEnv::from_env(&prefix)?))
+                })
+                .collect::<Result<_>>()?,
             records: env::var("DDNS_RECORDS")
                 .unwrap_or_default()
                 .split(',')
@@ -143,6 +253,13 @@ impl Config {
             min_update_interval_secs: env::var("DDNS_MIN_UPDATE_INTERVAL_SECS")
                 .ok()
                 .map(|s| s.parse().unwrap_or(60)),
+            reconcile_interval_secs: env::var("DDNS_RECONCILE_INTERVAL_SECS")
+                .ok()
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|_| {
+                    anyhow::anyhow!("DDNS_RECONCILE_INTERVAL_SECS must be a number of seconds")
+                })?,
             log_level: env::var("DDNS_LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
         })
     }
@@ -156,56 +273,55 @@ impl Config {
     /// - Type enumeration validation
     /// - Security checks (secret exposure, URL schemes)
     fn validate(&self) -> Result<()> {
-        // Validate API token presence and format
-        if self.provider_api_token.is_empty() {
-            anyhow::bail!(
-                "DDNS_PROVIDER_API_TOKEN is required. \
-                Set it via: export DDNS_PROVIDER_API_TOKEN=your_token"
-            );
-        }
+        // Validate providers
+        self.provider.validate()?;
 
-        // Cloudflare API tokens are typically 40 characters alphanumeric
-        // Other providers may have different formats, so we do basic validation
-        if self.provider_api_token.len() < 20 {
-            anyhow::bail!(
-                "DDNS_PROVIDER_API_TOKEN appears too short ({} chars). \
-                Cloudflare tokens are typically 40 characters. \
-                Verify your token is correct.",
-                self.provider_api_token.len()
-            );
+        for (name, provider) in &self.providers {
+            if !is_valid_name(name) {
+                anyhow::bail!(
+                    "DDNS_PROVIDERS entry '{}' is not a valid name. \
+                    Use letters, digits, '-' and '_' only.",
+                    name
+                );
+            }
+            provider.validate()?;
         }
 
-        // Check for obvious placeholder tokens (common mistake)
-        let token_lower = self.provider_api_token.to_lowercase();
-        if token_lower.contains("your_token")
-            || token_lower.contains("replace_me")
-            || token_lower.contains("example")
-            || token_lower == "token"
-        {
-            anyhow::bail!(
-                "DDNS_PROVIDER_API_TOKEN appears to be a placeholder. \
-                Use an actual API token from your DNS provider."
-            );
-        }
+        // Validate IP sources
+        self.ip_source.validate(&self.ip_sources)?;
 
-        // Validate provider type
-        match self.provider_type.as_str() {
-            "cloudflare" => {} // Currently supported
-            _ => anyhow::bail!(
-                "DDNS_PROVIDER_TYPE '{}' is not supported. \
-                Supported providers: cloudflare",
-                self.provider_type
-            ),
+        for (name, ip_source) in &self.ip_sources {
+            if !is_valid_name(name) {
+                anyhow::bail!(
+                    "DDNS_IP_SOURCES entry '{}' is not a valid name. \
+                    Use letters, digits, '-' and '_' only.",
+                    name
+                );
+            }
+            ip_source.validate(&self.ip_sources)?;
         }
 
-        // Validate IP source type
-        match self.ip_source_type.as_str() {
-            "netlink" | "http" | "file" => {}
-            _ => anyhow::bail!(
-                "DDNS_IP_SOURCE_TYPE '{}' is not supported. \
-                Supported types: netlink, http, file",
-                self.ip_source_type
-            ),
+        // SIGHUP reloads static control files, so it cannot also run a command
+        let ip_sources: Vec<&IpSourceEnv> = std::iter::once(&self.ip_source)
+            .chain(self.ip_sources.iter().map(|(_, ip_source)| ip_source))
+            .collect();
+        if ip_sources.iter().any(|ip_source| {
+            ip_source.source_type == "static" && ip_source.static_control.is_some()
+        }) {
+            for ip_source in &ip_sources {
+                if ip_source.source_type == "exec"
+                    && matches!(
+                        ip_source.exec_signal()?,
+                        Some(ddns_core::config::ExecSignal::Hup)
+                    )
+                {
+                    anyhow::bail!(
+                        "{}EXEC_SIGNAL cannot be HUP while a static IP source has a control file: \
+                        SIGHUP reloads the control files. Use USR1 or USR2",
+                        ip_source.prefix
+                    );
+                }
+            }
         }
 
         // Validate state store type
@@ -227,7 +343,38 @@ impl Config {
         }
 
         for record in &self.records {
-            self.validate_domain_name(record)?;
+            let (name, provider) = split_record(record);
+            self.validate_domain_name(name)?;
+
+            if let Some(provider) = provider
+                && !self.providers.iter().any(|(name, _)| name == provider)
+            {
+                anyhow::bail!(
+                    "DDNS_RECORDS entry '{}' uses provider '{}', which is not listed in DDNS_PROVIDERS",
+                    record,
+                    provider
+                );
+            }
+        }
+
+        for (record, ip_source) in &self.record_ip_sources {
+            if !self
+                .records
+                .iter()
+                .any(|entry| split_record(entry).0 == record)
+            {
+                anyhow::bail!(
+                    "DDNS_RECORD_IP_SOURCES entry '{}' is not listed in DDNS_RECORDS",
+                    record
+                );
+            }
+            if !self.ip_sources.iter().any(|(name, _)| name == ip_source) {
+                anyhow::bail!(
+                    "DDNS_RECORD_IP_SOURCES entry '{}' uses IP source '{}', which is not listed in DDNS_IP_SOURCES",
+                    record,
+                    ip_source
+                );
+            }
         }
 
         // Validate state store path for file store
@@ -260,41 +407,7 @@ impl Config {
             }
         }
 
-        // Validate IP source URL for HTTP source
-        if self.ip_source_type == "http" {
-            if self.ip_source_url.as_ref().is_none_or(|u| u.is_empty()) {
-                anyhow::bail!("DDNS_IP_SOURCE_URL is required when DDNS_IP_SOURCE_TYPE=http");
-            }
-
-            if let Some(ref url) = self.ip_source_url {
-                // Validate URL scheme (HTTPS only for security)
-                if !url.starts_with("https://") && !url.starts_with("http://") {
-                    anyhow::bail!(
-                        "DDNS_IP_SOURCE_URL must use HTTP or HTTPS scheme. Got: {}",
-                        url
-                    );
-                }
-
-                // Warn if using HTTP (not HTTPS)
-                if url.starts_with("http://") && !url.starts_with("https://") {
-                    eprintln!(
-                        "WARNING: DDNS_IP_SOURCE_URL uses HTTP (not HTTPS). \
-                              This is less secure. Consider using HTTPS."
-                    );
-                }
-            }
-        }
-
         // Validate numeric ranges
-        if let Some(interval) = self.ip_source_interval
-            && (!(10..=3600).contains(&interval))
-        {
-            anyhow::bail!(
-                "DDNS_IP_SOURCE_INTERVAL must be between 10 and 3600 seconds. Got: {}",
-                interval
-            );
-        }
-
         if let Some(max_retries) = self.max_retries
             && (max_retries == 0 || max_retries > 10)
         {
@@ -313,6 +426,16 @@ impl Config {
             );
         }
 
+        if let Some(interval) = self.reconcile_interval_secs
+            && interval != 0
+            && !(60..=86400).contains(&interval)
+        {
+            anyhow::bail!(
+                "DDNS_RECONCILE_INTERVAL_SECS must be 0 (disabled) or between 60 and 86400 seconds. Got: {}",
+                interval
+            );
+        }
+
         // Validate log level
         match self.log_level.to_lowercase().as_str() {
             "trace" | "debug" | "info" | "warn" | "error" => {}
@@ -380,6 +503,938 @@ impl Config {
     }
 }
 
+/// Read a boolean environment variable ("true"/"1"/"yes" or "false"/"0"/"no")
+///
+/// Unset or empty variables take the default; anything else is an error, so
+/// a typo cannot silently flip a setting back to its default.
+fn env_flag(name: &str, default: bool) -> Result<bool> {
+    let value = env::var(name).unwrap_or_default();
+    match value.trim().to_lowercase().as_str() {
+        "" => Ok(default),
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => anyhow::bail!(
+            "{} must be true or false (also 1/0, yes/no), got '{}'",
+            name,
+            value
+        ),
+    }
+}
+
+/// Read a comma-separated list environment variable
+fn env_list(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Read a comma-separated list of `key=value` pairs from an environment variable
+///
+/// `shape` describes the expected entry format for error messages.
+fn env_pairs(name: &str, shape: &str) -> Result<HashMap<String, String>> {
+    env_list(name)
+        .into_iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() && !value.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(anyhow::anyhow!(
+                "{} entries must be {}, got '{}'",
+                name,
+                shape,
+                pair
+            )),
+        })
+        .collect()
+}
+
+/// Turn a provider or IP source name into its environment variable infix
+/// (`home-lab` -> `HOME_LAB`)
+fn env_name(name: &str) -> String {
+    name.to_uppercase().replace('-', "_")
+}
+
+/// Whether a provider or IP source name is usable in environment variable names
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Split a `DDNS_RECORDS` entry into the record name and optional provider name
+fn split_record(entry: &str) -> (&str, Option<&str>) {
+    match entry.rsplit_once('@') {
+        Some((name, provider)) => (name, Some(provider)),
+        None => (entry, None),
+    }
+}
+
+/// DNS provider settings
+///
+/// Read from `DDNS_PROVIDER_*` for the default provider and from
+/// `DDNS_PROVIDER_<NAME>_*` for named providers.
+struct ProviderEnv {
+    /// Environment variable prefix, for error messages
+    prefix: String,
+    provider_type: String,
+    api_token: String,
+    zone_id: Option<String>,
+    zone_overrides: HashMap<String, String>,
+    create_if_missing: bool,
+    ttl: Option<u32>,
+    proxied: bool,
+    comment: Option<String>,
+    api_base_url: Option<String>,
+    server: Option<String>,
+    zone: Option<String>,
+    tsig_key_name: Option<String>,
+    tsig_secret: Option<String>,
+    tsig_algorithm: Option<String>,
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    session_token: Option<String>,
+    profile: Option<String>,
+    wait_for_sync: bool,
+    update_url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    extra_hostnames: HashMap<String, Vec<String>>,
+    api_key: Option<String>,
+    server_id: Option<String>,
+    rectify: bool,
+    notify: bool,
+}
+
+impl ProviderEnv {
+    /// Load provider settings from variables starting with `prefix`
+    fn from_env(prefix: &str) -> Result<Self> {
+        let var = |suffix: &str| env::var(format!("{}{}", prefix, suffix)).ok();
+
+        Ok(Self {
+            prefix: prefix.to_string(),
+            provider_type: var("TYPE").unwrap_or_else(|| "cloudflare".to_string()),
+            api_token: var("API_TOKEN").unwrap_or_default(),
+            zone_id: var("ZONE_ID"),
+            zone_overrides: env_pairs(&format!("{}ZONE_OVERRIDES", prefix), "record=zone_id")?,
+            create_if_missing: env_flag(&format!("{}CREATE_IF_MISSING", prefix), false)?,
+            ttl: var("TTL")
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|_| anyhow::anyhow!("{}TTL must be a number of seconds", prefix))?,
+            proxied: env_flag(&format!("{}PROXIED", prefix), false)?,
+            comment: var("COMMENT"),
+            api_base_url: var("API_BASE_URL"),
+            server: var("SERVER"),
+            zone: var("ZONE"),
+            tsig_key_name: var("TSIG_KEY_NAME"),
+            tsig_secret: var("TSIG_SECRET"),
+            tsig_algorithm: var("TSIG_ALGORITHM"),
+            access_key_id: var("ACCESS_KEY_ID"),
+            secret_access_key: var("SECRET_ACCESS_KEY"),
+            session_token: var("SESSION_TOKEN"),
+            profile: var("PROFILE"),
+            wait_for_sync: env_flag(&format!("{}WAIT_FOR_SYNC", prefix), false)?,
+            update_url: var("UPDATE_URL"),
+            username: var("USERNAME"),
+            password: var("PASSWORD"),
+            extra_hostnames: env_pairs(&format!("{}EXTRA_HOSTNAMES", prefix), "record=host+host")?
+                .into_iter()
+                .map(|(record, hostnames)| {
+                    let hostnames = hostnames.split('+').map(|h| h.trim().to_string()).collect();
+                    (record, hostnames)
+                })
+                .collect(),
+            api_key: var("API_KEY"),
+            server_id: var("SERVER_ID"),
+            rectify: env_flag(&format!("{}RECTIFY", prefix), false)?,
+            notify: env_flag(&format!("{}NOTIFY", prefix), false)?,
+        })
+    }
+
+    /// Validate the provider settings
+    fn validate(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        // Validate provider type
+        match self.provider_type.as_str() {
+            "cloudflare" => self.validate_api_token(),
+            "rfc2136" => self.validate_rfc2136(),
+            "route53" => self.validate_route53(),
+            "dyndns2" => self.validate_dyndns2(),
+            "powerdns" => self.validate_powerdns(),
+            "hetzner" | "digitalocean" => self.validate_api_token(),
+            _ => anyhow::bail!(
+                "{}TYPE '{}' is not supported. \
+                Supported providers: cloudflare, rfc2136, route53, dyndns2, powerdns, \
+                hetzner, digitalocean",
+                prefix,
+                self.provider_type
+            ),
+        }
+    }
+
+    /// Validate the API token of API-based providers
+    fn validate_api_token(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        // Validate API token presence and format
+        if self.api_token.is_empty() {
+            anyhow::bail!(
+                "{prefix}API_TOKEN is required. \
+                Set it via: export {prefix}API_TOKEN=your_token"
+            );
+        }
+
+        // Cloudflare API tokens are typically 40 characters alphanumeric,
+        // Hetzner tokens 32 and DigitalOcean tokens longer still, so we do
+        // basic validation
+        if self.api_token.len() < 20 {
+            anyhow::bail!(
+                "{}API_TOKEN appears too short ({} chars). \
+                API tokens are typically 32 characters or more. \
+                Verify your token is correct.",
+                prefix,
+                self.api_token.len()
+            );
+        }
+
+        // Check for obvious placeholder tokens (common mistake)
+        let token_lower = self.api_token.to_lowercase();
+        if token_lower.contains("your_token")
+            || token_lower.contains("replace_me")
+            || token_lower.contains("example")
+            || token_lower == "token"
+        {
+            anyhow::bail!(
+                "{prefix}API_TOKEN appears to be a placeholder. \
+                Use an actual API token from your DNS provider."
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validate the settings of an RFC 2136 provider
+    fn validate_rfc2136(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        if self
+            .server
+            .as_deref()
+            .is_none_or(|server| server.trim().is_empty())
+        {
+            anyhow::bail!(
+                "{prefix}SERVER is required when {prefix}TYPE=rfc2136. \
+                Set it via: export {prefix}SERVER=ns1.example.com"
+            );
+        }
+
+        if self.tsig_key_name.is_some() != self.tsig_secret.is_some() {
+            anyhow::bail!("{prefix}TSIG_KEY_NAME and {prefix}TSIG_SECRET must be set together");
+        }
+
+        self.tsig_algorithm()?;
+        Ok(())
+    }
+
+    /// Validate the settings of a Route 53 provider
+    fn validate_route53(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        if self.access_key_id.is_some() != self.secret_access_key.is_some() {
+            anyhow::bail!(
+                "{prefix}ACCESS_KEY_ID and {prefix}SECRET_ACCESS_KEY must be set together"
+            );
+        }
+
+        if self.session_token.is_some() && self.access_key_id.is_none() {
+            anyhow::bail!(
+                "{prefix}SESSION_TOKEN requires {prefix}ACCESS_KEY_ID and {prefix}SECRET_ACCESS_KEY"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validate the settings of a dyndns2 provider
+    fn validate_dyndns2(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        match self.update_url.as_deref() {
+            None | Some("") => anyhow::bail!(
+                "{prefix}UPDATE_URL is required when {prefix}TYPE=dyndns2. \
+                Set it via: export {prefix}UPDATE_URL=https://members.dyndns.org/nic/update"
+            ),
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                anyhow::bail!("{prefix}UPDATE_URL must start with http:// or https://, got {url}")
+            }
+            _ => {}
+        }
+
+        if self.username.as_deref().is_none_or(str::is_empty)
+            || self.password.as_deref().is_none_or(str::is_empty)
+        {
+            anyhow::bail!(
+                "{prefix}USERNAME and {prefix}PASSWORD are required when {prefix}TYPE=dyndns2"
+            );
+        }
+
+        if self
+            .extra_hostnames
+            .values()
+            .flatten()
+            .any(|hostname| hostname.is_empty())
+        {
+            anyhow::bail!("{prefix}EXTRA_HOSTNAMES entries must be record=host+host");
+        }
+
+        Ok(())
+    }
+
+    /// Validate the settings of a PowerDNS provider
+    fn validate_powerdns(&self) -> Result<()> {
+        let prefix = &self.prefix;
+
+        match self.api_base_url.as_deref() {
+            None | Some("") => anyhow::bail!(
+                "{prefix}API_BASE_URL is required when {prefix}TYPE=powerdns. \
+                Set it via: export {prefix}API_BASE_URL=http://127.0.0.1:8081"
+            ),
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                anyhow::bail!("{prefix}API_BASE_URL must start with http:// or https://, got {url}")
+            }
+            _ => {}
+        }
+
+        if self.api_key.as_deref().is_none_or(str::is_empty) {
+            anyhow::bail!(
+                "{prefix}API_KEY is required when {prefix}TYPE=powerdns. \
+                Set it via: export {prefix}API_KEY=your_api_key"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Parse `TSIG_ALGORITHM`
+    fn tsig_algorithm(&self) -> Result<ddns_core::config::TsigAlgorithm> {
+        use ddns_core::config::TsigAlgorithm;
+
+        match self
+            .tsig_algorithm
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            None | Some("hmac-sha256") => Ok(TsigAlgorithm::HmacSha256),
+            Some("hmac-sha512") => Ok(TsigAlgorithm::HmacSha512),
+            Some(other) => anyhow::bail!(
+                "{}TSIG_ALGORITHM '{}' is not supported. \
+                Supported algorithms: hmac-sha256, hmac-sha512",
+                self.prefix,
+                other
+            ),
+        }
+    }
+
+    /// Build the provider configuration
+    fn to_config(&self) -> Result<ddns_core::config::ProviderConfig> {
+        match self.provider_type.as_str() {
+            "cloudflare" => Ok(ddns_core::config::ProviderConfig::Cloudflare {
+                api_token: self.api_token.clone(),
+                zone_id: self.zone_id.clone(),
+                account_id: None,
+                create_if_missing: self.create_if_missing,
+                ttl: self.ttl,
+                proxied: self.proxied,
+                comment: self.comment.clone(),
+                api_base_url: self.api_base_url.clone(),
+                zone_overrides: self.zone_overrides.clone(),
+            }),
+            "rfc2136" => Ok(ddns_core::config::ProviderConfig::Rfc2136 {
+                server: self.server.clone().unwrap_or_default(),
+                zone: self.zone.clone(),
+                tsig_key_name: self.tsig_key_name.clone(),
+                tsig_secret: self.tsig_secret.clone(),
+                tsig_algorithm: self.tsig_algorithm()?,
+                // Dynamic records should expire quickly
+                ttl: self.ttl.unwrap_or(300),
+            }),
+            "route53" => Ok(ddns_core::config::ProviderConfig::Route53 {
+                access_key_id: self.access_key_id.clone(),
+                secret_access_key: self.secret_access_key.clone(),
+                session_token: self.session_token.clone(),
+                profile: self.profile.clone(),
+                hosted_zone_id: self.zone_id.clone(),
+                ttl: self.ttl.unwrap_or(300),
+                wait_for_sync: self.wait_for_sync,
+                endpoint: self.api_base_url.clone(),
+            }),
+            "dyndns2" => Ok(ddns_core::config::ProviderConfig::Dyndns2 {
+                update_url: self.update_url.clone().unwrap_or_default(),
+                username: self.username.clone().unwrap_or_default(),
+                password: self.password.clone().unwrap_or_default(),
+                extra_hostnames: self.extra_hostnames.clone(),
+            }),
+            "powerdns" => Ok(ddns_core::config::ProviderConfig::PowerDns {
+                api_url: self.api_base_url.clone().unwrap_or_default(),
+                api_key: self.api_key.clone().unwrap_or_default(),
+                server_id: self
+                    .server_id
+                    .clone()
+                    .unwrap_or_else(|| "localhost".to_string()),
+                zone: self.zone.clone(),
+                ttl: self.ttl.unwrap_or(300),
+                rectify: self.rectify,
+                notify: self.notify,
+            }),
+            "hetzner" => Ok(ddns_core::config::ProviderConfig::Hetzner {
+                api_token: self.api_token.clone(),
+                zone_id: self.zone_id.clone(),
+                create_if_missing: self.create_if_missing,
+                ttl: self.ttl,
+                api_base_url: self.api_base_url.clone(),
+            }),
+            "digitalocean" => Ok(ddns_core::config::ProviderConfig::DigitalOcean {
+                api_token: self.api_token.clone(),
+                domain: self.zone.clone(),
+                create_if_missing: self.create_if_missing,
+                ttl: self.ttl,
+                api_base_url: self.api_base_url.clone(),
+            }),
+            _ => Err(anyhow::anyhow!(
+                "Unknown provider type: {}",
+                self.provider_type
+            )),
+        }
+    }
+}
+
+/// IP source settings
+///
+/// Read from `DDNS_IP_SOURCE_*` for the default IP source and from
+/// `DDNS_IP_SOURCE_<NAME>_*` for named IP sources.
+struct IpSourceEnv {
+    /// Environment variable prefix, for error messages
+    prefix: String,
+    source_type: String,
+    interface: Option<String>,
+    selection: ddns_core::config::AddressSelection,
+    urls: Vec<String>,
+    v4_urls: Vec<String>,
+    v6_urls: Vec<String>,
+    strategy: String,
+    quorum: Option<usize>,
+    extract: Option<String>,
+    headers: HashMap<String, String>,
+    upnp_location: Option<String>,
+    nat_pmp_gateway: Option<String>,
+    stun_servers: Vec<String>,
+    version: Option<String>,
+    exec_command: Option<String>,
+    exec_timeout: Option<u64>,
+    exec_signal: Option<String>,
+    file_path: Option<String>,
+    file_debounce_ms: Option<u64>,
+    static_v4: Option<String>,
+    static_v6: Option<String>,
+    static_control: Option<String>,
+    interval: Option<u64>,
+    fallback: Vec<String>,
+}
+
+impl IpSourceEnv {
+    /// Load IP source settings from variables starting with `prefix`
+    fn from_env(prefix: &str) -> Result<Self> {
+        let var = |suffix: &str| env::var(format!("{}{}", prefix, suffix)).ok();
+        let name = |suffix: &str| format!("{}{}", prefix, suffix);
+
+        Ok(Self {
+            prefix: prefix.to_string(),
+            source_type: var("TYPE").unwrap_or_else(|| "netlink".to_string()),
+            interface: var("INTERFACE"),
+            selection: ddns_core::config::AddressSelection {
+                global_only: env_flag(&name("GLOBAL_ONLY"), true)?,
+                allow_temporary: env_flag(&name("ALLOW_TEMPORARY"), false)?,
+                allow_deprecated: env_flag(&name("ALLOW_DEPRECATED"), false)?,
+                allow_cidrs: env_list(&name("ALLOW_CIDRS")),
+                deny_cidrs: env_list(&name("DENY_CIDRS")),
+                prefer_eui64: env_flag(&name("PREFER_EUI64"), false)?,
+                interface_id: var("INTERFACE_ID"),
+            },
+            urls: env_list(&name("URL")),
+            v4_urls: env_list(&name("URL_V4")),
+            v6_urls: env_list(&name("URL_V6")),
+            strategy: var("STRATEGY").unwrap_or_else(|| "failover".to_string()),
+            quorum: var("QUORUM").map(|s| s.parse().unwrap_or(0)),
+            extract: var("EXTRACT"),
+            headers: env_pairs(&name("HEADERS"), "Name=value")?,
+            upnp_location: var("UPNP_LOCATION"),
+            nat_pmp_gateway: var("NATPMP_GATEWAY"),
+            stun_servers: env_list(&name("STUN_SERVERS")),
+            version: var("VERSION"),
+            exec_command: var("EXEC_COMMAND"),
+            exec_timeout: var("EXEC_TIMEOUT")
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|_| {
+                    anyhow::anyhow!("{}EXEC_TIMEOUT must be a number of seconds", prefix)
+                })?,
+            exec_signal: var("EXEC_SIGNAL"),
+            file_path: var("FILE_PATH"),
+            file_debounce_ms: var("FILE_DEBOUNCE_MS")
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|_| {
+                    anyhow::anyhow!(
+                        "{}FILE_DEBOUNCE_MS must be a number of milliseconds",
+                        prefix
+                    )
+                })?,
+            static_v4: var("STATIC_V4"),
+            static_v6: var("STATIC_V6"),
+            static_control: var("STATIC_CONTROL"),
+            interval: var("INTERVAL").map(|s| s.parse().unwrap_or(60)),
+            fallback: env_list(&name("FALLBACK")),
+        })
+    }
+
+    /// Validate the IP source settings
+    ///
+    /// `named` are the named IP sources a fallback source may refer to.
+    fn validate(&self, named: &[(String, IpSourceEnv)]) -> Result<()> {
+        let prefix = &self.prefix;
+
+        // Validate IP source type
+        match self.source_type.as_str() {
+            "netlink" | "http" | "upnp" | "stun" | "exec" | "file" | "static" | "fallback" => {}
+            _ => anyhow::bail!(
+                "{}TYPE '{}' is not supported. \
+                Supported types: netlink, http, upnp, stun, exec, file, static, fallback",
+                prefix,
+                self.source_type
+            ),
+        }
+
+        // Validate netlink address selection
+        if self.source_type == "netlink" {
+            self.selection
+                .validate()
+                .map_err(|e| anyhow::anyhow!("Invalid netlink address selection: {}", e))?;
+        }
+
+        // Validate IP source URL for HTTP source
+        if self.source_type == "http" {
+            let per_family = !self.v4_urls.is_empty() || !self.v6_urls.is_empty();
+            if self.urls.is_empty() && !per_family {
+                anyhow::bail!(
+                    "{prefix}URL (or {prefix}URL_V4 / {prefix}URL_V6) is required when {prefix}TYPE=http"
+                );
+            }
+            if !self.urls.is_empty() && per_family {
+                anyhow::bail!(
+                    "{prefix}URL cannot be combined with {prefix}URL_V4 / {prefix}URL_V6"
+                );
+            }
+
+            for url in self.urls.iter().chain(&self.v4_urls).chain(&self.v6_urls) {
+                // Validate URL scheme (HTTPS only for security)
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    anyhow::bail!("{}URL must use HTTP or HTTPS scheme. Got: {}", prefix, url);
+                }
+
+                // Warn if using HTTP (not HTTPS)
+                if url.starts_with("http://") && !url.starts_with("https://") {
+                    eprintln!(
+                        "WARNING: {prefix}URL uses HTTP (not HTTPS). \
+                              This is less secure. Consider using HTTPS."
+                    );
+                }
+            }
+
+            self.http_extractor()?
+                .validate()
+                .map_err(|e| anyhow::anyhow!("Invalid {}EXTRACT: {}", prefix, e))?;
+
+            if let ddns_core::config::HttpStrategy::Consensus { quorum } = self.http_strategy()?
+                && (quorum == 0 || quorum > self.url_count())
+            {
+                anyhow::bail!(
+                    "{}QUORUM must be between 1 and the number of URLs ({}). Got: {}",
+                    prefix,
+                    self.url_count(),
+                    quorum
+                );
+            }
+        }
+
+        // Validate UPnP gateway settings
+        if self.source_type == "upnp" {
+            if let Some(ref location) = self.upnp_location
+                && !location.starts_with("http://")
+                && !location.starts_with("https://")
+            {
+                anyhow::bail!(
+                    "{}UPNP_LOCATION must be an HTTP URL. Got: {}",
+                    prefix,
+                    location
+                );
+            }
+            self.nat_pmp_gateway()?;
+        }
+
+        // Validate STUN servers
+        if self.source_type == "stun" {
+            if self.stun_servers.is_empty() {
+                anyhow::bail!("{prefix}STUN_SERVERS is required when {prefix}TYPE=stun");
+            }
+            self.ip_version()?;
+        }
+
+        // Validate exec command
+        if self.source_type == "exec" {
+            if self
+                .exec_command
+                .as_deref()
+                .is_none_or(|command| command.trim().is_empty())
+            {
+                anyhow::bail!("{prefix}EXEC_COMMAND is required when {prefix}TYPE=exec");
+            }
+            if self.exec_timeout == Some(0) {
+                anyhow::bail!("{prefix}EXEC_TIMEOUT must be positive");
+            }
+            self.exec_json_pointer()?;
+            self.exec_signal()?;
+        }
+
+        // Validate watched file
+        if self.source_type == "file" {
+            match self.file_path.as_deref() {
+                None | Some("") => {
+                    anyhow::bail!("{prefix}FILE_PATH is required when {prefix}TYPE=file")
+                }
+                Some(path) if std::path::Path::new(path).file_name().is_none() => {
+                    anyhow::bail!("{}FILE_PATH must name a file. Got: {}", prefix, path)
+                }
+                Some(_) => {}
+            }
+        }
+
+        // Validate pinned addresses
+        if self.source_type == "static" {
+            if self.static_v4.is_none() && self.static_v6.is_none() {
+                anyhow::bail!(
+                    "{prefix}STATIC_V4 or {prefix}STATIC_V6 is required when {prefix}TYPE=static"
+                );
+            }
+            self.static_addresses()?;
+
+            if let Some(path) = &self.static_control
+                && std::path::Path::new(path).file_name().is_none()
+            {
+                anyhow::bail!("{}STATIC_CONTROL must name a file. Got: {}", prefix, path);
+            }
+        }
+
+        // Validate fallback chain
+        if self.source_type == "fallback" {
+            if self.fallback.is_empty() {
+                anyhow::bail!("{prefix}FALLBACK is required when {prefix}TYPE=fallback");
+            }
+
+            for member in &self.fallback {
+                match named.iter().find(|(name, _)| name == member) {
+                    None => anyhow::bail!(
+                        "{}FALLBACK entry '{}' is not listed in DDNS_IP_SOURCES",
+                        prefix,
+                        member
+                    ),
+                    Some((_, source)) if source.source_type == "fallback" => anyhow::bail!(
+                        "{}FALLBACK entry '{}' is itself a fallback source",
+                        prefix,
+                        member
+                    ),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // Validate numeric ranges
+        if let Some(interval) = self.interval
+            && (!(10..=3600).contains(&interval))
+        {
+            anyhow::bail!(
+                "{}INTERVAL must be between 10 and 3600 seconds. Got: {}",
+                prefix,
+                interval
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Number of URLs an HTTP source queries per family (the smallest, if
+    /// each family has its own URLs)
+    fn url_count(&self) -> usize {
+        if !self.urls.is_empty() {
+            return self.urls.len();
+        }
+        [self.v4_urls.len(), self.v6_urls.len()]
+            .into_iter()
+            .filter(|count| *count > 0)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// How the URLs of an HTTP source are queried
+    fn http_strategy(&self) -> Result<ddns_core::config::HttpStrategy> {
+        use ddns_core::config::HttpStrategy;
+
+        match self.strategy.as_str() {
+            "failover" => Ok(HttpStrategy::Failover),
+            "round_robin" => Ok(HttpStrategy::RoundRobin),
+            "consensus" => Ok(HttpStrategy::Consensus {
+                quorum: self.quorum.unwrap_or(self.url_count() / 2 + 1),
+            }),
+            _ => Err(anyhow::anyhow!(
+                "{}STRATEGY '{}' is not supported. \
+                Supported strategies: failover, round_robin, consensus",
+                self.prefix,
+                self.strategy
+            )),
+        }
+    }
+
+    /// Where the address is found in an HTTP response
+    fn http_extractor(&self) -> Result<ddns_core::config::HttpExtractor> {
+        use ddns_core::config::HttpExtractor;
+
+        let Some(ref extract) = self.extract else {
+            return Ok(HttpExtractor::Body);
+        };
+
+        match extract.split_once(':') {
+            None if extract == "body" => Ok(HttpExtractor::Body),
+            Some(("json", pointer)) => Ok(HttpExtractor::JsonPointer {
+                pointer: pointer.to_string(),
+            }),
+            Some(("regex", pattern)) => Ok(HttpExtractor::Regex {
+                pattern: pattern.to_string(),
+            }),
+            Some(("key", key)) => Ok(HttpExtractor::KeyValue {
+                key: key.to_string(),
+            }),
+            Some(("header", name)) => Ok(HttpExtractor::Header {
+                name: name.to_string(),
+            }),
+            _ => Err(anyhow::anyhow!(
+                "{}EXTRACT '{}' is not supported. \
+                Use body, json:<pointer>, regex:<pattern>, key:<key> or header:<name>",
+                self.prefix,
+                extract
+            )),
+        }
+    }
+
+    /// IP version to monitor, if restricted
+    fn ip_version(&self) -> Result<Option<ddns_core::config::IpVersion>> {
+        use ddns_core::config::IpVersion;
+
+        match self.version.as_deref() {
+            None => Ok(None),
+            Some("v4") => Ok(Some(IpVersion::V4)),
+            Some("v6") => Ok(Some(IpVersion::V6)),
+            Some("both") => Ok(Some(IpVersion::Both)),
+            Some(version) => Err(anyhow::anyhow!(
+                "{}VERSION '{}' is not supported. Use v4, v6 or both",
+                self.prefix,
+                version
+            )),
+        }
+    }
+
+    /// JSON pointer to the address in the output of an exec command
+    fn exec_json_pointer(&self) -> Result<Option<String>> {
+        use ddns_core::config::HttpExtractor;
+
+        match self.http_extractor()? {
+            HttpExtractor::Body => Ok(None),
+            HttpExtractor::JsonPointer { pointer } => Ok(Some(pointer)),
+            _ => Err(anyhow::anyhow!(
+                "{}EXTRACT must be body or json:<pointer> when {}TYPE=exec",
+                self.prefix,
+                self.prefix
+            )),
+        }
+    }
+
+    /// Signal that runs an exec command immediately, if configured
+    fn exec_signal(&self) -> Result<Option<ddns_core::config::ExecSignal>> {
+        use ddns_core::config::ExecSignal;
+
+        let Some(ref signal) = self.exec_signal else {
+            return Ok(None);
+        };
+
+        match signal.trim_start_matches("SIG") {
+            "HUP" => Ok(Some(ExecSignal::Hup)),
+            "USR1" => Ok(Some(ExecSignal::Usr1)),
+            "USR2" => Ok(Some(ExecSignal::Usr2)),
+            _ => Err(anyhow::anyhow!(
+                "{}EXEC_SIGNAL '{}' is not supported. Use HUP, USR1 or USR2",
+                self.prefix,
+                signal
+            )),
+        }
+    }
+
+    /// Addresses pinned by a static source
+    fn static_addresses(&self) -> Result<(Option<std::net::Ipv4Addr>, Option<std::net::Ipv6Addr>)> {
+        let v4 = self
+            .static_v4
+            .as_deref()
+            .map(|ip| {
+                ip.parse().map_err(|_| {
+                    anyhow::anyhow!(
+                        "{}STATIC_V4 must be an IPv4 address. Got: {}",
+                        self.prefix,
+                        ip
+                    )
+                })
+            })
+            .transpose()?;
+        let v6 = self
+            .static_v6
+            .as_deref()
+            .map(|ip| {
+                ip.parse().map_err(|_| {
+                    anyhow::anyhow!(
+                        "{}STATIC_V6 must be an IPv6 address. Got: {}",
+                        self.prefix,
+                        ip
+                    )
+                })
+            })
+            .transpose()?;
+        Ok((v4, v6))
+    }
+
+    /// Gateway to ask via NAT-PMP, if configured
+    fn nat_pmp_gateway(&self) -> Result<Option<std::net::IpAddr>> {
+        self.nat_pmp_gateway
+            .as_deref()
+            .map(|gateway| {
+                gateway.parse().map_err(|_| {
+                    anyhow::anyhow!(
+                        "{}NATPMP_GATEWAY must be an IP address. Got: {}",
+                        self.prefix,
+                        gateway
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Build the IP source configuration
+    ///
+    /// `named` are the named IP sources a fallback source may refer to.
+    fn to_config(
+        &self,
+        named: &[(String, IpSourceEnv)],
+    ) -> Result<ddns_core::config::IpSourceConfig> {
+        use ddns_core::config::IpSourceConfig;
+
+        match self.source_type.as_str() {
+            "netlink" => Ok(IpSourceConfig::Netlink {
+                interface: self.interface.clone(),
+                version: None,
+                selection: self.selection.clone(),
+            }),
+            "http" if !self.v4_urls.is_empty() || !self.v6_urls.is_empty() => {
+                Ok(IpSourceConfig::Http {
+                    url: String::new(),
+                    urls: Vec::new(),
+                    v4_urls: self.v4_urls.clone(),
+                    v6_urls: self.v6_urls.clone(),
+                    strategy: self.http_strategy()?,
+                    extractor: self.http_extractor()?,
+                    headers: self.headers.clone(),
+                    interval_secs: self.interval.unwrap_or(60),
+                })
+            }
+            "http" => Ok(IpSourceConfig::Http {
+                url: self
+                    .urls
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "https://api.ipify.org".to_string()),
+                urls: self.urls.iter().skip(1).cloned().collect(),
+                v4_urls: Vec::new(),
+                v6_urls: Vec::new(),
+                strategy: self.http_strategy()?,
+                extractor: self.http_extractor()?,
+                headers: self.headers.clone(),
+                interval_secs: self.interval.unwrap_or(60),
+            }),
+            "upnp" => Ok(IpSourceConfig::Upnp {
+                location: self.upnp_location.clone(),
+                nat_pmp_gateway: self.nat_pmp_gateway()?,
+                interval_secs: self.interval.unwrap_or(60),
+            }),
+            "stun" => Ok(IpSourceConfig::Stun {
+                servers: self.stun_servers.clone(),
+                version: self.ip_version()?,
+                interval_secs: self.interval.unwrap_or(60),
+            }),
+            "exec" => Ok(IpSourceConfig::Exec {
+                command: vec![
+                    "/bin/sh".to_string(),
+                    "-c".to_string(),
+                    self.exec_command.clone().unwrap_or_default(),
+                ],
+                json_pointer: self.exec_json_pointer()?,
+                timeout_secs: self.exec_timeout.unwrap_or(10),
+                signal: self.exec_signal()?,
+                interval_secs: self.interval.unwrap_or(60),
+            }),
+            "file" => Ok(IpSourceConfig::File {
+                path: self.file_path.clone().unwrap_or_default(),
+                debounce_ms: self.file_debounce_ms.unwrap_or(500),
+            }),
+            "static" => {
+                let (v4, v6) = self.static_addresses()?;
+                Ok(IpSourceConfig::Static {
+                    v4,
+                    v6,
+                    control: self.static_control.clone(),
+                })
+            }
+            "fallback" => Ok(IpSourceConfig::Fallback {
+                sources: self
+                    .fallback
+                    .iter()
+                    .map(|member| {
+                        named
+                            .iter()
+                            .find(|(name, _)| name == member)
+                            .ok_or_else(|| anyhow::anyhow!("Unknown IP source: {}", member))?
+                            .1
+                            .to_config(named)
+                    })
+                    .collect::<Result<_>>()?,
+            }),
+            _ => Err(anyhow::anyhow!(
+                "Unknown IP source type: {}",
+                self.source_type
+            )),
+        }
+    }
+}
+
 fn main() -> ExitCode {
     // Load configuration from environment
     let config = match Config::from_env() {
@@ -442,9 +1497,7 @@ fn main() -> ExitCode {
 
 /// Run the daemon
 async fn run_daemon(config: Config) -> Result<()> {
-    use ddns_core::config::{
-        DdnsConfig, EngineConfig, IpSourceConfig, ProviderConfig, RecordConfig, StateStoreConfig,
-    };
+    use ddns_core::config::{DdnsConfig, EngineConfig, RecordConfig, StateStoreConfig};
     use ddns_core::{DdnsEngine, ProviderRegistry};
 
     // Create provider registry
@@ -457,6 +1510,42 @@ async fn run_daemon(config: Config) -> Result<()> {
         ddns_provider_cloudflare::register(&registry);
     }
 
+    #[cfg(feature = "rfc2136")]
+    {
+        info!("Registering RFC 2136 provider");
+        ddns_provider_rfc2136::register(&registry);
+    }
+
+    #[cfg(feature = "route53")]
+    {
+        info!("Registering Route 53 provider");
+        ddns_provider_route53::register(&registry);
+    }
+
+    #[cfg(feature = "dyndns2")]
+    {
+        info!("Registering dyndns2 provider");
+        ddns_provider_dyndns2::register(&registry);
+    }
+
+    #[cfg(feature = "powerdns")]
+    {
+        info!("Registering PowerDNS provider");
+        ddns_provider_powerdns::register(&registry);
+    }
+
+    #[cfg(feature = "hetzner")]
+    {
+        info!("Registering Hetzner DNS provider");
+        ddns_provider_hetzner::register(&registry);
+    }
+
+    #[cfg(feature = "digitalocean")]
+    {
+        info!("Registering DigitalOcean provider");
+        ddns_provider_digitalocean::register(&registry);
+    }
+
     #[cfg(feature = "netlink")]
     {
         info!("Registering Netlink IP source");
@@ -469,6 +1558,35 @@ async fn run_daemon(config: Config) -> Result<()> {
         ddns_ip_http::register(&registry);
     }
 
+    #[cfg(feature = "upnp")]
+    {
+        info!("Registering UPnP IP source");
+        ddns_ip_upnp::register(&registry);
+    }
+
+    #[cfg(feature = "stun")]
+    {
+        info!("Registering STUN IP source");
+        ddns_ip_stun::register(&registry);
+    }
+
+    #[cfg(feature = "exec")]
+    {
+        info!("Registering exec IP source");
+        ddns_ip_exec::register(&registry);
+    }
+
+    #[cfg(feature = "file")]
+    {
+        info!("Registering file IP source");
+        ddns_ip_file::register(&registry);
+    }
+
+    info!("Registering static IP source");
+    let static_factory = ddns_core::StaticIpFactory::new();
+    let static_controls = static_factory.controls();
+    registry.register_ip_source("static", Box::new(static_factory));
+
     // Register built-in state stores
     info!("Registering file state store");
     registry.register_state_store("file", Box::new(ddns_core::FileStateStoreFactory));
@@ -476,40 +1594,21 @@ async fn run_daemon(config: Config) -> Result<()> {
     info!("Registering memory state store");
     registry.register_state_store("memory", Box::new(ddns_core::MemoryStateStoreFactory));
 
-    // Create IP source config
-    let ip_source_config = match config.ip_source_type.as_str() {
-        "netlink" => IpSourceConfig::Netlink {
-            interface: config.ip_source_interface.clone(),
-            version: None,
-        },
-        "http" => IpSourceConfig::Http {
-            url: config
-                .ip_source_url
-                .unwrap_or_else(|| "https://api.ipify.org".to_string()),
-            interval_secs: config.ip_source_interval.unwrap_or(60),
-        },
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unknown IP source type: {}",
-                config.ip_source_type
-            ));
-        }
-    };
+    // Create IP source configs
+    let ip_source_config = config.ip_source.to_config(&config.ip_sources)?;
+    let ip_sources_config = config
+        .ip_sources
+        .iter()
+        .map(|(name, ip_source)| Ok((name.clone(), ip_source.to_config(&config.ip_sources)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
 
-    // Create provider config
-    let provider_config = match config.provider_type.as_str() {
-        "cloudflare" => ProviderConfig::Cloudflare {
-            api_token: config.provider_api_token.clone(),
-            zone_id: config.provider_zone_id.clone(),
-            account_id: None,
-        },
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unknown provider type: {}",
-                config.provider_type
-            ));
-        }
-    };
+    // Create provider configs
+    let provider_config = config.provider.to_config()?;
+    let providers_config = config
+        .providers
+        .iter()
+        .map(|(name, provider)| Ok((name.clone(), provider.to_config()?)))
+        .collect::<Result<HashMap<_, _>>>()?;
 
     // Create state store config
     let state_store_config = match config.state_store_type.as_str() {
@@ -532,7 +1631,17 @@ async fn run_daemon(config: Config) -> Result<()> {
     let record_configs: Vec<RecordConfig> = config
         .records
         .iter()
-        .map(|name| RecordConfig::new(name.as_str()))
+        .map(|entry| {
+            let (name, provider) = split_record(entry);
+            let mut record = RecordConfig::new(name);
+            if let Some(provider) = provider {
+                record = record.with_provider(provider);
+            }
+            if let Some(ip_source) = config.record_ip_sources.get(name) {
+                record = record.with_ip_source(ip_source.clone());
+            }
+            record
+        })
         .collect();
 
     // Create engine config
@@ -542,12 +1651,15 @@ async fn run_daemon(config: Config) -> Result<()> {
         startup_delay_secs: config.startup_delay_secs.unwrap_or(0),
         min_update_interval_secs: config.min_update_interval_secs.unwrap_or(60),
         event_channel_capacity: 100,
+        reconcile_interval_secs: config.reconcile_interval_secs.unwrap_or(0),
         metadata: std::collections::HashMap::new(),
     };
 
     let ddns_config = DdnsConfig {
         ip_source: ip_source_config,
+        ip_sources: ip_sources_config,
         provider: provider_config,
+        providers: providers_config,
         state_store: state_store_config,
         records: record_configs,
         engine: engine_config,
@@ -555,11 +1667,21 @@ async fn run_daemon(config: Config) -> Result<()> {
 
     // Create components from registry
     let ip_source = registry.create_ip_source(&ddns_config.ip_source)?;
+    let ip_sources = registry.create_ip_sources(&ddns_config.ip_sources)?;
     let provider = registry.create_provider(&ddns_config.provider)?;
-    let state_store = registry.create_state_store(&ddns_config.state_store).await?;
-
-    info!("IP source type: {}", config.ip_source_type);
-    info!("Provider type: {}", config.provider_type);
+    let providers = registry.create_providers(&ddns_config.providers)?;
+    let state_store = registry
+        .create_state_store(&ddns_config.state_store)
+        .await?;
+
+    info!("IP source type: {}", config.ip_source.source_type);
+    for (name, ip_source) in &config.ip_sources {
+        info!("IP source '{}' type: {}", name, ip_source.source_type);
+    }
+    info!("Provider type: {}", config.provider.provider_type);
+    for (name, provider) in &config.providers {
+        info!("Provider '{}' type: {}", name, provider.provider_type);
+    }
     info!("State store type: {}", config.state_store_type);
 
     for record in &config.records {
@@ -567,7 +1689,23 @@ async fn run_daemon(config: Config) -> Result<()> {
     }
 
     // Create engine
-    let (engine, mut event_rx) = DdnsEngine::new(ip_source, provider, state_store, ddns_config)?;
+    let (engine, mut event_rx) = DdnsEngine::with_components(
+        ip_source,
+        ip_sources,
+        provider,
+        providers,
+        state_store,
+        ddns_config,
+    )?;
+
+    // Let operators re-pin static addresses through their control files
+    #[cfg(unix)]
+    let _static_control = if static_controls.names().is_empty() {
+        None
+    } else {
+        info!("Reloading static IP control files on SIGHUP");
+        Some(static_control::reload_on_hangup(static_controls)?)
+    };
 
     // Spawn event listener (optional, for logging)
     let event_listener = tokio::spawn(async move {
@@ -0,0 +1,157 @@
+// # Static IP Source Control Files
+//
+// A static IP source with `DDNS_IP_SOURCE_STATIC_CONTROL` set publishes its
+// control handle under the path of that control file. On SIGHUP, ddnsd
+// reads every control file and pins the addresses it lists, so an operator
+// can move records to a standby host without restarting the daemon:
+//
+// ```bash
+// echo 203.0.113.6 > /run/ddnsd/standby
+// pkill -HUP ddnsd
+// ```
+//
+// A control file holds at most one address per family, one per line, in
+// the forms the exec and file IP sources accept (a trailing "/prefix_len"
+// is ignored). Blank lines and lines starting with `#` are ignored. A family without an
+// address is unpinned, which leaves its records at their last value. A
+// missing or invalid file is logged and changes nothing.
+//
+// SIGHUP is taken by the control files, so an exec IP source cannot be run
+// by it while a control file is configured; configuration loading rejects
+// that combination.
+
+use anyhow::{Context, Result};
+use ddns_core::net::parse_address;
+use ddns_core::source::{StaticAddresses, StaticIpControls};
+use ddns_core::traits::IpVersion;
+use std::net::IpAddr;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Read the addresses listed in a control file
+pub(crate) fn read_control_file(path: &Path) -> Result<StaticAddresses> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut addresses = StaticAddresses::default();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match parse_address(line) {
+            Some(IpAddr::V4(ip)) if addresses.v4.is_none() => addresses.v4 = Some(ip),
+            Some(IpAddr::V6(ip)) if addresses.v6.is_none() => addresses.v6 = Some(ip),
+            Some(_) => anyhow::bail!("{} lists two addresses of one family", path.display()),
+            None => anyhow::bail!(
+                "{} holds something other than an IP address: '{}'",
+                path.display(),
+                line
+            ),
+        }
+    }
+
+    Ok(addresses)
+}
+
+/// Pin the addresses of every control file on its static IP source
+pub(crate) fn reload(controls: &StaticIpControls) {
+    for path in controls.names() {
+        let Some(control) = controls.get(&path) else {
+            continue;
+        };
+
+        let addresses = match read_control_file(Path::new(&path)) {
+            Ok(addresses) => addresses,
+            Err(e) => {
+                warn!("Keeping static IP addresses: {:#}", e);
+                continue;
+            }
+        };
+
+        info!(
+            "Static IP addresses from {}: {:?}, {:?}",
+            path, addresses.v4, addresses.v6
+        );
+        for version in [IpVersion::V4, IpVersion::V6] {
+            match addresses.get(version) {
+                Some(ip) => control.set(ip),
+                None => control.clear(version),
+            }
+        }
+    }
+}
+
+/// Reload the control files on every SIGHUP
+#[cfg(unix)]
+pub(crate) fn reload_on_hangup(controls: StaticIpControls) -> Result<tokio::task::JoinHandle<()>> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = signal(SignalKind::hangup()).context("Failed to register SIGHUP handler")?;
+
+    Ok(tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            reload(&controls);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddns_core::StaticIpFactory;
+    use ddns_core::config::IpSourceConfig;
+    use ddns_core::traits::IpSourceFactory;
+    use tokio_stream::StreamExt;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reload_moves_the_pinned_address() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("standby");
+
+        let factory = StaticIpFactory::new();
+        let controls = factory.controls();
+        let source = factory
+            .create(&IpSourceConfig::Static {
+                v4: Some("203.0.113.5".parse().unwrap()),
+                v6: None,
+                control: Some(path.display().to_string()),
+            })
+            .unwrap();
+        let mut events = source.watch();
+        assert_eq!(events.next().await.unwrap().new_ip, ip("203.0.113.5"));
+
+        std::fs::write(&path, "# standby host\n203.0.113.6/24\n2001:db8::6\n").unwrap();
+        reload(&controls);
+
+        let event = events.next().await.unwrap();
+        assert_eq!(
+            (event.new_ip, event.previous_ip),
+            (ip("203.0.113.6"), Some(ip("203.0.113.5")))
+        );
+        assert_eq!(events.next().await.unwrap().new_ip, ip("2001:db8::6"));
+
+        // An invalid file keeps the pinned addresses
+        std::fs::write(&path, "standby\n").unwrap();
+        reload(&controls);
+        assert_eq!(source.current().await.unwrap(), ip("203.0.113.6"));
+
+        // Families missing from the file are unpinned
+        std::fs::write(&path, "2001:db8::6\n").unwrap();
+        reload(&controls);
+        assert_eq!(source.current().await.unwrap(), ip("2001:db8::6"));
+    }
+
+    #[test]
+    fn test_read_control_file_rejects_two_addresses_of_one_family() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("standby");
+        std::fs::write(&path, "203.0.113.5\n203.0.113.6\n").unwrap();
+
+        assert!(read_control_file(&path).is_err());
+    }
+}
@@ -180,12 +180,21 @@ async fn main() -> Result<()> {
         ip_source: ddns_core::config::IpSourceConfig::Netlink {
             interface: None,
             version: None,
+            selection: Default::default(),
         },
+        ip_sources: Default::default(),
         provider: ddns_core::config::ProviderConfig::Cloudflare {
             api_token: "test-token".to_string(),
             zone_id: None,
             account_id: None,
+            create_if_missing: false,
+            ttl: None,
+            proxied: false,
+            comment: None,
+            api_base_url: None,
+            zone_overrides: Default::default(),
         },
+        providers: Default::default(),
         state_store: ddns_core::config::StateStoreConfig::Memory,
         records: vec![RecordConfig::new("example.com")],
         engine: ddns_core::config::EngineConfig {
@@ -194,6 +203,7 @@ async fn main() -> Result<()> {
             startup_delay_secs: 0,
             min_update_interval_secs: 0, // No rate limiting for example
             event_channel_capacity: 100, // Small buffer for example
+            reconcile_interval_secs: 0,
             metadata: std::collections::HashMap::new(),
         },
     };